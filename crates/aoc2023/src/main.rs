@@ -0,0 +1,98 @@
+mod session;
+
+use lib::{get_args, input};
+use runner::{available_days, registry, run_all, run_isolated, summary, to_json};
+use session::{load, Session};
+use std::{
+    env::current_exe,
+    error::Error,
+    fs::write,
+    io::{stdin, stdout, BufRead, Write},
+    path::Path,
+    process::exit,
+};
+
+fn usage(prog_name: String) {
+    println!(
+        "Usage: {} [repl DAY FILE|all [--inputs DIR] [--json FILE] [--isolate JOBS] [--bins DIR]|-h]",
+        prog_name
+    );
+    exit(0)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let (prog_name, args) = get_args()?;
+
+    match args.as_slice() {
+        [command, day, file] if command == "repl" => {
+            let day = day.parse::<u32>()?;
+            let lines = input::read(file)?.collect::<Result<Vec<_>, _>>()?;
+
+            repl(load(day, &lines)?)?
+        }
+        [command, options @ ..] if command == "all" => {
+            // the inputs are cached in the crate of each day
+            let mut inputs = "crates";
+            let mut json = None;
+            // with isolation, each part of each day runs in a process of the binary of the day,
+            // the binaries are by default next to this one
+            let mut jobs = None;
+            let mut bins = None;
+            for option in options.chunks(2) {
+                match option {
+                    [name, value] if name == "--inputs" => inputs = value,
+                    [name, value] if name == "--json" => json = Some(value),
+                    [name, value] if name == "--isolate" => jobs = Some(value.parse::<usize>()?),
+                    [name, value] if name == "--bins" => {
+                        bins = Some(Path::new(value).to_path_buf())
+                    }
+                    _ => return Err(format!("Invalid option: {}", option.join(" ")).into()),
+                }
+            }
+
+            let reports = match jobs {
+                Some(jobs) => {
+                    let bins = match bins {
+                        Some(bins) => bins,
+                        None => current_exe()?
+                            .parent()
+                            .ok_or("No directory for the binaries")?
+                            .to_path_buf(),
+                    };
+                    run_isolated(&available_days(&bins), &bins, Path::new(inputs), jobs)
+                }
+                None => run_all(&registry(), Path::new(inputs)),
+            };
+            print!("{}", summary(&reports));
+            if let Some(json) = json {
+                write(json, to_json(&reports)?)?;
+            }
+        }
+        _ => usage(prog_name),
+    }
+    Ok(())
+}
+
+// reads the queries from stdin until the end of the input or quit, errors are reported without
+// leaving the loop
+fn repl(session: Session) -> Result<(), Box<dyn Error>> {
+    let prompt = || -> Result<(), Box<dyn Error>> {
+        print!("> ");
+        stdout().flush().map_err(Into::into)
+    };
+
+    prompt()?;
+    for line in stdin().lock().lines() {
+        let line = line?;
+        match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [] => {}
+            ["quit"] => break,
+            query => match session(query) {
+                Ok(result) => println!("{}", result),
+                Err(e) => println!("Error: {}", e),
+            },
+        }
+        prompt()?;
+    }
+    Ok(())
+}
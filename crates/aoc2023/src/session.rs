@@ -0,0 +1,391 @@
+// the sessions of the repl, one per day. The days with parameters parse their input once and
+// answer the queries of the modes of their binary, the others answer both parts from the registry
+
+use day_09::{extrapolate_backward, extrapolate_forward, parse_line};
+use rand::{rngs::StdRng, SeedableRng};
+use runner::registry;
+use std::error::Error;
+
+// answers a query, made of the words typed by the user, on the input of a day parsed once
+pub type Session = Box<dyn Fn(&[&str]) -> Result<String, Box<dyn Error>>>;
+
+pub fn load(day: u32, lines: &[String]) -> Result<Session, Box<dyn Error>> {
+    match day {
+        9 => load_day09(lines),
+        11 => load_day11(lines),
+        16 => load_day16(lines),
+        17 => load_day17(lines),
+        19 => load_day19(lines),
+        21 => load_day21(lines),
+        23 => load_day23(lines),
+        _ => load_registry(day, lines),
+    }
+}
+
+// the solvers of the registry take the lines, the input is parsed again at each query
+fn load_registry(day: u32, lines: &[String]) -> Result<Session, Box<dyn Error>> {
+    let solver = registry()
+        .into_iter()
+        .find(|solver| solver.day == day)
+        .ok_or(format!("Day {} is not available in the runner", day))?;
+    let lines = lines.to_vec();
+
+    Ok(Box::new(move |query| match query {
+        ["1"] => (solver.parts[0])(&lines),
+        ["2"] => (solver.parts[1])(&lines),
+        _ => Err("Unknown query, expected 1 or 2".into()),
+    }))
+}
+
+fn load_day09(lines: &[String]) -> Result<Session, Box<dyn Error>> {
+    let sequences = lines
+        .iter()
+        .map(|line| parse_line(line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Box::new(move |query| {
+        let extrapolate = match query {
+            ["1"] => extrapolate_forward,
+            ["2"] => extrapolate_backward,
+            _ => return Err("Unknown query, expected 1 or 2".into()),
+        };
+
+        sequences
+            .iter()
+            .map(|sequence| extrapolate(sequence))
+            .sum::<Result<i64, _>>()
+            .map(|result| result.to_string())
+    }))
+}
+
+fn load_day11(lines: &[String]) -> Result<Session, Box<dyn Error>> {
+    let universe = day_11::parse(lines.iter().cloned())?;
+
+    Ok(Box::new(move |query| {
+        // each empty line or column is replaced by `expansion` of them
+        let expansion = match query {
+            ["1"] => 2,
+            ["2"] => 1_000_000,
+            ["expansion", expansion] => expansion.parse::<i64>()?,
+            _ => return Err("Unknown query, expected 1, 2 or expansion N".into()),
+        };
+        if expansion < 1 {
+            return Err("The expansion factor must be positive".into());
+        }
+
+        Ok(day_11::solve(&day_11::expand(&universe, expansion - 1)?)?.to_string())
+    }))
+}
+
+fn load_day16(lines: &[String]) -> Result<Session, Box<dyn Error>> {
+    let grid = day_16::parse(lines.iter().cloned())?;
+
+    Ok(Box::new(move |query| {
+        let (solve, options): (day_16::LimitedSolver, _) = match query {
+            ["1", options @ ..] => (day_16::solve1_limited, options),
+            ["2", options @ ..] => (day_16::solve2_limited, options),
+            _ => return Err("Unknown query, expected 1 or 2 [--max-steps N]".into()),
+        };
+        let max_steps = match options {
+            [] => None,
+            ["--max-steps", max_steps] => Some(max_steps.parse::<usize>()?),
+            _ => return Err(format!("unknown option: {}", options.join(" ")).into()),
+        };
+
+        let energized = solve(&grid, max_steps)?;
+        Ok(if energized.truncated {
+            format!(
+                "{} (truncated after {} steps)",
+                energized.count, energized.steps
+            )
+        } else {
+            energized.count.to_string()
+        })
+    }))
+}
+
+fn load_day17(lines: &[String]) -> Result<Session, Box<dyn Error>> {
+    let graph = day_17::parse(lines.iter().cloned())?;
+
+    Ok(Box::new(move |query| {
+        let (min_run, max_run) = match query {
+            ["1"] => day_17::CRUCIBLE_RUNS,
+            ["2"] => day_17::ULTRA_CRUCIBLE_RUNS,
+            ["runs", runs] => day_17::parse_runs(runs)?,
+            _ => return Err("Unknown query, expected 1, 2 or runs MIN:MAX".into()),
+        };
+
+        Ok(day_17::solve_with_constraints(graph.clone(), min_run, max_run)?.to_string())
+    }))
+}
+
+fn load_day19(lines: &[String]) -> Result<Session, Box<dyn Error>> {
+    let (workflows, parts) = day_19::parse(lines.iter().cloned())?;
+
+    Ok(Box::new(move |query| {
+        let (part, options) = match query {
+            [part @ ("1" | "2"), options @ ..] => (*part, options),
+            _ => {
+                return Err("Unknown query, expected 1 or 2 [--max-hops N] [--max-stack N]".into())
+            }
+        };
+        let options = options
+            .iter()
+            .map(|option| option.to_string())
+            .collect::<Vec<_>>();
+        let limits = day_19::parse_limits(&options)?;
+
+        Ok(if part == "1" {
+            day_19::solve1_limited(&workflows, &parts, &limits)?.to_string()
+        } else {
+            day_19::solve2_limited(&workflows, &limits)?.to_string()
+        })
+    }))
+}
+
+fn load_day21(lines: &[String]) -> Result<Session, Box<dyn Error>> {
+    let (grid, start) = day_21::parse(lines.iter().cloned())?;
+    let distance_map = day_21::DistanceMap::new(&grid, &start)?;
+
+    Ok(Box::new(move |query| match query {
+        ["1"] => Ok(day_21::solve1(&grid, &start)?.to_string()),
+        ["2"] => Ok(day_21::solve2(&grid, &start)?.to_string()),
+        ["steps", steps] => Ok(distance_map.count(steps.parse::<usize>()?).to_string()),
+        _ => Err("Unknown query, expected 1, 2 or steps N".into()),
+    }))
+}
+
+fn load_day23(lines: &[String]) -> Result<Session, Box<dyn Error>> {
+    let map = day_23::parse(lines.iter().cloned())?;
+    // the slopes are regular paths in part 2 and for the heuristic
+    let map_without_slopes = day_23::parse(lines.iter().map(|line| day_23::remove_slopes(line)))?;
+
+    Ok(Box::new(move |query| {
+        let (restarts, seed) = match query {
+            ["1"] => return Ok(day_23::solve1(&map)?.to_string()),
+            ["2"] => return Ok(day_23::solve2(&map_without_slopes)?.to_string()),
+            ["heuristic", restarts] => (restarts.parse::<usize>()?, 0),
+            ["heuristic", restarts, seed] => (restarts.parse::<usize>()?, seed.parse::<u64>()?),
+            _ => return Err("Unknown query, expected 1, 2 or heuristic N [SEED]".into()),
+        };
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        // the heuristic only finds a path, there might be a longer one
+        Ok(
+            match day_23::solve_heuristic(&map_without_slopes, restarts, &mut rng)? {
+                Some(length) => format!("at least {}", length),
+                None => "no path found".to_string(),
+            },
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::session::load;
+
+    const DAY01: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+    const DAY09: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+    const DAY11: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    const DAY16: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    const DAY17: &str = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    const DAY19: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+
+    const DAY21: &str = "\
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    const DAY23: &str = "\
+#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
+
+    fn lines(s: &str) -> Vec<String> {
+        s.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_day09() -> Result<(), Box<dyn Error>> {
+        let session = load(9, &lines(DAY09))?;
+        assert_eq!(session(&["1"])?, "114");
+        assert_eq!(session(&["2"])?, "2");
+        // the session survives an invalid query
+        assert!(session(&["3"]).is_err());
+        assert_eq!(session(&["1"])?, "114");
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry() -> Result<(), Box<dyn Error>> {
+        let session = load(1, &lines(DAY01))?;
+        assert_eq!(session(&["1"])?, "142");
+        assert_eq!(session(&["2"])?, "142");
+        assert!(session(&["steps", "6"]).is_err());
+
+        // the input is only parsed by the queries
+        let session = load(4, &lines(DAY09))?;
+        assert!(session(&["1"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_day11() -> Result<(), Box<dyn Error>> {
+        let session = load(11, &lines(DAY11))?;
+        assert_eq!(session(&["1"])?, "374");
+        assert_eq!(session(&["expansion", "10"])?, "1030");
+        assert_eq!(session(&["expansion", "100"])?, "8410");
+        assert!(session(&["expansion", "0"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_day16() -> Result<(), Box<dyn Error>> {
+        let session = load(16, &lines(DAY16))?;
+        assert_eq!(session(&["1"])?, "46");
+        assert_eq!(session(&["2"])?, "51");
+        assert_eq!(session(&["1", "--max-steps", "1000"])?, "46");
+        assert!(session(&["1", "--max-steps", "3"])?.ends_with("(truncated after 3 steps)"));
+        assert!(session(&["1", "--max-hops", "3"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_day17() -> Result<(), Box<dyn Error>> {
+        let session = load(17, &lines(DAY17))?;
+        assert_eq!(session(&["1"])?, "102");
+        assert_eq!(session(&["2"])?, "94");
+        assert_eq!(session(&["runs", "1:3"])?, "102");
+        assert!(session(&["runs", "3:1"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_day19() -> Result<(), Box<dyn Error>> {
+        let session = load(19, &lines(DAY19))?;
+        assert_eq!(session(&["1"])?, "19114");
+        assert_eq!(session(&["2"])?, "167409079868000");
+        assert_eq!(session(&["2", "--max-hops", "10"])?, "167409079868000");
+        // the boxes of parts don't fit in a stack of one
+        assert!(session(&["2", "--max-stack", "1"]).is_err());
+        assert!(session(&["3"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_day21() -> Result<(), Box<dyn Error>> {
+        let session = load(21, &lines(DAY21))?;
+        assert_eq!(session(&["steps", "6"])?, "16");
+        assert_eq!(session(&["steps", "1"])?, "2");
+        assert!(session(&["steps", "x"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_day23() -> Result<(), Box<dyn Error>> {
+        let session = load(23, &lines(DAY23))?;
+        assert_eq!(session(&["1"])?, "94");
+        assert_eq!(session(&["2"])?, "154");
+        assert_eq!(session(&["heuristic", "1000", "23"])?, "at least 154");
+        assert_eq!(session(&["heuristic", "0"])?, "no path found");
+        Ok(())
+    }
+
+    #[test]
+    fn test_unavailable_day() {
+        assert!(load(26, &lines(DAY09)).is_err());
+        assert!(load(9, &lines("1 2 x")).is_err());
+        assert!(load(21, &lines(DAY09)).is_err());
+    }
+}
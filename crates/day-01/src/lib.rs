@@ -0,0 +1,64 @@
+use lib::scan::first_last_digits;
+use std::error::Error;
+
+fn first_last(line: &str, allow_words: bool) -> Result<u32, Box<dyn Error>> {
+    let (first, last) = first_last_digits(line, allow_words)
+        .ok_or_else(|| format!("Cant find a digit in {}", line))?;
+
+    Ok(first * 10 + last)
+}
+
+pub fn solve1(mut itr: impl Iterator<Item = String>) -> Result<u32, Box<dyn Error>> {
+    itr.try_fold(0, |acc, line| Ok(acc + first_last(&line, false)?))
+}
+
+pub fn solve2(mut itr: impl Iterator<Item = String>) -> Result<u32, Box<dyn Error>> {
+    itr.try_fold(0, |acc, line| Ok(acc + first_last(&line, true)?))
+}
+
+#[cfg(test)]
+mod day01 {
+    use lib::aoc_tests;
+    use std::error::Error;
+
+    use crate::solve1;
+    use crate::solve2;
+
+    const INPUT1: &str = "\
+        1abc2\n\
+        pqr3stu8vwx\n\
+        a1b2c3d4e5f\n\
+        treb7uchet";
+
+    const INPUT2: &str = "\
+        two1nine\n\
+        eightwothree\n\
+        abcone2threexyz\n\
+        xtwone3four\n\
+        4nineeightseven2\n\
+        zoneight234\n\
+        7pqrstsixteen";
+
+    aoc_tests! {
+        example1_solve1: example(INPUT1), Ok, solve1 => 142;
+        example1_solve2: example(INPUT1), Ok, solve2 => 142;
+        example2_solve2: example(INPUT2), Ok, solve2 => 281;
+        input_solve1: file("input"), Ok, solve1 => 56397;
+        input_solve2: file("input"), Ok, solve2 => 55701;
+    }
+
+    #[test]
+    fn no_digit() {
+        assert!(solve1(["abc".to_string()].into_iter()).is_err());
+        assert!(solve1(["one".to_string()].into_iter()).is_err());
+        assert!(solve2(["zero".to_string()].into_iter()).is_err());
+    }
+
+    #[test]
+    fn overlapping_words() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve2(["oneight".to_string()].into_iter())?, 18);
+        assert_eq!(solve2(["twone".to_string()].into_iter())?, 21);
+        assert_eq!(solve2(["eightwo3".to_string()].into_iter())?, 83);
+        Ok(())
+    }
+}
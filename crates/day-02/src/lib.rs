@@ -0,0 +1,670 @@
+use itertools::Itertools;
+use lib::{
+    parse::{Cursor, ParseError},
+    table::table,
+};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Cubes {
+    pub blue: u32,
+    pub green: u32,
+    pub red: u32,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Cubes>,
+}
+
+pub fn parse_costs(s: &str) -> Result<Cubes, Box<dyn Error>> {
+    Ok(Cursor::new(s)
+        .split(",")
+        .try_fold(Cubes::default(), |mut costs, assignment| {
+            let (color, value) = assignment.split_once("=")?;
+            *costs.get_mut(color)? = value.value::<u32>()?;
+            Ok::<_, ParseError>(costs)
+        })?)
+}
+
+impl FromStr for Game {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        cursor.tag("Game ")?;
+        let id = cursor.until(":")?.value::<u32>()?;
+        let draws = cursor
+            .split(";")
+            .map(Cubes::from_cursor)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Game { id, draws })
+    }
+}
+
+impl Cubes {
+    // the count of the color named by the cursor
+    fn get_mut(&mut self, color: Cursor) -> Result<&mut u32, ParseError> {
+        match color.rest() {
+            "blue" => Ok(&mut self.blue),
+            "green" => Ok(&mut self.green),
+            "red" => Ok(&mut self.red),
+            _ => Err(color.error(format!("invalid color '{}'", color.rest()))),
+        }
+    }
+
+    // the draws are parsed from the line of their game, the errors locating them in it
+    fn from_cursor(cursor: Cursor) -> Result<Self, ParseError> {
+        cursor
+            .split(",")
+            .try_fold(Cubes::default(), |mut draw, count_color| {
+                let (count, color) = count_color.trim().split_once(" ")?;
+                *draw.get_mut(color)? = count.value::<u32>()?;
+                Ok(draw)
+            })
+    }
+}
+
+impl FromStr for Cubes {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Cubes::from_cursor(Cursor::new(s))?)
+    }
+}
+
+// in the format of the draws, so that it parses back
+impl Display for Cubes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} red, {} green, {} blue",
+            self.red, self.green, self.blue
+        )
+    }
+}
+
+// the smallest bag making all the draws possible
+fn minimal_bag<'a>(draws: impl IntoIterator<Item = &'a Cubes>) -> Cubes {
+    draws.into_iter().fold(Cubes::default(), |acc, draw| Cubes {
+        blue: acc.blue.max(draw.blue),
+        green: acc.green.max(draw.green),
+        red: acc.red.max(draw.red),
+    })
+}
+
+fn min(draws: &[Cubes]) -> u32 {
+    power(&minimal_bag(draws))
+}
+
+pub fn solve2(games: impl Iterator<Item = Game>) -> u32 {
+    games
+        .map(|game| -> u32 { min(game.draws.as_slice()) })
+        .sum()
+}
+
+fn power(cube: &Cubes) -> u32 {
+    cube.blue * cube.green * cube.red
+}
+
+fn draw_possible(bag: &Cubes, draw: &Cubes) -> bool {
+    bag.blue >= draw.blue && bag.green >= draw.green && bag.red >= draw.red
+}
+
+fn game_possible(bag: &Cubes, game: &Game) -> bool {
+    game.draws.iter().all(|draw| draw_possible(bag, draw))
+}
+
+// a bag the games must fit in, the one of part 1 unless given with --bag
+#[derive(Debug, PartialEq, Eq)]
+pub struct Constraint {
+    bag: Cubes,
+}
+
+impl Default for Constraint {
+    fn default() -> Self {
+        Constraint {
+            bag: Cubes {
+                red: 12,
+                green: 13,
+                blue: 14,
+            },
+        }
+    }
+}
+
+// written as a draw: "12 red, 13 green, 14 blue"
+impl FromStr for Constraint {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Constraint { bag: s.parse()? })
+    }
+}
+
+impl Constraint {
+    fn allows(&self, game: &Game) -> bool {
+        game_possible(&self.bag, game)
+    }
+}
+
+pub fn parse_bag_option(args: &[String]) -> Result<Constraint, Box<dyn Error>> {
+    let mut constraint = Constraint::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bag" => constraint = args.next().ok_or("missing bag")?.parse()?,
+            _ => return Err(format!("unknown option: {}", arg).into()),
+        }
+    }
+
+    Ok(constraint)
+}
+
+// one bag per line, the blank lines skipped
+pub fn parse_constraints(s: &str) -> Result<Vec<Constraint>, Box<dyn Error>> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|e| format!("line {}: {}", i + 1, e).into())
+        })
+        .collect()
+}
+
+// the minimal bag of each game and whether it fits in each of the candidate bags
+pub fn feasibility_report(constraints: &[Constraint], games: &[Game]) -> String {
+    let header = ["game".to_string(), "minimal bag".to_string()]
+        .into_iter()
+        .chain((1..=constraints.len()).map(|i| format!("bag {}", i)))
+        .collect::<Vec<_>>();
+
+    let rows = games
+        .iter()
+        .map(|game| {
+            [game.id.to_string(), minimal_bag(&game.draws).to_string()]
+                .into_iter()
+                .chain(constraints.iter().map(|constraint| {
+                    if constraint.allows(game) { "yes" } else { "no" }.to_string()
+                }))
+                .collect()
+        })
+        .collect::<Vec<_>>();
+
+    table(
+        &header.iter().map(String::as_str).collect::<Vec<_>>(),
+        &rows,
+    )
+}
+
+pub fn solve1(constraint: &Constraint, games: impl Iterator<Item = Game>) -> u32 {
+    games
+        .filter_map(|game| {
+            if constraint.allows(&game) {
+                Some(game.id)
+            } else {
+                None
+            }
+        })
+        .sum()
+}
+
+fn cost(costs: &Cubes, bag: &Cubes) -> u64 {
+    u64::from(costs.blue) * u64::from(bag.blue)
+        + u64::from(costs.green) * u64::from(bag.green)
+        + u64::from(costs.red) * u64::from(bag.red)
+}
+
+// a bag, its cost and the ids of the games it makes possible
+#[derive(Debug, PartialEq, Eq)]
+pub struct Bag {
+    pub cubes: Cubes,
+    pub cost: u64,
+    pub games: Vec<u32>,
+}
+
+// the cheapest bag making all the given games possible
+pub fn bag_for(costs: &Cubes, games: &[&Game]) -> Bag {
+    let cubes = minimal_bag(games.iter().flat_map(|game| game.draws.iter()));
+
+    Bag {
+        cost: cost(costs, &cubes),
+        games: games.iter().map(|game| game.id).collect(),
+        cubes,
+    }
+}
+
+// the bag within the budget making the most games possible, the cheapest one on ties
+//
+// an optimal bag only holds, for each color, a count found in the minimal bag of a game: a
+// smaller count removes a game and a larger one only costs more. so we try every red and green
+// count taken from the minimal bags and complete with the most blue cubes the budget allows
+pub fn cheapest_bag(costs: &Cubes, budget: u64, games: &[Game]) -> Option<Bag> {
+    let minimal_bags = games
+        .iter()
+        .map(|game| minimal_bag(&game.draws))
+        .collect::<Vec<_>>();
+    let candidates = |color: fn(&Cubes) -> u32| {
+        minimal_bags
+            .iter()
+            .map(color)
+            .chain([0])
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>()
+    };
+    let blues = candidates(|cubes| cubes.blue);
+
+    candidates(|cubes| cubes.red)
+        .into_iter()
+        .cartesian_product(candidates(|cubes| cubes.green))
+        .filter_map(|(red, green)| {
+            let used = cost(
+                costs,
+                &Cubes {
+                    blue: 0,
+                    green,
+                    red,
+                },
+            );
+            let left = budget.checked_sub(used)?;
+            let blue = blues
+                .iter()
+                .rev()
+                .find(|blue| u64::from(costs.blue) * u64::from(**blue) <= left)?;
+
+            let cubes = Cubes {
+                blue: *blue,
+                green,
+                red,
+            };
+            let possible = games
+                .iter()
+                .filter(|game| game_possible(&cubes, game))
+                .collect::<Vec<_>>();
+
+            Some(bag_for(costs, &possible))
+        })
+        .max_by(|bag1, bag2| {
+            bag1.games
+                .len()
+                .cmp(&bag2.games.len())
+                .then(bag2.cost.cmp(&bag1.cost))
+        })
+}
+
+#[cfg(test)]
+mod day02 {
+    use itertools::Itertools;
+    use lib::alloc::{count_allocations, CountingAllocator};
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+        str::FromStr,
+    };
+
+    use crate::{
+        bag_for, cheapest_bag, feasibility_report, game_possible, minimal_bag, parse_bag_option,
+        parse_constraints, parse_costs, solve1, solve2, Bag, Constraint, Cubes, Game,
+    };
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    const GAME_1_STR: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+    fn game_1() -> Game {
+        Game {
+            id: 1,
+            draws: vec![
+                Cubes {
+                    blue: 3,
+                    green: 0,
+                    red: 4,
+                },
+                Cubes {
+                    blue: 6,
+                    green: 2,
+                    red: 1,
+                },
+                Cubes {
+                    blue: 0,
+                    green: 2,
+                    red: 0,
+                },
+            ],
+        }
+    }
+
+    const GAME_2_STR: &str = "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue";
+    fn game_2() -> Game {
+        Game {
+            id: 2,
+            draws: vec![
+                Cubes {
+                    blue: 1,
+                    green: 2,
+                    red: 0,
+                },
+                Cubes {
+                    blue: 4,
+                    green: 3,
+                    red: 1,
+                },
+                Cubes {
+                    blue: 1,
+                    green: 1,
+                    red: 0,
+                },
+            ],
+        }
+    }
+
+    const GAME_3_STR: &str =
+        "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
+    fn game_3() -> Game {
+        Game {
+            id: 3,
+            draws: vec![
+                Cubes {
+                    blue: 6,
+                    green: 8,
+                    red: 20,
+                },
+                Cubes {
+                    blue: 5,
+                    green: 13,
+                    red: 4,
+                },
+                Cubes {
+                    blue: 0,
+                    green: 5,
+                    red: 1,
+                },
+            ],
+        }
+    }
+
+    const GAME_4_STR: &str =
+        "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red";
+    fn game_4() -> Game {
+        Game {
+            id: 4,
+            draws: vec![
+                Cubes {
+                    blue: 6,
+                    green: 1,
+                    red: 3,
+                },
+                Cubes {
+                    blue: 0,
+                    green: 3,
+                    red: 6,
+                },
+                Cubes {
+                    blue: 15,
+                    green: 3,
+                    red: 14,
+                },
+            ],
+        }
+    }
+
+    const GAME_5_STR: &str = "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+    fn game_5() -> Game {
+        Game {
+            id: 5,
+            draws: vec![
+                Cubes {
+                    blue: 1,
+                    green: 3,
+                    red: 6,
+                },
+                Cubes {
+                    blue: 2,
+                    green: 2,
+                    red: 1,
+                },
+            ],
+        }
+    }
+
+    fn games() -> Vec<Game> {
+        vec![game_1(), game_2(), game_3(), game_4(), game_5()]
+    }
+
+    #[test]
+    fn parse_single_game() -> Result<(), Box<dyn Error>> {
+        assert_eq!(game_1(), Game::from_str(GAME_1_STR)?);
+        assert_eq!(game_2(), Game::from_str(GAME_2_STR)?);
+        assert_eq!(game_3(), Game::from_str(GAME_3_STR)?);
+        assert_eq!(game_4(), Game::from_str(GAME_4_STR)?);
+        assert_eq!(game_5(), Game::from_str(GAME_5_STR)?);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_multiple_games() -> Result<(), Box<dyn Error>> {
+        let games_str = format!(
+            "{}\n{}\n{}\n{}\n{}",
+            GAME_1_STR, GAME_2_STR, GAME_3_STR, GAME_4_STR, GAME_5_STR
+        );
+
+        assert_eq!(
+            games(),
+            games_str
+                .lines()
+                .map(Game::from_str)
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_allocations() -> Result<(), Box<dyn Error>> {
+        let lines = [GAME_1_STR, GAME_2_STR, GAME_3_STR, GAME_4_STR, GAME_5_STR];
+        let (parsed, allocations) = count_allocations(|| {
+            lines
+                .iter()
+                .map(|line| Game::from_str(line))
+                .collect::<Result<Vec<_>, _>>()
+        });
+
+        // the vectors of draws only, 7 already before the cursors as the str splitting borrowed
+        // the fields as well
+        assert_eq!(parsed?, games());
+        assert_eq!(allocations, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve1() {
+        assert_eq!(solve1(&Constraint::default(), games().into_iter()), 8);
+    }
+
+    #[test]
+    fn test_constraints() -> Result<(), Box<dyn Error>> {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(parse_bag_option(&[])?, Constraint::default());
+        assert_eq!(
+            parse_bag_option(&args(&["--bag", "12 red, 13 green, 14 blue"]))?,
+            Constraint::default()
+        );
+        assert!(parse_bag_option(&args(&["--bag"])).is_err());
+        assert!(parse_bag_option(&args(&["--bag", "12 purple"])).is_err());
+        assert!(parse_bag_option(&args(&["--box"])).is_err());
+
+        // game 3 needs 20 red, game 4 needs 15 blue
+        let bag = parse_bag_option(&args(&["--bag", "20 red, 13 green, 14 blue"]))?;
+        assert_eq!(solve1(&bag, games().into_iter()), 8 + 3);
+        let bag = parse_bag_option(&args(&["--bag", "6 red, 3 green, 6 blue"]))?;
+        assert_eq!(solve1(&bag, games().into_iter()), 1 + 2 + 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_feasibility_report() -> Result<(), Box<dyn Error>> {
+        let constraints =
+            parse_constraints("12 red, 13 green, 14 blue\n\n14 blue, 20 red, 13 green\n")?;
+        assert_eq!(constraints.len(), 2);
+        assert_eq!(
+            parse_constraints("1 red\n1 reed")
+                .err()
+                .map(|e| e.to_string()),
+            Some("line 2: column 3: invalid color 'reed'".to_string())
+        );
+
+        assert_eq!(
+            feasibility_report(&constraints, &games()[2..4]),
+            "\
+            game  minimal bag               bag 1  bag 2\n\
+            ----  ------------------------  -----  -----\n\
+            \x20  3  20 red, 13 green, 6 blue  no     yes\n\
+            \x20  4  14 red, 3 green, 15 blue  no     no\n"
+        );
+
+        // the minimal bags parse back
+        assert_eq!(
+            minimal_bag(&game_3().draws).to_string().parse::<Cubes>()?,
+            minimal_bag(&game_3().draws)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve2() {
+        assert_eq!(solve2(games().into_iter()), 2286);
+    }
+
+    const COSTS: Cubes = Cubes {
+        blue: 3,
+        green: 2,
+        red: 1,
+    };
+
+    #[test]
+    fn test_minimal_bag() {
+        assert_eq!(
+            minimal_bag(&game_1().draws),
+            Cubes {
+                blue: 6,
+                green: 2,
+                red: 4
+            }
+        );
+        assert_eq!(minimal_bag(&[]), Cubes::default());
+    }
+
+    #[test]
+    fn test_parse_costs() -> Result<(), Box<dyn Error>> {
+        assert_eq!(parse_costs("red=1,green=2,blue=3")?, COSTS);
+        assert!(parse_costs("red=1,yellow=2").is_err());
+        assert!(parse_costs("red").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bag_for() {
+        let games = games();
+        let bag = bag_for(&COSTS, &[&games[0], &games[1]]);
+
+        // 4 red, 3 green, 6 blue
+        assert_eq!(bag.cost, 4 + 3 * 2 + 6 * 3);
+        assert_eq!(bag.games, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_cheapest_bag() -> Result<(), Box<dyn Error>> {
+        let games = games();
+
+        // the minimal bag of all the games fits exactly
+        let all = bag_for(&COSTS, &games.iter().collect::<Vec<_>>());
+        assert_eq!(cheapest_bag(&COSTS, all.cost, &games), Some(all));
+
+        // nothing fits in an empty budget
+        assert_eq!(
+            cheapest_bag(&COSTS, 0, &games),
+            Some(Bag {
+                cubes: Cubes::default(),
+                cost: 0,
+                games: vec![],
+            })
+        );
+
+        // games 1, 2 and 5 need 6 red, 3 green and 6 blue for 30, with 29 only two of them fit
+        // and games 2 and 5 are the cheapest pair
+        let bag = cheapest_bag(&COSTS, 30, &games).ok_or("No bag")?;
+        assert_eq!(bag.games, vec![1, 2, 5]);
+        assert_eq!(bag.cost, 30);
+
+        let bag = cheapest_bag(&COSTS, 29, &games).ok_or("No bag")?;
+        assert_eq!(bag.games, vec![2, 5]);
+        assert_eq!(bag.cost, 24);
+        assert!(bag
+            .games
+            .iter()
+            .filter_map(|id| games.iter().find(|game| game.id == *id))
+            .all(|game| game_possible(&bag.cubes, game)));
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve1() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let result = reader.lines().process_results(|itr| {
+            itr.map(|l| Game::from_str(&l))
+                .process_results(|itr| solve1(&Constraint::default(), itr))
+        })??;
+
+        assert_eq!(result, 2439);
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve2() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let result = reader.lines().process_results(|itr| {
+            itr.map(|l| Game::from_str(&l))
+                .process_results(|itr| solve2(itr))
+        })??;
+
+        assert_eq!(result, 63711);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_malformed_game() {
+        for s in [
+            "",
+            "Game",
+            "Game 1",
+            "Game x: 1 red",
+            "Game 1:",
+            "Game 1: red",
+            "Game 1: 1 purple",
+            "Game 1: 1 red,",
+            "Game 1: 1 red;",
+            "Game 99999999999: 1 red",
+        ] {
+            assert!(s.parse::<Game>().is_err(), "{:?}", s);
+        }
+
+        assert_eq!(
+            "Game 1: 1 red, 2 purple"
+                .parse::<Game>()
+                .map_err(|e| e.to_string()),
+            Err("column 18: invalid color 'purple'".to_string())
+        );
+    }
+}
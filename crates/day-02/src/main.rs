@@ -1,333 +1,120 @@
-use itertools::Itertools;
-use lib::{get_args, INVALID_INPUT};
-use std::{
-    error::Error,
-    io::{stdin, BufRead},
-    process::exit,
-    str::FromStr,
+use day_02::{
+    bag_for, cheapest_bag, feasibility_report, parse_bag_option, parse_constraints, parse_costs,
+    solve1, solve2, Game,
 };
-
-const BAG: Cubes = Cubes {
-    red: 12,
-    green: 13,
-    blue: 14,
+use itertools::Itertools;
+use lib::{
+    cli::Options,
+    validate::{check_lines, check_not_empty, check_numbers, Problem},
+    INVALID_INPUT,
 };
+use std::{error::Error, fs::read_to_string, io::BufRead, process::exit, str::FromStr};
 
-fn usage(prog_name: String) {
-    println!("Usage: {} [-1|-2|-h]", prog_name);
+fn usage(options: &Options) {
+    println!("{}", options.usage("[-1 [--bag \"R red, G green, B blue\"]|-2|-3 BAGS|--cheapest-bag costs red=R,green=G,blue=B budget=N [games=ID,..]|--validate|-h]"));
     exit(0)
 }
 
+fn validate(lines: &[String]) -> Vec<Problem> {
+    check_not_empty(lines)
+        .into_iter()
+        .chain(check_lines(lines, 1, Game::from_str))
+        .chain(check_numbers(lines, 0..=u32::MAX.into()))
+        .collect()
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let (prog_name, args) = get_args()?;
+    let options = Options::parse()?;
+    let args = &options.args;
+    let _timer = options.timer();
 
-    match args.get(0) {
+    match options.mode() {
         Some(arg) if arg == "-1" || arg == "-2" => {
-            let games = stdin().lock().lines().map(|line| Game::from_str(&line?));
-            games.process_results(|games| match args.get(0) {
-                Some(arg) if arg == "-1" => {
-                    let result = solve1(&BAG, games);
+            // the bag only applies to part 1
+            let constraint = match (arg.as_str(), args.get(1)) {
+                ("-2", Some(option)) => return Err(format!("unknown option: {}", option).into()),
+                ("-2", None) => None,
+                _ => Some(parse_bag_option(&args[1..])?),
+            };
+            let games = options.input()?.lines().map(|line| Game::from_str(&line?));
+            games.process_results(|games| match constraint {
+                Some(constraint) => {
+                    let result = solve1(&constraint, games);
                     println!("{}", result);
                 }
-                Some(arg) if arg == "-2" => {
+                None => {
                     let result = solve2(games);
                     println!("{}", result);
                 }
-                _ => usage(prog_name),
             })?;
         }
-        _ => usage(prog_name),
-    };
-    Ok(())
-}
-
-#[derive(Debug, Default, PartialEq, Eq)]
-struct Cubes {
-    blue: u32,
-    green: u32,
-    red: u32,
-}
-
-#[derive(Debug, Default, PartialEq, Eq)]
-struct Game {
-    id: u32,
-    draws: Vec<Cubes>,
-}
-
-fn min(draws: &[Cubes]) -> u32 {
-    let min = draws.iter().fold(Cubes::default(), |acc, draw| Cubes {
-        blue: acc.blue.max(draw.blue),
-        green: acc.green.max(draw.green),
-        red: acc.red.max(draw.red),
-    });
-
-    power(&min)
-}
-
-fn solve2(games: impl Iterator<Item = Game>) -> u32 {
-    games
-        .map(|game| -> u32 { min(game.draws.as_slice()) })
-        .sum()
-}
-
-fn power(cube: &Cubes) -> u32 {
-    cube.blue * cube.green * cube.red
-}
-
-fn draw_possible(bag: &Cubes, draw: &Cubes) -> bool {
-    bag.blue >= draw.blue && bag.green >= draw.green && bag.red >= draw.red
-}
-
-fn game_possible(bag: &Cubes, game: &Game) -> bool {
-    game.draws.iter().all(|draw| draw_possible(bag, draw))
-}
-
-fn solve1(bag: &Cubes, games: impl Iterator<Item = Game>) -> u32 {
-    games
-        .filter_map(|game| {
-            if game_possible(bag, &game) {
-                Some(game.id)
-            } else {
-                None
-            }
-        })
-        .sum()
-}
-
-impl FromStr for Game {
-    type Err = Box<dyn Error>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let without_game = s.strip_prefix("Game ").ok_or(INVALID_INPUT)?;
-        let (id_str, draw_str) = without_game.split_once(":").ok_or(INVALID_INPUT)?;
-
-        let id = id_str.parse::<u32>()?;
-        let draws = draw_str
-            .split(";")
-            .map(|draw_str| Cubes::from_str(draw_str))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(Game { id, draws })
-    }
-}
-
-impl FromStr for Cubes {
-    type Err = Box<dyn Error>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut draw = Cubes::default();
-        for count_color_str in s.split(",") {
-            let (count_str, color_str) = count_color_str
-                .trim()
-                .split_once(" ")
-                .ok_or(INVALID_INPUT)?;
+        Some(arg) if arg == "-3" => {
+            let path = args.get(1).ok_or("missing bags")?;
+            let constraints =
+                parse_constraints(&read_to_string(path).map_err(|e| format!("{}: {}", path, e))?)
+                    .map_err(|e| format!("{}: {}", path, e))?;
+
+            let games = options
+                .input()?
+                .lines()
+                .map(|line| Game::from_str(&line?))
+                .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
 
-            let count = count_str.parse::<u32>()?;
-            match color_str {
-                "blue" => draw.blue = count,
-                "green" => draw.green = count,
-                "red" => draw.red = count,
-                _ => return Err(INVALID_INPUT.into()),
+            print!("{}", feasibility_report(&constraints, &games));
+        }
+        Some(arg) if arg == "--cheapest-bag" => {
+            let costs = match (args.get(1), args.get(2)) {
+                (Some(costs), Some(values)) if costs == "costs" => parse_costs(values)?,
+                _ => return Err("Missing costs".into()),
+            };
+            let budget = args
+                .get(3)
+                .and_then(|arg| arg.strip_prefix("budget="))
+                .ok_or("Missing budget")?
+                .parse::<u64>()?;
+            let ids = args
+                .get(4)
+                .map(|arg| -> Result<Vec<u32>, Box<dyn Error>> {
+                    arg.strip_prefix("games=")
+                        .ok_or(INVALID_INPUT)?
+                        .split(',')
+                        .map(|id| Ok(id.parse::<u32>()?))
+                        .collect()
+                })
+                .transpose()?;
+
+            let games = options
+                .input()?
+                .lines()
+                .map(|line| Game::from_str(&line?))
+                .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+            let bag = match ids {
+                Some(ids) => {
+                    let chosen = games
+                        .iter()
+                        .filter(|game| ids.contains(&game.id))
+                        .collect::<Vec<_>>();
+                    if chosen.len() != ids.len() {
+                        return Err("Unknown game".into());
+                    }
+                    Some(bag_for(&costs, &chosen)).filter(|bag| bag.cost <= budget)
+                }
+                None => cheapest_bag(&costs, budget, &games),
             }
+            .ok_or("No bag within the budget")?;
+
+            println!(
+                "red={} green={} blue={} cost={} games={}",
+                bag.cubes.red,
+                bag.cubes.green,
+                bag.cubes.blue,
+                bag.cost,
+                bag.games.iter().join(",")
+            );
         }
-
-        Ok(draw)
-    }
-}
-
-#[cfg(test)]
-mod day02 {
-    use itertools::Itertools;
-    use std::{
-        error::Error,
-        fs::File,
-        io::{BufRead, BufReader},
-        str::FromStr,
+        Some(arg) if arg == "--validate" => lib::validate::run(options.input()?, validate)?,
+        _ => usage(&options),
     };
-
-    use crate::{solve1, solve2, Cubes, Game, BAG};
-
-    const GAME_1_STR: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-    fn game_1() -> Game {
-        Game {
-            id: 1,
-            draws: vec![
-                Cubes {
-                    blue: 3,
-                    green: 0,
-                    red: 4,
-                },
-                Cubes {
-                    blue: 6,
-                    green: 2,
-                    red: 1,
-                },
-                Cubes {
-                    blue: 0,
-                    green: 2,
-                    red: 0,
-                },
-            ],
-        }
-    }
-
-    const GAME_2_STR: &str = "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue";
-    fn game_2() -> Game {
-        Game {
-            id: 2,
-            draws: vec![
-                Cubes {
-                    blue: 1,
-                    green: 2,
-                    red: 0,
-                },
-                Cubes {
-                    blue: 4,
-                    green: 3,
-                    red: 1,
-                },
-                Cubes {
-                    blue: 1,
-                    green: 1,
-                    red: 0,
-                },
-            ],
-        }
-    }
-
-    const GAME_3_STR: &str =
-        "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
-    fn game_3() -> Game {
-        Game {
-            id: 3,
-            draws: vec![
-                Cubes {
-                    blue: 6,
-                    green: 8,
-                    red: 20,
-                },
-                Cubes {
-                    blue: 5,
-                    green: 13,
-                    red: 4,
-                },
-                Cubes {
-                    blue: 0,
-                    green: 5,
-                    red: 1,
-                },
-            ],
-        }
-    }
-
-    const GAME_4_STR: &str =
-        "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red";
-    fn game_4() -> Game {
-        Game {
-            id: 4,
-            draws: vec![
-                Cubes {
-                    blue: 6,
-                    green: 1,
-                    red: 3,
-                },
-                Cubes {
-                    blue: 0,
-                    green: 3,
-                    red: 6,
-                },
-                Cubes {
-                    blue: 15,
-                    green: 3,
-                    red: 14,
-                },
-            ],
-        }
-    }
-
-    const GAME_5_STR: &str = "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-    fn game_5() -> Game {
-        Game {
-            id: 5,
-            draws: vec![
-                Cubes {
-                    blue: 1,
-                    green: 3,
-                    red: 6,
-                },
-                Cubes {
-                    blue: 2,
-                    green: 2,
-                    red: 1,
-                },
-            ],
-        }
-    }
-
-    fn games() -> Vec<Game> {
-        vec![game_1(), game_2(), game_3(), game_4(), game_5()]
-    }
-
-    #[test]
-    fn parse_single_game() -> Result<(), Box<dyn Error>> {
-        assert_eq!(game_1(), Game::from_str(GAME_1_STR)?);
-        assert_eq!(game_2(), Game::from_str(GAME_2_STR)?);
-        assert_eq!(game_3(), Game::from_str(GAME_3_STR)?);
-        assert_eq!(game_4(), Game::from_str(GAME_4_STR)?);
-        assert_eq!(game_5(), Game::from_str(GAME_5_STR)?);
-        Ok(())
-    }
-
-    #[test]
-    fn parse_multiple_games() -> Result<(), Box<dyn Error>> {
-        let games_str = format!(
-            "{}\n{}\n{}\n{}\n{}",
-            GAME_1_STR, GAME_2_STR, GAME_3_STR, GAME_4_STR, GAME_5_STR
-        );
-
-        assert_eq!(
-            games(),
-            games_str
-                .lines()
-                .map(Game::from_str)
-                .collect::<Result<Vec<_>, _>>()?,
-        );
-        Ok(())
-    }
-
-    #[test]
-    fn example_solve1() {
-        assert_eq!(solve1(&BAG, games().into_iter()), 8);
-    }
-
-    #[test]
-    fn example_solve2() {
-        assert_eq!(solve2(games().into_iter()), 2286);
-    }
-
-    #[test]
-    fn input_solve1() -> Result<(), Box<dyn Error>> {
-        let file = File::open("input")?;
-        let reader = BufReader::new(file);
-        let result = reader.lines().process_results(|itr| {
-            itr.map(|l| Game::from_str(&l))
-                .process_results(|itr| solve1(&BAG, itr))
-        })??;
-
-        assert_eq!(result, 2439);
-        Ok(())
-    }
-
-    #[test]
-    fn input_solve2() -> Result<(), Box<dyn Error>> {
-        let file = File::open("input")?;
-        let reader = BufReader::new(file);
-        let result = reader.lines().process_results(|itr| {
-            itr.map(|l| Game::from_str(&l))
-                .process_results(|itr| solve2(itr))
-        })??;
-
-        assert_eq!(result, 63711);
-        Ok(())
-    }
+    Ok(())
 }
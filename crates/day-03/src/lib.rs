@@ -0,0 +1,624 @@
+use itertools::Itertools;
+use std::error::Error;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Element {
+    Symbol { symbol: char },
+    Number { number: i32 },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LocatedElement {
+    element: Element,
+    location: i32,
+}
+
+fn state_to_located_element(state: &(i32, String)) -> Option<LocatedElement> {
+    let (location, number) = state;
+    Some(LocatedElement {
+        element: Element::Number {
+            number: number.parse::<i32>().ok()?,
+        },
+        location: *location,
+    })
+}
+
+pub fn char_to_located_element<I>(itr: I) -> impl Iterator<Item = LocatedElement>
+where
+    I: Iterator<Item = char>,
+{
+    // add a dot at the end of the iterator to loop on a two element window
+    itr.chain(['.'])
+        .tuple_windows::<(_, _)>()
+        .enumerate()
+        .scan(
+            None,
+            |prev_state: &mut Option<(i32, String)>, (location, (c, next))| {
+                // skip dots
+                if c == '.' {
+                    assert!(prev_state.is_none());
+
+                    Some(None)
+                // yield a symbol
+                } else if !c.is_numeric() {
+                    assert!(prev_state.is_none());
+
+                    i32::try_from(location)
+                        .ok()
+                        .map(|location| LocatedElement {
+                            element: Element::Symbol { symbol: c },
+                            location,
+                        })
+                        .map(Some)
+                // c is a number
+                } else {
+                    // update the current state
+                    match prev_state {
+                        None => {
+                            *prev_state = i32::try_from(location)
+                                .ok()
+                                .map(|location| (location, c.to_string()));
+                        }
+                        Some((_, s)) => {
+                            s.push(c);
+                        }
+                    };
+
+                    if next.is_numeric() {
+                        Some(None)
+                    } else {
+                        let item = prev_state
+                            .as_ref()
+                            .map(|state| state_to_located_element(&state));
+                        *prev_state = None;
+
+                        item
+                    }
+                }
+            },
+        )
+        .filter_map(|x| x)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Number {
+    number: i32,
+    row: i32,
+    // the first and last columns of the digits
+    columns: (i32, i32),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Symbol {
+    symbol: char,
+    row: i32,
+    column: i32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+}
+
+// a symbol touches a number if it is in one of the cells around its digits, diagonals included
+fn adjacent(number: &Number, symbol: &Symbol) -> bool {
+    (symbol.row - number.row).abs() <= 1
+        && symbol.column >= number.columns.0 - 1
+        && symbol.column <= number.columns.1 + 1
+}
+
+impl Schematic {
+    pub fn new(itr: impl Iterator<Item = Vec<LocatedElement>>) -> Result<Self, Box<dyn Error>> {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+
+        for (row, line) in itr.enumerate() {
+            let row = i32::try_from(row)?;
+            for located_element in line {
+                let column = located_element.location;
+                match located_element.element {
+                    Element::Number { number } => {
+                        let nb_digits = i32::try_from(number.to_string().len())?;
+                        numbers.push(Number {
+                            number,
+                            row,
+                            columns: (column, column + nb_digits - 1),
+                        });
+                    }
+                    Element::Symbol { symbol } => symbols.push(Symbol {
+                        symbol,
+                        row,
+                        column,
+                    }),
+                }
+            }
+        }
+
+        Ok(Self { numbers, symbols })
+    }
+
+    fn numbers_adjacent_to<'a>(&'a self, symbol: &'a Symbol) -> impl Iterator<Item = &'a Number> {
+        self.numbers
+            .iter()
+            .filter(move |number| adjacent(number, symbol))
+    }
+
+    fn symbols_adjacent_to<'a>(&'a self, number: &'a Number) -> impl Iterator<Item = &'a Symbol> {
+        self.symbols
+            .iter()
+            .filter(move |symbol| adjacent(number, symbol))
+    }
+}
+
+pub fn solve1(schematic: &Schematic) -> i32 {
+    schematic
+        .numbers
+        .iter()
+        .filter(|number| schematic.symbols_adjacent_to(number).next().is_some())
+        .map(|number| number.number)
+        .sum()
+}
+
+// the gears are the symbols with exactly count adjacent numbers, or at least count of them
+#[derive(Debug, PartialEq, Eq)]
+pub struct GearRule {
+    pub symbol: char,
+    pub count: usize,
+    pub at_least: bool,
+}
+
+impl Default for GearRule {
+    fn default() -> Self {
+        GearRule {
+            symbol: '*',
+            count: 2,
+            at_least: false,
+        }
+    }
+}
+
+impl GearRule {
+    fn matches(&self, adjacent: usize) -> bool {
+        if self.at_least {
+            adjacent >= self.count
+        } else {
+            adjacent == self.count
+        }
+    }
+}
+
+pub fn parse_gear_rule(args: &[String]) -> Result<GearRule, Box<dyn Error>> {
+    let mut rule = GearRule::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--gear-symbol" => {
+                let symbol = args.next().ok_or("missing gear symbol")?;
+                rule.symbol = match symbol.chars().collect::<Vec<_>>()[..] {
+                    [c] if c != '.' && !c.is_ascii_digit() => c,
+                    _ => return Err(format!("invalid gear symbol: {}", symbol).into()),
+                };
+            }
+            "--gear-count" => {
+                rule.count = args.next().ok_or("missing gear count")?.parse()?;
+                if rule.count == 0 {
+                    return Err("the gear count must be at least 1".into());
+                }
+            }
+            _ => return Err(format!("unknown option: {}", arg).into()),
+        }
+    }
+
+    Ok(rule)
+}
+
+// the sum of the products of the numbers adjacent to the gears, in i64 as the products of more
+// than two numbers quickly overflow
+pub fn solve2(schematic: &Schematic, rule: &GearRule) -> Result<i64, Box<dyn Error>> {
+    schematic
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.symbol == rule.symbol)
+        .map(|symbol| schematic.numbers_adjacent_to(symbol).collect::<Vec<_>>())
+        .filter(|numbers| rule.matches(numbers.len()))
+        .try_fold(0i64, |sum, numbers| {
+            numbers
+                .iter()
+                .try_fold(1i64, |ratio, number| {
+                    ratio.checked_mul(number.number.into())
+                })
+                .and_then(|ratio| sum.checked_add(ratio))
+                .ok_or("Gear ratio overflow".into())
+        })
+}
+
+#[cfg(test)]
+mod day03 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+
+    use crate::{
+        char_to_located_element, parse_gear_rule, solve1, solve2, Element, GearRule,
+        LocatedElement, Number, Schematic, Symbol,
+    };
+
+    const LINE1: &str = "467..114..";
+    fn line1() -> Vec<LocatedElement> {
+        vec![
+            LocatedElement {
+                element: Element::Number { number: 467 },
+                location: 0,
+            },
+            LocatedElement {
+                element: Element::Number { number: 114 },
+                location: 5,
+            },
+        ]
+    }
+
+    const LINE2: &str = "...*......";
+    fn line2() -> Vec<LocatedElement> {
+        vec![LocatedElement {
+            element: Element::Symbol { symbol: '*' },
+            location: 3,
+        }]
+    }
+
+    const LINE3: &str = "..35..633.";
+    fn line3() -> Vec<LocatedElement> {
+        vec![
+            LocatedElement {
+                element: Element::Number { number: 35 },
+                location: 2,
+            },
+            LocatedElement {
+                element: Element::Number { number: 633 },
+                location: 6,
+            },
+        ]
+    }
+
+    const LINE4: &str = "......#...";
+    fn line4() -> Vec<LocatedElement> {
+        vec![LocatedElement {
+            element: Element::Symbol { symbol: '#' },
+            location: 6,
+        }]
+    }
+
+    const LINE5: &str = "617*......";
+    fn line5() -> Vec<LocatedElement> {
+        vec![
+            LocatedElement {
+                element: Element::Number { number: 617 },
+                location: 0,
+            },
+            LocatedElement {
+                element: Element::Symbol { symbol: '*' },
+                location: 3,
+            },
+        ]
+    }
+
+    const LINE6: &str = ".....+.58.";
+    fn line6() -> Vec<LocatedElement> {
+        vec![
+            LocatedElement {
+                element: Element::Symbol { symbol: '+' },
+                location: 5,
+            },
+            LocatedElement {
+                element: Element::Number { number: 58 },
+                location: 7,
+            },
+        ]
+    }
+
+    const LINE7: &str = "..592.....";
+    fn line7() -> Vec<LocatedElement> {
+        vec![LocatedElement {
+            element: Element::Number { number: 592 },
+            location: 2,
+        }]
+    }
+
+    const LINE8: &str = "......755.";
+    fn line8() -> Vec<LocatedElement> {
+        vec![LocatedElement {
+            element: Element::Number { number: 755 },
+            location: 6,
+        }]
+    }
+
+    const LINE9: &str = "...$.*....";
+    fn line9() -> Vec<LocatedElement> {
+        vec![
+            LocatedElement {
+                element: Element::Symbol { symbol: '$' },
+                location: 3,
+            },
+            LocatedElement {
+                element: Element::Symbol { symbol: '*' },
+                location: 5,
+            },
+        ]
+    }
+
+    const LINE10: &str = ".664.598..";
+    fn line10() -> Vec<LocatedElement> {
+        vec![
+            LocatedElement {
+                element: Element::Number { number: 664 },
+                location: 1,
+            },
+            LocatedElement {
+                element: Element::Number { number: 598 },
+                location: 5,
+            },
+        ]
+    }
+
+    fn engine() -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            LINE1, LINE2, LINE3, LINE4, LINE5, LINE6, LINE7, LINE8, LINE9, LINE10
+        )
+    }
+
+    #[test]
+    fn parse_line() {
+        assert_eq!(
+            char_to_located_element(LINE1.chars()).collect::<Vec<LocatedElement>>(),
+            line1()
+        );
+        assert_eq!(
+            char_to_located_element(LINE2.chars()).collect::<Vec<LocatedElement>>(),
+            line2()
+        );
+        assert_eq!(
+            char_to_located_element(LINE3.chars()).collect::<Vec<LocatedElement>>(),
+            line3()
+        );
+        assert_eq!(
+            char_to_located_element(LINE4.chars()).collect::<Vec<LocatedElement>>(),
+            line4()
+        );
+        assert_eq!(
+            char_to_located_element(LINE5.chars()).collect::<Vec<LocatedElement>>(),
+            line5()
+        );
+        assert_eq!(
+            char_to_located_element(LINE6.chars()).collect::<Vec<LocatedElement>>(),
+            line6()
+        );
+        assert_eq!(
+            char_to_located_element(LINE7.chars()).collect::<Vec<LocatedElement>>(),
+            line7()
+        );
+        assert_eq!(
+            char_to_located_element(LINE8.chars()).collect::<Vec<LocatedElement>>(),
+            line8()
+        );
+        assert_eq!(
+            char_to_located_element(LINE9.chars()).collect::<Vec<LocatedElement>>(),
+            line9()
+        );
+        assert_eq!(
+            char_to_located_element(LINE10.chars()).collect::<Vec<LocatedElement>>(),
+            line10()
+        );
+    }
+
+    fn schematic(s: &str) -> Result<Schematic, Box<dyn Error>> {
+        Schematic::new(
+            s.lines()
+                .map(|line| char_to_located_element(line.chars()).collect()),
+        )
+    }
+
+    #[test]
+    fn parse_schematic() -> Result<(), Box<dyn Error>> {
+        let schematic = schematic(engine().as_str())?;
+
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(schematic.symbols.len(), 6);
+        assert_eq!(
+            schematic.numbers[0],
+            Number {
+                number: 467,
+                row: 0,
+                columns: (0, 2)
+            }
+        );
+        assert_eq!(
+            schematic.symbols[0],
+            Symbol {
+                symbol: '*',
+                row: 1,
+                column: 3
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn adjacency_queries() -> Result<(), Box<dyn Error>> {
+        let schematic = schematic(engine().as_str())?;
+
+        // the first star touches 467 above and 35 below
+        let star = &schematic.symbols[0];
+        assert_eq!(
+            schematic
+                .numbers_adjacent_to(star)
+                .map(|number| number.number)
+                .collect::<Vec<_>>(),
+            vec![467, 35]
+        );
+
+        // 114 and 58 are not part numbers
+        schematic
+            .numbers
+            .iter()
+            .filter(|number| number.number == 114 || number.number == 58)
+            .for_each(|number| assert_eq!(schematic.symbols_adjacent_to(number).count(), 0));
+
+        // 617 touches the star on its right
+        let number = &schematic.numbers[4];
+        assert_eq!(number.number, 617);
+        assert_eq!(
+            schematic
+                .symbols_adjacent_to(number)
+                .map(|symbol| symbol.symbol)
+                .collect::<Vec<_>>(),
+            vec!['*']
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn diagonal_adjacency() -> Result<(), Box<dyn Error>> {
+        // each number only touches a symbol by one of its corners on the line before or after
+        let diagonals = schematic(
+            "\
+            #.....\n\
+            .12...\n\
+            ...34.\n\
+            .....$\n\
+            ..7...\n\
+            ......\n\
+            ...*..\n\
+            ....56",
+        )?;
+
+        assert_eq!(solve1(&diagonals), 12 + 34 + 56);
+        assert_eq!(solve2(&diagonals, &GearRule::default())?, 0);
+
+        // a gear whose numbers are on diagonals above and below
+        let gear = schematic(
+            "\
+            11....\n\
+            ..*...\n\
+            ...22.",
+        )?;
+
+        assert_eq!(solve1(&gear), 11 + 22);
+        assert_eq!(solve2(&gear, &GearRule::default())?, 11 * 22);
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve1() -> Result<(), Box<dyn Error>> {
+        let result = solve1(&schematic(engine().as_str())?);
+
+        assert_eq!(result, 4361);
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve2() -> Result<(), Box<dyn Error>> {
+        let result = solve2(&schematic(engine().as_str())?, &GearRule::default())?;
+
+        assert_eq!(result, 467835);
+        Ok(())
+    }
+
+    #[test]
+    fn example_gear_rules() -> Result<(), Box<dyn Error>> {
+        let engine = schematic(engine().as_str())?;
+        let rule = |symbol, count, at_least| GearRule {
+            symbol,
+            count,
+            at_least,
+        };
+
+        // the * next to 617 alone joins the gears
+        assert_eq!(solve2(&engine, &rule('*', 1, true))?, 467835 + 617);
+        assert_eq!(solve2(&engine, &rule('*', 1, false))?, 617);
+        assert_eq!(solve2(&engine, &rule('#', 1, false))?, 633);
+        assert_eq!(solve2(&engine, &rule('*', 3, true))?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn n_way_gears() -> Result<(), Box<dyn Error>> {
+        let gears = schematic(
+            "\
+            1.2..4\n\
+            .*...*\n\
+            3....5",
+        )?;
+        let rule = |count, at_least| GearRule {
+            count,
+            at_least,
+            ..GearRule::default()
+        };
+
+        assert_eq!(solve2(&gears, &rule(2, false))?, 4 * 5);
+        assert_eq!(solve2(&gears, &rule(3, false))?, 2 * 3);
+        assert_eq!(solve2(&gears, &rule(2, true))?, 4 * 5 + 2 * 3);
+
+        let overflow = schematic("999999.999999\n......*......\n999999.999999")?;
+        assert!(solve2(&overflow, &rule(4, false)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn gear_rule_options() -> Result<(), Box<dyn Error>> {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(parse_gear_rule(&[])?, GearRule::default());
+        assert_eq!(
+            parse_gear_rule(&args(&["--gear-symbol", "#", "--gear-count", "3"]))?,
+            GearRule {
+                symbol: '#',
+                count: 3,
+                at_least: false
+            }
+        );
+        for invalid in [
+            &["--gear-symbol"][..],
+            &["--gear-symbol", "##"],
+            &["--gear-symbol", "."],
+            &["--gear-symbol", "1"],
+            &["--gear-count", "0"],
+            &["--gear-count", "x"],
+            &["--gears"],
+        ] {
+            assert!(parse_gear_rule(&args(invalid)).is_err(), "{:?}", invalid);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve1() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let schematic = reader.lines().process_results(|itr| {
+            Schematic::new(itr.map(|line| char_to_located_element(line.chars()).collect()))
+        })??;
+        let result = solve1(&schematic);
+
+        assert_eq!(result, 533784);
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve2() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let schematic = reader.lines().process_results(|itr| {
+            Schematic::new(itr.map(|line| char_to_located_element(line.chars()).collect()))
+        })??;
+        let result = solve2(&schematic, &GearRule::default())?;
+
+        assert_eq!(result, 78826761);
+        Ok(())
+    }
+}
@@ -0,0 +1,463 @@
+use itertools::Itertools;
+use lib::parse::{Cursor, ParseError};
+use std::{collections::HashSet, error::Error, iter::zip, str::FromStr};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    winning: HashSet<u32>,
+    have: HashSet<u32>,
+}
+
+impl FromStr for Card {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        cursor.tag("Card")?;
+        let id = cursor.until(":")?.trim_start().value::<u32>()?;
+
+        let (winning, have) = cursor.split_once("|")?;
+        Ok(Self {
+            id,
+            winning: numbers(winning)?,
+            have: numbers(have)?,
+        })
+    }
+}
+
+// the numbers are counted first so that the set is allocated once
+fn numbers(cursor: Cursor) -> Result<HashSet<u32>, ParseError> {
+    let mut numbers = HashSet::with_capacity(cursor.words().count());
+    for word in cursor.words() {
+        numbers.insert(word.value::<u32>()?);
+    }
+    Ok(numbers)
+}
+
+fn matching_numbers(card: &Card) -> usize {
+    card.winning.intersection(&card.have).count()
+}
+
+// the points of a card with its number of matching numbers
+fn points(matching: usize) -> Result<u64, Box<dyn Error>> {
+    match u32::try_from(matching)? {
+        0 => Ok(0),
+        matching => Ok(2u64.pow(matching - 1)),
+    }
+}
+
+pub fn solve1(cards: impl Iterator<Item = Card>) -> Result<u64, Box<dyn Error>> {
+    cards.map(|card| points(matching_numbers(&card))).sum()
+}
+
+// the number of instances of each card, the original and its copies. Each card is processed once:
+// its copies are all won by the cards before it, so they are known when we reach it and are
+// forwarded to the next cards
+fn instances(matches: &[usize]) -> Result<Vec<u64>, Box<dyn Error>> {
+    let mut counts = vec![1u64; matches.len()];
+
+    for (i, matching) in matches.iter().enumerate() {
+        let copies = counts[i];
+        let end = (i + 1 + matching).min(counts.len());
+        counts[i + 1..end].iter_mut().try_for_each(|count| {
+            *count = count.checked_add(copies).ok_or("Too many cards")?;
+            Ok::<_, Box<dyn Error>>(())
+        })?;
+    }
+
+    Ok(counts)
+}
+
+pub fn solve2(cards: impl Iterator<Item = Card>) -> Result<u64, Box<dyn Error>> {
+    let matches = cards
+        .map(|card| matching_numbers(&card))
+        .collect::<Vec<_>>();
+
+    instances(&matches)?
+        .iter()
+        .try_fold(0u64, |acc, count| acc.checked_add(*count))
+        .ok_or("Too many cards".into())
+}
+
+// what a card is worth in both parts: its winning numbers in increasing order with their points,
+// and the number of its instances with the cards each of them wins a copy of
+#[derive(Debug, PartialEq, Eq)]
+pub struct CardReport {
+    pub id: u32,
+    pub winners: Vec<u32>,
+    pub points: u64,
+    pub instances: u64,
+    pub copies_of: Vec<u32>,
+}
+
+pub fn explain(cards: &[Card]) -> Result<Vec<CardReport>, Box<dyn Error>> {
+    let matches = cards.iter().map(matching_numbers).collect::<Vec<_>>();
+    let instances = instances(&matches)?;
+
+    cards
+        .iter()
+        .enumerate()
+        .map(|(i, card)| {
+            let end = (i + 1 + matches[i]).min(cards.len());
+            Ok(CardReport {
+                id: card.id,
+                winners: card
+                    .winning
+                    .intersection(&card.have)
+                    .copied()
+                    .sorted()
+                    .collect(),
+                points: points(matches[i])?,
+                instances: instances[i],
+                copies_of: cards[i + 1..end].iter().map(|card| card.id).collect(),
+            })
+        })
+        .collect()
+}
+
+// the propagation matrix of the copies: the row of a card holds, for each original card, how
+// many of its copies come from the win chain started by that original. The original of a card
+// starts its own chain and a copy won by another one belongs to the chain of the winner
+pub fn provenance(cards: &[Card]) -> Result<Vec<Vec<u64>>, Box<dyn Error>> {
+    let mut matrix = (0..cards.len())
+        .map(|i| {
+            let mut row = vec![0u64; cards.len()];
+            row[i] = 1;
+            row
+        })
+        .collect::<Vec<_>>();
+
+    for (i, card) in cards.iter().enumerate() {
+        let end = (i + 1 + matching_numbers(card)).min(cards.len());
+        let (before, after) = matrix.split_at_mut(i + 1);
+        let winner = &before[i];
+
+        after[..end - i - 1].iter_mut().try_for_each(|row| {
+            zip(row.iter_mut(), winner).try_for_each(|(count, copies)| {
+                *count = count.checked_add(*copies).ok_or("Too many cards")?;
+                Ok::<_, Box<dyn Error>>(())
+            })
+        })?;
+    }
+
+    Ok(matrix)
+}
+
+// the number of copies coming from the win chain of each original card
+pub fn contributions(matrix: &[Vec<u64>]) -> Result<Vec<u64>, Box<dyn Error>> {
+    (0..matrix.len())
+        .map(|original| {
+            matrix
+                .iter()
+                .try_fold(0u64, |acc, row| acc.checked_add(row[original]))
+                .ok_or("Too many cards".into())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod day04 {
+    use itertools::Itertools;
+    use std::{
+        collections::{HashMap, HashSet, VecDeque},
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+        str::FromStr,
+    };
+
+    use crate::{contributions, explain, provenance, solve1, solve2, Card, CardReport};
+    use lib::alloc::{count_allocations, CountingAllocator};
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    // the original solver queuing every copy of the cards, exponential in the worst case
+    fn solve2_queue(cards: impl Iterator<Item = Card>) -> Result<u32, Box<dyn Error>> {
+        let cards = cards.collect::<Vec<_>>();
+
+        let mut count = 0;
+        let mut queue: VecDeque<_> = (0..u32::try_from(cards.len())?).collect();
+        let mut cache: HashMap<u32, u32> = HashMap::new();
+
+        while let Some(card_id) = queue.pop_front() {
+            let card = cards
+                .get(usize::try_from(card_id)?)
+                .ok_or(format!("Unable to find card {}", card_id))?;
+            count += 1;
+
+            let winning_in_have = if let Some(&cached) = cache.get(&card_id) {
+                cached
+            } else {
+                let winning_in_have_ =
+                    u32::try_from(card.winning.intersection(&card.have).count())?;
+                cache.insert(card_id, winning_in_have_);
+                winning_in_have_
+            };
+
+            (card_id + 1..card_id + winning_in_have + 1).for_each(|id| {
+                queue.push_back(id);
+            });
+        }
+
+        Ok(count)
+    }
+
+    // a card whose numbers all win
+    fn winning_card(id: u32) -> Card {
+        Card {
+            id,
+            winning: HashSet::from([1, 2, 3, 4, 5]),
+            have: HashSet::from([1, 2, 3, 4, 5]),
+        }
+    }
+
+    const CARD1: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
+    fn card1() -> Card {
+        Card {
+            id: 1,
+            winning: HashSet::from([41, 48, 83, 86, 17]),
+            have: HashSet::from([83, 86, 6, 31, 17, 9, 48, 53]),
+        }
+    }
+
+    const CARD2: &str = "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19";
+    fn card2() -> Card {
+        Card {
+            id: 2,
+            winning: HashSet::from([13, 32, 20, 16, 61]),
+            have: HashSet::from([61, 30, 68, 82, 17, 32, 24, 19]),
+        }
+    }
+
+    const CARD3: &str = "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1";
+    fn card3() -> Card {
+        Card {
+            id: 3,
+            winning: HashSet::from([1, 21, 53, 59, 44]),
+            have: HashSet::from([69, 82, 63, 72, 16, 21, 14, 1]),
+        }
+    }
+
+    const CARD4: &str = "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83";
+    fn card4() -> Card {
+        Card {
+            id: 4,
+            winning: HashSet::from([41, 92, 73, 84, 69]),
+            have: HashSet::from([59, 84, 76, 51, 58, 5, 54, 83]),
+        }
+    }
+
+    const CARD5: &str = "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36";
+    fn card5() -> Card {
+        Card {
+            id: 5,
+            winning: HashSet::from([87, 83, 26, 28, 32]),
+            have: HashSet::from([88, 30, 70, 12, 93, 22, 82, 36]),
+        }
+    }
+
+    const CARD6: &str = "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+    fn card6() -> Card {
+        Card {
+            id: 6,
+            winning: HashSet::from([31, 18, 13, 56, 72]),
+            have: HashSet::from([74, 77, 10, 23, 35, 67, 36, 11]),
+        }
+    }
+
+    fn cards() -> Vec<Card> {
+        vec![card1(), card2(), card3(), card4(), card5(), card6()]
+    }
+
+    #[test]
+    fn parse_single_card() -> Result<(), Box<dyn Error>> {
+        assert_eq!(card1(), Card::from_str(CARD1)?,);
+        assert_eq!(card2(), Card::from_str(CARD2)?,);
+        assert_eq!(card3(), Card::from_str(CARD3)?,);
+        assert_eq!(card4(), Card::from_str(CARD4)?,);
+        assert_eq!(card5(), Card::from_str(CARD5)?,);
+        assert_eq!(card6(), Card::from_str(CARD6)?,);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_multiple_cards() -> Result<(), Box<dyn Error>> {
+        let cards_str = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            CARD1, CARD2, CARD3, CARD4, CARD5, CARD6
+        );
+        let result = cards_str
+            .lines()
+            .map(Card::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(cards(), result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_allocations() -> Result<(), Box<dyn Error>> {
+        let lines = [CARD1, CARD2, CARD3, CARD4, CARD5, CARD6];
+        let (parsed, allocations) = count_allocations(|| {
+            lines
+                .iter()
+                .map(|line| Card::from_str(line))
+                .collect::<Result<Vec<_>, _>>()
+        });
+
+        // the two sets of each card and the vector, 32 when the sets grew as the numbers were
+        // inserted
+        assert_eq!(
+            parsed?,
+            vec![card1(), card2(), card3(), card4(), card5(), card6()]
+        );
+        assert_eq!(allocations, 14);
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve1() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve1(cards().into_iter())?, 13);
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve2() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve2(cards().into_iter())?, 30);
+        Ok(())
+    }
+
+    #[test]
+    fn solve2_matches_queue() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            solve2(cards().into_iter())?,
+            u64::from(solve2_queue(cards().into_iter())?)
+        );
+
+        // every card wins the next five ones, the last five ones win nothing
+        let winning = || {
+            (1..=12).map(|id| {
+                let mut card = winning_card(id);
+                if id > 7 {
+                    card.have.clear();
+                }
+                card
+            })
+        };
+        assert_eq!(solve2(winning())?, u64::from(solve2_queue(winning())?));
+        Ok(())
+    }
+
+    #[test]
+    fn solve2_many_copies() -> Result<(), Box<dyn Error>> {
+        // far too many copies to be queued one by one
+        let result = solve2((1..=60).map(winning_card))?;
+
+        assert!(result > u64::from(u32::MAX));
+        assert!(solve2((1..=200).map(winning_card)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn provenance_example() -> Result<(), Box<dyn Error>> {
+        let matrix = provenance(&cards())?;
+
+        // card 4 has its original, one copy in the chain of card 3, two in the chain of card 2
+        // and four in the chain of card 1
+        assert_eq!(matrix[3], vec![4, 2, 1, 1, 0, 0]);
+        assert_eq!(
+            matrix
+                .iter()
+                .map(|row| row.iter().sum::<u64>())
+                .collect::<Vec<_>>(),
+            vec![1, 2, 4, 8, 14, 1]
+        );
+
+        let contributions = contributions(&matrix)?;
+        assert_eq!(contributions, vec![15, 7, 4, 2, 1, 1]);
+        assert_eq!(
+            contributions.iter().sum::<u64>(),
+            solve2(cards().into_iter())?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn explain_example() -> Result<(), Box<dyn Error>> {
+        let reports = explain(&cards())?;
+
+        assert_eq!(
+            reports[2],
+            CardReport {
+                id: 3,
+                winners: vec![1, 21],
+                points: 2,
+                instances: 4,
+                copies_of: vec![4, 5],
+            }
+        );
+        assert_eq!(
+            reports[5],
+            CardReport {
+                id: 6,
+                winners: vec![],
+                points: 0,
+                instances: 1,
+                copies_of: vec![],
+            }
+        );
+        assert_eq!(reports[0].winners, vec![17, 48, 83, 86]);
+        assert_eq!(
+            reports.iter().map(|report| report.points).sum::<u64>(),
+            solve1(cards().into_iter())?
+        );
+        assert_eq!(
+            reports.iter().map(|report| report.instances).sum::<u64>(),
+            solve2(cards().into_iter())?
+        );
+        Ok(())
+    }
+
+    // the copies won by the last cards are cut at the end of the table
+    #[test]
+    fn explain_last_cards() -> Result<(), Box<dyn Error>> {
+        let reports = explain(&(1..=3).map(winning_card).collect::<Vec<_>>())?;
+
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| (report.points, report.instances, report.copies_of.clone()))
+                .collect::<Vec<_>>(),
+            vec![(16, 1, vec![2, 3]), (16, 2, vec![3]), (16, 4, vec![])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve1() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let result = reader.lines().process_results(|itr| {
+            itr.map(move |l| Card::from_str(&l))
+                .process_results(|itr| solve1(itr))
+        })???;
+
+        assert_eq!(result, 23847);
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve2() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let result = reader.lines().process_results(|itr| {
+            itr.map(move |l| Card::from_str(&l))
+                .process_results(|itr| solve2(itr))
+        })???;
+
+        assert_eq!(result, 8570000);
+        Ok(())
+    }
+}
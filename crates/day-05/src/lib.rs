@@ -0,0 +1,972 @@
+use itertools::Itertools;
+use lib::{parse::Cursor, INVALID_INPUT};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use std::{error::Error, iter::once, str::FromStr};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Input {
+    pub seeds: Vec<Seed>,
+    pub garden_maps: Vec<GardenMap>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Seed {
+    pub from: u64,
+    pub len: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GardenMap {
+    pub from: String,
+    pub to: String,
+    pub garden_ranges: Vec<GardenRange>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GardenRange {
+    pub destination: u64,
+    pub source: u64,
+    pub length: u64,
+}
+
+pub fn parse_seeds(s: &str) -> Result<Vec<Seed>, Box<dyn Error>> {
+    let mut cursor = Cursor::new(s);
+    cursor.tag("seeds:")?;
+
+    let mut words = cursor.words();
+    let mut seeds = Vec::new();
+    while let Some(from) = words.next() {
+        let len = words
+            .next()
+            .ok_or_else(|| from.error("missing the length of the range"))?;
+        let (from, len) = (from.value::<u64>()?, len.value::<u64>()?);
+        // the seeds of the range have to be representable
+        from.checked_add(len).ok_or("Seed range overflow")?;
+        seeds.push(Seed { from, len });
+    }
+    Ok(seeds)
+}
+
+pub fn parse_input(itr: impl Iterator<Item = String>) -> Result<Input, Box<dyn Error>> {
+    let mut itr = itr.peekable();
+
+    // the seeds fit on a line, the other lines of their section are joined to it
+    let mut seeds_lines = itr.by_ref().take_while(|line| !line.is_empty());
+    let first_line = seeds_lines.next().ok_or(INVALID_INPUT)?;
+    let other_lines = seeds_lines.collect::<Vec<_>>();
+    let seeds = if other_lines.is_empty() {
+        parse_seeds(&first_line)?
+    } else {
+        parse_seeds(&once(first_line).chain(other_lines).join("\n"))?
+    };
+
+    // the maps are read from the lines of their sections as they come
+    let mut garden_maps = Vec::new();
+    while itr.peek().is_some() {
+        let lines = itr.by_ref().take_while(|line| !line.is_empty());
+        garden_maps.push(GardenMap::from_lines(lines)?);
+    }
+
+    Ok(Input { seeds, garden_maps })
+}
+
+impl GardenMap {
+    // the header then the ranges, one per line
+    pub fn from_lines(
+        lines: impl Iterator<Item = impl AsRef<str>>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut lines = lines;
+        let first_line = lines.next().ok_or(INVALID_INPUT)?;
+        let header = Cursor::new(first_line.as_ref());
+
+        let (from, to) = header
+            .words()
+            .next()
+            .ok_or_else(|| header.error("missing the name of the map"))?
+            .split_once("-to-")?;
+
+        let garden_ranges = lines
+            .map(|line| line.as_ref().parse::<GardenRange>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            from: from.rest().to_string(),
+            to: to.rest().to_string(),
+            garden_ranges,
+        })
+    }
+}
+
+impl FromStr for GardenMap {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_lines(s.lines())
+    }
+}
+
+impl FromStr for GardenRange {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cursor = Cursor::new(s);
+        let mut words = cursor.words();
+        let mut value = || -> Result<u64, Box<dyn Error>> {
+            Ok(words.next().ok_or("expected 3 values")?.value::<u64>()?)
+        };
+
+        let (destination, source, length) = (value()?, value()?, value()?);
+        if let Some(word) = words.next() {
+            return Err(word.error("expected 3 values").into());
+        }
+
+        // checked once here, the ends of the ranges and the mapped values can't overflow later
+        source.checked_add(length).ok_or("Source range overflow")?;
+        destination
+            .checked_add(length)
+            .ok_or("Destination range overflow")?;
+
+        Ok(Self {
+            destination,
+            source,
+            length,
+        })
+    }
+}
+
+// a half open interval [start, end) of values
+pub type Interval = (u64, u64);
+
+// map the intervals through a garden map, splitting them on the bounds of its ranges
+pub fn map_intervals(intervals: Vec<Interval>, garden_map: &GardenMap) -> Vec<Interval> {
+    let (mut mapped, unmapped) = garden_map.garden_ranges.iter().fold(
+        (Vec::new(), intervals),
+        |(mut mapped, intervals), garden_range| {
+            let source = garden_range.source;
+            let source_end = source + garden_range.length;
+            let destination = garden_range.destination;
+
+            let mut remaining = Vec::new();
+            for (start, end) in intervals {
+                let (overlap_start, overlap_end) = (start.max(source), end.min(source_end));
+
+                if overlap_start >= overlap_end {
+                    remaining.push((start, end));
+                    continue;
+                }
+
+                mapped.push((
+                    overlap_start - source + destination,
+                    overlap_end - source + destination,
+                ));
+                if start < overlap_start {
+                    remaining.push((start, overlap_start));
+                }
+                if overlap_end < end {
+                    remaining.push((overlap_end, end));
+                }
+            }
+
+            (mapped, remaining)
+        },
+    );
+
+    // the values not covered by any range are mapped to themselves
+    mapped.extend(unmapped);
+    mapped
+}
+
+impl GardenMap {
+    pub fn apply(&self, value: u64) -> u64 {
+        self.garden_ranges
+            .iter()
+            .find(|garden_range| {
+                garden_range.source <= value && value < garden_range.source + garden_range.length
+            })
+            .map_or(value, |garden_range| {
+                value - garden_range.source + garden_range.destination
+            })
+    }
+
+    pub fn apply_range(&self, interval: Interval) -> Vec<Interval> {
+        map_intervals(vec![interval], self)
+    }
+}
+
+pub fn solve1(input: Input) -> Result<u64, Box<dyn Error>> {
+    let garden_map = compose(&input.garden_maps)?;
+
+    input
+        .seeds
+        .iter()
+        .flat_map(|seed| [seed.from, seed.len])
+        .map(|seed| garden_map.apply(seed))
+        .min()
+        .ok_or("Empty vector".into())
+}
+
+pub fn solve2_brut_force(input: Input) -> Result<u64, Box<dyn Error>> {
+    input
+        .seeds
+        .into_par_iter()
+        .flat_map(|seed| seed.from..seed.from + seed.len)
+        .map(|seed| {
+            input.garden_maps.iter().fold(seed, |acc, garden_map| {
+                // go through all the garden ranges and stop when we find the one that contains the
+                // seed
+                let mapped = garden_map.garden_ranges.iter().find_map(|garden_range| {
+                    if acc >= garden_range.source
+                        && (acc - garden_range.source) < garden_range.length
+                    {
+                        let offset = acc - garden_range.source;
+                        Some(garden_range.destination + offset)
+                    } else {
+                        None
+                    }
+                });
+
+                mapped.unwrap_or(acc)
+            })
+        })
+        .min()
+        .ok_or("Empty vector".into())
+}
+
+pub fn solve2_brut_force_reverse(input: Input) -> Result<u64, Box<dyn Error>> {
+    (0..)
+        .into_iter()
+        .find(|location| {
+            let soil = input
+                .garden_maps
+                .iter()
+                .rev()
+                .fold(*location, |acc, garden_map| {
+                    garden_map
+                        .garden_ranges
+                        .iter()
+                        .find_map(|garden_range| {
+                            if acc >= garden_range.destination
+                                && (acc - garden_range.destination) < garden_range.length
+                            {
+                                let offset = acc - garden_range.destination;
+                                Some(garden_range.source + offset)
+                            } else {
+                                None
+                            }
+                        })
+                        .unwrap_or(acc)
+                });
+            let seed = input.seeds.iter().find(|seed_range| {
+                soil >= seed_range.from && (soil - seed_range.from) < seed_range.len
+            });
+            seed.is_some()
+        })
+        .ok_or("Not found".into())
+}
+
+// a piece of a composed map: the values in [start, end) are mapped to [image, image + end - start)
+type Piece = (u64, u64, u64);
+
+// map the images of the pieces through a garden map, splitting them on the bounds of its ranges
+fn map_pieces(pieces: Vec<Piece>, garden_map: &GardenMap) -> Vec<Piece> {
+    let (mut mapped, unmapped) = garden_map.garden_ranges.iter().fold(
+        (Vec::new(), pieces),
+        |(mut mapped, pieces), garden_range| {
+            let source = garden_range.source;
+            let source_end = source + garden_range.length;
+            let destination = garden_range.destination;
+
+            let mut remaining = Vec::new();
+            for (start, end, image) in pieces {
+                let image_end = image + (end - start);
+                let (overlap_start, overlap_end) = (image.max(source), image_end.min(source_end));
+
+                if overlap_start >= overlap_end {
+                    remaining.push((start, end, image));
+                    continue;
+                }
+
+                mapped.push((
+                    start + (overlap_start - image),
+                    start + (overlap_end - image),
+                    overlap_start - source + destination,
+                ));
+                if image < overlap_start {
+                    remaining.push((start, start + (overlap_start - image), image));
+                }
+                if overlap_end < image_end {
+                    remaining.push((start + (overlap_end - image), end, overlap_end));
+                }
+            }
+
+            (mapped, remaining)
+        },
+    );
+
+    mapped.extend(unmapped);
+    mapped
+}
+
+// the garden maps applied one after the other as a single map, whose ranges are the pieces of
+// the values that don't map to themselves. u64::MAX is left out, no range can contain it.
+fn compose(garden_maps: &[GardenMap]) -> Result<GardenMap, Box<dyn Error>> {
+    let first = garden_maps.first().ok_or("No garden map")?;
+    let last = garden_maps.last().ok_or("No garden map")?;
+
+    let pieces = garden_maps.iter().fold(vec![(0, u64::MAX, 0)], map_pieces);
+
+    let garden_ranges = pieces
+        .into_iter()
+        .filter(|(start, _, image)| start != image)
+        .sorted()
+        .map(|(start, end, image)| GardenRange {
+            destination: image,
+            source: start,
+            length: end - start,
+        })
+        .collect();
+
+    Ok(GardenMap {
+        from: first.from.clone(),
+        to: last.to.clone(),
+        garden_ranges,
+    })
+}
+
+// the location intervals the seed ranges end up in
+fn location_intervals(input: &Input) -> Result<Vec<Interval>, Box<dyn Error>> {
+    let garden_map = compose(&input.garden_maps)?;
+
+    Ok(input
+        .seeds
+        .iter()
+        .map(|seed| (seed.from, seed.from + seed.len))
+        .filter(|(start, end)| start < end)
+        .flat_map(|interval| garden_map.apply_range(interval))
+        .collect())
+}
+
+pub fn solve2_intervals(input: Input) -> Result<u64, Box<dyn Error>> {
+    location_intervals(&input)?
+        .into_iter()
+        .map(|(start, _)| start)
+        .min()
+        .ok_or("Empty vector".into())
+}
+
+// split the span of the locations into buckets of the same width and count how many seeds fall
+// into each of them
+pub fn location_histogram(
+    input: &Input,
+    buckets: usize,
+) -> Result<Vec<(Interval, u64)>, Box<dyn Error>> {
+    if buckets == 0 {
+        return Err("The number of buckets must be positive".into());
+    }
+
+    let intervals = location_intervals(input)?;
+    let min = intervals
+        .iter()
+        .map(|(start, _)| *start)
+        .min()
+        .ok_or("Empty vector")?;
+    let max = intervals
+        .iter()
+        .map(|(_, end)| *end)
+        .max()
+        .ok_or("Empty vector")?;
+    let width = (max - min).div_ceil(buckets as u64);
+
+    Ok((0..buckets as u64)
+        .map(|bucket| {
+            let start = min.saturating_add(bucket.saturating_mul(width));
+            let end = min.saturating_add((bucket + 1).saturating_mul(width));
+            (start, end.min(max))
+        })
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| {
+            let count = intervals
+                .iter()
+                .map(|(from, to)| to.min(&end).saturating_sub(*from.max(&start)))
+                .sum();
+            ((start, end), count)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod day05 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+        str::FromStr,
+    };
+
+    use itertools::Itertools;
+    use lib::alloc::{count_allocations, CountingAllocator};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{map_intervals, Seed};
+
+    use crate::{
+        compose, location_histogram, location_intervals, parse_input, parse_seeds, solve1,
+        solve2_brut_force, solve2_brut_force_reverse, solve2_intervals, GardenMap, GardenRange,
+        Input,
+    };
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    const SEEDS: &str = "seeds: 79 14 55 13";
+    fn seeds() -> Vec<Seed> {
+        vec![Seed { from: 79, len: 14 }, Seed { from: 55, len: 13 }]
+    }
+
+    const GARDEN_MAP1: &str = "\
+        seed-to-soil map:\n\
+        50 98 2\n\
+        52 50 48";
+    fn garden_map1() -> GardenMap {
+        GardenMap {
+            from: "seed".to_string(),
+            to: "soil".to_string(),
+            garden_ranges: vec![
+                GardenRange {
+                    destination: 50,
+                    source: 98,
+                    length: 2,
+                },
+                GardenRange {
+                    destination: 52,
+                    source: 50,
+                    length: 48,
+                },
+            ],
+        }
+    }
+
+    const GARDEN_MAP2: &str = "\
+        soil-to-fertilizer map:\n\
+        0 15 37\n\
+        37 52 2\n\
+        39 0 15";
+    fn garden_map2() -> GardenMap {
+        GardenMap {
+            from: "soil".to_string(),
+            to: "fertilizer".to_string(),
+            garden_ranges: vec![
+                GardenRange {
+                    destination: 0,
+                    source: 15,
+                    length: 37,
+                },
+                GardenRange {
+                    destination: 37,
+                    source: 52,
+                    length: 2,
+                },
+                GardenRange {
+                    destination: 39,
+                    source: 0,
+                    length: 15,
+                },
+            ],
+        }
+    }
+
+    const GARDEN_MAP3: &str = "\
+        fertilizer-to-water map:\n\
+        49 53 8\n\
+        0 11 42\n\
+        42 0 7\n\
+        57 7 4";
+    fn garden_map3() -> GardenMap {
+        GardenMap {
+            from: "fertilizer".to_string(),
+            to: "water".to_string(),
+            garden_ranges: vec![
+                GardenRange {
+                    destination: 49,
+                    source: 53,
+                    length: 8,
+                },
+                GardenRange {
+                    destination: 0,
+                    source: 11,
+                    length: 42,
+                },
+                GardenRange {
+                    destination: 42,
+                    source: 0,
+                    length: 7,
+                },
+                GardenRange {
+                    destination: 57,
+                    source: 7,
+                    length: 4,
+                },
+            ],
+        }
+    }
+
+    const GARDEN_MAP4: &str = "\
+        water-to-light map:\n\
+        88 18 7\n\
+        18 25 70";
+    fn garden_map4() -> GardenMap {
+        GardenMap {
+            from: "water".to_string(),
+            to: "light".to_string(),
+            garden_ranges: vec![
+                GardenRange {
+                    destination: 88,
+                    source: 18,
+                    length: 7,
+                },
+                GardenRange {
+                    destination: 18,
+                    source: 25,
+                    length: 70,
+                },
+            ],
+        }
+    }
+
+    const GARDEN_MAP5: &str = "\
+        light-to-temperature map:\n\
+        45 77 23\n\
+        81 45 19\n\
+        68 64 13";
+    fn garden_map5() -> GardenMap {
+        GardenMap {
+            from: "light".to_string(),
+            to: "temperature".to_string(),
+            garden_ranges: vec![
+                GardenRange {
+                    destination: 45,
+                    source: 77,
+                    length: 23,
+                },
+                GardenRange {
+                    destination: 81,
+                    source: 45,
+                    length: 19,
+                },
+                GardenRange {
+                    destination: 68,
+                    source: 64,
+                    length: 13,
+                },
+            ],
+        }
+    }
+
+    const GARDEN_MAP6: &str = "\
+        temperature-to-humidity map:\n\
+        0 69 1\n\
+        1 0 69";
+    fn garden_map6() -> GardenMap {
+        GardenMap {
+            from: "temperature".to_string(),
+            to: "humidity".to_string(),
+            garden_ranges: vec![
+                GardenRange {
+                    destination: 0,
+                    source: 69,
+                    length: 1,
+                },
+                GardenRange {
+                    destination: 1,
+                    source: 0,
+                    length: 69,
+                },
+            ],
+        }
+    }
+
+    const GARDEN_MAP7: &str = "\
+        humidity-to-location map:\n\
+        60 56 37\n\
+        56 93 4";
+    fn garden_map7() -> GardenMap {
+        GardenMap {
+            from: "humidity".to_string(),
+            to: "location".to_string(),
+            garden_ranges: vec![
+                GardenRange {
+                    destination: 60,
+                    source: 56,
+                    length: 37,
+                },
+                GardenRange {
+                    destination: 56,
+                    source: 93,
+                    length: 4,
+                },
+            ],
+        }
+    }
+
+    fn input1() -> Input {
+        Input {
+            seeds: seeds(),
+            garden_maps: vec![
+                garden_map1(),
+                garden_map2(),
+                garden_map3(),
+                garden_map4(),
+                garden_map5(),
+                garden_map6(),
+                garden_map7(),
+            ],
+        }
+    }
+
+    fn input_str() -> String {
+        format!(
+            "{}\n\n{}\n\n{}\n\n{}\n\n{}\n\n{}\n\n{}\n\n{}",
+            SEEDS,
+            GARDEN_MAP1,
+            GARDEN_MAP2,
+            GARDEN_MAP3,
+            GARDEN_MAP4,
+            GARDEN_MAP5,
+            GARDEN_MAP6,
+            GARDEN_MAP7,
+        )
+    }
+
+    #[test]
+    fn parse_seeds_() -> Result<(), Box<dyn Error>> {
+        assert_eq!(seeds(), parse_seeds(SEEDS)?);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_single_garden_map() -> Result<(), Box<dyn Error>> {
+        assert_eq!(garden_map1(), GardenMap::from_str(GARDEN_MAP1)?);
+        assert_eq!(garden_map2(), GardenMap::from_str(GARDEN_MAP2)?);
+        assert_eq!(garden_map3(), GardenMap::from_str(GARDEN_MAP3)?);
+        assert_eq!(garden_map4(), GardenMap::from_str(GARDEN_MAP4)?);
+        assert_eq!(garden_map5(), GardenMap::from_str(GARDEN_MAP5)?);
+        assert_eq!(garden_map6(), GardenMap::from_str(GARDEN_MAP6)?);
+        assert_eq!(garden_map7(), GardenMap::from_str(GARDEN_MAP7)?);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_input_() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            input1(),
+            parse_input(input_str().lines().map(|s| s.to_string()))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_allocations() -> Result<(), Box<dyn Error>> {
+        let lines = input_str()
+            .lines()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        let (parsed, allocations) = count_allocations(|| parse_input(lines.into_iter()));
+
+        // the names and the ranges of the maps with the vectors holding them, 61 when the
+        // sections were concatenated into strings and the values collected before being checked
+        assert_eq!(parsed?, input1());
+        assert_eq!(allocations, 24);
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve1() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve1(input1())?, 35);
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve2_brut_force() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve2_brut_force(input1())?, 46);
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve2_brut_force_reverse() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve2_brut_force_reverse(input1())?, 46);
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve2_intervals() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve2_intervals(input1())?, 46);
+        Ok(())
+    }
+
+    #[test]
+    fn map_intervals_split() {
+        // seed-to-soil maps 50..98 to 52..100 and 98..100 to 50..52
+        let mut intervals = map_intervals(vec![(40, 60), (97, 105)], &garden_map1());
+        intervals.sort();
+
+        assert_eq!(
+            intervals,
+            vec![(40, 50), (50, 52), (52, 62), (99, 100), (100, 105)]
+        );
+    }
+
+    fn apply_sequentially(garden_maps: &[GardenMap], value: u64) -> u64 {
+        garden_maps
+            .iter()
+            .fold(value, |acc, garden_map| garden_map.apply(acc))
+    }
+
+    #[test]
+    fn compose_example() -> Result<(), Box<dyn Error>> {
+        let input = input1();
+        let garden_map = compose(&input.garden_maps)?;
+
+        assert_eq!(garden_map.from, "seed");
+        assert_eq!(garden_map.to, "location");
+        // the seeds of the puzzle text
+        assert_eq!(garden_map.apply(79), 82);
+        assert_eq!(garden_map.apply(14), 43);
+        assert_eq!(garden_map.apply(55), 86);
+        assert_eq!(garden_map.apply(13), 35);
+
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..1000 {
+            let seed = rng.gen_range(0..200);
+            assert_eq!(
+                garden_map.apply(seed),
+                apply_sequentially(&input.garden_maps, seed)
+            );
+        }
+        Ok(())
+    }
+
+    // a map made of disjoint ranges of the values below 1000
+    fn random_garden_map(rng: &mut StdRng) -> GardenMap {
+        let mut sources = (0..rng.gen_range(0..6))
+            .map(|_| rng.gen_range(0..1000))
+            .collect::<Vec<u64>>();
+        sources.sort();
+        sources.dedup();
+
+        GardenMap {
+            from: "a".to_string(),
+            to: "b".to_string(),
+            garden_ranges: sources
+                .windows(2)
+                .map(|window| GardenRange {
+                    destination: rng.gen_range(0..1000),
+                    source: window[0],
+                    length: rng.gen_range(1..=window[1] - window[0]),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn compose_random() -> Result<(), Box<dyn Error>> {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let garden_maps = (0..rng.gen_range(1..8))
+                .map(|_| random_garden_map(&mut rng))
+                .collect::<Vec<_>>();
+            let garden_map = compose(&garden_maps)?;
+
+            for _ in 0..100 {
+                let seed = rng.gen_range(0..2000);
+                assert_eq!(
+                    garden_map.apply(seed),
+                    apply_sequentially(&garden_maps, seed)
+                );
+            }
+
+            let start = rng.gen_range(0..1000);
+            let interval = (start, start + rng.gen_range(1..1000));
+            let mut composed = garden_map
+                .apply_range(interval)
+                .into_iter()
+                .flat_map(|(start, end)| start..end)
+                .collect::<Vec<_>>();
+            composed.sort();
+            let mut sequential = (interval.0..interval.1)
+                .map(|seed| apply_sequentially(&garden_maps, seed))
+                .collect::<Vec<_>>();
+            sequential.sort();
+            assert_eq!(composed, sequential);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn compose_empty() {
+        assert!(compose(&[]).is_err());
+    }
+
+    #[test]
+    fn example_location_intervals() -> Result<(), Box<dyn Error>> {
+        let input = input1();
+        let seeds = input.seeds.iter().map(|seed| seed.len).sum::<u64>();
+        let intervals = location_intervals(&input)?;
+
+        assert_eq!(
+            intervals
+                .iter()
+                .map(|(start, end)| end - start)
+                .sum::<u64>(),
+            seeds
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn example_location_histogram() -> Result<(), Box<dyn Error>> {
+        let input = input1();
+        let histogram = location_histogram(&input, 4)?;
+
+        // the counts agree with mapping each seed on its own
+        let locations = input
+            .seeds
+            .iter()
+            .flat_map(|seed| seed.from..seed.from + seed.len)
+            .map(|seed| {
+                input.garden_maps.iter().fold(seed, |acc, garden_map| {
+                    map_intervals(vec![(acc, acc + 1)], garden_map)[0].0
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(histogram.len(), 4);
+        assert_eq!(histogram[0].0 .0, 46);
+        histogram.iter().for_each(|((start, end), count)| {
+            let expected = locations
+                .iter()
+                .filter(|location| start <= *location && *location < end)
+                .count();
+            assert_eq!(*count, expected as u64);
+        });
+        assert_eq!(histogram.iter().map(|(_, count)| count).sum::<u64>(), 27);
+        Ok(())
+    }
+
+    // the seeds around u32::MAX, the ones after it being mapped to the bottom
+    const U32_BOUNDARY: &str = "\
+        seeds: 4294967290 10\n\
+        \n\
+        seed-to-location map:\n\
+        0 4294967296 4\n\
+        100 4294967290 6";
+
+    #[test]
+    fn u32_boundary() -> Result<(), Box<dyn Error>> {
+        let input = || parse_input(U32_BOUNDARY.lines().map(|s| s.to_string()));
+
+        assert_eq!(
+            input()?.seeds,
+            vec![Seed {
+                from: 4294967290,
+                len: 10
+            }]
+        );
+        assert_eq!(
+            input()?.garden_maps[0].garden_ranges[0],
+            GardenRange {
+                destination: 0,
+                source: 4294967296,
+                length: 4
+            }
+        );
+
+        // 4294967290 goes to 100 and 10 is left untouched
+        assert_eq!(solve1(input()?)?, 10);
+        assert_eq!(solve2_brut_force(input()?)?, 0);
+        assert_eq!(solve2_brut_force_reverse(input()?)?, 0);
+        assert_eq!(solve2_intervals(input()?)?, 0);
+
+        let garden_map = compose(&input()?.garden_maps)?;
+        assert_eq!(garden_map.apply(u32::MAX as u64), 105);
+        assert_eq!(garden_map.apply(u32::MAX as u64 + 1), 0);
+        assert_eq!(garden_map.apply(u32::MAX as u64 + 4), 3);
+        assert_eq!(garden_map.apply(u32::MAX as u64 + 5), u32::MAX as u64 + 5);
+        Ok(())
+    }
+
+    #[test]
+    fn u64_overflow() {
+        assert!(parse_seeds("seeds: 18446744073709551615 1").is_err());
+        assert!(parse_seeds("seeds: 18446744073709551616 0").is_err());
+        assert!(parse_seeds("seeds: 18446744073709551614 1").is_ok());
+
+        assert!(GardenRange::from_str("0 18446744073709551615 1").is_err());
+        assert!(GardenRange::from_str("18446744073709551615 0 1").is_err());
+        assert!(GardenRange::from_str("0 0 18446744073709551616").is_err());
+        assert!(GardenRange::from_str("1 0 18446744073709551614").is_ok());
+    }
+
+    #[test]
+    fn input_solve1() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let input = reader.lines().process_results(|itr| parse_input(itr))??;
+
+        assert_eq!(solve1(input)?, 382895070);
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve2_brut_force_reverse() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let input = reader.lines().process_results(|itr| parse_input(itr))??;
+
+        assert_eq!(solve2_brut_force_reverse(input)?, 17729182);
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve2_intervals() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let input = reader.lines().process_results(|itr| parse_input(itr))??;
+
+        assert_eq!(solve2_intervals(input)?, 17729182);
+        Ok(())
+    }
+
+    // This takes too much time for tests
+    // #[test]
+    // fn input_solve2() -> Result<(), Box<dyn Error>> {
+    //     let file = File::open("input")?;
+    //     let reader = BufReader::new(file);
+    //     let input = reader.lines().process_results(|itr| parse_input(itr))??;
+
+    //     assert_eq!(solve2_brut_force(input)?, 17729182);
+    //     Ok(())
+    // }
+
+    #[test]
+    fn parse_malformed_input() {
+        for s in [
+            "",
+            "seeds",
+            "seeds: 1",
+            "seeds: 1 x",
+            "seeds: 18446744073709551615 1",
+            "seeds: 1 2\n\nmap:\n1 2 3",
+            "seeds: 1 2\n\nx-to-y map:\n1 2",
+            "seeds: 1 2\n\nx-to-y map:\n1 2 3 4",
+            "seeds: 1 2\n\nx-to-y map:\n18446744073709551615 0 1",
+            "seeds: 1 2\n\nx-to-y map:\n0 18446744073709551615 1",
+        ] {
+            assert!(
+                parse_input(s.lines().map(|s| s.to_string())).is_err(),
+                "{:?}",
+                s
+            );
+        }
+    }
+}
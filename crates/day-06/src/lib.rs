@@ -0,0 +1,301 @@
+use lib::INVALID_INPUT;
+use std::{error::Error, iter::zip};
+
+// t: time of the race
+// m: max distance
+// h: time to hold the button
+// s: time to sail
+// d: distance sailed
+// v: sailing speed, v = h
+//
+// We need to find all h such that:
+// h + s = t and d > m
+// s x v > m
+// (t - h) x h > m
+// -h^2 + t x h - m > 0
+//
+// That's a quadratic equation with:
+// delta = t^2 - 4 x m
+// x1 = (t - sqrt(t^2 - 4 x m)) / 2
+// x2 = (t + sqrt(t^2 - 4 x m)) / 2
+// The solutions are the integer x such that x1 < x < x2
+//
+// Floats can't represent large times exactly, so the square root is an integer one. x1 computed
+// from it is only an estimate, corrected by checking the distances around it. When t^2 doesn't
+// fit in u128, x1 is found by a binary search instead. The solutions are symmetric around t / 2,
+// which gives the count from the smallest one.
+//
+// The brute force solver, trying every hold time, is kept to cross check the closed form one.
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Race {
+    time: u128,
+    distance: u128,
+}
+
+pub fn parse_line1(s: String, header: String) -> Result<Vec<u128>, Box<dyn Error>> {
+    let without_header = s
+        .strip_prefix(&header)
+        .ok_or::<Box<dyn Error>>(INVALID_INPUT.into())?;
+
+    without_header
+        .split_whitespace()
+        .map(|s| s.parse::<u128>())
+        .collect::<Result<Vec<u128>, _>>()
+        .map_err(|e| e.into())
+}
+
+pub fn parse_races(itr: impl Iterator<Item = String>) -> Result<Vec<Race>, Box<dyn Error>> {
+    let mut itr = itr;
+
+    let first_line = itr.next().ok_or::<Box<dyn Error>>(INVALID_INPUT.into())?;
+    let times = parse_line1(first_line, "Time:".into())?;
+
+    let second_line = itr.next().ok_or::<Box<dyn Error>>("".into())?;
+    let distances = parse_line1(second_line, "Distance:".into())?;
+
+    Ok(zip(times, distances)
+        .map(|(time, distance)| Race { time, distance })
+        .collect())
+}
+
+fn parse_line2(s: String, header: String) -> Result<u128, Box<dyn Error>> {
+    let without_header = s
+        .strip_prefix(&header)
+        .ok_or::<Box<dyn Error>>(INVALID_INPUT.into())?;
+
+    without_header
+        .chars()
+        .filter(|c| c.is_digit(10))
+        .collect::<String>()
+        .parse::<u128>()
+        .map_err(|e| e.into())
+}
+
+pub fn parse_race(itr: impl Iterator<Item = String>) -> Result<Race, Box<dyn Error>> {
+    let mut itr = itr;
+
+    let first_line = itr.next().ok_or::<Box<dyn Error>>(INVALID_INPUT.into())?;
+    let time = parse_line2(first_line, "Time:".into())?;
+
+    let second_line = itr.next().ok_or::<Box<dyn Error>>("".into())?;
+    let distance = parse_line2(second_line, "Distance:".into())?;
+
+    Ok(Race { time, distance })
+}
+
+pub fn solve(
+    races: impl Iterator<Item = Race>,
+    solve_race: fn(Race) -> Result<u128, Box<dyn Error>>,
+) -> Result<u128, Box<dyn Error>> {
+    races.map(solve_race).try_fold(1u128, |acc, count| {
+        acc.checked_mul(count?).ok_or("Overflow".into())
+    })
+}
+
+// a product too large for u128 beats any record
+fn beats(race: &Race, hold: u128) -> bool {
+    hold.checked_mul(race.time - hold)
+        .is_none_or(|distance| distance > race.distance)
+}
+
+// linear in the time of the race
+pub fn solve_race_brute_force(race: Race) -> Result<u128, Box<dyn Error>> {
+    Ok((0..=race.time)
+        .filter(|hold| beats(&race, *hold))
+        .fold(0, |count, _| count + 1))
+}
+
+pub fn solve_race(race: Race) -> Result<u128, Box<dyn Error>> {
+    let half = race.time / 2;
+    if !beats(&race, half) {
+        return Ok(0);
+    }
+
+    let mut hold = match race.time.checked_mul(race.time) {
+        // t / 2 winning means 4 x m < t^2
+        Some(square) => (race.time - (square - 4 * race.distance).isqrt()) / 2,
+        // t^2 doesn't fit, look for the first winning hold in [0, t / 2] instead
+        None => {
+            let (mut low, mut high) = (0, half);
+            while low < high {
+                let middle = low + (high - low) / 2;
+                if beats(&race, middle) {
+                    high = middle;
+                } else {
+                    low = middle + 1;
+                }
+            }
+            low
+        }
+    };
+
+    while hold > 0 && beats(&race, hold - 1) {
+        hold -= 1;
+    }
+    while !beats(&race, hold) {
+        hold += 1;
+    }
+
+    Ok(race.time - 2 * hold + 1)
+}
+
+#[cfg(test)]
+mod day06 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+
+    use crate::{parse_race, parse_races, solve, solve_race, solve_race_brute_force, Race};
+
+    const EXAMPLE: &str = "\
+        Time:      7  15   30\n\
+        Distance:  9  40  200";
+    fn race1() -> Race {
+        Race {
+            time: 7,
+            distance: 9,
+        }
+    }
+
+    fn race2() -> Race {
+        Race {
+            time: 15,
+            distance: 40,
+        }
+    }
+
+    fn race3() -> Race {
+        Race {
+            time: 30,
+            distance: 200,
+        }
+    }
+
+    fn example1() -> Vec<Race> {
+        vec![race1(), race2(), race3()]
+    }
+
+    fn example2() -> Race {
+        Race {
+            time: 71530,
+            distance: 940200,
+        }
+    }
+
+    #[test]
+    fn parse_races_() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            parse_races(EXAMPLE.lines().map(|s| s.to_string()))?,
+            example1()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_race_() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            parse_race(EXAMPLE.lines().map(|s| s.to_string()))?,
+            example2()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn solve_race_() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve_race(race1())?, 4);
+        assert_eq!(solve_race(race2())?, 8);
+        assert_eq!(solve_race(race3())?, 9);
+        Ok(())
+    }
+
+    #[test]
+    fn solve_race2() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve_race(example2())?, 71503);
+        Ok(())
+    }
+
+    #[test]
+    fn solve_race_cross_check() -> Result<(), Box<dyn Error>> {
+        for time in 0..=60 {
+            for distance in 0..=1000 {
+                assert_eq!(
+                    solve_race(Race { time, distance })?,
+                    solve_race_brute_force(Race { time, distance })?
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn solve_example_both() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve(example1().into_iter(), solve_race)?, 288);
+        assert_eq!(solve(example1().into_iter(), solve_race_brute_force)?, 288);
+        assert_eq!(solve_race_brute_force(example2())?, 71503);
+        Ok(())
+    }
+
+    #[test]
+    fn solve_race_brute_force_boundaries() -> Result<(), Box<dyn Error>> {
+        // records right around the best distance and the perfect squares of the delta
+        for time in [999, 1000, 65_535, 65_536, 100_001] {
+            let best = (time / 2) * (time - time / 2);
+            for distance in (0..5).flat_map(|k| [best - k * k, best - k * k - 1, best + k]) {
+                let race = || Race { time, distance };
+                assert_eq!(solve_race(race())?, solve_race_brute_force(race())?);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn solve_race_huge() -> Result<(), Box<dyn Error>> {
+        // (t / 2 - k) x (t / 2 + k) = (t / 2)^2 - k^2 so only the holds closer than k to t / 2 win
+        let half = 10_000_000_000_000_000_000u128;
+        for k in [1, 2, 3, 1_000, 123_456_789] {
+            let race = Race {
+                time: 2 * half,
+                distance: half * half - k * k,
+            };
+            assert_eq!(solve_race(race)?, 2 * k - 1);
+        }
+
+        let race = Race {
+            time: 2 * half + 1,
+            distance: half * (half + 1),
+        };
+        assert_eq!(solve_race(race)?, 0);
+
+        let race = Race {
+            time: u128::MAX,
+            distance: 0,
+        };
+        assert_eq!(solve_race(race)?, u128::MAX - 1);
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve1() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let input = reader.lines().process_results(|itr| parse_races(itr))??;
+
+        assert_eq!(solve(input.into_iter(), solve_race)?, 170000);
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve2() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let input = reader.lines().process_results(|itr| parse_race(itr))??;
+
+        assert_eq!(solve_race(input)?, 20537782);
+        Ok(())
+    }
+}
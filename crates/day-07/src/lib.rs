@@ -0,0 +1,393 @@
+use itertools::Itertools;
+use lib::INVALID_INPUT;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    error::Error,
+    io::BufRead,
+    iter::zip,
+};
+
+#[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Clone, Copy, Hash)]
+enum Card {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Height,
+    Nine,
+    T,
+    J,
+    Q,
+    K,
+    A,
+}
+
+#[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Clone, Copy)]
+enum Type {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+type Hand = [Card; 5];
+
+#[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Clone)]
+pub struct HandAndBid {
+    hand: Hand,
+    bid: u32,
+}
+
+fn parse_card(c: char) -> Result<Card, Box<dyn Error>> {
+    match c {
+        '2' => Ok(Card::Two),
+        '3' => Ok(Card::Three),
+        '4' => Ok(Card::Four),
+        '5' => Ok(Card::Five),
+        '6' => Ok(Card::Six),
+        '7' => Ok(Card::Seven),
+        '8' => Ok(Card::Height),
+        '9' => Ok(Card::Nine),
+        'T' => Ok(Card::T),
+        'J' => Ok(Card::J),
+        'Q' => Ok(Card::Q),
+        'K' => Ok(Card::K),
+        'A' => Ok(Card::A),
+        _ => Err(INVALID_INPUT.into()),
+    }
+}
+
+fn parse_hand(s: &str) -> Result<Hand, Box<dyn Error>> {
+    let len = s.chars().count();
+    if len != 5 {
+        return Err(format!("Invalid hand length: {}", len).into());
+    }
+
+    s.chars()
+        .map(parse_card)
+        .collect::<Result<Vec<Card>, Box<dyn Error>>>()?
+        .as_slice()
+        .try_into()
+        .map(|x: &Hand| *x)
+        .map_err(|e| e.into())
+}
+
+fn type1(hand: &Hand) -> Type {
+    let cards_counts = hand.iter().fold(HashMap::new(), |mut acc, x| {
+        *acc.entry(x).or_insert(0) += 1;
+        acc
+    });
+
+    let mut counts = cards_counts.values().collect::<Vec<_>>();
+    counts.sort();
+    match counts.as_slice() {
+        [1, 1, 1, 1, 1] => Type::HighCard,
+        [1, 1, 1, 2] => Type::OnePair,
+        [1, 2, 2] => Type::TwoPair,
+        [1, 1, 3] => Type::ThreeOfAKind,
+        [2, 3] => Type::FullHouse,
+        [1, 4] => Type::FourOfAKind,
+        [5] => Type::FiveOfAKind,
+        _ => unreachable!(),
+    }
+}
+
+fn type2(hand: &Hand) -> Type {
+    let non_jocker_cards = hand
+        .iter()
+        .cloned()
+        .filter(|card| *card != Card::J)
+        .collect::<HashSet<Card>>();
+
+    let joker_use = non_jocker_cards.into_iter().map(|x| {
+        let new_hand = hand.map(|y| if y == Card::J { x } else { y });
+        type1(&new_hand)
+    });
+
+    [type1(hand)]
+        .iter()
+        .cloned()
+        .chain(joker_use)
+        .max()
+        .unwrap_or(Type::HighCard)
+}
+
+pub fn parse_hand_and_bid(s: &str) -> Result<HandAndBid, Box<dyn Error>> {
+    let (hand_str, bid_str) = s.split_once(' ').ok_or(INVALID_INPUT)?;
+    let hand = parse_hand(hand_str)?;
+    let bid = bid_str.parse::<u32>()?;
+
+    Ok(HandAndBid { hand, bid })
+}
+
+// a single deck holds only four cards of each kind, the jokers aside
+fn check_deck(hand: &Hand) -> Result<(), Box<dyn Error>> {
+    match hand
+        .iter()
+        .filter(|card| **card != Card::J)
+        .counts()
+        .into_iter()
+        .find(|(_, count)| *count > 4)
+    {
+        Some((card, count)) => Err(format!("{} cards {:?} in a single deck", count, card).into()),
+        None => Ok(()),
+    }
+}
+
+// parse the lines as they are read, in a single buffer, reporting the number of the offending one.
+// In strict mode the hands must come from a single deck
+pub fn parse_input(
+    mut reader: impl BufRead,
+    strict: bool,
+) -> Result<Vec<HandAndBid>, Box<dyn Error>> {
+    let mut hand_and_bids = Vec::new();
+    let mut line = String::new();
+
+    while reader.read_line(&mut line)? > 0 {
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        let hand_and_bid = parse_hand_and_bid(trimmed)
+            .and_then(|hand_and_bid| {
+                if strict {
+                    check_deck(&hand_and_bid.hand)?;
+                }
+                Ok(hand_and_bid)
+            })
+            .map_err(|e| format!("line {}: {}: {}", hand_and_bids.len() + 1, trimmed, e))?;
+
+        hand_and_bids.push(hand_and_bid);
+        line.clear();
+    }
+
+    Ok(hand_and_bids)
+}
+
+// the order of two hands of the same type
+fn compare_cards(hand1: &Hand, hand2: &Hand, cmp: fn(&Card, &Card) -> Ordering) -> Ordering {
+    zip(hand1.iter(), hand2.iter())
+        .find_map(|(x, y)| match cmp(x, y) {
+            Ordering::Equal => None,
+            x => Some(x),
+        })
+        .unwrap_or(Ordering::Equal)
+}
+
+// the hands with their rank, from 1 for the weakest. The type of each hand is evaluated once, and
+// the equal hands keep the order of the input so that the ranking doesn't depend on the sort
+fn rank_hands(
+    hand_and_bids: &[HandAndBid],
+    type_: fn(&Hand) -> Type,
+    cmp: fn(&Card, &Card) -> Ordering,
+) -> Vec<(u32, &HandAndBid)> {
+    let mut typed = hand_and_bids
+        .iter()
+        .enumerate()
+        .map(|(i, hand_and_bid)| (type_(&hand_and_bid.hand), i, hand_and_bid))
+        .collect::<Vec<_>>();
+
+    typed.sort_unstable_by(|(type1_, i1, x), (type2_, i2, y)| {
+        type1_
+            .cmp(type2_)
+            .then_with(|| compare_cards(&x.hand, &y.hand, cmp))
+            .then(i1.cmp(i2))
+    });
+
+    zip(1.., typed)
+        .map(|(rank, (_, _, hand_and_bid))| (rank, hand_and_bid))
+        .collect()
+}
+
+fn card_level(card: &Card) -> u32 {
+    match card {
+        Card::J => 1,
+        Card::Two => 2,
+        Card::Three => 3,
+        Card::Four => 4,
+        Card::Five => 5,
+        Card::Six => 6,
+        Card::Seven => 7,
+        Card::Height => 8,
+        Card::Nine => 9,
+        Card::T => 10,
+        Card::Q => 11,
+        Card::K => 12,
+        Card::A => 13,
+    }
+}
+
+fn cmp2(card1: &Card, card2: &Card) -> Ordering {
+    card_level(card1).cmp(&card_level(card2))
+}
+
+fn winnings(ranked: &[(u32, &HandAndBid)]) -> u32 {
+    ranked
+        .iter()
+        .map(|(rank, hand_and_bid)| rank * hand_and_bid.bid)
+        .sum()
+}
+
+pub fn solve1(hand_and_bids: &[HandAndBid]) -> u32 {
+    winnings(&rank_hands(hand_and_bids, type1, Card::cmp))
+}
+
+pub fn solve2(hand_and_bids: &[HandAndBid]) -> u32 {
+    winnings(&rank_hands(hand_and_bids, type2, cmp2))
+}
+
+#[cfg(test)]
+mod day07 {
+
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use crate::{
+        check_deck, cmp2, parse_hand, parse_hand_and_bid, parse_input, rank_hands, solve1, solve2,
+        type1, type2, Card, HandAndBid,
+    };
+
+    const EXAMPLE: &str = "\
+        32T3K 765\n\
+        T55J5 684\n\
+        KK677 28\n\
+        KTJJT 220\n\
+        QQQJA 483";
+
+    fn example() -> Vec<HandAndBid> {
+        vec![
+            HandAndBid {
+                hand: [Card::Three, Card::Two, Card::T, Card::Three, Card::K],
+                bid: 765,
+            },
+            HandAndBid {
+                hand: [Card::T, Card::Five, Card::Five, Card::J, Card::Five],
+                bid: 684,
+            },
+            HandAndBid {
+                hand: [Card::K, Card::K, Card::Six, Card::Seven, Card::Seven],
+                bid: 28,
+            },
+            HandAndBid {
+                hand: [Card::K, Card::T, Card::J, Card::J, Card::T],
+                bid: 220,
+            },
+            HandAndBid {
+                hand: [Card::Q, Card::Q, Card::Q, Card::J, Card::A],
+                bid: 483,
+            },
+        ]
+    }
+
+    #[test]
+    fn parse_example() -> Result<(), Box<dyn Error>> {
+        let parsed_example = EXAMPLE
+            .lines()
+            .map(parse_hand_and_bid)
+            .collect::<Result<Vec<HandAndBid>, _>>()?;
+
+        assert_eq!(parsed_example, example());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_hand_length() -> Result<(), Box<dyn Error>> {
+        assert!(parse_hand("32T3").is_err());
+        assert_eq!(
+            parse_hand("32T3K")?,
+            [Card::Three, Card::Two, Card::T, Card::Three, Card::K]
+        );
+        assert!(parse_hand("32T3KA").is_err());
+        assert!(parse_hand_and_bid("32T3KA 765").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn strict_deck() -> Result<(), Box<dyn Error>> {
+        assert!(check_deck(&parse_hand("AAAAK")?).is_ok());
+        assert!(check_deck(&parse_hand("AAAAA")?).is_err());
+        assert!(check_deck(&parse_hand("JJJJJ")?).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_input_strict() -> Result<(), Box<dyn Error>> {
+        let lines = "32T3K 765\r\n22222 1\n".as_bytes();
+
+        assert_eq!(parse_input(lines, false)?.len(), 2);
+        assert_eq!(
+            parse_input(lines, true).err().map(|e| e.to_string()),
+            Some("line 2: 22222 1: 5 cards Two in a single deck".to_string())
+        );
+        assert_eq!(parse_input(EXAMPLE.as_bytes(), true)?, example());
+        Ok(())
+    }
+
+    #[test]
+    fn solve1_example() {
+        assert_eq!(solve1(&example()), 6440);
+    }
+
+    #[test]
+    fn solve2_example() {
+        assert_eq!(solve2(&example()), 5905);
+    }
+
+    #[test]
+    fn rank_ties() -> Result<(), Box<dyn Error>> {
+        let hand_and_bids = parse_input(
+            "KK677 1\nKTJJT 2\nKK677 3\n32T3K 4\nKK677 5".as_bytes(),
+            false,
+        )?;
+
+        // the equal hands rank in the order of the input
+        let bids = |ranked: Vec<(u32, &HandAndBid)>| {
+            ranked
+                .into_iter()
+                .map(|(rank, hand_and_bid)| (rank, hand_and_bid.bid))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            bids(rank_hands(&hand_and_bids, type1, Card::cmp)),
+            vec![(1, 4), (2, 2), (3, 1), (4, 3), (5, 5)]
+        );
+        // with the jokers, KTJJT is a four of a kind
+        assert_eq!(
+            bids(rank_hands(&hand_and_bids, type2, cmp2)),
+            vec![(1, 4), (2, 1), (3, 3), (4, 5), (5, 2)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve1() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let cards = reader
+            .lines()
+            .map(|x| parse_hand_and_bid(&x?))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(solve1(&cards), 249483956);
+        Ok(())
+    }
+
+    #[test]
+    fn input_solve2() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let cards = reader
+            .lines()
+            .map(|x| parse_hand_and_bid(&x?))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(solve2(&cards), 252137472);
+        Ok(())
+    }
+}
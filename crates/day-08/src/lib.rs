@@ -0,0 +1,807 @@
+use itertools::FoldWhile::{Continue, Done};
+use itertools::Itertools;
+use lib::INVALID_INPUT;
+use num::integer::{ExtendedGcd, Integer};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+type Path = Vec<Direction>;
+type Label = String;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Directions {
+    left: Label,
+    right: Label,
+}
+
+type Node = (Label, Directions);
+
+pub fn parse_path(s: &str) -> Result<Path, Box<dyn Error>> {
+    s.chars()
+        .map(|c| match c {
+            'L' => Ok(Direction::Left),
+            'R' => Ok(Direction::Right),
+            _ => Err("Invalid direction".into()),
+        })
+        .collect()
+}
+
+pub fn parse_line(s: &str) -> Result<Node, Box<dyn Error>> {
+    let without_whitespaces = s
+        .chars()
+        .filter(|c| !(*c == '(' || *c == ')' || c.is_whitespace()))
+        .collect::<String>();
+
+    let (label, directions_str) = without_whitespaces.split_once('=').ok_or(INVALID_INPUT)?;
+    let (left, right) = directions_str.split_once(',').ok_or(INVALID_INPUT)?;
+
+    Ok((
+        label.to_string(),
+        Directions {
+            left: left.to_string(),
+            right: right.to_string(),
+        },
+    ))
+}
+
+pub fn parse_input(
+    lines: impl Iterator<Item = String>,
+) -> Result<(Path, Vec<Node>), Box<dyn Error>> {
+    let mut lines = lines;
+    let path = parse_path(&lines.next().ok_or(INVALID_INPUT)?)?;
+
+    lines.next();
+
+    let nodes = lines
+        .map(|line| parse_line(&line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((path, nodes))
+}
+
+// the labels interned to their index in the definition order, with the left and right
+// transitions of each node stored at the same index, as well as whether its label ends with A
+// or Z
+#[derive(Debug, PartialEq, Eq)]
+struct Network {
+    labels: Vec<Label>,
+    transitions: Vec<[u16; 2]>,
+    starts: Vec<bool>,
+    ends: Vec<bool>,
+}
+
+impl Network {
+    fn new(nodes: &[Node]) -> Result<Self, Box<dyn Error>> {
+        let indices = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, (label, _))| Ok((label.as_str(), u16::try_from(index)?)))
+            .collect::<Result<HashMap<_, _>, Box<dyn Error>>>()?;
+        if indices.len() != nodes.len() {
+            return Err("Duplicate label".into());
+        }
+
+        let index = |label: &Label| {
+            indices
+                .get(label.as_str())
+                .copied()
+                .ok_or_else(|| format!("Unknown label: {}", label))
+        };
+        let transitions = nodes
+            .iter()
+            .map(|(_, directions)| Ok([index(&directions.left)?, index(&directions.right)?]))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Network {
+            labels: nodes.iter().map(|(label, _)| label.clone()).collect(),
+            transitions,
+            starts: nodes
+                .iter()
+                .map(|(label, _)| label.ends_with('A'))
+                .collect(),
+            ends: nodes
+                .iter()
+                .map(|(label, _)| label.ends_with('Z'))
+                .collect(),
+        })
+    }
+
+    fn index(&self, label: &str) -> Option<u16> {
+        self.labels
+            .iter()
+            .position(|l| l == label)
+            .and_then(|index| u16::try_from(index).ok())
+    }
+
+    // the number of steps to reach a node ending with Z
+    fn steps(&self, path: &Path, start: u16) -> u64 {
+        let mut node = start as usize;
+        let mut count = 0;
+
+        for direction in path.iter().cycle() {
+            if self.ends[node] {
+                break;
+            }
+            node = self.transitions[node][(*direction == Direction::Right) as usize] as usize;
+            count += 1;
+        }
+
+        count
+    }
+}
+
+pub fn solve1(path: Path, start_node: String, nodes: Vec<Node>) -> Result<u64, Box<dyn Error>> {
+    let network = Network::new(&nodes)?;
+    let start = network
+        .index(&start_node)
+        .ok_or("Unable to find the label into the map")?;
+
+    Ok(network.steps(&path, start))
+}
+
+// the walk of a ghost is periodic from offset on, z_positions are the steps before
+// offset + period at which the ghost is on a node ending with Z
+#[derive(Debug, PartialEq, Eq)]
+struct CycleInfo {
+    offset: u64,
+    period: u64,
+    z_positions: Vec<u64>,
+}
+
+impl CycleInfo {
+    fn at_end(&self, step: u64) -> bool {
+        let step = if step < self.offset {
+            step
+        } else {
+            self.offset + (step - self.offset) % self.period
+        };
+        self.z_positions.contains(&step)
+    }
+
+    // the residues modulo the period of the steps ending with Z once in the cycle
+    fn residues(&self) -> Vec<u64> {
+        self.z_positions
+            .iter()
+            .filter(|step| **step >= self.offset)
+            .map(|step| step % self.period)
+            .collect()
+    }
+}
+
+// walk from start until a state, made of the node and the position in the path, repeats
+fn analyze_cycle(path: &Path, start: u16, network: &Network) -> Result<CycleInfo, Box<dyn Error>> {
+    if path.is_empty() {
+        return Err("Empty path".into());
+    }
+
+    let mut seen = vec![None; network.labels.len() * path.len()];
+    let mut node = start as usize;
+    let mut z_positions = Vec::new();
+    let mut step = 0;
+
+    loop {
+        let position = (step % path.len() as u64) as usize;
+        let state = node * path.len() + position;
+        if let Some(offset) = seen[state] {
+            return Ok(CycleInfo {
+                offset,
+                period: step - offset,
+                z_positions,
+            });
+        }
+        seen[state] = Some(step);
+
+        if network.ends[node] {
+            z_positions.push(step);
+        }
+        node = network.transitions[node][(path[position] == Direction::Right) as usize] as usize;
+        step += 1;
+    }
+}
+
+// the number of steps before the ghosts meet doesn't even fit in a u128
+#[derive(Debug, PartialEq, Eq)]
+struct StepsOverflow;
+
+impl Display for StepsOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The number of steps overflows u128")
+    }
+}
+
+impl Error for StepsOverflow {}
+
+// the periods are u64 but their LCM quickly isn't, so it is computed in u128
+fn checked_lcm(m1: u128, m2: u128) -> Result<u128, StepsOverflow> {
+    (m1 / m1.gcd(&m2)).checked_mul(m2).ok_or(StepsOverflow)
+}
+
+// merge x = a1 mod m1 and x = a2 mod m2 into x = a mod lcm(m1, m2), the moduli don't have to be
+// coprime but then a1 and a2 must agree modulo their gcd. m1 is the LCM of the periods merged so
+// far and m2 a period, which keeps the modular inverse in i128
+fn crt(
+    (a1, m1): (u128, u128),
+    (a2, m2): (u64, u64),
+) -> Result<Option<(u128, u128)>, StepsOverflow> {
+    let m2 = u128::from(m2);
+    let gcd = m1.gcd(&m2);
+    let l = checked_lcm(m1, m2)?;
+
+    let diff = (i128::from(a2) - (a1 % m2) as i128).rem_euclid(m2 as i128) as u128;
+    if !diff.is_multiple_of(gcd) {
+        return Ok(None);
+    }
+
+    // m1 * k = diff mod m2, all the values below m2 / gcd so that the product fits
+    let modulus = m2 / gcd;
+    let ExtendedGcd { x, .. } = ((m1 / gcd % modulus) as i128).extended_gcd(&(modulus as i128));
+    let inverse = x.rem_euclid(modulus as i128) as u128;
+    let k = diff / gcd % modulus * inverse % modulus;
+
+    // below l as k < m2 / gcd
+    Ok(Some((a1 + k * m1, l)))
+}
+
+const MAX_COMBINATIONS: usize = 1_000_000;
+
+// The ghosts usually reach Z exactly at the end of their period which makes the answer the LCM
+// of the periods. This handles instead any offset and several Z per cycle, and fails when the
+// ghosts never meet.
+pub fn solve2(path: Path, nodes: Vec<Node>) -> Result<u128, Box<dyn Error>> {
+    let network = Network::new(&nodes)?;
+
+    let cycles = (0..network.labels.len())
+        .filter(|index| network.starts[*index])
+        .map(|index| analyze_cycle(&path, u16::try_from(index)?, &network))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    meet(&cycles)
+}
+
+// the first step at which all the ghosts are on a node ending with Z
+fn meet(cycles: &[CycleInfo]) -> Result<u128, Box<dyn Error>> {
+    if cycles.is_empty() {
+        return Err("Empty node list".into());
+    }
+
+    // the ghosts can meet before all of them are in their cycle
+    let bound = cycles.iter().map(|cycle| cycle.offset).max().unwrap_or(0);
+    let early = cycles
+        .iter()
+        .flat_map(|cycle| cycle.z_positions.iter().copied())
+        .filter(|step| *step < bound)
+        .sorted()
+        .find(|step| cycles.iter().all(|cycle| cycle.at_end(*step)));
+    if let Some(step) = early {
+        return Ok(step.into());
+    }
+
+    let residues = cycles
+        .iter()
+        .map(|cycle| cycle.residues())
+        .collect::<Vec<_>>();
+    if residues.iter().any(|residues| residues.is_empty()) {
+        return Err("A ghost never reaches a node ending with Z in its cycle".into());
+    }
+    if residues
+        .iter()
+        .try_fold(1usize, |acc, residues| acc.checked_mul(residues.len()))
+        .is_none_or(|count| count > MAX_COMBINATIONS)
+    {
+        return Err("Too many combinations of nodes ending with Z".into());
+    }
+
+    residues
+        .iter()
+        .zip(cycles)
+        .map(|(residues, cycle)| residues.iter().map(|residue| (*residue, cycle.period)))
+        .multi_cartesian_product()
+        .map(|congruences| first_step(congruences, bound))
+        .filter_map_ok(|step| step)
+        .process_results(|steps| steps.min())?
+        .ok_or("The ghosts never meet".into())
+}
+
+// the first step after bound satisfying all the congruences, none when they are incompatible
+fn first_step(congruences: Vec<(u64, u64)>, bound: u64) -> Result<Option<u128>, StepsOverflow> {
+    let mut merged = (0, 1);
+    for congruence in congruences {
+        match crt(merged, congruence)? {
+            Some(next) => merged = next,
+            None => return Ok(None),
+        }
+    }
+
+    let ((a, m), bound) = (merged, u128::from(bound));
+    if a >= bound {
+        return Ok(Some(a));
+    }
+    (bound - a)
+        .div_ceil(m)
+        .checked_mul(m)
+        .and_then(|steps| steps.checked_add(a))
+        .map(Some)
+        .ok_or(StepsOverflow)
+}
+
+// the original walk on the labels, kept to check and benchmark the interned one
+fn solve1_hashmap(path: Path, start_node: String, nodes: Vec<Node>) -> Result<u64, Box<dyn Error>> {
+    let map: HashMap<_, _> = nodes.into_iter().collect();
+
+    path.iter()
+        .cycle()
+        .fold_while(
+            Ok((start_node, 0)),
+            |acc: Result<(String, u64), Box<dyn Error>>, current| match acc {
+                e @ Err(_) => Done(e),
+                Ok((label, count)) => {
+                    if label.ends_with('Z') {
+                        Done(Ok((label, count)))
+                    } else {
+                        match map.get(&label) {
+                            Some(directions) => {
+                                let next_node = if *current == Direction::Left {
+                                    directions.left.clone()
+                                } else {
+                                    directions.right.clone()
+                                };
+                                Continue(Ok((next_node, count + 1)))
+                            }
+                            None => Done(Err("Unable to find the label into the map".into())),
+                        }
+                    }
+                }
+            },
+        )
+        .into_inner()
+        .map(|(_, count)| count)
+}
+
+pub fn solve2_hashmap(path: Path, nodes: Vec<Node>) -> Result<u128, Box<dyn Error>> {
+    nodes
+        .iter()
+        .filter(|(label, _)| label.ends_with('A'))
+        .map(|(node, _)| solve1_hashmap(path.clone(), node.to_string(), nodes.clone()))
+        .map(|steps| Ok(u128::from(steps?)))
+        .reduce(|x, y| Ok(checked_lcm(x?, y?)?))
+        .ok_or("Empty node list")?
+}
+
+#[cfg(test)]
+mod day08 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+
+    use crate::{
+        analyze_cycle, checked_lcm, crt, meet, parse_input, solve1, solve1_hashmap, solve2,
+        solve2_hashmap, CycleInfo, Direction, Directions, Network, Node, Path, StepsOverflow,
+    };
+
+    const EXAMPLE1: &str = "\
+        RL\n\
+        \n\
+        AAA = (BBB, CCC)\n\
+        BBB = (DDD, EEE)\n\
+        CCC = (ZZZ, GGG)\n\
+        DDD = (DDD, DDD)\n\
+        EEE = (EEE, EEE)\n\
+        GGG = (GGG, GGG)\n\
+        ZZZ = (ZZZ, ZZZ)";
+
+    fn example1() -> (Path, Vec<Node>) {
+        (
+            vec![Direction::Right, Direction::Left],
+            vec![
+                (
+                    "AAA".to_string(),
+                    Directions {
+                        left: "BBB".to_string(),
+                        right: "CCC".to_string(),
+                    },
+                ),
+                (
+                    "BBB".to_string(),
+                    Directions {
+                        left: "DDD".to_string(),
+                        right: "EEE".to_string(),
+                    },
+                ),
+                (
+                    "CCC".to_string(),
+                    Directions {
+                        left: "ZZZ".to_string(),
+                        right: "GGG".to_string(),
+                    },
+                ),
+                (
+                    "DDD".to_string(),
+                    Directions {
+                        left: "DDD".to_string(),
+                        right: "DDD".to_string(),
+                    },
+                ),
+                (
+                    "EEE".to_string(),
+                    Directions {
+                        left: "EEE".to_string(),
+                        right: "EEE".to_string(),
+                    },
+                ),
+                (
+                    "GGG".to_string(),
+                    Directions {
+                        left: "GGG".to_string(),
+                        right: "GGG".to_string(),
+                    },
+                ),
+                (
+                    "ZZZ".to_string(),
+                    Directions {
+                        left: "ZZZ".to_string(),
+                        right: "ZZZ".to_string(),
+                    },
+                ),
+            ],
+        )
+    }
+
+    const EXAMPLE2: &str = "\
+        LLR\n\
+        \n\
+        AAA = (BBB, BBB)\n\
+        BBB = (AAA, ZZZ)\n\
+        ZZZ = (ZZZ, ZZZ)";
+
+    fn example2() -> (Path, Vec<Node>) {
+        (
+            vec![Direction::Left, Direction::Left, Direction::Right],
+            vec![
+                (
+                    "AAA".to_string(),
+                    Directions {
+                        left: "BBB".to_string(),
+                        right: "BBB".to_string(),
+                    },
+                ),
+                (
+                    "BBB".to_string(),
+                    Directions {
+                        left: "AAA".to_string(),
+                        right: "ZZZ".to_string(),
+                    },
+                ),
+                (
+                    "ZZZ".to_string(),
+                    Directions {
+                        left: "ZZZ".to_string(),
+                        right: "ZZZ".to_string(),
+                    },
+                ),
+            ],
+        )
+    }
+
+    const EXAMPLE3: &str = "\
+        LR\n\
+        \n\
+        11A = (11B, XXX)\n\
+        11B = (XXX, 11Z)\n\
+        11Z = (11B, XXX)\n\
+        22A = (22B, XXX)\n\
+        22B = (22C, 22C)\n\
+        22C = (22Z, 22Z)\n\
+        22Z = (22B, 22B)\n\
+        XXX = (XXX, XXX)";
+
+    fn example3() -> (Path, Vec<Node>) {
+        (
+            vec![Direction::Left, Direction::Right],
+            vec![
+                (
+                    "11A".to_string(),
+                    Directions {
+                        left: "11B".to_string(),
+                        right: "XXX".to_string(),
+                    },
+                ),
+                (
+                    "11B".to_string(),
+                    Directions {
+                        left: "XXX".to_string(),
+                        right: "11Z".to_string(),
+                    },
+                ),
+                (
+                    "11Z".to_string(),
+                    Directions {
+                        left: "11B".to_string(),
+                        right: "XXX".to_string(),
+                    },
+                ),
+                (
+                    "22A".to_string(),
+                    Directions {
+                        left: "22B".to_string(),
+                        right: "XXX".to_string(),
+                    },
+                ),
+                (
+                    "22B".to_string(),
+                    Directions {
+                        left: "22C".to_string(),
+                        right: "22C".to_string(),
+                    },
+                ),
+                (
+                    "22C".to_string(),
+                    Directions {
+                        left: "22Z".to_string(),
+                        right: "22Z".to_string(),
+                    },
+                ),
+                (
+                    "22Z".to_string(),
+                    Directions {
+                        left: "22B".to_string(),
+                        right: "22B".to_string(),
+                    },
+                ),
+                (
+                    "XXX".to_string(),
+                    Directions {
+                        left: "XXX".to_string(),
+                        right: "XXX".to_string(),
+                    },
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_parse_example1() -> Result<(), Box<dyn Error>> {
+        let parsed_example = parse_input(EXAMPLE1.lines().map(|line| line.to_string()))?;
+
+        assert_eq!(parsed_example, example1());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_example2() -> Result<(), Box<dyn Error>> {
+        let parsed_example = parse_input(EXAMPLE2.lines().map(|line| line.to_string()))?;
+
+        assert_eq!(parsed_example, example2());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_example3() -> Result<(), Box<dyn Error>> {
+        let parsed_example = parse_input(EXAMPLE3.lines().map(|line| line.to_string()))?;
+
+        assert_eq!(parsed_example, example3());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_example1() -> Result<(), Box<dyn Error>> {
+        let result = solve1(example1().0, "AAA".to_string(), example1().1)?;
+        assert_eq!(result, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_example2() -> Result<(), Box<dyn Error>> {
+        let result = solve1(example2().0, "AAA".to_string(), example2().1)?;
+
+        assert_eq!(result, 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_example3() -> Result<(), Box<dyn Error>> {
+        let result = solve2(example3().0, example3().1)?;
+
+        assert_eq!(result, 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_cycle() -> Result<(), Box<dyn Error>> {
+        let (path, nodes) = example3();
+        let network = Network::new(&nodes)?;
+
+        assert_eq!(
+            analyze_cycle(&path, 0, &network)?,
+            CycleInfo {
+                offset: 1,
+                period: 2,
+                z_positions: vec![2]
+            }
+        );
+        assert_eq!(
+            analyze_cycle(&path, 3, &network)?,
+            CycleInfo {
+                offset: 1,
+                period: 6,
+                z_positions: vec![3, 6]
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_crt() -> Result<(), Box<dyn Error>> {
+        assert_eq!(crt((2, 3), (1, 2)), Ok(Some((5, 6))));
+        assert_eq!(crt((0, 4), (2, 6)), Ok(Some((8, 12))));
+        assert_eq!(crt((1, 4), (2, 6)), Ok(None));
+
+        // consecutive periods are coprime, their LCM doesn't fit in u64
+        let (m1, m2) = (u64::MAX, u64::MAX - 1);
+        let (a, l) = crt((5, m1.into()), (7, m2))?.ok_or("Incompatible congruences")?;
+        assert_eq!(l, u128::from(m1) * u128::from(m2));
+        assert_eq!((a % u128::from(m1), a % u128::from(m2)), (5, 7));
+        assert_eq!(crt((a, l), (0, u64::MAX - 2)), Err(StepsOverflow));
+        Ok(())
+    }
+
+    fn cycle(period: u64) -> CycleInfo {
+        CycleInfo {
+            offset: 0,
+            period,
+            z_positions: vec![period - 1],
+        }
+    }
+
+    #[test]
+    fn test_meet_overflow() -> Result<(), Box<dyn Error>> {
+        // on Z one step before the end of their periods, so they meet one step before the LCM
+        let (m1, m2) = (u64::MAX, u64::MAX - 1);
+        assert_eq!(
+            checked_lcm(m1.into(), m2.into()),
+            Ok(u128::from(m1) * u128::from(m2))
+        );
+        assert_eq!(
+            meet(&[cycle(m1), cycle(m2)])?,
+            u128::from(m1) * u128::from(m2) - 1
+        );
+        assert_eq!(meet(&[cycle(m1), cycle(m1)])?, u128::from(m1) - 1);
+
+        let overflow = meet(&[cycle(m1), cycle(m2), cycle(u64::MAX - 2)]);
+        assert!(overflow.is_err_and(|e| e.downcast_ref::<StepsOverflow>().is_some()));
+        assert_eq!(checked_lcm(u128::MAX, u128::from(m2)), Err(StepsOverflow));
+        Ok(())
+    }
+
+    fn ghosts(nodes: &str) -> Result<(Path, Vec<Node>), Box<dyn Error>> {
+        parse_input(["L", ""].into_iter().chain(nodes.lines()).map(String::from))
+    }
+
+    #[test]
+    fn test_solve2_offsets() -> Result<(), Box<dyn Error>> {
+        // on Z at 2, 5, 8... and at 1, 3, 5... the LCM of the first steps would give 2
+        let (path, nodes) = ghosts(
+            "\
+            1A = (1B, 1B)\n\
+            1B = (1Z, 1Z)\n\
+            1Z = (1A, 1A)\n\
+            2A = (2Z, 2Z)\n\
+            2Z = (2A, 2A)",
+        )?;
+        assert_eq!(solve2(path, nodes)?, 5);
+
+        // both on Z at 1, the first ghost then stays away from Z
+        let (path, nodes) = ghosts(
+            "\
+            1A = (1Z, 1Z)\n\
+            1Z = (1X, 1X)\n\
+            1X = (1X, 1X)\n\
+            2A = (2Z, 2Z)\n\
+            2Z = (2Z, 2Z)",
+        )?;
+        assert_eq!(solve2(path, nodes)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_invalid() -> Result<(), Box<dyn Error>> {
+        // on Z at odd steps and at even steps
+        let (path, nodes) = ghosts(
+            "\
+            1A = (1Z, 1Z)\n\
+            1Z = (1A, 1A)\n\
+            2A = (2B, 2B)\n\
+            2B = (2Z, 2Z)\n\
+            2Z = (2B, 2B)",
+        )?;
+        assert!(solve2(path, nodes).is_err());
+
+        // the second ghost never reaches Z
+        let (path, nodes) = ghosts(
+            "\
+            1A = (1Z, 1Z)\n\
+            1Z = (1A, 1A)\n\
+            2A = (2A, 2A)",
+        )?;
+        assert!(solve2(path, nodes).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_network() -> Result<(), Box<dyn Error>> {
+        let network = Network::new(&example2().1)?;
+
+        assert_eq!(network.labels, vec!["AAA", "BBB", "ZZZ"]);
+        assert_eq!(network.transitions, vec![[1, 1], [0, 2], [2, 2]]);
+        assert_eq!(network.starts, vec![true, false, false]);
+        assert_eq!(network.ends, vec![false, false, true]);
+        assert_eq!(network.index("BBB"), Some(1));
+        assert_eq!(network.index("CCC"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_network_invalid() {
+        let mut nodes = example2().1;
+        nodes[0].1.left = "CCC".to_string();
+        assert!(Network::new(&nodes).is_err());
+
+        let mut nodes = example2().1;
+        nodes.push(nodes[0].clone());
+        assert!(Network::new(&nodes).is_err());
+    }
+
+    #[test]
+    fn test_interned_matches_hashmap() -> Result<(), Box<dyn Error>> {
+        [example1(), example2()].into_iter().try_for_each(
+            |(path, nodes)| -> Result<(), Box<dyn Error>> {
+                assert_eq!(
+                    solve1(path.clone(), "AAA".to_string(), nodes.clone())?,
+                    solve1_hashmap(path, "AAA".to_string(), nodes)?
+                );
+                Ok(())
+            },
+        )?;
+
+        let (path, nodes) = example3();
+        assert_eq!(
+            solve2(path.clone(), nodes.clone())?,
+            solve2_hashmap(path, nodes)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let (path, nodes) = reader.lines().process_results(|itr| parse_input(itr))??;
+        let result = solve1(path, "AAA".to_string(), nodes)?;
+
+        assert_eq!(result, 16531);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let (path, nodes) = reader.lines().process_results(|itr| parse_input(itr))??;
+        let result = solve2(path, nodes)?;
+
+        assert_eq!(result, 24035773251517);
+        Ok(())
+    }
+}
@@ -1,395 +1,80 @@
-use itertools::FoldWhile::{Continue, Done};
+use day_08::{parse_input, parse_line, parse_path, solve1, solve2, solve2_hashmap};
 use itertools::Itertools;
-use lib::{get_args, INVALID_INPUT};
-use num::integer::lcm;
-use std::io::{stdin, BufRead};
-use std::{collections::HashMap, error::Error, process::exit};
-
-fn usage(prog_name: String) {
-    println!("Usage: {} [-1|-2|-h]", prog_name);
+use lib::{
+    cli::Options,
+    validate::{check_lines, check_sections, sections, Problem},
+};
+use std::io::BufRead;
+use std::{error::Error, process::exit, time::Instant};
+
+fn usage(options: &Options) {
+    println!("{}", options.usage("[-1|-2|--bench|--validate|-h]"));
     exit(0)
 }
 
+// the path on the first line, then the nodes after an empty line
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let sections = sections(lines);
+
+    let path = sections
+        .first()
+        .into_iter()
+        .flat_map(|(line, section)| check_lines(section, *line, parse_path));
+
+    let nodes = sections
+        .get(1)
+        .into_iter()
+        .flat_map(|(line, section)| check_lines(section, *line, parse_line));
+
+    check_sections(lines, Some(2))
+        .into_iter()
+        .chain(path)
+        .chain(nodes)
+        .collect()
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let (prog_name, args) = get_args()?;
+    let options = Options::parse()?;
+    let _timer = options.timer();
 
-    match args.get(0) {
+    match options.mode() {
         Some(arg) if arg == "-1" || arg == "-2" => {
-            let solve = match arg.as_str() {
-                "-1" => |path, nodes| solve1(path, "AAA".to_string(), nodes),
+            let solve: fn(_, _) -> Result<u128, Box<dyn Error>> = match arg.as_str() {
+                "-1" => |path, nodes| Ok(solve1(path, "AAA".to_string(), nodes)?.into()),
                 _ => solve2,
             };
 
-            let (path, nodes) = stdin()
-                .lock()
+            let (path, nodes) = options
+                .input()?
                 .lines()
                 .process_results(|itr| parse_input(itr))??;
             let result = solve(path, nodes)?;
 
             println!("{}", result)
         }
-        _ => usage(prog_name),
-    }
-    Ok(())
-}
-
-#[derive(Debug, PartialEq, Eq, Clone)]
-enum Direction {
-    Left,
-    Right,
-}
-
-type Path = Vec<Direction>;
-type Label = String;
-
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct Directions {
-    left: Label,
-    right: Label,
-}
-
-type Node = (Label, Directions);
-
-fn parse_path(s: &str) -> Result<Path, Box<dyn Error>> {
-    s.chars()
-        .map(|c| match c {
-            'L' => Ok(Direction::Left),
-            'R' => Ok(Direction::Right),
-            _ => Err("Invalid direction".into()),
-        })
-        .collect()
-}
-
-fn parse_line(s: &str) -> Result<Node, Box<dyn Error>> {
-    let without_whitespaces = s
-        .chars()
-        .filter(|c| !(*c == '(' || *c == ')' || c.is_whitespace()))
-        .collect::<String>();
-
-    let (label, directions_str) = without_whitespaces.split_once('=').ok_or(INVALID_INPUT)?;
-    let (left, right) = directions_str.split_once(',').ok_or(INVALID_INPUT)?;
-
-    Ok((
-        label.to_string(),
-        Directions {
-            left: left.to_string(),
-            right: right.to_string(),
-        },
-    ))
-}
-
-fn parse_input(lines: impl Iterator<Item = String>) -> Result<(Path, Vec<Node>), Box<dyn Error>> {
-    let mut lines = lines;
-    let path = parse_path(&lines.next().ok_or(INVALID_INPUT)?)?;
-
-    lines.next();
-
-    let nodes = lines
-        .map(|line| parse_line(&line))
-        .collect::<Result<Vec<_>, _>>()?;
-
-    Ok((path, nodes))
-}
-
-fn solve1(path: Path, start_node: String, nodes: Vec<Node>) -> Result<u64, Box<dyn Error>> {
-    let map: HashMap<_, _> = nodes.into_iter().collect();
-
-    path.iter()
-        .cycle()
-        .fold_while(
-            Ok((start_node, 0)),
-            |acc: Result<(String, u64), Box<dyn Error>>, current| match acc {
-                e @ Err(_) => Done(e),
-                Ok((label, count)) => {
-                    if label.ends_with('Z') {
-                        Done(Ok((label, count)))
-                    } else {
-                        match map.get(&label) {
-                            Some(directions) => {
-                                let next_node = if *current == Direction::Left {
-                                    directions.left.clone()
-                                } else {
-                                    directions.right.clone()
-                                };
-                                Continue(Ok((next_node, count + 1)))
-                            }
-                            None => Done(Err("Unable to find the label into the map".into())),
-                        }
-                    }
-                }
-            },
-        )
-        .into_inner()
-        .map(|(_, count)| count)
-}
-
-fn solve2(path: Path, nodes: Vec<Node>) -> Result<u64, Box<dyn Error>> {
-    nodes
-        .iter()
-        .filter(|(label, _)| label.ends_with('A'))
-        .map(|(node, _)| solve1(path.clone(), node.to_string(), nodes.clone()))
-        .reduce(|x, y| Ok(lcm(x?, y?)))
-        .ok_or("Empty node list")?
-}
-
-#[cfg(test)]
-mod day08 {
-    use std::{
-        error::Error,
-        fs::File,
-        io::{BufRead, BufReader},
-    };
-
-    use itertools::Itertools;
-
-    use crate::{parse_input, solve1, solve2, Direction, Directions, Node, Path};
-
-    const EXAMPLE1: &str = "\
-        RL\n\
-        \n\
-        AAA = (BBB, CCC)\n\
-        BBB = (DDD, EEE)\n\
-        CCC = (ZZZ, GGG)\n\
-        DDD = (DDD, DDD)\n\
-        EEE = (EEE, EEE)\n\
-        GGG = (GGG, GGG)\n\
-        ZZZ = (ZZZ, ZZZ)";
-
-    fn example1() -> (Path, Vec<Node>) {
-        (
-            vec![Direction::Right, Direction::Left],
-            vec![
-                (
-                    "AAA".to_string(),
-                    Directions {
-                        left: "BBB".to_string(),
-                        right: "CCC".to_string(),
-                    },
-                ),
-                (
-                    "BBB".to_string(),
-                    Directions {
-                        left: "DDD".to_string(),
-                        right: "EEE".to_string(),
-                    },
-                ),
-                (
-                    "CCC".to_string(),
-                    Directions {
-                        left: "ZZZ".to_string(),
-                        right: "GGG".to_string(),
-                    },
-                ),
-                (
-                    "DDD".to_string(),
-                    Directions {
-                        left: "DDD".to_string(),
-                        right: "DDD".to_string(),
-                    },
-                ),
-                (
-                    "EEE".to_string(),
-                    Directions {
-                        left: "EEE".to_string(),
-                        right: "EEE".to_string(),
-                    },
-                ),
-                (
-                    "GGG".to_string(),
-                    Directions {
-                        left: "GGG".to_string(),
-                        right: "GGG".to_string(),
-                    },
-                ),
-                (
-                    "ZZZ".to_string(),
-                    Directions {
-                        left: "ZZZ".to_string(),
-                        right: "ZZZ".to_string(),
-                    },
-                ),
-            ],
-        )
-    }
-
-    const EXAMPLE2: &str = "\
-        LLR\n\
-        \n\
-        AAA = (BBB, BBB)\n\
-        BBB = (AAA, ZZZ)\n\
-        ZZZ = (ZZZ, ZZZ)";
-
-    fn example2() -> (Path, Vec<Node>) {
-        (
-            vec![Direction::Left, Direction::Left, Direction::Right],
-            vec![
-                (
-                    "AAA".to_string(),
-                    Directions {
-                        left: "BBB".to_string(),
-                        right: "BBB".to_string(),
-                    },
-                ),
-                (
-                    "BBB".to_string(),
-                    Directions {
-                        left: "AAA".to_string(),
-                        right: "ZZZ".to_string(),
-                    },
-                ),
-                (
-                    "ZZZ".to_string(),
-                    Directions {
-                        left: "ZZZ".to_string(),
-                        right: "ZZZ".to_string(),
-                    },
-                ),
-            ],
-        )
-    }
-
-    const EXAMPLE3: &str = "\
-        LR\n\
-        \n\
-        11A = (11B, XXX)\n\
-        11B = (XXX, 11Z)\n\
-        11Z = (11B, XXX)\n\
-        22A = (22B, XXX)\n\
-        22B = (22C, 22C)\n\
-        22C = (22Z, 22Z)\n\
-        22Z = (22B, 22B)\n\
-        XXX = (XXX, XXX)";
-
-    fn example3() -> (Path, Vec<Node>) {
-        (
-            vec![Direction::Left, Direction::Right],
-            vec![
-                (
-                    "11A".to_string(),
-                    Directions {
-                        left: "11B".to_string(),
-                        right: "XXX".to_string(),
-                    },
-                ),
-                (
-                    "11B".to_string(),
-                    Directions {
-                        left: "XXX".to_string(),
-                        right: "11Z".to_string(),
-                    },
-                ),
-                (
-                    "11Z".to_string(),
-                    Directions {
-                        left: "11B".to_string(),
-                        right: "XXX".to_string(),
-                    },
-                ),
-                (
-                    "22A".to_string(),
-                    Directions {
-                        left: "22B".to_string(),
-                        right: "XXX".to_string(),
-                    },
-                ),
-                (
-                    "22B".to_string(),
-                    Directions {
-                        left: "22C".to_string(),
-                        right: "22C".to_string(),
-                    },
-                ),
-                (
-                    "22C".to_string(),
-                    Directions {
-                        left: "22Z".to_string(),
-                        right: "22Z".to_string(),
-                    },
-                ),
-                (
-                    "22Z".to_string(),
-                    Directions {
-                        left: "22B".to_string(),
-                        right: "22B".to_string(),
-                    },
-                ),
-                (
-                    "XXX".to_string(),
-                    Directions {
-                        left: "XXX".to_string(),
-                        right: "XXX".to_string(),
-                    },
-                ),
-            ],
-        )
-    }
-
-    #[test]
-    fn test_parse_example1() -> Result<(), Box<dyn Error>> {
-        let parsed_example = parse_input(EXAMPLE1.lines().map(|line| line.to_string()))?;
-
-        assert_eq!(parsed_example, example1());
-        Ok(())
-    }
-
-    #[test]
-    fn test_parse_example2() -> Result<(), Box<dyn Error>> {
-        let parsed_example = parse_input(EXAMPLE2.lines().map(|line| line.to_string()))?;
-
-        assert_eq!(parsed_example, example2());
-        Ok(())
-    }
-
-    #[test]
-    fn test_parse_example3() -> Result<(), Box<dyn Error>> {
-        let parsed_example = parse_input(EXAMPLE3.lines().map(|line| line.to_string()))?;
-
-        assert_eq!(parsed_example, example3());
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve1_example1() -> Result<(), Box<dyn Error>> {
-        let result = solve1(example1().0, "AAA".to_string(), example1().1)?;
-        assert_eq!(result, 2);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve1_example2() -> Result<(), Box<dyn Error>> {
-        let result = solve1(example2().0, "AAA".to_string(), example2().1)?;
-
-        assert_eq!(result, 6);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve2_example3() -> Result<(), Box<dyn Error>> {
-        let result = solve2(example3().0, example3().1)?;
-
-        assert_eq!(result, 6);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
-        let file = File::open("input")?;
-        let reader = BufReader::new(file);
-        let (path, nodes) = reader.lines().process_results(|itr| parse_input(itr))??;
-        let result = solve1(path, "AAA".to_string(), nodes)?;
+        Some(arg) if arg == "--bench" => {
+            let (path, nodes) = options
+                .input()?
+                .lines()
+                .process_results(|itr| parse_input(itr))??;
 
-        assert_eq!(result, 16531);
-        Ok(())
-    }
+            let start = Instant::now();
+            let hashmap = solve2_hashmap(path.clone(), nodes.clone())?;
+            let hashmap_elapsed = start.elapsed();
 
-    #[test]
-    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
-        let file = File::open("input")?;
-        let reader = BufReader::new(file);
-        let (path, nodes) = reader.lines().process_results(|itr| parse_input(itr))??;
-        let result = solve2(path, nodes)?;
+            let start = Instant::now();
+            let interned = solve2(path, nodes)?;
+            let interned_elapsed = start.elapsed();
 
-        assert_eq!(result, 24035773251517);
-        Ok(())
+            if hashmap != interned {
+                return Err(format!("Results differ: {} and {}", hashmap, interned).into());
+            }
+            println!("{}", interned);
+            println!("hashmap:  {:?}", hashmap_elapsed);
+            println!("interned: {:?}", interned_elapsed);
+        }
+        Some(arg) if arg == "--validate" => lib::validate::run(options.input()?, validate)?,
+        _ => usage(&options),
     }
+    Ok(())
 }
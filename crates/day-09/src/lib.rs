@@ -0,0 +1,418 @@
+use itertools::Itertools;
+use std::{error::Error, iter::from_fn};
+
+pub fn parse_line(line: &str) -> Result<Vec<i64>, Box<dyn Error>> {
+    line.split_whitespace()
+        .map(|s| s.parse::<i64>().map_err(|e| e.into()))
+        .collect()
+}
+
+// iterate over the rows of differences of a sequence, starting with the sequence itself and
+// ending with the first row made only of zeros. Only the current row is kept in memory.
+pub fn differences(numbers: &[i64]) -> impl Iterator<Item = Result<Vec<i64>, Box<dyn Error>>> {
+    let mut next_row: Option<Result<Vec<i64>, Box<dyn Error>>> = Some(Ok(numbers.to_vec()));
+
+    from_fn(move || {
+        let row = next_row.take()?;
+
+        if let Ok(numbers) = &row {
+            if !numbers.iter().all(|n| *n == 0) {
+                next_row = Some(
+                    numbers
+                        .windows(2)
+                        .map(|w| match w {
+                            [x0, x1] => x1.checked_sub(*x0).ok_or("Overflow".into()),
+                            _ => Err("Invalid window".into()),
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        Some(row)
+    })
+}
+
+// extrapolates a sequence forward or backward
+pub type Extrapolate = fn(&[i64]) -> Result<i64, Box<dyn Error>>;
+
+// the next value is the sum of the last values of all the rows
+pub fn extrapolate_forward(numbers: &[i64]) -> Result<i64, Box<dyn Error>> {
+    if numbers.is_empty() {
+        return Err("Empty sequence".into());
+    }
+
+    differences(numbers).try_fold(0i64, |acc, row| {
+        let last = row?.last().copied().unwrap_or(0);
+        acc.checked_add(last).ok_or("Overflow".into())
+    })
+}
+
+// the previous value is the alternating sum of the first values of all the rows
+pub fn extrapolate_backward(numbers: &[i64]) -> Result<i64, Box<dyn Error>> {
+    if numbers.is_empty() {
+        return Err("Empty sequence".into());
+    }
+
+    differences(numbers)
+        .enumerate()
+        .try_fold(0i64, |acc, (i, row)| {
+            let first = row?.first().copied().unwrap_or(0);
+            let result = if i % 2 == 0 {
+                acc.checked_add(first)
+            } else {
+                acc.checked_sub(first)
+            };
+            result.ok_or("Overflow".into())
+        })
+}
+
+// the number of rows of differences before the first row made only of zeros
+pub fn degree(numbers: &[i64]) -> Result<usize, Box<dyn Error>> {
+    let mut degree = 0;
+    for row in differences(numbers) {
+        if row?.iter().all(|n| *n == 0) {
+            break;
+        }
+        degree += 1;
+    }
+    Ok(degree)
+}
+
+// a sequence is stable when its differences reach a row of at least two zeros. Any single value
+// can be chosen to cancel the last row so a shorter row of zeros is no evidence of anything.
+fn is_stable(numbers: &[i64], degree: usize) -> bool {
+    numbers.len() < 3 || degree + 2 <= numbers.len()
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Outlier {
+    pub index: usize,
+    pub value: i64,
+    pub expected: i64,
+}
+
+// replace the values at the unknown indices, one at a time, by extrapolating the longest run of
+// known values next to one of them
+fn fill(numbers: &[i64], unknown: &[usize]) -> Result<Vec<i64>, Box<dyn Error>> {
+    let mut numbers = numbers.to_vec();
+    let mut known = vec![true; numbers.len()];
+    for index in unknown {
+        known[*index] = false;
+    }
+
+    while known.contains(&false) {
+        let (start, end) = (0..numbers.len())
+            .filter(|i| known[*i] && (*i == 0 || !known[*i - 1]))
+            .map(|start| {
+                let end = (start..numbers.len())
+                    .find(|i| !known[*i])
+                    .unwrap_or(numbers.len());
+                (start, end)
+            })
+            .max_by_key(|(start, end)| end - start)
+            .ok_or("No known value")?;
+
+        let index = if end < numbers.len() {
+            numbers[end] = extrapolate_forward(&numbers[start..end])?;
+            end
+        } else {
+            numbers[start - 1] = extrapolate_backward(&numbers[start..end])?;
+            start - 1
+        };
+        known[index] = true;
+    }
+
+    Ok(numbers)
+}
+
+// replace up to tolerance corrupted values so that the sequence becomes stable. The smallest set
+// of values whose removal makes the differences stabilize wins, ties are broken by the lowest
+// degree. Returns the cleaned sequence along with the replaced values.
+pub fn clean(
+    numbers: &[i64],
+    tolerance: usize,
+) -> Result<(Vec<i64>, Vec<Outlier>), Box<dyn Error>> {
+    if numbers.is_empty() {
+        return Err("Empty sequence".into());
+    }
+
+    if is_stable(numbers, degree(numbers)?) {
+        return Ok((numbers.to_vec(), vec![]));
+    }
+
+    for size in 1..=tolerance.min(numbers.len() - 1) {
+        let best = (0..numbers.len())
+            .combinations(size)
+            .filter_map(|unknown| {
+                let repaired = fill(numbers, &unknown).ok()?;
+                let repaired_degree = degree(&repaired).ok()?;
+                is_stable(&repaired, repaired_degree).then_some((repaired_degree, repaired))
+            })
+            .min_by_key(|(repaired_degree, _)| *repaired_degree);
+
+        if let Some((_, repaired)) = best {
+            let outliers = numbers
+                .iter()
+                .zip(&repaired)
+                .enumerate()
+                .filter(|(_, (value, expected))| value != expected)
+                .map(|(index, (value, expected))| Outlier {
+                    index,
+                    value: *value,
+                    expected: *expected,
+                })
+                .collect();
+            return Ok((repaired, outliers));
+        }
+    }
+
+    Err(format!("More than {} corrupted values", tolerance).into())
+}
+
+pub fn solve(
+    itr: impl Iterator<Item = String>,
+    extrapolate: Extrapolate,
+) -> Result<i64, Box<dyn Error>> {
+    itr.map(|line| {
+        let parsed_lined = parse_line(&line)?;
+        extrapolate(&parsed_lined)
+    })
+    .sum()
+}
+
+// clean every history before extrapolating it, the replaced values are reported on stderr
+pub fn solve_robust(
+    itr: impl Iterator<Item = String>,
+    extrapolate: Extrapolate,
+    tolerance: usize,
+) -> Result<i64, Box<dyn Error>> {
+    itr.enumerate()
+        .map(|(i, line)| {
+            let parsed_line = parse_line(&line)?;
+            let (numbers, outliers) =
+                clean(&parsed_line, tolerance).map_err(|e| format!("line {}: {}", i + 1, e))?;
+            for outlier in outliers {
+                eprintln!(
+                    "line {}: value {} at index {} replaced by {}",
+                    i + 1,
+                    outlier.value,
+                    outlier.index,
+                    outlier.expected
+                );
+            }
+            extrapolate(&numbers)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod day09 {
+    use std::error::Error;
+
+    use lib::{aoc_tests, testing::Lines};
+
+    use crate::{
+        clean, degree, differences, extrapolate_backward, extrapolate_forward, parse_line, solve,
+        solve_robust, Outlier,
+    };
+
+    #[test]
+    fn test_differences() -> Result<(), Box<dyn Error>> {
+        let rows = differences(&[1, 3, 6, 10, 15, 21]).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            rows,
+            vec![
+                vec![1, 3, 6, 10, 15, 21],
+                vec![2, 3, 4, 5, 6],
+                vec![1, 1, 1, 1],
+                vec![0, 0, 0],
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_example() -> Result<(), Box<dyn Error>> {
+        assert_eq!(extrapolate_forward(&[0, 3, 6, 9, 12, 15])?, 18);
+        assert_eq!(extrapolate_forward(&[1, 3, 6, 10, 15, 21])?, 28);
+        assert_eq!(extrapolate_forward(&[10, 13, 16, 21, 30, 45])?, 68);
+
+        assert_eq!(extrapolate_backward(&[0, 3, 6, 9, 12, 15])?, -3);
+        assert_eq!(extrapolate_backward(&[1, 3, 6, 10, 15, 21])?, 0);
+        assert_eq!(extrapolate_backward(&[10, 13, 16, 21, 30, 45])?, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_constant() -> Result<(), Box<dyn Error>> {
+        assert_eq!(extrapolate_forward(&[7])?, 7);
+        assert_eq!(extrapolate_forward(&[7; 1000])?, 7);
+        assert_eq!(extrapolate_backward(&[7; 1000])?, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn test_linear() -> Result<(), Box<dyn Error>> {
+        let numbers = (0..10_000).map(|i| 3 * i - 5).collect::<Vec<_>>();
+        assert_eq!(extrapolate_forward(&numbers)?, 3 * 10_000 - 5);
+        assert_eq!(extrapolate_backward(&numbers)?, -8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quadratic() -> Result<(), Box<dyn Error>> {
+        let f = |i: i64| 2 * i * i - 3 * i + 1;
+        let numbers = (0..10_000).map(f).collect::<Vec<_>>();
+        assert_eq!(extrapolate_forward(&numbers)?, f(10_000));
+        assert_eq!(extrapolate_backward(&numbers)?, f(-1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(extrapolate_forward(&[]).is_err());
+        assert!(extrapolate_backward(&[]).is_err());
+        assert!(clean(&[], 1).is_err());
+    }
+
+    #[test]
+    fn test_degree() -> Result<(), Box<dyn Error>> {
+        assert_eq!(degree(&[0, 0, 0])?, 0);
+        assert_eq!(degree(&[7, 7, 7])?, 1);
+        assert_eq!(degree(&[0, 3, 6, 9, 12, 15])?, 2);
+        assert_eq!(degree(&[1, 3, 6, 10, 15, 21])?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_untouched() -> Result<(), Box<dyn Error>> {
+        let numbers = [10, 13, 16, 21, 30, 45];
+        assert_eq!(clean(&numbers, 0)?, (numbers.to_vec(), vec![]));
+
+        // too short to tell anything
+        assert_eq!(clean(&[7, 8], 0)?, (vec![7, 8], vec![]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_one_outlier() -> Result<(), Box<dyn Error>> {
+        let (numbers, outliers) = clean(&[1, 3, 6, 99, 15, 21], 1)?;
+        assert_eq!(numbers, vec![1, 3, 6, 10, 15, 21]);
+        assert_eq!(
+            outliers,
+            vec![Outlier {
+                index: 3,
+                value: 99,
+                expected: 10
+            }]
+        );
+        assert_eq!(extrapolate_forward(&numbers)?, 28);
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_edges() -> Result<(), Box<dyn Error>> {
+        let (numbers, outliers) = clean(&[-4, 3, 6, 9, 12, 15], 1)?;
+        assert_eq!(numbers, vec![0, 3, 6, 9, 12, 15]);
+        assert_eq!(outliers[0].index, 0);
+
+        let (numbers, outliers) = clean(&[0, 3, 6, 9, 12, 16], 1)?;
+        assert_eq!(numbers, vec![0, 3, 6, 9, 12, 15]);
+        assert_eq!(outliers[0].index, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_two_outliers() -> Result<(), Box<dyn Error>> {
+        let f = |i: i64| 2 * i * i - 3 * i + 1;
+        let expected = (0..12).map(f).collect::<Vec<_>>();
+        let mut noisy = expected.clone();
+        noisy[2] += 5;
+        noisy[8] -= 40;
+
+        assert!(clean(&noisy, 1).is_err());
+
+        let (numbers, outliers) = clean(&noisy, 2)?;
+        assert_eq!(numbers, expected);
+        assert_eq!(
+            outliers,
+            vec![
+                Outlier {
+                    index: 2,
+                    value: f(2) + 5,
+                    expected: f(2)
+                },
+                Outlier {
+                    index: 8,
+                    value: f(8) - 40,
+                    expected: f(8)
+                }
+            ]
+        );
+        Ok(())
+    }
+
+    const LINE1: &str = "0 3 6 9 12 15";
+    fn line1() -> Vec<i64> {
+        vec![0, 3, 6, 9, 12, 15]
+    }
+    const LINE2: &str = "1 3 6 10 15 21";
+    fn line2() -> Vec<i64> {
+        vec![1, 3, 6, 10, 15, 21]
+    }
+    const LINE3: &str = "10 13 16 21 30 45";
+    fn line3() -> Vec<i64> {
+        vec![10, 13, 16, 21, 30, 45]
+    }
+
+    const EXAMPLE: &str = "\
+        0 3 6 9 12 15\n\
+        1 3 6 10 15 21\n\
+        10 13 16 21 30 45";
+
+    fn forward(lines: Lines) -> Result<i64, Box<dyn Error>> {
+        solve(lines, extrapolate_forward)
+    }
+
+    fn backward(lines: Lines) -> Result<i64, Box<dyn Error>> {
+        solve(lines, extrapolate_backward)
+    }
+
+    #[test]
+    fn test_parse_lines() -> Result<(), Box<dyn Error>> {
+        let parsed_line1 = parse_line(LINE1)?;
+        assert_eq!(parsed_line1, line1());
+
+        let parsed_line2 = parse_line(LINE2)?;
+        assert_eq!(parsed_line2, line2());
+
+        let parsed_line3 = parse_line(LINE3)?;
+        assert_eq!(parsed_line3, line3());
+
+        Ok(())
+    }
+
+    aoc_tests! {
+        test_solve1_lines: example(EXAMPLE), Ok, forward => 114;
+        test_solve2_lines: example(EXAMPLE), Ok, backward => 2;
+        test_solve1_input: file("input"), Ok, forward => 1969958987;
+        test_solve2_input: file("input"), Ok, backward => 1068;
+    }
+
+    #[test]
+    fn test_solve_robust_lines() -> Result<(), Box<dyn Error>> {
+        let noisy = vec![
+            LINE1.to_string(),
+            "1 3 6 10 15 21 28 36 45 55 -3".to_string(),
+            "10 13 16 21 30 44 68".to_string(),
+        ];
+        let result = solve_robust(noisy.into_iter(), extrapolate_forward, 1)?;
+        assert_eq!(result, 18 + 78 + 101);
+
+        let noisy = vec!["1 2 3 4 5 6 7 0 9 10 11 12".to_string()];
+        assert!(solve_robust(noisy.into_iter(), extrapolate_forward, 0).is_err());
+
+        Ok(())
+    }
+}
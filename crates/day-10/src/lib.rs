@@ -0,0 +1,939 @@
+use itertools::Itertools;
+use lib::geom::interior_point_count;
+use std::{cell::RefCell, collections::HashSet, error::Error, rc::Rc};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Tile {
+    NorthSouth,
+    EastWest,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+    Start,
+    Ground,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+pub type Maze = Vec<Vec<Tile>>;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Tree {
+    position: Coordinates,
+    children: Vec<Rc<RefCell<Tree>>>,
+}
+
+// Tree is a recursive structure. The default Drop implementation is recursive too. It can lead to
+// stack overflows. So we need an iterative implementation.
+impl Drop for Tree {
+    fn drop(&mut self) {
+        let mut stack: Vec<Rc<RefCell<Tree>>> = Vec::new();
+        for child in self.children.clone() {
+            stack.push(child);
+        }
+        while let Some(current) = stack.pop() {
+            for child in current.borrow().children.iter() {
+                stack.push(child.clone());
+            }
+            current.borrow_mut().children.clear();
+        }
+    }
+}
+
+type Coordinates = (i32, i32);
+
+fn parse_char(c: char) -> Result<Tile, Box<dyn Error>> {
+    match c {
+        '|' => Ok(Tile::NorthSouth),
+        '-' => Ok(Tile::EastWest),
+        'L' => Ok(Tile::NorthEast),
+        'J' => Ok(Tile::NorthWest),
+        'F' => Ok(Tile::SouthEast),
+        '7' => Ok(Tile::SouthWest),
+        'S' => Ok(Tile::Start),
+        '.' => Ok(Tile::Ground),
+        _ => Err(format!("Invalid character: {}", c).into()),
+    }
+}
+
+pub fn tile_char(tile: Tile) -> char {
+    match tile {
+        Tile::NorthSouth => '|',
+        Tile::EastWest => '-',
+        Tile::NorthEast => 'L',
+        Tile::NorthWest => 'J',
+        Tile::SouthEast => 'F',
+        Tile::SouthWest => '7',
+        Tile::Start => 'S',
+        Tile::Ground => '.',
+    }
+}
+
+pub fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::North => "north",
+        Direction::East => "east",
+        Direction::South => "south",
+        Direction::West => "west",
+    }
+}
+
+pub fn parse_maze(itr: impl Iterator<Item = String>) -> Result<Maze, Box<dyn Error>> {
+    itr.map(|line| line.chars().map(|c| parse_char(c)).collect())
+        .collect()
+}
+
+fn offset(direction: &Direction) -> Coordinates {
+    match direction {
+        Direction::North => (0, -1),
+        Direction::East => (1, 0),
+        Direction::South => (0, 1),
+        Direction::West => (-1, 0),
+    }
+}
+
+fn all_direction() -> Vec<Direction> {
+    vec![
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ]
+}
+
+fn valid_to(maze: &Maze, position: Coordinates, direction: Direction) -> Option<Coordinates> {
+    let offset = offset(&direction);
+    let new_coordinates = (position.0 + offset.0, position.1 + offset.1);
+
+    let new_coordinates0 = usize::try_from(new_coordinates.0).ok()?;
+    let new_coordinates1 = usize::try_from(new_coordinates.1).ok()?;
+    let destination_tile = maze
+        .get(new_coordinates1)
+        .and_then(|row| row.get(new_coordinates0))?;
+
+    let valid = (direction == Direction::North
+        && (*destination_tile == Tile::NorthSouth
+            || *destination_tile == Tile::SouthEast
+            || *destination_tile == Tile::SouthWest))
+        || (direction == Direction::South
+            && (*destination_tile == Tile::NorthSouth
+                || *destination_tile == Tile::NorthEast
+                || *destination_tile == Tile::NorthWest))
+        || (direction == Direction::East
+            && (*destination_tile == Tile::EastWest
+                || *destination_tile == Tile::NorthWest
+                || *destination_tile == Tile::SouthWest))
+        || (direction == Direction::West
+            && (*destination_tile == Tile::EastWest
+                || *destination_tile == Tile::NorthEast
+                || *destination_tile == Tile::SouthEast));
+
+    valid.then(|| new_coordinates)
+}
+
+fn valid_from(
+    maze: &Maze,
+    position: Coordinates,
+    direction: Direction,
+) -> Result<bool, Box<dyn Error>> {
+    let tile = maze
+        .get(usize::try_from(position.1)?)
+        .and_then(|row| row.get(usize::try_from(position.0).ok()?))
+        .ok_or("Invalid coordinates")?;
+
+    let valid = match tile {
+        Tile::NorthSouth => direction == Direction::North || direction == Direction::South,
+        Tile::EastWest => direction == Direction::East || direction == Direction::West,
+        Tile::NorthEast => direction == Direction::North || direction == Direction::East,
+        Tile::NorthWest => direction == Direction::North || direction == Direction::West,
+        Tile::SouthEast => direction == Direction::South || direction == Direction::East,
+        Tile::SouthWest => direction == Direction::South || direction == Direction::West,
+        Tile::Start => true,
+        Tile::Ground => false,
+    };
+
+    Ok(valid)
+}
+
+fn opposite(direction: Direction) -> Direction {
+    match direction {
+        Direction::North => Direction::South,
+        Direction::East => Direction::West,
+        Direction::South => Direction::North,
+        Direction::West => Direction::East,
+    }
+}
+
+fn next(
+    maze: &Maze,
+    last_direction: Option<Direction>,
+    position: Coordinates,
+) -> Result<Vec<(Direction, Coordinates)>, Box<dyn Error>> {
+    all_direction()
+        .iter()
+        .filter(|direction| Some(opposite(**direction)) != last_direction)
+        .map(|direction| -> Result<_, Box<dyn Error>> {
+            let from = valid_from(maze, position, *direction)?;
+            let to = valid_to(maze, position, *direction);
+            let direction_and_to = to.map(|to| (*direction, to));
+            Ok(if from { direction_and_to } else { None })
+        })
+        .process_results(|itr| itr.filter_map(|r| r).collect::<Vec<_>>())
+}
+
+fn create_tree(maze: &Maze) -> Result<Rc<RefCell<Tree>>, Box<dyn Error>> {
+    let start = maze
+        .iter()
+        .enumerate()
+        .find_map(|(y, row)| {
+            row.iter().enumerate().find_map(|(x, tile)| {
+                (*tile == Tile::Start)
+                    .then_some(i32::try_from(x).and_then(|x| i32::try_from(y).map(|y| (x, y))))
+            })
+        })
+        .ok_or("No start tile found")??;
+
+    let mut stack: Vec<(Option<Direction>, Rc<RefCell<Tree>>)> = Vec::new();
+    let tree = Rc::new(RefCell::new(Tree {
+        position: start,
+        children: Vec::new(),
+    }));
+    stack.push((None, tree.clone()));
+
+    while let Some((last_direction, current)) = stack.pop() {
+        let next_coordinates = next(maze, last_direction, current.borrow().position)?;
+
+        for (direction, coordinate) in next_coordinates {
+            let child = Rc::new(RefCell::new(Tree {
+                position: coordinate,
+                children: Vec::new(),
+            }));
+
+            current.borrow_mut().children.push(child.clone());
+            stack.push((Some(direction), child));
+            break;
+        }
+    }
+
+    Ok(tree)
+}
+
+fn paths(tree: Rc<RefCell<Tree>>) -> Vec<Vec<Rc<RefCell<Tree>>>> {
+    let mut stack = vec![vec![tree.clone()]];
+    let mut paths = Vec::new();
+
+    while let Some(mut path) = stack.pop() {
+        if let Some(last) = path.last().cloned() {
+            if last.borrow().children.is_empty() {
+                paths.push(path);
+            } else {
+                last.borrow().children.iter().for_each(|child| {
+                    path.push(child.clone());
+                    stack.push(path.clone());
+                });
+            }
+        }
+    }
+
+    paths
+}
+
+fn longuest_path(tree: Rc<RefCell<Tree>>) -> Result<Vec<Rc<RefCell<Tree>>>, Box<dyn Error>> {
+    paths(tree)
+        .into_iter()
+        .max_by_key(|path| path.len())
+        .ok_or("No path found".into())
+}
+
+// a closed path of pipes, each tile connected to the previous and the next one
+#[derive(Debug, PartialEq, Eq)]
+pub struct Loop {
+    // in order along the loop
+    pub tiles: Vec<Coordinates>,
+    // the number of tiles inside the loop
+    pub enclosed: u32,
+    // whether it goes through the start tile
+    pub start: bool,
+}
+
+fn tile_at(maze: &Maze, position: Coordinates) -> Option<Tile> {
+    let x = usize::try_from(position.0).ok()?;
+    let y = usize::try_from(position.1).ok()?;
+    maze.get(y).and_then(|row| row.get(x)).copied()
+}
+
+// the first direction in which a pipe can be left, apart from `except`
+fn pipe_exit(
+    maze: &Maze,
+    position: Coordinates,
+    except: Option<Direction>,
+) -> Result<Option<Direction>, Box<dyn Error>> {
+    for direction in all_direction() {
+        if Some(direction) != except && valid_from(maze, position, direction)? {
+            return Ok(Some(direction));
+        }
+    }
+    Ok(None)
+}
+
+// follows the pipes from `start` leaving it towards `direction`, returns the tiles in order if the
+// path comes back to `start`. The start tile can go anywhere so the path stops on it, unless it is
+// where it began
+fn walk(
+    maze: &Maze,
+    start: Coordinates,
+    direction: Direction,
+) -> Result<Option<Vec<Coordinates>>, Box<dyn Error>> {
+    let mut tiles = vec![start];
+    let (mut position, mut direction) = (start, direction);
+
+    loop {
+        // valid_to doesn't know where the start tile connects
+        let offset = offset(&direction);
+        if (position.0 + offset.0, position.1 + offset.1) == start {
+            return Ok(valid_from(maze, start, opposite(direction))?.then_some(tiles));
+        }
+        let Some(to) = valid_to(maze, position, direction) else {
+            return Ok(None);
+        };
+        if tile_at(maze, to) == Some(Tile::Start) {
+            return Ok(None);
+        }
+        let Some(next_direction) = pipe_exit(maze, to, Some(opposite(direction)))? else {
+            return Ok(None);
+        };
+
+        tiles.push(to);
+        (position, direction) = (to, next_direction);
+    }
+}
+
+fn to_loop(tiles: Vec<Coordinates>, start: bool) -> Result<Loop, Box<dyn Error>> {
+    let enclosed = u32::try_from(interior_point_count(&loop_vertices(&tiles)))?;
+
+    Ok(Loop {
+        tiles,
+        enclosed,
+        start,
+    })
+}
+
+// all the loops of the maze, the one going through the start tile first if any. Any pipe can be
+// part of a loop, not only the ones connected to the start tile
+pub fn find_loops(maze: &Maze) -> Result<Vec<Loop>, Box<dyn Error>> {
+    let positions = maze
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| (0..row.len()).map(move |x| (x, y)))
+        .map(|(x, y)| Ok((i32::try_from(x)?, i32::try_from(y)?)))
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    let mut loops = Vec::new();
+    let mut visited = HashSet::new();
+
+    // the start tile connects to any neighbor, the first direction closing a loop is taken
+    if let Some(start) = positions
+        .iter()
+        .find(|position| tile_at(maze, **position) == Some(Tile::Start))
+    {
+        for direction in all_direction() {
+            if let Some(tiles) = walk(maze, *start, direction)? {
+                visited.extend(tiles.iter().copied());
+                loops.push(to_loop(tiles, true)?);
+                break;
+            }
+        }
+    }
+
+    for position in positions {
+        if visited.contains(&position) || tile_at(maze, position) == Some(Tile::Start) {
+            continue;
+        }
+        visited.insert(position);
+
+        // the tiles of a loop are all found from any of them, leaving by either end
+        let Some(direction) = pipe_exit(maze, position, None)? else {
+            continue;
+        };
+        if let Some(tiles) = walk(maze, position, direction)? {
+            visited.extend(tiles.iter().copied());
+            loops.push(to_loop(tiles, false)?);
+        }
+    }
+
+    Ok(loops)
+}
+
+// a tile of a loop with the directions the loop enters and leaves it
+#[derive(Debug, PartialEq, Eq)]
+pub struct LoopRecord {
+    pub position: Coordinates,
+    pub tile: Tile,
+    pub incoming: Direction,
+    pub outgoing: Direction,
+}
+
+fn direction_between(from: Coordinates, to: Coordinates) -> Result<Direction, Box<dyn Error>> {
+    all_direction()
+        .into_iter()
+        .find(|direction| offset(direction) == (to.0 - from.0, to.1 - from.1))
+        .ok_or("Tiles not adjacent".into())
+}
+
+// the tiles of the loop in order, the loop coming back to the first one from the last one
+pub fn loop_records(maze: &Maze, a_loop: &Loop) -> Result<Vec<LoopRecord>, Box<dyn Error>> {
+    a_loop
+        .tiles
+        .iter()
+        .circular_tuple_windows()
+        .map(|(previous, current, next)| {
+            Ok(LoopRecord {
+                position: *current,
+                tile: tile_at(maze, *current).ok_or("Invalid coordinates")?,
+                incoming: direction_between(*previous, *current)?,
+                outgoing: direction_between(*current, *next)?,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()
+        // the windows start on the second tile
+        .map(|mut records| {
+            records.rotate_right(1);
+            records
+        })
+}
+
+pub fn start_loop(loops: &[Loop]) -> Result<&Loop, Box<dyn Error>> {
+    loops
+        .iter()
+        .find(|a_loop| a_loop.start)
+        .ok_or("No loop through the start tile".into())
+}
+
+pub fn solve1(maze: Maze) -> Result<u32, Box<dyn Error>> {
+    let loops = find_loops(&maze)?;
+
+    Ok(u32::try_from(start_loop(&loops)?.tiles.len())? / 2)
+}
+
+fn get_start_replacement(path: &[Rc<RefCell<Tree>>]) -> Result<Tile, Box<dyn Error>> {
+    let first_coord = path.first().ok_or("Invalid path")?.borrow().position;
+    let second_coord = path.get(1).ok_or("Invalid path")?.borrow().position;
+    let last_coord = path.last().ok_or("Invalid path")?.borrow().position;
+    let first_second = (
+        second_coord.0 - first_coord.0,
+        second_coord.1 - first_coord.1,
+    );
+    let first_last = (last_coord.0 - first_coord.0, last_coord.1 - first_coord.1);
+
+    match (first_second, first_last) {
+        ((-1, 0), (1, 0)) => Ok(Tile::EastWest),
+        ((1, 0), (-1, 0)) => Ok(Tile::EastWest),
+
+        ((0, -1), (0, 1)) => Ok(Tile::NorthSouth),
+        ((0, 1), (0, -1)) => Ok(Tile::NorthSouth),
+
+        ((0, -1), (-1, 0)) => Ok(Tile::NorthWest),
+        ((0, -1), (1, 0)) => Ok(Tile::NorthEast),
+        ((0, 1), (-1, 0)) => Ok(Tile::SouthWest),
+        ((0, 1), (1, 0)) => Ok(Tile::SouthEast),
+
+        ((-1, 0), (0, -1)) => Ok(Tile::NorthWest),
+        ((-1, 0), (0, 1)) => Ok(Tile::SouthWest),
+        ((1, 0), (0, -1)) => Ok(Tile::NorthEast),
+        ((1, 0), (0, 1)) => Ok(Tile::SouthEast),
+
+        _ => Err("Invalid first and last tiles".into()),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Region {
+    Loop,
+    Inside,
+    Outside,
+}
+
+// classify the tiles by casting a ray along each row, crossing a wall of the loop switches
+// between outside and inside
+pub fn classify(maze: &Maze) -> Result<Vec<Vec<Region>>, Box<dyn Error>> {
+    let tree = create_tree(maze)?;
+
+    let path = longuest_path(tree.clone())?;
+
+    // To make it easier to handle the start tile, we replace it by the proper tile
+    let new_start = get_start_replacement(&path)?;
+
+    // put all the coordinates in a set
+    let coordinates: HashSet<(i32, i32)> =
+        HashSet::from_iter(path.iter().map(|node| node.borrow().position));
+
+    maze.iter()
+        .enumerate()
+        .map(|(y, row)| {
+            // in the state we store:
+            // - if we are inside the path
+            // - the tile starting a wall NorthEast or SouthEast
+            let mut inside = false;
+            let mut first_tile: Option<Tile> = None;
+
+            row.iter()
+                .enumerate()
+                .map(|(x, tile)| -> Result<_, Box<dyn Error>> {
+                    let tile = if *tile == Tile::Start {
+                        new_start
+                    } else {
+                        *tile
+                    };
+
+                    // we are on a wall
+                    if coordinates.contains(&(i32::try_from(x)?, i32::try_from(y)?)) {
+                        (inside, first_tile) = match (first_tile, tile) {
+                            (None, Tile::NorthSouth) => (!inside, None),
+
+                            (None, Tile::NorthEast) => (inside, Some(Tile::NorthEast)),
+                            (None, Tile::SouthEast) => (inside, Some(Tile::SouthEast)),
+
+                            (Some(Tile::NorthEast), Tile::SouthWest) => (!inside, None),
+                            (Some(Tile::NorthEast), Tile::NorthWest) => (inside, None),
+
+                            (Some(Tile::SouthEast), Tile::NorthWest) => (!inside, None),
+                            (Some(Tile::SouthEast), Tile::SouthWest) => (inside, None),
+
+                            _ => (inside, first_tile),
+                        };
+                        Ok(Region::Loop)
+                    // not on a wall
+                    } else {
+                        first_tile = None;
+                        Ok(if inside {
+                            Region::Inside
+                        } else {
+                            Region::Outside
+                        })
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn count(regions: &[Region], region: Region) -> usize {
+    regions.iter().filter(|r| **r == region).count()
+}
+
+pub fn solve2(maze: Maze) -> Result<u32, Box<dyn Error>> {
+    let regions = classify(&maze)?;
+
+    Ok(u32::try_from(
+        regions
+            .iter()
+            .map(|row| count(row, Region::Inside))
+            .sum::<usize>(),
+    )?)
+}
+
+// the corners of the loop in order, the tiles where the path changes direction
+fn loop_vertices(path: &[Coordinates]) -> Vec<(i64, i64)> {
+    path.iter()
+        .circular_tuple_windows()
+        .filter(|(previous, current, next)| {
+            (current.0 - previous.0, current.1 - previous.1)
+                != (next.0 - current.0, next.1 - current.1)
+        })
+        .map(|(_, current, _)| (i64::from(current.0), i64::from(current.1)))
+        .collect()
+}
+
+// according to the pick theorem, the number of tiles inside the loop can be deduced from its area
+// and its number of tiles, see lib::geom
+pub fn solve2_shoelace(maze: Maze) -> Result<u32, Box<dyn Error>> {
+    let loops = find_loops(&maze)?;
+
+    Ok(start_loop(&loops)?.enclosed)
+}
+
+#[cfg(test)]
+mod day10 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+
+    use crate::{
+        classify, count, create_tree, find_loops, get_start_replacement, longuest_path,
+        loop_records, loop_vertices, offset, parse_maze, solve1, solve2, solve2_shoelace,
+        start_loop, Direction, Maze, Region, Tile,
+    };
+
+    const EXAMPLE1: &str = "\
+        -L|F7\n\
+        7S-7|\n\
+        L|7||\n\
+        -L-J|\n\
+        L|-JF";
+
+    fn example1() -> Maze {
+        vec![
+            vec![
+                Tile::EastWest,
+                Tile::NorthEast,
+                Tile::NorthSouth,
+                Tile::SouthEast,
+                Tile::SouthWest,
+            ],
+            vec![
+                Tile::SouthWest,
+                Tile::Start,
+                Tile::EastWest,
+                Tile::SouthWest,
+                Tile::NorthSouth,
+            ],
+            vec![
+                Tile::NorthEast,
+                Tile::NorthSouth,
+                Tile::SouthWest,
+                Tile::NorthSouth,
+                Tile::NorthSouth,
+            ],
+            vec![
+                Tile::EastWest,
+                Tile::NorthEast,
+                Tile::EastWest,
+                Tile::NorthWest,
+                Tile::NorthSouth,
+            ],
+            vec![
+                Tile::NorthEast,
+                Tile::NorthSouth,
+                Tile::EastWest,
+                Tile::NorthWest,
+                Tile::SouthEast,
+            ],
+        ]
+    }
+
+    const EXAMPLE2: &str = "\
+        7-F7-\n\
+        .FJ|7\n\
+        SJLL7\n\
+        |F--J\n\
+        LJ.LJ";
+
+    fn example2() -> Maze {
+        vec![
+            vec![
+                Tile::SouthWest,
+                Tile::EastWest,
+                Tile::SouthEast,
+                Tile::SouthWest,
+                Tile::EastWest,
+            ],
+            vec![
+                Tile::Ground,
+                Tile::SouthEast,
+                Tile::NorthWest,
+                Tile::NorthSouth,
+                Tile::SouthWest,
+            ],
+            vec![
+                Tile::Start,
+                Tile::NorthWest,
+                Tile::NorthEast,
+                Tile::NorthEast,
+                Tile::SouthWest,
+            ],
+            vec![
+                Tile::NorthSouth,
+                Tile::SouthEast,
+                Tile::EastWest,
+                Tile::EastWest,
+                Tile::NorthWest,
+            ],
+            vec![
+                Tile::NorthEast,
+                Tile::NorthWest,
+                Tile::Ground,
+                Tile::NorthEast,
+                Tile::NorthWest,
+            ],
+        ]
+    }
+
+    const EXAMPLE3: &str = "\
+        ...........\n\
+        .S-------7.\n\
+        .|F-----7|.\n\
+        .||.....||.\n\
+        .||.....||.\n\
+        .|L-7.F-J|.\n\
+        .|..|.|..|.\n\
+        .L--J.L--J.\n\
+        ...........";
+
+    const EXAMPLE4: &str = "\
+        .F----7F7F7F7F-7....\n\
+        .|F--7||||||||FJ....\n\
+        .||.FJ||||||||L7....\n\
+        FJL7L7LJLJ||LJ.L-7..\n\
+        L--J.L7...LJS7F-7L7.\n\
+        ....F-J..F7FJ|L7L7L7\n\
+        ....L7.F7||L7|.L7L7|\n\
+        .....|FJLJ|FJ|F7|.LJ\n\
+        ....FJL-7.||.||||...\n\
+        ....L---J.LJ.LJLJ...";
+
+    const EXAMPLE5: &str = "\
+        FF7FSF7F7F7F7F7F---7\n\
+        L|LJ||||||||||||F--J\n\
+        FL-7LJLJ||||||LJL-77\n\
+        F--JF--7||LJLJ7F7FJ-\n\
+        L---JF-JLJ.||-FJLJJ7\n\
+        |F|F-JF---7F7-L7L|7|\n\
+        |FFJF7L7F-JF7|JL---7\n\
+        7-L-JL7||F7|L7F-7F7|\n\
+        L.L7LFJ|||||FJL7||LJ\n\
+        L7JLJL-JLJLJL--JLJ.L";
+
+    #[test]
+    fn test_parse_maze() -> Result<(), Box<dyn Error>> {
+        let result = parse_maze(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        assert_eq!(result, example1());
+
+        let result = parse_maze(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        assert_eq!(result, example2());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_example1() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve1(example1())?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_example2() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve1(example2())?, 8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_example3() -> Result<(), Box<dyn Error>> {
+        let maze = parse_maze(EXAMPLE3.lines().map(|s| s.to_string()))?;
+        assert_eq!(solve2(maze)?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_example4() -> Result<(), Box<dyn Error>> {
+        let maze = parse_maze(EXAMPLE4.lines().map(|s| s.to_string()))?;
+        assert_eq!(solve2(maze)?, 8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_example5() -> Result<(), Box<dyn Error>> {
+        let maze = parse_maze(EXAMPLE5.lines().map(|s| s.to_string()))?;
+        assert_eq!(solve2(maze)?, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_loop_vertices() -> Result<(), Box<dyn Error>> {
+        let path = longuest_path(create_tree(&example1())?)?
+            .iter()
+            .map(|node| node.borrow().position)
+            .collect::<Vec<_>>();
+        let vertices = loop_vertices(&path);
+
+        assert_eq!(vertices.len(), 4);
+        for corner in [(1, 1), (3, 1), (3, 3), (1, 3)] {
+            assert!(vertices.contains(&corner));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_shoelace_examples() -> Result<(), Box<dyn Error>> {
+        for example in [EXAMPLE1, EXAMPLE2, EXAMPLE3, EXAMPLE4, EXAMPLE5] {
+            let maze = parse_maze(example.lines().map(|s| s.to_string()))?;
+            assert_eq!(solve2_shoelace(maze.clone())?, solve2(maze)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_shoelace_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let maze = reader.lines().process_results(|itr| parse_maze(itr))??;
+
+        assert_eq!(solve2_shoelace(maze.clone())?, solve2(maze.clone())?);
+        assert_eq!(solve2_shoelace(maze)?, 453);
+        Ok(())
+    }
+
+    #[test]
+    fn test_classify_examples() -> Result<(), Box<dyn Error>> {
+        for (example, inside) in [(EXAMPLE3, 4), (EXAMPLE4, 8), (EXAMPLE5, 10)] {
+            let maze = parse_maze(example.lines().map(|s| s.to_string()))?;
+            let regions = classify(&maze)?;
+            let total = |region| regions.iter().map(|row| count(row, region)).sum::<usize>();
+
+            assert_eq!(total(Region::Inside), inside);
+            assert_eq!(total(Region::Loop), 2 * solve1(maze.clone())? as usize);
+            assert_eq!(
+                total(Region::Loop) + total(Region::Inside) + total(Region::Outside),
+                maze.iter().map(|row| row.len()).sum::<usize>()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_classify_rows() -> Result<(), Box<dyn Error>> {
+        let maze = parse_maze(EXAMPLE3.lines().map(|s| s.to_string()))?;
+        let regions = classify(&maze)?;
+
+        assert!(regions[0].iter().all(|region| *region == Region::Outside));
+        assert_eq!(count(&regions[6], Region::Inside), 4);
+        assert_eq!(count(&regions[6], Region::Loop), 4);
+        Ok(())
+    }
+
+    // the loop through the start tile, a larger loop on the right, a small one below it and a pipe
+    // that goes nowhere
+    const MULTIPLE_LOOPS: &str = "\
+        .....F-7\n\
+        S-7..|.|\n\
+        |.|..L-J\n\
+        L-J.F7..\n\
+        ....LJ.-";
+
+    #[test]
+    fn test_find_loops() -> Result<(), Box<dyn Error>> {
+        let maze = parse_maze(MULTIPLE_LOOPS.lines().map(|s| s.to_string()))?;
+        let loops = find_loops(&maze)?;
+
+        let summary = loops
+            .iter()
+            .map(|a_loop| {
+                (
+                    a_loop.tiles[0],
+                    a_loop.tiles.len(),
+                    a_loop.enclosed,
+                    a_loop.start,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ((0, 1), 8, 1, true),
+                ((5, 0), 8, 1, false),
+                ((4, 3), 4, 0, false)
+            ]
+        );
+
+        // the parts only consider the loop through the start tile
+        let maze = || parse_maze(MULTIPLE_LOOPS.lines().map(|s| s.to_string()));
+        assert_eq!(solve1(maze()?)?, 4);
+        assert_eq!(solve2(maze()?)?, 1);
+        assert_eq!(solve2_shoelace(maze()?)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_loops_examples() -> Result<(), Box<dyn Error>> {
+        // the pipes around the loop of the first example don't form other loops
+        let loops = find_loops(&example1())?;
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].tiles.len(), 8);
+
+        let maze = parse_maze(".....\n.F-7.\n.|.|.\n.L-J.".lines().map(|s| s.to_string()))?;
+        let loops = find_loops(&maze)?;
+        assert_eq!(loops.len(), 1);
+        assert!(!loops[0].start);
+        assert!(solve1(maze).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_loop_records() -> Result<(), Box<dyn Error>> {
+        let maze = example1();
+        let loops = find_loops(&maze)?;
+        let records = loop_records(&maze, start_loop(&loops)?)?;
+
+        assert_eq!(records.len(), 8);
+        assert_eq!(records[0].position, (1, 1));
+        assert_eq!(records[0].tile, Tile::Start);
+
+        // each record leads to the next one, the last one to the first one
+        records
+            .iter()
+            .circular_tuple_windows()
+            .for_each(|(record, next)| {
+                let (dx, dy) = offset(&record.outgoing);
+                assert_eq!(
+                    (record.position.0 + dx, record.position.1 + dy),
+                    next.position
+                );
+                assert_eq!(record.outgoing, next.incoming);
+            });
+
+        let (first, last) = (&records[0], &records[records.len() - 1]);
+        assert_eq!(last.outgoing, first.incoming);
+        assert_eq!(
+            (first.incoming, first.outgoing),
+            (Direction::North, Direction::East)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_start_replacement() -> Result<(), Box<dyn Error>> {
+        // the start tile is on a vertical side of the loop
+        let maze = parse_maze(
+            "\
+            .F-7.\n\
+            .S.|.\n\
+            .L-J."
+                .lines()
+                .map(|s| s.to_string()),
+        )?;
+        let path = longuest_path(create_tree(&maze)?)?;
+        assert_eq!(get_start_replacement(&path)?, Tile::NorthSouth);
+        assert_eq!(solve2(maze.clone())?, 1);
+        assert_eq!(solve2_shoelace(maze)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let maze = reader.lines().process_results(|itr| parse_maze(itr))??;
+
+        assert_eq!(solve1(maze)?, 6890);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let maze = reader.lines().process_results(|itr| parse_maze(itr))??;
+
+        assert_eq!(solve2(maze)?, 453);
+        Ok(())
+    }
+}
@@ -0,0 +1,352 @@
+use itertools::Itertools;
+use lib::geom::manhattan;
+use std::{collections::HashSet, error::Error};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Universe {
+    width: i64,
+    height: i64,
+    galaxies: HashSet<(i64, i64)>,
+}
+
+pub fn expand(universe: &Universe, factor: i64) -> Result<Universe, Box<dyn Error>> {
+    let lines_with_galaxies = universe
+        .galaxies
+        .iter()
+        .map(|coordinates| coordinates.1)
+        .collect::<HashSet<_>>();
+    let lines_without_galaxies = (0..universe.height)
+        .filter(|&y| !lines_with_galaxies.contains(&y))
+        .collect::<HashSet<_>>();
+
+    let columns_with_galaxies = universe
+        .galaxies
+        .iter()
+        .map(|coordinates| coordinates.0)
+        .collect::<HashSet<_>>();
+    let columns_without_galaxies = (0..universe.width)
+        .filter(|&x| !columns_with_galaxies.contains(&x))
+        .collect::<HashSet<_>>();
+
+    let galaxies = universe
+        .galaxies
+        .iter()
+        .map(|(x, y)| -> Result<(i64, i64), Box<dyn Error>> {
+            let x = x + factor
+                * i64::try_from(columns_without_galaxies.iter().filter(|&c| c < x).count())?;
+            let y = y + factor
+                * i64::try_from(lines_without_galaxies.iter().filter(|&c| c < y).count())?;
+
+            Ok((x, y))
+        })
+        .collect::<Result<HashSet<_>, _>>()?;
+
+    let width = universe.width + i64::try_from(columns_without_galaxies.len())?;
+    let height = universe.height + i64::try_from(lines_without_galaxies.len())?;
+    Ok(Universe {
+        width,
+        height,
+        galaxies,
+    })
+}
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Universe, Box<dyn Error>> {
+    let mut width = 0;
+    let mut height = 0;
+    let cells: HashSet<(i64, i64)> = itr
+        .enumerate()
+        .map(|(y, line)| -> Result<Vec<(i64, i64)>, Box<dyn Error>> {
+            width = width.max(i64::try_from(line.len())?);
+            height += 1;
+            line.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(|(x, _)| Ok((i64::try_from(x)?, i64::try_from(y)?)))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(Universe {
+        width,
+        height,
+        galaxies: cells,
+    })
+}
+
+pub fn solve(universe: &Universe) -> Result<i64, Box<dyn Error>> {
+    universe
+        .galaxies
+        .iter()
+        .combinations(2)
+        .map(|pair| {
+            let x = pair.get(0).ok_or("No first element")?;
+            let y = pair.get(1).ok_or("No second element")?;
+            Ok(manhattan(x, y))
+        })
+        .sum()
+}
+
+// the sum of the distances between all the pairs of values: once sorted, each value is bigger
+// than all the ones before it
+fn sum_of_distances(mut values: Vec<i64>) -> i64 {
+    values.sort_unstable();
+
+    values
+        .iter()
+        .enumerate()
+        .fold((0, 0), |(sum, prefix), (i, value)| {
+            (sum + value * i as i64 - prefix, prefix + value)
+        })
+        .0
+}
+
+// the manhattan distance is the sum of the distances on each axis, so the axes can be summed
+// separately in O(n log n)
+pub fn solve_fast(universe: &Universe) -> Result<i64, Box<dyn Error>> {
+    let (xs, ys) = universe.galaxies.iter().copied().unzip();
+
+    Ok(sum_of_distances(xs) + sum_of_distances(ys))
+}
+
+// threshold=N and the optional expansion=N, the latter being 2 as in part 1 by default
+pub fn parse_clusters_args(args: &[String]) -> Result<(i64, i64), Box<dyn Error>> {
+    let mut threshold = None;
+    let mut expansion = 2;
+
+    for arg in args {
+        match arg.split_once('=') {
+            Some(("threshold", value)) => threshold = Some(value.parse()?),
+            Some(("expansion", value)) => expansion = value.parse()?,
+            _ => return Err(format!("Invalid argument: {}", arg).into()),
+        }
+    }
+
+    if expansion < 1 {
+        return Err("The expansion factor must be positive".into());
+    }
+
+    Ok((threshold.ok_or("Missing threshold")?, expansion))
+}
+
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+// the galaxies grouped with a union-find when their distance is at most threshold. The clusters
+// are sorted by decreasing size, the galaxies inside them by coordinates.
+pub fn clusters(universe: &Universe, threshold: i64) -> Vec<Vec<(i64, i64)>> {
+    let galaxies = universe
+        .galaxies
+        .iter()
+        .copied()
+        .sorted()
+        .collect::<Vec<_>>();
+    let mut parents = (0..galaxies.len()).collect::<Vec<_>>();
+
+    for (i, j) in (0..galaxies.len()).tuple_combinations() {
+        if manhattan(&galaxies[i], &galaxies[j]) <= threshold {
+            let (root_i, root_j) = (find(&mut parents, i), find(&mut parents, j));
+            parents[root_i.max(root_j)] = root_i.min(root_j);
+        }
+    }
+
+    (0..galaxies.len())
+        .into_group_map_by(|i| find(&mut parents, *i))
+        .into_values()
+        .map(|cluster| {
+            cluster
+                .into_iter()
+                .map(|i| galaxies[i])
+                .sorted()
+                .collect::<Vec<_>>()
+        })
+        .sorted_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)))
+        .collect()
+}
+
+#[cfg(test)]
+mod day11 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+
+    use crate::{
+        clusters, expand, parse, parse_clusters_args, solve, solve_fast, sum_of_distances, Universe,
+    };
+
+    const EXAMPLE1: &str = "\
+        ...#......\n\
+        .......#..\n\
+        #.........\n\
+        ..........\n\
+        ......#...\n\
+        .#........\n\
+        .........#\n\
+        ..........\n\
+        .......#..\n\
+        #...#.....";
+
+    const EXAMPLE1_EXPANDED: &str = "\
+        ....#........\n\
+        .........#...\n\
+        #............\n\
+        .............\n\
+        .............\n\
+        ........#....\n\
+        .#...........\n\
+        ............#\n\
+        .............\n\
+        .............\n\
+        .........#...\n\
+        #....#.......";
+
+    fn to_string(universe: &Universe) -> String {
+        (0..universe.height)
+            .map(|y| {
+                (0..universe.width)
+                    .map(|x| {
+                        if universe.galaxies.contains(&(x, y)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .chain("\n".chars())
+                    .collect::<String>()
+            })
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        let universe = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+
+        assert_eq!(EXAMPLE1, to_string(&universe));
+        Ok(())
+    }
+
+    #[test]
+    fn test_expand() -> Result<(), Box<dyn Error>> {
+        let universe = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        let expanded = expand(&universe, 1)?;
+
+        assert_eq!(EXAMPLE1_EXPANDED, to_string(&expanded));
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1() -> Result<(), Box<dyn Error>> {
+        let universe = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        let expanded = expand(&universe, 1)?;
+
+        assert_eq!(solve(&expanded)?, 374);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2() -> Result<(), Box<dyn Error>> {
+        let universe = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        let expanded = expand(&universe, 10 - 1)?;
+
+        assert_eq!(solve(&expanded)?, 1030);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sum_of_distances() {
+        assert_eq!(sum_of_distances(vec![]), 0);
+        assert_eq!(sum_of_distances(vec![4]), 0);
+        // |1-4| + |1-9| + |4-9|
+        assert_eq!(sum_of_distances(vec![9, 1, 4]), 16);
+        assert_eq!(sum_of_distances(vec![2, 2, 2]), 0);
+    }
+
+    #[test]
+    fn test_solve_fast() -> Result<(), Box<dyn Error>> {
+        let universe = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+
+        [0, 1, 9, 99, 1_000_000 - 1].into_iter().try_for_each(
+            |factor| -> Result<(), Box<dyn Error>> {
+                let expanded = expand(&universe, factor)?;
+                assert_eq!(solve_fast(&expanded)?, solve(&expanded)?);
+                Ok(())
+            },
+        )?;
+
+        assert_eq!(solve_fast(&expand(&universe, 1)?)?, 374);
+        assert_eq!(solve_fast(&expand(&universe, 100 - 1)?)?, 8410);
+        Ok(())
+    }
+
+    #[test]
+    fn test_clusters() -> Result<(), Box<dyn Error>> {
+        let universe = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        let expanded = expand(&universe, 1)?;
+
+        let sizes = |threshold| {
+            clusters(&expanded, threshold)
+                .iter()
+                .map(|cluster| cluster.len())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sizes(0), vec![1; 9]);
+        assert_eq!(sizes(4), vec![1; 9]);
+        assert_eq!(sizes(5), vec![3, 2, 2, 1, 1]);
+        assert_eq!(sizes(6), vec![9]);
+        assert_eq!(sizes(100), vec![9]);
+
+        assert_eq!(clusters(&expanded, 5)[0], vec![(0, 11), (5, 11), (9, 10)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_clusters_args() -> Result<(), Box<dyn Error>> {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(parse_clusters_args(&args(&["threshold=5"]))?, (5, 2));
+        assert_eq!(
+            parse_clusters_args(&args(&["expansion=10", "threshold=3"]))?,
+            (3, 10)
+        );
+        assert!(parse_clusters_args(&args(&["expansion=10"])).is_err());
+        assert!(parse_clusters_args(&args(&["threshold=3", "expansion=0"])).is_err());
+        assert!(parse_clusters_args(&args(&["threshold"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let universe = reader.lines().process_results(|itr| parse(itr))??;
+        let expanded = expand(&universe, 1)?;
+
+        assert_eq!(solve(&expanded)?, 9684228);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let universe = reader.lines().process_results(|itr| parse(itr))??;
+        let expanded = expand(&universe, 1_000_000 - 1)?;
+
+        assert_eq!(solve(&expanded)?, 483844716556);
+        assert_eq!(solve_fast(&expanded)?, 483844716556);
+        Ok(())
+    }
+}
@@ -0,0 +1,1042 @@
+use itertools::intersperse;
+use itertools::Itertools;
+use lib::{par::map_indexed, INVALID_INPUT};
+use std::collections::HashMap;
+use std::{
+    error::Error,
+    iter::repeat,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Spring {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputLine {
+    springs: Vec<Spring>,
+    damaged: Vec<i64>,
+}
+
+fn repeat_five(input_line: &InputLine) -> InputLine {
+    let springs: Vec<Spring> = intersperse(
+        repeat(input_line.springs.clone()).take(5),
+        vec![Spring::Unknown; 1],
+    )
+    .flatten()
+    .collect();
+
+    let damaged = repeat(&input_line.damaged)
+        .take(5)
+        .flatten()
+        .cloned()
+        .collect();
+
+    InputLine { springs, damaged }
+}
+
+fn display(springs: &[Spring]) -> String {
+    springs
+        .iter()
+        .map(|s| match s {
+            Spring::Operational => '.',
+            Spring::Damaged => '#',
+            Spring::Unknown => '?',
+        })
+        .collect()
+}
+
+// the line without the operational springs that don't change the arrangements: the ones at
+// both ends and the repeated ones between the groups
+fn canonical(input_line: &InputLine) -> String {
+    let groups = input_line
+        .springs
+        .split(|s| *s == Spring::Operational)
+        .filter(|group| !group.is_empty())
+        .map(display)
+        .join(".");
+
+    format!("{} {}", groups, input_line.damaged.iter().join(","))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DedupStats {
+    pub lines: usize,
+    pub distinct: usize,
+}
+
+// the counts of the lines of a run, keyed by their canonical form so that identical lines are
+// counted once. It is shared by the threads counting the lines in parallel
+#[derive(Debug, Default)]
+pub struct LineCache {
+    counts: Mutex<HashMap<String, i64>>,
+    lines: AtomicUsize,
+}
+
+impl LineCache {
+    fn combinations(&self, input_line: &InputLine) -> i64 {
+        let key = canonical(input_line);
+        self.lines.fetch_add(1, Ordering::Relaxed);
+
+        let cached = self
+            .counts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+            .copied();
+
+        // the lock is not held while counting, two threads may count the same line but they
+        // find the same result
+        cached.unwrap_or_else(|| {
+            let count = combinations2(input_line);
+            self.counts
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(key, count);
+            count
+        })
+    }
+
+    pub fn stats(&self) -> DedupStats {
+        DedupStats {
+            lines: self.lines.load(Ordering::Relaxed),
+            distinct: self
+                .counts
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .len(),
+        }
+    }
+}
+
+// the lines are independent so they are counted in parallel
+pub fn solve1(itr: impl Iterator<Item = InputLine>, cache: &LineCache) -> i64 {
+    let lines = itr.collect::<Vec<_>>();
+
+    map_indexed(&lines, |line| cache.combinations(line))
+        .into_iter()
+        .map(|timed| timed.value)
+        .sum()
+}
+
+pub fn solve2(itr: impl Iterator<Item = InputLine>, cache: &LineCache) -> i64 {
+    let lines = itr.map(|line| repeat_five(&line)).collect::<Vec<_>>();
+
+    map_indexed(&lines, |line| cache.combinations(line))
+        .into_iter()
+        .map(|timed| timed.value)
+        .sum()
+}
+
+// the lines counted one after the other without a cache, the reference for the parallel solver
+pub fn solve2_sequential(lines: &[InputLine]) -> i64 {
+    lines
+        .iter()
+        .map(|line| combinations2(&repeat_five(line)))
+        .sum()
+}
+
+fn check(springs: &[Spring], damaged_count: &[i64]) -> Result<bool, Box<dyn Error>> {
+    let damaged_count_in_springs = springs
+        .split(|s| *s != Spring::Damaged)
+        .filter(|s| !s.is_empty())
+        .map(|s| i64::try_from(s.len()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(damaged_count_in_springs == *damaged_count)
+}
+
+fn char_to_spring(c: char) -> Result<Spring, Box<dyn Error>> {
+    match c {
+        '.' => Ok(Spring::Operational),
+        '#' => Ok(Spring::Damaged),
+        '?' => Ok(Spring::Unknown),
+        _ => Err(INVALID_INPUT.into()),
+    }
+}
+
+fn combinations1(input_line: &InputLine) -> Result<i64, Box<dyn Error>> {
+    let damaged_count_in_springs = i64::try_from(
+        input_line
+            .springs
+            .iter()
+            .filter(|s| **s == Spring::Damaged)
+            .count(),
+    )?;
+    let number_to_fit = input_line.damaged.iter().sum::<i64>() - damaged_count_in_springs;
+    if number_to_fit < 0 {
+        return Ok(0);
+    }
+
+    let unknown_refs = input_line.springs.iter().enumerate().filter_map(|(i, s)| {
+        if *s == Spring::Unknown {
+            Some(i)
+        } else {
+            None
+        }
+    });
+
+    i64::try_from(
+        unknown_refs
+            .combinations(usize::try_from(number_to_fit)?)
+            .map(|replacements| {
+                let mut trial = input_line.springs.iter().cloned().collect::<Vec<_>>();
+                replacements
+                    .iter()
+                    .for_each(|i| trial[*i] = Spring::Damaged);
+
+                check(&trial, &input_line.damaged)
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .iter()
+            .filter(|&&b| b)
+            .count(),
+    )
+    .map_err(|e| e.into())
+}
+
+fn combinations2(input_line: &InputLine) -> i64 {
+    let mut results: HashMap<Parameters, i64> = HashMap::new();
+
+    combinations_rec(
+        &mut results,
+        Parameters {
+            springs: input_line.springs.clone(),
+            damaged: input_line.damaged.clone(),
+            current: None,
+        },
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub line: usize,
+    pub input: String,
+    pub enumerated: i64,
+    pub counted: i64,
+}
+
+// compares the count of the arrangements with their enumeration on the lines with at most
+// max_unknowns unknown springs, returns the number of lines checked and the mismatches
+pub fn verify_small(
+    input_lines: &[InputLine],
+    max_unknowns: usize,
+) -> Result<(usize, Vec<Mismatch>), Box<dyn Error>> {
+    let small_lines = input_lines.iter().enumerate().filter(|(_, input_line)| {
+        input_line
+            .springs
+            .iter()
+            .filter(|s| **s == Spring::Unknown)
+            .count()
+            <= max_unknowns
+    });
+
+    let mut checked = 0;
+    let mut mismatches = Vec::new();
+    for (i, input_line) in small_lines {
+        let enumerated = combinations1(input_line)?;
+        let counted = combinations2(input_line);
+        checked += 1;
+
+        if enumerated != counted {
+            mismatches.push(Mismatch {
+                line: i + 1,
+                input: format!(
+                    "{} {}",
+                    display(&input_line.springs),
+                    input_line.damaged.iter().join(",")
+                ),
+                enumerated,
+                counted,
+            });
+        }
+    }
+
+    Ok((checked, mismatches))
+}
+
+// the k-th arrangement counted by combinations2, in the order of the unknown springs being
+// decided from the left, operational first. The count of the arrangements with the spring
+// operational tells which way the k-th one goes. None when k is out of the count
+fn nth_assignment(input_line: &InputLine, k: i64) -> Option<Vec<Spring>> {
+    if k < 0 || k >= combinations2(input_line) {
+        return None;
+    }
+
+    let mut line = input_line.clone();
+    let mut k = k;
+    for i in 0..line.springs.len() {
+        if line.springs[i] == Spring::Unknown {
+            line.springs[i] = Spring::Operational;
+            let operational = combinations2(&line);
+            if k >= operational {
+                k -= operational;
+                line.springs[i] = Spring::Damaged;
+            }
+        }
+    }
+
+    Some(line.springs)
+}
+
+// all the arrangements counted by combinations2 when there are at most limit of them, limit of
+// them evenly spread in the order of nth_assignment otherwise
+fn enumerate_assignments(input_line: &InputLine, limit: usize) -> Vec<Vec<Spring>> {
+    let count = i128::from(combinations2(input_line));
+    let limit = i128::try_from(limit).unwrap_or(i128::MAX);
+
+    let ranks = if count <= limit {
+        (0..count).collect::<Vec<_>>()
+    } else {
+        (0..limit).map(|i| i * count / limit).collect()
+    };
+
+    ranks
+        .into_iter()
+        .filter_map(|rank| i64::try_from(rank).ok())
+        .filter_map(|rank| nth_assignment(input_line, rank))
+        .collect()
+}
+
+// checks the arrangements given by enumerate_assignments: they must keep the known springs and
+// pass check, and be as many as counted when they are all enumerated. Returns the number of
+// arrangements checked and the problems found
+pub fn verify(
+    input_lines: &[InputLine],
+    limit: usize,
+) -> Result<(usize, Vec<String>), Box<dyn Error>> {
+    let mut checked = 0;
+    let mut problems = Vec::new();
+
+    for (i, input_line) in input_lines.iter().enumerate() {
+        let input = format!(
+            "{} {}",
+            display(&input_line.springs),
+            input_line.damaged.iter().join(",")
+        );
+        let assignments = enumerate_assignments(input_line, limit);
+        checked += assignments.len();
+
+        for assignment in &assignments {
+            let keeps_known = assignment
+                .iter()
+                .zip(&input_line.springs)
+                .all(|(assigned, spring)| *spring == Spring::Unknown || assigned == spring);
+
+            if !keeps_known || !check(assignment, &input_line.damaged)? {
+                problems.push(format!(
+                    "line {}: {}: {} is not an arrangement",
+                    i + 1,
+                    input,
+                    display(assignment)
+                ));
+            }
+        }
+
+        let counted = combinations2(input_line);
+        let distinct = assignments.iter().unique().count();
+        let all = i64::try_from(limit).map_or(true, |limit| counted <= limit);
+        if all && i64::try_from(distinct)? != counted {
+            problems.push(format!(
+                "line {}: {}: {} distinct arrangements, counted {}",
+                i + 1,
+                input,
+                distinct,
+                counted
+            ));
+        }
+    }
+
+    Ok((checked, problems))
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct Parameters {
+    springs: Vec<Spring>,
+    damaged: Vec<i64>,
+    current: Option<i64>,
+}
+
+fn combinations_rec(memoized: &mut HashMap<Parameters, i64>, parameters: Parameters) -> i64 {
+    if let Some(result) = memoized.get(&parameters) {
+        *result
+    } else {
+        let Parameters {
+            ref springs,
+            ref damaged,
+            current,
+        } = parameters;
+
+        let result = match springs.get(0) {
+            Some(Spring::Damaged) => combinations_rec(
+                memoized,
+                Parameters {
+                    springs: springs[1..].to_vec(),
+                    damaged: damaged.clone(),
+                    current: current.map_or(Some(1), |c| Some(c + 1)),
+                },
+            ),
+
+            Some(Spring::Unknown) => {
+                let mut springs_operational = springs[1..].to_vec();
+                springs_operational.insert(0, Spring::Operational);
+
+                let mut springs_damaged = springs[1..].to_vec();
+                springs_damaged.insert(0, Spring::Damaged);
+
+                combinations_rec(
+                    memoized,
+                    Parameters {
+                        springs: springs_operational,
+                        damaged: damaged.clone(),
+                        current,
+                    },
+                ) + combinations_rec(
+                    memoized,
+                    Parameters {
+                        springs: springs_damaged,
+                        damaged: damaged.clone(),
+                        current,
+                    },
+                )
+            }
+
+            Some(Spring::Operational) => match (damaged.get(0), current) {
+                (Some(count1), Some(count2)) => {
+                    if *count1 == count2 {
+                        combinations_rec(
+                            memoized,
+                            Parameters {
+                                springs: springs[1..].to_vec(),
+                                damaged: damaged[1..].to_vec(),
+                                current: None,
+                            },
+                        )
+                    } else {
+                        0
+                    }
+                }
+                (_, None) => combinations_rec(
+                    memoized,
+                    Parameters {
+                        springs: springs[1..].to_vec(),
+                        damaged: damaged.clone(),
+                        current: None,
+                    },
+                ),
+                _ => 0,
+            },
+
+            None => match (damaged.get(0), current) {
+                (Some(count1), Some(count2)) => {
+                    if *count1 == count2 && damaged.len() == 1 {
+                        1
+                    } else {
+                        0
+                    }
+                }
+                (None, None) => 1,
+                _ => 0,
+            },
+        };
+
+        memoized.insert(parameters, result);
+
+        result
+    }
+}
+
+pub fn parse_line(line: String) -> Result<InputLine, Box<dyn Error>> {
+    let (springs_str, damaged_str) = line.split_once(" ").ok_or("Invalid input")?;
+    let springs = springs_str
+        .chars()
+        .map(|c| char_to_spring(c))
+        .collect::<Result<_, _>>()?;
+    let damaged = damaged_str
+        .split(",")
+        .map(|s| s.parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(InputLine { springs, damaged })
+}
+
+#[cfg(test)]
+mod day12 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+
+    use crate::{
+        canonical, combinations1, combinations2, display, enumerate_assignments, nth_assignment,
+        parse_line, repeat_five, solve1, solve2, solve2_sequential, verify, verify_small,
+        DedupStats, InputLine, LineCache, Spring,
+    };
+
+    const EXAMPLE1: &str = "\
+        #.#.### 1,1,3\n\
+        .#...#....###. 1,1,3\n\
+        .#.###.#.###### 1,3,1,6\n\
+        ####.#...#... 4,1,1\n\
+        #....######..#####. 1,6,5\n\
+        .###.##....# 3,2,1";
+
+    fn example1() -> Vec<InputLine> {
+        vec![
+            InputLine {
+                springs: vec![
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                ],
+                damaged: vec![1, 1, 3],
+            },
+            InputLine {
+                springs: vec![
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Operational,
+                ],
+                damaged: vec![1, 1, 3],
+            },
+            InputLine {
+                springs: vec![
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                ],
+                damaged: vec![1, 3, 1, 6],
+            },
+            InputLine {
+                springs: vec![
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Operational,
+                ],
+                damaged: vec![4, 1, 1],
+            },
+            InputLine {
+                springs: vec![
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Operational,
+                ],
+                damaged: vec![1, 6, 5],
+            },
+            InputLine {
+                springs: vec![
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Damaged,
+                    Spring::Damaged,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Operational,
+                    Spring::Damaged,
+                ],
+                damaged: vec![3, 2, 1],
+            },
+        ]
+    }
+
+    const LINE1: &str = "???.### 1,1,3";
+    fn line1() -> InputLine {
+        InputLine {
+            springs: vec![
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Operational,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Damaged,
+            ],
+            damaged: vec![1, 1, 3],
+        }
+    }
+
+    const LINE2: &str = ".??..??...?##. 1,1,3";
+    fn line2() -> InputLine {
+        InputLine {
+            springs: vec![
+                Spring::Operational,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Operational,
+                Spring::Operational,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Operational,
+                Spring::Operational,
+                Spring::Operational,
+                Spring::Unknown,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Operational,
+            ],
+            damaged: vec![1, 1, 3],
+        }
+    }
+
+    const LINE3: &str = "?#?#?#?#?#?#?#? 1,3,1,6";
+    fn line3() -> InputLine {
+        InputLine {
+            springs: vec![
+                Spring::Unknown,
+                Spring::Damaged,
+                Spring::Unknown,
+                Spring::Damaged,
+                Spring::Unknown,
+                Spring::Damaged,
+                Spring::Unknown,
+                Spring::Damaged,
+                Spring::Unknown,
+                Spring::Damaged,
+                Spring::Unknown,
+                Spring::Damaged,
+                Spring::Unknown,
+                Spring::Damaged,
+                Spring::Unknown,
+            ],
+            damaged: vec![1, 3, 1, 6],
+        }
+    }
+
+    const LINE4: &str = "????.#...#... 4,1,1";
+    fn line4() -> InputLine {
+        InputLine {
+            springs: vec![
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Operational,
+                Spring::Damaged,
+                Spring::Operational,
+                Spring::Operational,
+                Spring::Operational,
+                Spring::Damaged,
+                Spring::Operational,
+                Spring::Operational,
+                Spring::Operational,
+            ],
+            damaged: vec![4, 1, 1],
+        }
+    }
+
+    const LINE5: &str = "????.######..#####. 1,6,5";
+    fn line5() -> InputLine {
+        InputLine {
+            springs: vec![
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Operational,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Operational,
+                Spring::Operational,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Operational,
+            ],
+            damaged: vec![1, 6, 5],
+        }
+    }
+
+    const LINE6: &str = "?###???????? 3,2,1";
+    fn line6() -> InputLine {
+        InputLine {
+            springs: vec![
+                Spring::Unknown,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Damaged,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+                Spring::Unknown,
+            ],
+            damaged: vec![3, 2, 1],
+        }
+    }
+
+    fn example2_str() -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            LINE1, LINE2, LINE3, LINE4, LINE5, LINE6
+        )
+    }
+
+    fn example2() -> Vec<InputLine> {
+        vec![line1(), line2(), line3(), line4(), line5(), line6()]
+    }
+
+    #[test]
+    fn test_parse_example1() -> Result<(), Box<dyn Error>> {
+        let parsed = EXAMPLE1
+            .lines()
+            .map(|line| parse_line(line.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(parsed, example1());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_example2() -> Result<(), Box<dyn Error>> {
+        let parsed = example2_str()
+            .lines()
+            .map(|line| parse_line(line.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(parsed, example2());
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations1_line1() -> Result<(), Box<dyn Error>> {
+        let input = line1();
+        assert_eq!(combinations1(&input)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations2_line1() {
+        let input = line1();
+        assert_eq!(combinations2(&input), 1);
+    }
+
+    #[test]
+    fn test_combinations2_repeat_line1() {
+        let input = repeat_five(&line1());
+        assert_eq!(combinations2(&input), 1);
+    }
+
+    #[test]
+    fn test_combinations1_line2() -> Result<(), Box<dyn Error>> {
+        let input = line2();
+        assert_eq!(combinations1(&input)?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations2_line2() {
+        let input = line2();
+        assert_eq!(combinations2(&input), 4);
+    }
+
+    #[test]
+    fn test_combinations2_repeat_line2() {
+        let input = repeat_five(&line2());
+        assert_eq!(combinations2(&input), 16384);
+    }
+
+    #[test]
+    fn test_combinations1_line3() -> Result<(), Box<dyn Error>> {
+        let input = line3();
+        assert_eq!(combinations1(&input)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations2_line3() {
+        let input = line3();
+        assert_eq!(combinations2(&input), 1);
+    }
+
+    #[test]
+    fn test_combinations2_repeat_line3() {
+        let input = repeat_five(&line3());
+        assert_eq!(combinations2(&input), 1);
+    }
+
+    #[test]
+    fn test_combinations1_line4() -> Result<(), Box<dyn Error>> {
+        let input = line4();
+        assert_eq!(combinations1(&input)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations2_line4() {
+        let input = line4();
+        assert_eq!(combinations2(&input), 1);
+    }
+
+    #[test]
+    fn test_combinations2_repeat_line4() {
+        let input = repeat_five(&line4());
+        assert_eq!(combinations2(&input), 16);
+    }
+
+    #[test]
+    fn test_combinations1_line5() -> Result<(), Box<dyn Error>> {
+        let input = line5();
+        assert_eq!(combinations1(&input)?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations2_line5() {
+        let input = line5();
+        assert_eq!(combinations2(&input), 4);
+    }
+
+    #[test]
+    fn test_combinations2_repeat_line5() {
+        let input = repeat_five(&line5());
+        assert_eq!(combinations2(&input), 2500);
+    }
+
+    #[test]
+    fn test_combinations1_line6() -> Result<(), Box<dyn Error>> {
+        let input = line6();
+        assert_eq!(combinations1(&input)?, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations2_line6() {
+        let input = line6();
+        assert_eq!(combinations2(&input), 10);
+    }
+
+    #[test]
+    fn test_combinations2_repeat_line6() {
+        let input = repeat_five(&line6());
+        assert_eq!(combinations2(&input), 506250);
+    }
+
+    #[test]
+    fn test_verify_small_example() -> Result<(), Box<dyn Error>> {
+        let (checked, mismatches) = verify_small(&example2(), 20)?;
+        assert_eq!(checked, 6);
+        assert!(mismatches.is_empty());
+
+        // line 6 has 9 unknowns, the others less
+        let (checked, _) = verify_small(&example2(), 8)?;
+        assert_eq!(checked, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_enumerate_assignments() {
+        let displayed = |line: &InputLine, limit| {
+            enumerate_assignments(line, limit)
+                .iter()
+                .map(|springs| display(springs))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(displayed(&line1(), 10), vec!["#.#.###"]);
+        assert_eq!(
+            displayed(&line2(), 10),
+            vec![
+                "..#...#...###.",
+                "..#..#....###.",
+                ".#....#...###.",
+                ".#...#....###.",
+            ]
+        );
+
+        // line 6 has 10 arrangements, the ranks 0, 3 and 6 are taken
+        let all = enumerate_assignments(&line6(), 10);
+        assert_eq!(all.len(), 10);
+        assert_eq!(
+            enumerate_assignments(&line6(), 3),
+            vec![all[0].clone(), all[3].clone(), all[6].clone()]
+        );
+        assert_eq!(nth_assignment(&line6(), 10), None);
+        assert!(enumerate_assignments(&line6(), 0).is_empty());
+    }
+
+    #[test]
+    fn test_verify() -> Result<(), Box<dyn Error>> {
+        // 1 + 4 + 1 + 1 + 4 + 10
+        let (checked, problems) = verify(&example2(), 100)?;
+        assert_eq!(checked, 21);
+        assert!(problems.is_empty());
+
+        // sampled on the unfolded lines, which have up to 506250 arrangements
+        let unfolded = [repeat_five(&line1()), repeat_five(&line6())];
+        let (checked, problems) = verify(&unfolded, 2)?;
+        assert_eq!(checked, 1 + 2);
+        assert!(problems.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations1_too_many_damaged() -> Result<(), Box<dyn Error>> {
+        let input = parse_line("###.? 1".to_string())?;
+        assert_eq!(combinations1(&input)?, 0);
+        assert_eq!(combinations2(&input), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_parallel() {
+        assert_eq!(
+            solve2(example2().into_iter(), &LineCache::default()),
+            525152
+        );
+        assert_eq!(solve2_sequential(&example2()), 525152);
+    }
+
+    #[test]
+    fn test_solve2_parallel_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let lines = reader
+            .lines()
+            .process_results(|itr| itr.map(parse_line).collect::<Result<Vec<_>, _>>())??;
+
+        assert_eq!(solve2_sequential(&lines), 17391848518844);
+        assert_eq!(
+            solve2(lines.into_iter(), &LineCache::default()),
+            17391848518844
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical() -> Result<(), Box<dyn Error>> {
+        let line = parse_line("..#..?.##... 1,1,2".to_string())?;
+        assert_eq!(canonical(&line), "#.?.## 1,1,2");
+        assert_eq!(
+            canonical(&line),
+            canonical(&parse_line("#.?.## 1,1,2".to_string())?)
+        );
+        assert_eq!(canonical(&parse_line("... 1".to_string())?), " 1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_cache() -> Result<(), Box<dyn Error>> {
+        let lines = [
+            "???.### 1,1,3",
+            ".??..??...?##. 1,1,3",
+            "???.### 1,1,3",
+            "..???.###.. 1,1,3",
+            "???.### 1,1,4",
+        ]
+        .into_iter()
+        .map(|line| parse_line(line.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let cache = LineCache::default();
+        let result = solve1(lines.iter().cloned(), &cache);
+
+        assert_eq!(result, lines.iter().map(combinations2).sum::<i64>());
+        assert_eq!(
+            cache.stats(),
+            DedupStats {
+                lines: 5,
+                distinct: 3
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let result = reader.lines().process_results(|itr| {
+            itr.map(parse_line)
+                .process_results(|itr| solve1(itr, &LineCache::default()))
+        })??;
+
+        assert_eq!(result, 7047);
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinations2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let result = reader.lines().process_results(|itr| {
+            itr.map(parse_line)
+                .process_results(|itr| solve2(itr, &LineCache::default()))
+        })??;
+
+        assert_eq!(result, 17391848518844);
+        Ok(())
+    }
+}
@@ -0,0 +1,445 @@
+use itertools::Itertools;
+use lib::par::map_indexed;
+use std::{convert::identity, error::Error, str::FromStr};
+
+pub mod strings;
+
+// the bit i of a row is its column i and the bit j of a column its row j, a pattern compares two
+// lines with a xor
+#[derive(Debug, PartialEq, Eq)]
+pub struct Pattern {
+    width: usize,
+    rows: Vec<u32>,
+    columns: Vec<u32>,
+}
+
+impl Pattern {
+    pub fn from_lines(lines: &[String]) -> Result<Self, Box<dyn Error>> {
+        let width = lines.first().map_or(0, |line| line.len());
+        if width > 32 {
+            return Err("Pattern too wide".into());
+        }
+        if lines.len() > 32 {
+            return Err("Pattern too tall".into());
+        }
+
+        let rows = lines
+            .iter()
+            .map(|line| {
+                if line.len() != width {
+                    return Err(format!("Invalid line length: {}", line).into());
+                }
+
+                line.chars().enumerate().try_fold(0, |acc, (i, c)| match c {
+                    '#' => Ok(acc | 1 << i),
+                    '.' => Ok(acc),
+                    _ => Err(format!("Invalid char: {}", c).into()),
+                })
+            })
+            .collect::<Result<Vec<u32>, Box<dyn Error>>>()?;
+
+        let columns = (0..width)
+            .map(|i| {
+                rows.iter()
+                    .enumerate()
+                    .fold(0, |acc, (j, row)| acc | (row >> i & 1) << j)
+            })
+            .collect();
+
+        Ok(Pattern {
+            width,
+            rows,
+            columns,
+        })
+    }
+}
+
+fn get_mirror(
+    masks: &[u32],
+    number_of_different_chars: u32,
+) -> Result<Option<i32>, Box<dyn Error>> {
+    let indexes = (1..masks.len()).map(|i| -> Result<Option<i32>, Box<dyn Error>> {
+        let start = masks.get(0..i).ok_or("No start")?;
+        let end = masks.get(i..).ok_or("No end")?;
+
+        let different_chars = start
+            .iter()
+            .rev()
+            .zip(end)
+            .map(|(mask1, mask2)| (mask1 ^ mask2).count_ones())
+            .sum::<u32>();
+
+        Ok((different_chars == number_of_different_chars).then_some(i32::try_from(i)?))
+    });
+
+    indexes.process_results(|mut itr| itr.find_map(identity))
+}
+
+// a bit of one of the masks, as (mask index, bit index)
+type MaskBit = (usize, usize);
+
+// the pairs of bits that differ when the masks of a number of bits are folded before index i
+fn mismatches(masks: &[u32], bits: usize, i: usize) -> Result<Vec<[MaskBit; 2]>, Box<dyn Error>> {
+    let start = masks.get(0..i).ok_or("No start")?;
+    let end = masks.get(i..).ok_or("No end")?;
+
+    Ok(start
+        .iter()
+        .enumerate()
+        .rev()
+        .zip(end.iter().enumerate())
+        .flat_map(|((index1, mask1), (index2, mask2))| {
+            let different = mask1 ^ mask2;
+            (0..bits)
+                .filter(move |bit| different >> bit & 1 == 1)
+                .map(move |bit| [(index1, bit), (i + index2, bit)])
+        })
+        .collect())
+}
+
+// a reflection line after a number of rows or columns
+#[derive(Debug, PartialEq, Eq)]
+pub enum Reflection {
+    Horizontal(usize),
+    Vertical(usize),
+}
+
+impl FromStr for Reflection {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("h", index)) => Ok(Reflection::Horizontal(index.parse()?)),
+            Some(("v", index)) => Ok(Reflection::Vertical(index.parse()?)),
+            _ => Err(format!("Invalid reflection: {}", s).into()),
+        }
+    }
+}
+
+// a (row, column) position in a pattern
+type Cell = (usize, usize);
+
+// the two cells whose flip makes the target a reflection line of the
+// pattern, None if the line is already a reflection or needs more than one flip
+pub fn smudge_for(
+    pattern: &Pattern,
+    target: &Reflection,
+) -> Result<Option<[Cell; 2]>, Box<dyn Error>> {
+    let (masks, bits, index) = match target {
+        Reflection::Horizontal(index) => (&pattern.rows, pattern.width, *index),
+        Reflection::Vertical(index) => (&pattern.columns, pattern.rows.len(), *index),
+    };
+    if index == 0 || index >= masks.len() {
+        return Err(format!("Reflection out of the pattern: {:?}", target).into());
+    }
+
+    let to_cell = |(index, bit): MaskBit| match target {
+        Reflection::Horizontal(_) => (index, bit),
+        Reflection::Vertical(_) => (bit, index),
+    };
+
+    Ok(match mismatches(masks, bits, index)?[..] {
+        [[bit1, bit2]] => Some([to_cell(bit1), to_cell(bit2)]),
+        _ => None,
+    })
+}
+
+fn get_mirror_horizontally(
+    pattern: &Pattern,
+    number_of_different_chars: u32,
+) -> Result<Option<i32>, Box<dyn Error>> {
+    get_mirror(&pattern.rows, number_of_different_chars)
+}
+
+fn get_mirror_vertically(
+    pattern: &Pattern,
+    number_of_different_chars: u32,
+) -> Result<Option<i32>, Box<dyn Error>> {
+    get_mirror(&pattern.columns, number_of_different_chars)
+}
+
+pub fn solve_pattern(
+    pattern: &Pattern,
+    number_of_different_chars: u32,
+) -> Result<i32, Box<dyn Error>> {
+    let vertically = get_mirror_vertically(pattern, number_of_different_chars)?.map_or(0, identity);
+    let horizontally =
+        get_mirror_horizontally(pattern, number_of_different_chars)?.map_or(0, identity);
+
+    Ok(vertically + horizontally * 100)
+}
+
+pub fn solve_pattern1(pattern: &Pattern) -> Result<i32, Box<dyn Error>> {
+    solve_pattern(pattern, 0)
+}
+
+pub fn solve_pattern2(pattern: &Pattern) -> Result<i32, Box<dyn Error>> {
+    solve_pattern(pattern, 1)
+}
+
+// the patterns are independent so they are solved in parallel, errors aren't Send so they are
+// carried as strings between the threads
+pub fn solve(
+    patterns: &[Pattern],
+    solve_pattern: fn(&Pattern) -> Result<i32, Box<dyn Error>>,
+) -> Result<i32, Box<dyn Error>> {
+    map_indexed(patterns, |pattern| {
+        solve_pattern(pattern).map_err(|e| e.to_string())
+    })
+    .into_iter()
+    .map(|timed| timed.value)
+    .sum::<Result<i32, _>>()
+    .map_err(Into::into)
+}
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Vec<Pattern>, Box<dyn Error>> {
+    strings::parse(itr)
+        .iter()
+        .map(|lines| Pattern::from_lines(lines))
+        .collect()
+}
+
+#[cfg(test)]
+mod day13 {
+    use itertools::Itertools;
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use crate::{
+        get_mirror_horizontally, get_mirror_vertically, mismatches, parse, smudge_for, solve,
+        solve_pattern1, solve_pattern2, strings, Pattern, Reflection,
+    };
+
+    const EXAMPLE1: &str = "\
+        #.##..##.\n\
+        ..#.##.#.\n\
+        ##......#\n\
+        ##......#\n\
+        ..#.##.#.\n\
+        ..##..##.\n\
+        #.#.##.#.";
+
+    const EXAMPLE2: &str = "\
+        #...##..#\n\
+        #....#..#\n\
+        ..##..###\n\
+        #####.##.\n\
+        #####.##.\n\
+        ..##..###\n\
+        #....#..#";
+
+    fn both_examples() -> String {
+        format!("{}\n\n{}", EXAMPLE1, EXAMPLE2)
+    }
+
+    fn pattern(s: &str) -> Result<Pattern, Box<dyn Error>> {
+        Pattern::from_lines(&s.lines().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_pattern_from_lines() -> Result<(), Box<dyn Error>> {
+        let result = pattern("#.\n.#\n##")?;
+        assert_eq!(
+            result,
+            Pattern {
+                width: 2,
+                rows: vec![0b01, 0b10, 0b11],
+                columns: vec![0b101, 0b110]
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_from_lines_invalid() {
+        assert!(pattern("#.\n.#.").is_err());
+        assert!(pattern("#.\n.x").is_err());
+        assert!(pattern(&"#".repeat(33)).is_err());
+        assert!(pattern(&["#"; 33].join("\n")).is_err());
+    }
+
+    #[test]
+    fn test_mirror_vertically() -> Result<(), Box<dyn Error>> {
+        let result = get_mirror_vertically(&pattern(EXAMPLE1)?, 0)?.ok_or("No result")?;
+        assert_eq!(result, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mirror_horizontally() -> Result<(), Box<dyn Error>> {
+        let result = get_mirror_horizontally(&pattern(EXAMPLE2)?, 0)?.ok_or("No result")?;
+        assert_eq!(result, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_pattern1_example1() -> Result<(), Box<dyn Error>> {
+        let result = solve_pattern1(&pattern(EXAMPLE1)?)?;
+        assert_eq!(result, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_pattern1_example2() -> Result<(), Box<dyn Error>> {
+        let result = solve_pattern1(&pattern(EXAMPLE2)?)?;
+        assert_eq!(result, 400);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_pattern1_both() -> Result<(), Box<dyn Error>> {
+        let patterns = parse(both_examples().lines().map(|s| s.to_string()))?;
+        let result = solve(&patterns, solve_pattern1)?;
+        assert_eq!(result, 405);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_pattern2_example1() -> Result<(), Box<dyn Error>> {
+        let result = solve_pattern2(&pattern(EXAMPLE1)?)?;
+        assert_eq!(result, 300);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_pattern2_example2() -> Result<(), Box<dyn Error>> {
+        let result = solve_pattern2(&pattern(EXAMPLE2)?)?;
+        assert_eq!(result, 100);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_pattern2_both() -> Result<(), Box<dyn Error>> {
+        let patterns = parse(both_examples().lines().map(|s| s.to_string()))?;
+        let result = solve(&patterns, solve_pattern2)?;
+        assert_eq!(result, 400);
+        Ok(())
+    }
+
+    #[test]
+    fn test_strings() -> Result<(), Box<dyn Error>> {
+        let sections = strings::parse(both_examples().lines().map(|s| s.to_string()));
+        assert_eq!(sections.len(), 2);
+
+        let patterns = parse(both_examples().lines().map(|s| s.to_string()))?;
+        assert_eq!(
+            strings::solve(&sections, 0)?,
+            solve(&patterns, solve_pattern1)?
+        );
+        assert_eq!(
+            strings::solve(&sections, 1)?,
+            solve(&patterns, solve_pattern2)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_mismatches() -> Result<(), Box<dyn Error>> {
+        // the first example reflects vertically after the fifth column
+        let pattern = pattern(EXAMPLE1)?;
+        assert!(mismatches(&pattern.columns, 7, 5)?.is_empty());
+        // its smudge is on the first row, folded on the row after the third one
+        assert_eq!(mismatches(&pattern.rows, 9, 3)?, vec![[(0, 0), (5, 0)]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_reflection() -> Result<(), Box<dyn Error>> {
+        assert_eq!("h:4".parse::<Reflection>()?, Reflection::Horizontal(4));
+        assert_eq!("v:5".parse::<Reflection>()?, Reflection::Vertical(5));
+        assert!("x:5".parse::<Reflection>().is_err());
+        assert!("h:".parse::<Reflection>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_smudge_for() -> Result<(), Box<dyn Error>> {
+        // the smudges of part 2
+        assert_eq!(
+            smudge_for(&pattern(EXAMPLE1)?, &Reflection::Horizontal(3))?,
+            Some([(0, 0), (5, 0)])
+        );
+        assert_eq!(
+            smudge_for(&pattern(EXAMPLE2)?, &Reflection::Horizontal(1))?,
+            Some([(0, 4), (1, 4)])
+        );
+
+        // already a reflection
+        assert_eq!(
+            smudge_for(&pattern(EXAMPLE1)?, &Reflection::Vertical(5))?,
+            None
+        );
+        assert_eq!(
+            smudge_for(&pattern(EXAMPLE2)?, &Reflection::Horizontal(4))?,
+            None
+        );
+
+        // too many differences
+        assert_eq!(
+            smudge_for(&pattern(EXAMPLE1)?, &Reflection::Vertical(1))?,
+            None
+        );
+
+        // out of the pattern
+        assert!(smudge_for(&pattern(EXAMPLE1)?, &Reflection::Horizontal(0)).is_err());
+        assert!(smudge_for(&pattern(EXAMPLE1)?, &Reflection::Vertical(9)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_smudge_for_flip() -> Result<(), Box<dyn Error>> {
+        // flipping the other cell of the pair works as well
+        let [_, (row, column)] =
+            smudge_for(&pattern(EXAMPLE1)?, &Reflection::Horizontal(3))?.ok_or("No smudge")?;
+        let mut lines = EXAMPLE1.lines().map(|s| s.to_string()).collect::<Vec<_>>();
+        let flipped = if &lines[row][column..=column] == "#" {
+            "."
+        } else {
+            "#"
+        };
+        lines[row].replace_range(column..=column, flipped);
+
+        let flipped = Pattern::from_lines(&lines)?;
+        assert!(mismatches(&flipped.rows, flipped.width, 3)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_error() -> Result<(), Box<dyn Error>> {
+        // an error on one of the patterns is reported once back on the main thread
+        let patterns = vec![pattern(EXAMPLE1)?, pattern("#.\n##")?];
+        let result = solve(&patterns, |pattern| {
+            (pattern.rows.len() < 7)
+                .then_some(0)
+                .ok_or_else(|| "Too many rows".into())
+        });
+        assert_eq!(
+            result.err().map(|e| e.to_string()),
+            Some("Too many rows".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let patterns = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve(&patterns, solve_pattern1)?;
+        assert_eq!(result, 35232);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let patterns = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve(&patterns, solve_pattern2)?;
+        assert_eq!(result, 37982);
+        Ok(())
+    }
+}
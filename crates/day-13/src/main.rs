@@ -1,250 +1,114 @@
+use day_13::{
+    parse, smudge_for, solve, solve_pattern, solve_pattern1, solve_pattern2, strings, Pattern,
+    Reflection,
+};
 use itertools::Itertools;
-use lib::get_args;
-use std::{
-    convert::identity,
-    error::Error,
-    io::{stdin, BufRead},
-    process::exit,
+use lib::{
+    cli::Options,
+    validate::{check_each_section, check_grid, check_sections, Problem},
 };
+use std::{error::Error, io::BufRead, process::exit, time::Instant};
 
-fn usage(prog_name: String) {
-    println!("Usage: {} [-1|-2|-h]", prog_name);
+fn usage(options: &Options) {
+    println!(
+        "{}",
+        options.usage("[-1|-2|--target-reflection h:N|v:N|--bench|--validate|-h]")
+    );
     exit(0)
 }
 
+fn validate(lines: &[String]) -> Vec<Problem> {
+    check_sections(lines, None)
+        .into_iter()
+        .chain(check_each_section(lines, |section| {
+            check_grid(section, ".#")
+                .into_iter()
+                .chain(
+                    Pattern::from_lines(section)
+                        .err()
+                        .map(|e| Problem::new(1, None, e.to_string())),
+                )
+                .collect()
+        }))
+        .collect()
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let (prog_name, args) = get_args()?;
+    let options = Options::parse()?;
+    let args = &options.args;
+    let _timer = options.timer();
 
-    match args.get(0) {
+    match options.mode() {
         Some(arg) if arg == "-1" || arg == "-2" => {
-            let result = stdin()
-                .lock()
+            let patterns = options
+                .input()?
                 .lines()
-                .process_results(|itr| -> Result<i32, _> {
-                    let patterns = parse(itr);
-                    patterns
-                        .iter()
-                        .map(|p| {
-                            if arg == "-1" {
-                                solve_pattern1(p.iter().cloned())
-                            } else {
-                                solve_pattern2(p.iter().cloned())
-                            }
-                        })
-                        .sum::<Result<i32, _>>()
-                })??;
+                .process_results(|itr| parse(itr))??;
+            let solve_pattern = if arg == "-1" {
+                solve_pattern1
+            } else {
+                solve_pattern2
+            };
+            let result = solve(&patterns, solve_pattern)?;
 
             println!("{}", result);
         }
-        _ => usage(prog_name),
-    }
-    Ok(())
-}
-
-fn get_mirror_horizontally(
-    itr: impl Iterator<Item = String>,
-    number_of_different_chars: usize,
-) -> Result<Option<i32>, Box<dyn Error>> {
-    let lines = itr.collect::<Vec<_>>();
-    let before_last = if lines.len() != 0 { lines.len() - 1 } else { 0 };
-
-    let indexes = (0..before_last).map(|i| -> Result<Option<i32>, Box<dyn Error>> {
-        let start = lines.as_slice().get(0..i + 1).ok_or("No start")?;
-        let end = lines.as_slice().get(i + 1..).ok_or("No end")?;
-
-        let mirror_equal = start
-            .iter()
-            .rev()
-            .zip(end)
-            .map(|(string1, string2)| {
-                // get the number of different chars
-                string1
-                    .chars()
-                    .zip(string2.chars())
-                    .filter(|(c1, c2)| c1 != c2)
-                    .count()
-            })
-            .sum::<usize>();
-
-        Ok((mirror_equal == number_of_different_chars).then_some(i32::try_from(i)? + 1))
-    });
-
-    indexes.process_results(|mut itr| itr.find_map(identity))
-}
-
-fn get_mirror_vertically(
-    itr: impl Iterator<Item = String>,
-    number_of_different_chars: usize,
-) -> Result<Option<i32>, Box<dyn Error>> {
-    let vect_of_strings = itr.collect::<Vec<_>>();
-    match vect_of_strings.get(0) {
-        None => Ok(None),
-        Some(s) => {
-            let transposed = (0..s.len())
-                .map(|i| {
-                    vect_of_strings
-                        .iter()
-                        .map(|s| s.chars().nth(i).ok_or("No char".into()))
-                        .collect::<Result<String, Box<dyn Error>>>()
-                })
-                .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
-
-            get_mirror_horizontally(transposed.into_iter(), number_of_different_chars)
+        Some(arg) if arg == "--target-reflection" => {
+            let target = args
+                .get(1)
+                .ok_or("Missing target reflection")?
+                .parse::<Reflection>()?;
+            let patterns = options
+                .input()?
+                .lines()
+                .process_results(|itr| parse(itr))??;
+
+            patterns
+                .iter()
+                .try_for_each(|pattern| -> Result<(), Box<dyn Error>> {
+                    match smudge_for(pattern, &target)? {
+                        Some([(row1, column1), (row2, column2)]) => {
+                            println!("{},{} or {},{}", row1, column1, row2, column2)
+                        }
+                        None => println!("none"),
+                    }
+                    Ok(())
+                })?;
         }
+        Some(arg) if arg == "--bench" => {
+            let sections = options
+                .input()?
+                .lines()
+                .process_results(|itr| strings::parse(itr))?;
+
+            // both run on a single thread, the masks are built in the time of the bitmasks
+            for (part, number_of_different_chars) in [(1, 0), (2, 1)] {
+                let start = Instant::now();
+                let with_strings =
+                    strings::solve(&sections, usize::try_from(number_of_different_chars)?)?;
+                let strings_elapsed = start.elapsed();
+
+                let start = Instant::now();
+                let with_bitmasks = sections
+                    .iter()
+                    .map(|lines| {
+                        solve_pattern(&Pattern::from_lines(lines)?, number_of_different_chars)
+                    })
+                    .sum::<Result<i32, _>>()?;
+                let bitmasks_elapsed = start.elapsed();
+
+                if with_strings != with_bitmasks {
+                    return Err(
+                        format!("Results differ: {} and {}", with_strings, with_bitmasks).into(),
+                    );
+                }
+                println!("part {}: {}", part, with_bitmasks);
+                println!("strings:  {:?}", strings_elapsed);
+                println!("bitmasks: {:?}", bitmasks_elapsed);
+            }
+        }
+        Some(arg) if arg == "--validate" => lib::validate::run(options.input()?, validate)?,
+        _ => usage(&options),
     }
-}
-
-fn solve_pattern(
-    itr: impl Iterator<Item = String> + Clone,
-    number_of_different_chars: usize,
-) -> Result<i32, Box<dyn Error>> {
-    let vertically =
-        get_mirror_vertically(itr.clone(), number_of_different_chars)?.map_or(0, identity);
-    let horizontally = get_mirror_horizontally(itr, number_of_different_chars)?.map_or(0, identity);
-
-    Ok(vertically + horizontally * 100)
-}
-
-fn solve_pattern1(itr: impl Iterator<Item = String> + Clone) -> Result<i32, Box<dyn Error>> {
-    solve_pattern(itr, 0)
-}
-
-fn solve_pattern2(itr: impl Iterator<Item = String> + Clone) -> Result<i32, Box<dyn Error>> {
-    solve_pattern(itr, 1)
-}
-
-fn parse(itr: impl Iterator<Item = String>) -> Vec<Vec<String>> {
-    itr.chunk_by(|s| s.is_empty())
-        .into_iter()
-        .filter(|(empty, _)| !empty)
-        .map(|(_, group)| group.collect())
-        .collect()
-}
-
-#[cfg(test)]
-mod day13 {
-    use itertools::Itertools;
-    use std::{
-        error::Error,
-        fs::File,
-        io::{BufRead, BufReader},
-    };
-
-    use crate::{
-        get_mirror_horizontally, get_mirror_vertically, parse, solve_pattern1, solve_pattern2,
-    };
-
-    const EXAMPLE1: &str = "\
-        #.##..##.\n\
-        ..#.##.#.\n\
-        ##......#\n\
-        ##......#\n\
-        ..#.##.#.\n\
-        ..##..##.\n\
-        #.#.##.#.";
-
-    const EXAMPLE2: &str = "\
-        #...##..#\n\
-        #....#..#\n\
-        ..##..###\n\
-        #####.##.\n\
-        #####.##.\n\
-        ..##..###\n\
-        #....#..#";
-
-    fn both_examples() -> String {
-        format!("{}\n\n{}", EXAMPLE1, EXAMPLE2)
-    }
-
-    #[test]
-    fn test_mirror_vertically() -> Result<(), Box<dyn Error>> {
-        let result = get_mirror_vertically(EXAMPLE1.lines().map(|s| s.to_string()), 0)?
-            .ok_or("No result")?;
-        assert_eq!(result, 5);
-        Ok(())
-    }
-
-    #[test]
-    fn test_mirror_horizontally() -> Result<(), Box<dyn Error>> {
-        let result = get_mirror_horizontally(EXAMPLE2.lines().map(|s| s.to_string()), 0)?
-            .ok_or("No result")?;
-        assert_eq!(result, 4);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve_pattern1_example1() -> Result<(), Box<dyn Error>> {
-        let result = solve_pattern1(EXAMPLE1.lines().map(|s| s.to_string()))?;
-        assert_eq!(result, 5);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve_pattern1_example2() -> Result<(), Box<dyn Error>> {
-        let result = solve_pattern1(EXAMPLE2.lines().map(|s| s.to_string()))?;
-        assert_eq!(result, 400);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve_pattern1_both() -> Result<(), Box<dyn Error>> {
-        let patterns = parse(both_examples().lines().map(|s| s.to_string()));
-        let result = patterns
-            .iter()
-            .map(|p| solve_pattern1(p.iter().cloned()))
-            .sum::<Result<i32, _>>()?;
-        assert_eq!(result, 405);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve_pattern2_example1() -> Result<(), Box<dyn Error>> {
-        let result = solve_pattern2(EXAMPLE1.lines().map(|s| s.to_string()))?;
-        assert_eq!(result, 300);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve_pattern2_example2() -> Result<(), Box<dyn Error>> {
-        let result = solve_pattern2(EXAMPLE2.lines().map(|s| s.to_string()))?;
-        assert_eq!(result, 100);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve_pattern2_both() -> Result<(), Box<dyn Error>> {
-        let patterns = parse(both_examples().lines().map(|s| s.to_string()));
-        let result = patterns
-            .iter()
-            .map(|p| solve_pattern2(p.iter().cloned()))
-            .sum::<Result<i32, _>>()?;
-        assert_eq!(result, 400);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
-        let file = File::open("input")?;
-        let reader = BufReader::new(file);
-        let patterns = reader.lines().process_results(|itr| parse(itr))?;
-        let result = patterns
-            .iter()
-            .map(|p| solve_pattern1(p.iter().cloned()))
-            .sum::<Result<i32, _>>()?;
-        assert_eq!(result, 35232);
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
-        let file = File::open("input")?;
-        let reader = BufReader::new(file);
-        let patterns = reader.lines().process_results(|itr| parse(itr))?;
-        let result = patterns
-            .iter()
-            .map(|p| solve_pattern2(p.iter().cloned()))
-            .sum::<Result<i32, _>>()?;
-        assert_eq!(result, 37982);
-        Ok(())
-    }
+    Ok(())
 }
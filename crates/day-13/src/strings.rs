@@ -0,0 +1,67 @@
+// the patterns as lines of chars, compared one char at a time. The bitmasks replaced it, it is
+// kept to check and benchmark them
+
+use itertools::Itertools;
+use std::error::Error;
+
+type Lines = Vec<Vec<char>>;
+
+// the sections of lines separated by empty lines
+pub fn parse(itr: impl Iterator<Item = String>) -> Vec<Vec<String>> {
+    itr.chunk_by(|s| s.is_empty())
+        .into_iter()
+        .filter(|(empty, _)| !empty)
+        .map(|(_, group)| group.collect())
+        .collect()
+}
+
+fn get_mirror(lines: &Lines, number_of_different_chars: usize) -> Option<usize> {
+    (1..lines.len()).find(|&i| {
+        let different_chars = lines[..i]
+            .iter()
+            .rev()
+            .zip(&lines[i..])
+            .map(|(line1, line2)| line1.iter().zip(line2).filter(|(c1, c2)| c1 != c2).count())
+            .sum::<usize>();
+
+        different_chars == number_of_different_chars
+    })
+}
+
+fn transpose(lines: &Lines) -> Result<Lines, Box<dyn Error>> {
+    let width = lines.first().map_or(0, |line| line.len());
+
+    (0..width)
+        .map(|i| {
+            lines
+                .iter()
+                .map(|line| line.get(i).copied().ok_or("No char".into()))
+                .collect()
+        })
+        .collect()
+}
+
+pub fn solve_pattern(
+    lines: &[String],
+    number_of_different_chars: usize,
+) -> Result<i32, Box<dyn Error>> {
+    let rows = lines
+        .iter()
+        .map(|line| line.chars().collect())
+        .collect::<Lines>();
+
+    let vertically = get_mirror(&transpose(&rows)?, number_of_different_chars).unwrap_or(0);
+    let horizontally = get_mirror(&rows, number_of_different_chars).unwrap_or(0);
+
+    Ok(i32::try_from(vertically + horizontally * 100)?)
+}
+
+pub fn solve(
+    sections: &[Vec<String>],
+    number_of_different_chars: usize,
+) -> Result<i32, Box<dyn Error>> {
+    sections
+        .iter()
+        .map(|lines| solve_pattern(lines, number_of_different_chars))
+        .sum()
+}
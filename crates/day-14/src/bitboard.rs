@@ -0,0 +1,161 @@
+// the platform as one bitmask per row, the bit x of a row being its column x. A tilt moves the
+// rounded rocks of a whole row at once instead of one rock at a time
+
+use crate::{Cell, Direction, SPIN_CYCLE};
+use std::{collections::HashMap, error::Error};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bitboard {
+    width: usize,
+    cubes: Vec<u128>,
+    rocks: Vec<u128>,
+}
+
+impl Bitboard {
+    pub fn new(cells: &[Vec<Cell>]) -> Result<Self, Box<dyn Error>> {
+        let width = cells.first().map_or(0, |row| row.len());
+        if cells.iter().any(|row| row.len() != width) {
+            return Err("Inconsistent row length".into());
+        }
+        if width > 128 {
+            return Err(format!("Too wide for a bitboard: {}", width).into());
+        }
+
+        let mask_of = |row: &Vec<Cell>, cell: Cell| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, c)| **c == cell)
+                .fold(0u128, |mask, (x, _)| mask | 1 << x)
+        };
+
+        Ok(Bitboard {
+            width,
+            cubes: cells.iter().map(|row| mask_of(row, Cell::Cube)).collect(),
+            rocks: cells
+                .iter()
+                .map(|row| mask_of(row, Cell::Rounded))
+                .collect(),
+        })
+    }
+
+    fn row_mask(&self) -> u128 {
+        u128::MAX.checked_shr(128 - self.width as u32).unwrap_or(0)
+    }
+
+    // the rows are settled in the order the rocks fall, each one going as far as the ones already
+    // settled allow
+    fn tilt_vertically(&mut self, rows: impl Iterator<Item = usize>, towards: fn(usize) -> usize) {
+        let height = self.rocks.len();
+
+        for y in rows {
+            let mut moving = self.rocks[y];
+            self.rocks[y] = 0;
+
+            let mut current = y;
+            while moving != 0 {
+                let target = towards(current);
+                if target >= height {
+                    break;
+                }
+
+                let free = !(self.rocks[target] | self.cubes[target]);
+                self.rocks[current] |= moving & !free;
+                moving &= free;
+                current = target;
+            }
+            self.rocks[current] |= moving;
+        }
+    }
+
+    // the rocks free to go one column further move together, until none of them can
+    fn tilt_horizontally(&mut self, west: bool) {
+        let mask = self.row_mask();
+
+        for (rocks, cubes) in self.rocks.iter_mut().zip(&self.cubes) {
+            loop {
+                let free = !(*rocks | cubes) & mask;
+                let movable = if west {
+                    *rocks & (free << 1)
+                } else {
+                    *rocks & (free >> 1)
+                };
+                if movable == 0 {
+                    break;
+                }
+
+                let moved = if west { movable >> 1 } else { movable << 1 };
+                *rocks = (*rocks & !movable) | moved;
+            }
+        }
+    }
+
+    pub fn tilt(&mut self, direction: Direction) {
+        let height = self.rocks.len();
+
+        match direction {
+            Direction::North => self.tilt_vertically(1..height, |y| y.wrapping_sub(1)),
+            Direction::South => self.tilt_vertically((0..height).rev(), |y| y + 1),
+            Direction::West => self.tilt_horizontally(true),
+            Direction::East => self.tilt_horizontally(false),
+        }
+    }
+
+    pub fn tilt_sequence(&mut self, sequence: &[Direction]) {
+        sequence.iter().for_each(|direction| self.tilt(*direction));
+    }
+
+    pub fn load(&self) -> Result<i32, Box<dyn Error>> {
+        let height = self.rocks.len();
+
+        self.rocks
+            .iter()
+            .enumerate()
+            .map(|(y, row)| Ok(i32::try_from(row.count_ones() as usize * (height - y))?))
+            .sum()
+    }
+}
+
+pub fn solve1(cells: Vec<Vec<Cell>>) -> Result<i32, Box<dyn Error>> {
+    let mut bitboard = Bitboard::new(&cells)?;
+    bitboard.tilt(Direction::North);
+
+    bitboard.load()
+}
+
+pub fn solve2(cells: Vec<Vec<Cell>>) -> Result<i32, Box<dyn Error>> {
+    run_cycles(&cells, &SPIN_CYCLE, 1_000_000_000)?.load()
+}
+
+// same as crate::run_cycles, the states being the rows of rocks
+pub fn run_cycles(
+    cells: &[Vec<Cell>],
+    sequence: &[Direction],
+    n: u64,
+) -> Result<Bitboard, Box<dyn Error>> {
+    let mut bitboard = Bitboard::new(cells)?;
+
+    let mut cache: HashMap<Vec<u128>, u64> = HashMap::new();
+    let mut states: Vec<Vec<u128>> = Vec::new();
+
+    for i in 0..n {
+        if let Some(&cached) = cache.get(&bitboard.rocks) {
+            let number_of_states_in_cycle = i - cached;
+            let remaining_steps = n - i;
+            let last_state_index = cached + remaining_steps % number_of_states_in_cycle;
+
+            bitboard.rocks = states
+                .get(usize::try_from(last_state_index)?)
+                .ok_or("Index out of bounds")?
+                .clone();
+
+            break;
+        }
+
+        states.push(bitboard.rocks.clone());
+        cache.insert(bitboard.rocks.clone(), i);
+
+        bitboard.tilt_sequence(sequence);
+    }
+
+    Ok(bitboard)
+}
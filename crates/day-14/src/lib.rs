@@ -0,0 +1,1058 @@
+use lib::cli::Implementation;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    error::Error,
+};
+
+#[cfg(feature = "simd")]
+pub mod bitboard;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Cell {
+    Rounded,
+    Cube,
+    Empty,
+}
+
+// the load on the north beams after tilting north, column by column without moving the rocks: a
+// rounded rock rolls to the next free row of its column, which is below the last cube or rock seen
+pub fn solve1(cells: Vec<Vec<Cell>>) -> Result<i32, Box<dyn Error>> {
+    let height = i32::try_from(cells.len())?;
+    let width = cells.first().map_or(0, |row| row.len());
+
+    (0..width)
+        .map(|x| {
+            let mut free = 0;
+            cells
+                .iter()
+                .zip(0..)
+                .map(|(row, y)| -> Result<i32, Box<dyn Error>> {
+                    match row.get(x).ok_or("Vec too small")? {
+                        Cell::Rounded => {
+                            let load = height - free;
+                            free += 1;
+                            Ok(load)
+                        }
+                        Cell::Cube => {
+                            free = y + 1;
+                            Ok(0)
+                        }
+                        Cell::Empty => Ok(0),
+                    }
+                })
+                .sum::<Result<i32, Box<dyn Error>>>()
+        })
+        .sum()
+}
+
+// the first version, tilting the transposed grid to the left, kept to check the scan against
+#[cfg(test)]
+fn solve1_transpose(cells: Vec<Vec<Cell>>) -> Result<i32, Box<dyn Error>> {
+    transpose(cells).and_then(|cells| count(&tilt_left(cells)))
+}
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Vec<Vec<Cell>>, Box<dyn Error>> {
+    itr.map(|line| -> Result<Vec<Cell>, Box<dyn Error>> {
+        line.chars()
+            .map(|c| match c {
+                'O' => Ok(Cell::Rounded),
+                '#' => Ok(Cell::Cube),
+                '.' => Ok(Cell::Empty),
+                _ => Err("Invalid character".into()),
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })
+    .collect::<Result<Vec<_>, _>>()
+}
+
+fn transpose(cells: Vec<Vec<Cell>>) -> Result<Vec<Vec<Cell>>, Box<dyn Error>> {
+    (0..cells.len())
+        .map(|i| {
+            cells
+                .iter()
+                .map(|line| line.get(i).map(|c| c.clone()).ok_or("Vec too small".into()))
+                .collect::<Result<Vec<_>, Box<dyn Error>>>()
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()
+}
+
+fn cmp1(c1: &Cell, c2: &Cell) -> Ordering {
+    match (c1, c2) {
+        (Cell::Rounded, Cell::Empty) => Ordering::Less,
+        _ => Ordering::Equal,
+    }
+}
+
+fn cmp2(c1: &Cell, c2: &Cell) -> Ordering {
+    match (c1, c2) {
+        (Cell::Empty, Cell::Rounded) => Ordering::Less,
+        _ => Ordering::Equal,
+    }
+}
+
+fn tilt(cells: Vec<Vec<Cell>>, cmp: fn(c1: &Cell, c2: &Cell) -> Ordering) -> Vec<Vec<Cell>> {
+    cells
+        .into_iter()
+        .map(|mut row| {
+            row.as_mut_slice()
+                .split_mut(|c| c == &Cell::Cube)
+                .for_each(|continuous_chunk| {
+                    continuous_chunk.sort_by(cmp);
+                });
+            row
+        })
+        .collect::<Vec<_>>()
+}
+
+fn tilt_left(cells: Vec<Vec<Cell>>) -> Vec<Vec<Cell>> {
+    tilt(cells, cmp1)
+}
+
+fn tilt_right(cells: Vec<Vec<Cell>>) -> Vec<Vec<Cell>> {
+    tilt(cells, cmp2)
+}
+
+fn cycle(cells: Vec<Vec<Cell>>) -> Result<Vec<Vec<Cell>>, Box<dyn Error>> {
+    // north
+    let cells = tilt_left(transpose(cells)?);
+    // west
+    let cells = tilt_left(transpose(cells)?);
+    // south
+    let cells = tilt_right(transpose(cells)?);
+    // east
+    let cells = tilt_right(transpose(cells)?);
+
+    Ok(cells)
+}
+
+pub fn solve2(cells: Vec<Vec<Cell>>) -> Result<i32, Box<dyn Error>> {
+    let mut cache: HashMap<Vec<Vec<Cell>>, usize> = HashMap::new();
+    let mut states: Vec<Vec<Vec<Cell>>> = Vec::new();
+
+    let mut current_cells = cells;
+
+    for i in 0..100_000_000 {
+        if let Some(cached) = cache.get(&current_cells) {
+            let number_of_states_in_cycle = i - cached;
+            let remaining_steps = 1_000_000_000 - i;
+            let last_state_index = cached + remaining_steps % number_of_states_in_cycle;
+
+            current_cells = states
+                .get(last_state_index)
+                .ok_or("Index out of bounds")?
+                .clone();
+
+            break;
+        } else {
+            let new_cells = cycle(current_cells.clone())?;
+
+            states.push(current_cells.clone());
+            cache.insert(current_cells.clone(), i);
+
+            current_cells = new_cells;
+        }
+    }
+
+    transpose(current_cells).and_then(|cells| -> Result<i32, Box<dyn Error>> { count(&cells) })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+// the cube rocks never move so they are only stored as the cells where the rounded rocks stop
+// when tilting the platform, the rounded rocks are the sorted indexes of their cells
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparsePlatform {
+    width: usize,
+    height: usize,
+    stops: [Vec<usize>; 4],
+    rocks: Vec<usize>,
+}
+
+impl SparsePlatform {
+    fn new(cells: &[Vec<Cell>]) -> Result<Self, Box<dyn Error>> {
+        let height = cells.len();
+        let width = cells.first().map_or(0, |row| row.len());
+        if cells.iter().any(|row| row.len() != width) {
+            return Err("Rows of different lengths".into());
+        }
+
+        // without columns there is no border to stop at, nor rocks
+        if width == 0 {
+            return Ok(SparsePlatform {
+                width,
+                height,
+                stops: Default::default(),
+                rocks: Vec::new(),
+            });
+        }
+
+        let is_cube = |x: usize, y: usize| cells[y][x] == Cell::Cube;
+        let index = |x: usize, y: usize| y * width + x;
+
+        let mut stops = [
+            vec![0; width * height],
+            vec![0; width * height],
+            vec![0; width * height],
+            vec![0; width * height],
+        ];
+
+        for x in 0..width {
+            let mut north = index(x, 0);
+            for y in 0..height {
+                if is_cube(x, y) {
+                    north = index(x, y + 1);
+                }
+                stops[0][index(x, y)] = north;
+            }
+
+            let mut south = index(x, height - 1);
+            for y in (0..height).rev() {
+                if is_cube(x, y) && y > 0 {
+                    south = index(x, y - 1);
+                }
+                stops[2][index(x, y)] = south;
+            }
+        }
+
+        for y in 0..height {
+            let mut west = index(0, y);
+            for x in 0..width {
+                if is_cube(x, y) {
+                    west = index(x + 1, y);
+                }
+                stops[1][index(x, y)] = west;
+            }
+
+            let mut east = index(width - 1, y);
+            for x in (0..width).rev() {
+                if is_cube(x, y) && x > 0 {
+                    east = index(x - 1, y);
+                }
+                stops[3][index(x, y)] = east;
+            }
+        }
+
+        let rocks = cells
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c == Cell::Rounded)
+                    .map(move |(x, _)| index(x, y))
+            })
+            .collect();
+
+        Ok(SparsePlatform {
+            width,
+            height,
+            stops,
+            rocks,
+        })
+    }
+
+    fn tilt(&mut self, direction: Direction) {
+        let stops = &self.stops[direction as usize];
+
+        // count the rocks stopping at each cell, they then pile up from there
+        let mut buckets: HashMap<usize, usize> = HashMap::new();
+        self.rocks.iter().for_each(|&rock| {
+            *buckets.entry(stops[rock]).or_default() += 1;
+        });
+
+        let width = self.width;
+        self.rocks = buckets
+            .into_iter()
+            .flat_map(|(stop, count)| {
+                (0..count).map(move |i| match direction {
+                    Direction::North => stop + i * width,
+                    Direction::West => stop + i,
+                    Direction::South => stop - i * width,
+                    Direction::East => stop - i,
+                })
+            })
+            .collect();
+        self.rocks.sort_unstable();
+    }
+
+    fn tilt_sequence(&mut self, sequence: &[Direction]) {
+        sequence.iter().for_each(|direction| self.tilt(*direction));
+    }
+
+    // the cells with the rounded rocks where they are now, the cube rocks are taken from the cells
+    // the platform was created from
+    pub fn cells(&self, original: &[Vec<Cell>]) -> Vec<Vec<Cell>> {
+        let mut cells = original
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|c| {
+                        if *c == Cell::Rounded {
+                            Cell::Empty
+                        } else {
+                            c.clone()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        self.rocks.iter().for_each(|rock| {
+            cells[rock / self.width][rock % self.width] = Cell::Rounded;
+        });
+        cells
+    }
+
+    pub fn load(&self) -> Result<i32, Box<dyn Error>> {
+        self.rocks
+            .iter()
+            .map(|rock| i32::try_from(self.height - rock / self.width).map_err(Into::into))
+            .sum()
+    }
+}
+
+pub const SPIN_CYCLE: [Direction; 4] = [
+    Direction::North,
+    Direction::West,
+    Direction::South,
+    Direction::East,
+];
+
+pub fn solve2_sparse(cells: Vec<Vec<Cell>>) -> Result<i32, Box<dyn Error>> {
+    run_cycles(&cells, &SPIN_CYCLE, 1_000_000_000)?.load()
+}
+
+// applies the sequence of tilts n times. The platform ends up going through the same states
+// again, whatever the sequence, so the remaining cycles are skipped once a state is seen twice
+pub fn run_cycles(
+    cells: &Vec<Vec<Cell>>,
+    sequence: &[Direction],
+    n: u64,
+) -> Result<SparsePlatform, Box<dyn Error>> {
+    let mut platform = SparsePlatform::new(cells)?;
+
+    let mut cache: HashMap<Vec<usize>, u64> = HashMap::new();
+    let mut states: Vec<Vec<usize>> = Vec::new();
+
+    for i in 0..n {
+        if let Some(&cached) = cache.get(&platform.rocks) {
+            let number_of_states_in_cycle = i - cached;
+            let remaining_steps = n - i;
+            let last_state_index = cached + remaining_steps % number_of_states_in_cycle;
+
+            platform.rocks = states
+                .get(usize::try_from(last_state_index)?)
+                .ok_or("Index out of bounds")?
+                .clone();
+
+            break;
+        }
+
+        states.push(platform.rocks.clone());
+        cache.insert(platform.rocks.clone(), i);
+
+        platform.tilt_sequence(sequence);
+    }
+
+    Ok(platform)
+}
+
+// the load after each application of the sequence, the first one being the load of the platform as
+// parsed. It stops after n cycles or as soon as a state is seen again, the loads repeating from there
+pub fn load_history(
+    cells: &[Vec<Cell>],
+    sequence: &[Direction],
+    n: u64,
+) -> Result<Vec<i32>, Box<dyn Error>> {
+    let mut platform = SparsePlatform::new(cells)?;
+
+    let mut loads = vec![platform.load()?];
+    let mut seen = HashSet::from([platform.rocks.clone()]);
+    for _ in 0..n {
+        platform.tilt_sequence(sequence);
+        loads.push(platform.load()?);
+
+        if !seen.insert(platform.rocks.clone()) {
+            break;
+        }
+    }
+
+    Ok(loads)
+}
+
+// the sequence of tilts and the number of times it is applied
+type CyclesOptions = (Vec<Direction>, u64);
+
+// the cycles of part 2 given on the command line, None when they are not
+pub fn parse_cycles_options(args: &[String]) -> Result<Option<CyclesOptions>, Box<dyn Error>> {
+    let mut sequence = None;
+    let mut cycles = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--sequence" => sequence = Some(parse_script(args.next().ok_or("Missing sequence")?)?),
+            "--cycles" => cycles = Some(args.next().ok_or("Missing cycles")?.parse::<u64>()?),
+            _ => return Err(format!("Invalid argument: {}", arg).into()),
+        }
+    }
+
+    if sequence.is_none() && cycles.is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some((
+        sequence.unwrap_or(SPIN_CYCLE.to_vec()),
+        cycles.unwrap_or(1_000_000_000),
+    )))
+}
+
+// the implementation given with --impl and the other arguments
+pub fn parse_implementation(
+    args: &[String],
+) -> Result<(Implementation, Vec<String>), Box<dyn Error>> {
+    let mut implementation = Implementation::default();
+    let mut others = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--impl" {
+            implementation = args
+                .next()
+                .ok_or("Missing implementation")?
+                .parse::<Implementation>()?
+                .available(cfg!(feature = "simd"))?;
+        } else {
+            others.push(arg.clone());
+        }
+    }
+
+    Ok((implementation, others))
+}
+
+// a sequence of tilts, one letter per direction
+pub fn parse_script(s: &str) -> Result<Vec<Direction>, Box<dyn Error>> {
+    s.chars()
+        .map(|c| match c {
+            'N' => Ok(Direction::North),
+            'W' => Ok(Direction::West),
+            'S' => Ok(Direction::South),
+            'E' => Ok(Direction::East),
+            _ => Err(format!("Invalid direction: {}", c).into()),
+        })
+        .collect()
+}
+
+pub fn run_script(
+    cells: &Vec<Vec<Cell>>,
+    script: &[Direction],
+) -> Result<SparsePlatform, Box<dyn Error>> {
+    let mut platform = SparsePlatform::new(cells)?;
+    platform.tilt_sequence(script);
+
+    Ok(platform)
+}
+
+pub fn render(cells: &[Vec<Cell>]) -> String {
+    cells
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|c| match c {
+                    Cell::Rounded => 'O',
+                    Cell::Cube => '#',
+                    Cell::Empty => '.',
+                })
+                .collect::<String>()
+                + "\n"
+        })
+        .collect()
+}
+
+fn count(cells: &Vec<Vec<Cell>>) -> Result<i32, Box<dyn Error>> {
+    cells
+        .iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(i, c)| -> Result<i32, Box<dyn Error>> {
+                    Ok(if c == &Cell::Rounded {
+                        i32::try_from(row.len())? - i32::try_from(i)?
+                    } else {
+                        0
+                    })
+                })
+                .sum::<Result<i32, Box<dyn Error>>>()
+        })
+        .sum::<Result<i32, Box<dyn Error>>>()
+}
+
+#[cfg(test)]
+mod day14 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+
+    use crate::{
+        count, cycle, load_history, parse, parse_cycles_options, parse_implementation,
+        parse_script, render, run_cycles, run_script, solve1, solve1_transpose, solve2,
+        solve2_sparse, tilt_left, transpose, Cell, Direction, Implementation, SparsePlatform,
+        SPIN_CYCLE,
+    };
+
+    const EXAMPLE: &str = "\
+        O....#....\n\
+        O.OO#....#\n\
+        .....##...\n\
+        OO.#O....O\n\
+        .O.....O#.\n\
+        O.#..O.#.#\n\
+        ..O..#O..O\n\
+        .......O..\n\
+        #....###..\n\
+        #OO..#....";
+
+    fn example() -> Vec<Vec<Cell>> {
+        vec![
+            vec![
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Rounded,
+                Cell::Rounded,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+            ],
+            vec![
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Rounded,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Rounded,
+            ],
+            vec![
+                Cell::Empty,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Rounded,
+                Cell::Cube,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Cube,
+            ],
+            vec![
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Rounded,
+            ],
+            vec![
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Cube,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+        ]
+    }
+
+    fn example_tilted_north() -> Vec<Vec<Cell>> {
+        vec![
+            vec![
+                Cell::Rounded,
+                Cell::Rounded,
+                Cell::Rounded,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Cube,
+            ],
+            vec![
+                Cell::Rounded,
+                Cell::Rounded,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Cube,
+            ],
+            vec![
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+            vec![
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Cube,
+                Cell::Rounded,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+            ],
+        ]
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        let result = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        assert_eq!(result, example());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tilted_north() -> Result<(), Box<dyn Error>> {
+        let result = tilt_left(transpose(example())?);
+        assert_eq!(result, example_tilted_north());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_column_scan() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve1(example())?, 136);
+        assert_eq!(solve1(Vec::new())?, 0);
+
+        // on the platform after each spin cycle as well, the rocks being anywhere
+        let mut cells = example();
+        for _ in 0..5 {
+            assert_eq!(solve1(cells.clone())?, solve1_transpose(cells.clone())?);
+            cells = cycle(cells)?;
+        }
+
+        // not on the transposed grid, the scan is column-wise
+        let rows = parse(["O#", ".O"].map(String::from).into_iter())?;
+        assert_eq!(solve1(rows)?, 2 + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_count() -> Result<(), Box<dyn Error>> {
+        let result = count(&example_tilted_north())?;
+        assert_eq!(result, 136);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sparse_tilted_north() -> Result<(), Box<dyn Error>> {
+        let mut platform = SparsePlatform::new(&example())?;
+        platform.tilt(Direction::North);
+
+        // the expected grid is transposed
+        let expected = SparsePlatform::new(&transpose(example_tilted_north())?)?;
+        assert_eq!(platform.rocks, expected.rocks);
+        assert_eq!(platform.load()?, 136);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sparse_empty() -> Result<(), Box<dyn Error>> {
+        for cells in [vec![], vec![vec![], vec![]]] {
+            let mut platform = SparsePlatform::new(&cells)?;
+            platform.tilt(Direction::East);
+            assert_eq!(platform.load()?, 0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sparse_cycle() -> Result<(), Box<dyn Error>> {
+        let mut platform = SparsePlatform::new(&example())?;
+        let mut cells = example();
+        for _ in 0..3 {
+            platform.tilt_sequence(&SPIN_CYCLE);
+            cells = cycle(cells)?;
+            assert_eq!(platform.rocks, SparsePlatform::new(&cells)?.rocks);
+        }
+        Ok(())
+    }
+
+    const EXAMPLE_CYCLED: &str = "\
+        .....#....\n\
+        ....#...O#\n\
+        ...OO##...\n\
+        .OO#......\n\
+        .....OOO#.\n\
+        .O#...O#.#\n\
+        ....O#....\n\
+        ......OOOO\n\
+        #...O###..\n\
+        #..OO#....\n";
+
+    #[test]
+    fn test_script() -> Result<(), Box<dyn Error>> {
+        let platform = run_script(&example(), &parse_script("N")?)?;
+        assert_eq!(platform.load()?, 136);
+
+        let platform = run_script(&example(), &parse_script("NWSE")?)?;
+        assert_eq!(render(&platform.cells(&example())), EXAMPLE_CYCLED);
+
+        // a tilt in the same direction twice changes nothing
+        let platform = run_script(&example(), &parse_script("NWSEE")?)?;
+        assert_eq!(render(&platform.cells(&example())), EXAMPLE_CYCLED);
+
+        assert_eq!(
+            render(&run_script(&example(), &[])?.cells(&example())),
+            EXAMPLE.to_string() + "\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_script() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            parse_script("NWSE")?,
+            vec![
+                Direction::North,
+                Direction::West,
+                Direction::South,
+                Direction::East
+            ]
+        );
+        assert_eq!(parse_script("")?, vec![]);
+        assert!(parse_script("NX").is_err());
+        assert!(parse_script("n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_cycles() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            run_cycles(&example(), &SPIN_CYCLE, 1_000_000_000)?.load()?,
+            64
+        );
+
+        // the shortcut gives the same platform as applying all the cycles, for sequences going
+        // through states with different periods
+        for sequence in ["NWSE", "N", "NS", "WE", "NE", "SWNE", "NNWSEE", ""] {
+            let sequence = parse_script(sequence)?;
+            let mut platform = SparsePlatform::new(&example())?;
+            for n in 0..30 {
+                assert_eq!(run_cycles(&example(), &sequence, n)?, platform);
+                platform.tilt_sequence(&sequence);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_history() -> Result<(), Box<dyn Error>> {
+        // the state after 10 cycles is the one after 3 cycles
+        let loads = load_history(&example(), &SPIN_CYCLE, 1_000_000_000)?;
+        assert_eq!(loads, [104, 87, 69, 69, 69, 65, 64, 65, 63, 68, 69]);
+
+        for (n, load) in loads.iter().enumerate() {
+            assert_eq!(
+                run_cycles(&example(), &SPIN_CYCLE, u64::try_from(n)?)?.load()?,
+                *load
+            );
+        }
+        assert_eq!(load_history(&example(), &SPIN_CYCLE, 2)?, loads[..3]);
+        assert_eq!(load_history(&example(), &SPIN_CYCLE, 0)?, [104]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_cycles_options() -> Result<(), Box<dyn Error>> {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(parse_cycles_options(&[])?, None);
+        assert_eq!(
+            parse_cycles_options(&args(&["--cycles", "3"]))?,
+            Some((SPIN_CYCLE.to_vec(), 3))
+        );
+        assert_eq!(
+            parse_cycles_options(&args(&["--sequence", "NS"]))?,
+            Some((vec![Direction::North, Direction::South], 1_000_000_000))
+        );
+        assert!(parse_cycles_options(&args(&["--cycles", "-1"])).is_err());
+        assert!(parse_cycles_options(&args(&["--sequence", "NX"])).is_err());
+        assert!(parse_cycles_options(&args(&["--sequence"])).is_err());
+        assert!(parse_cycles_options(&args(&["--grid"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_implementation() -> Result<(), Box<dyn Error>> {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            parse_implementation(&args(&["--cycles", "3"]))?,
+            (Implementation::Scalar, args(&["--cycles", "3"]))
+        );
+        assert_eq!(
+            parse_implementation(&args(&["--impl", "scalar", "--cycles", "3"]))?,
+            (Implementation::Scalar, args(&["--cycles", "3"]))
+        );
+        assert!(parse_implementation(&args(&["--impl"])).is_err());
+        assert!(parse_implementation(&args(&["--impl", "gpu"])).is_err());
+        // only available with the simd feature
+        assert_eq!(
+            parse_implementation(&args(&["--impl", "bitboard"])).is_ok(),
+            cfg!(feature = "simd")
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn test_bitboard() -> Result<(), Box<dyn Error>> {
+        use crate::bitboard::{self, Bitboard};
+
+        assert_eq!(bitboard::solve1(example())?, 136);
+        assert_eq!(bitboard::solve2(example())?, 64);
+
+        // every tilt gives the same rocks as the sparse platform
+        let mut platform = SparsePlatform::new(&example())?;
+        let mut bitboard = Bitboard::new(&example())?;
+        for direction in parse_script("NWSEENNWSSWNEEWS")? {
+            platform.tilt(direction);
+            bitboard.tilt(direction);
+            assert_eq!(bitboard, Bitboard::new(&platform.cells(&example()))?);
+            assert_eq!(bitboard.load()?, platform.load()?);
+        }
+
+        for sequence in ["NWSE", "N", "NS", "WE", "SWNE", ""] {
+            let sequence = parse_script(sequence)?;
+            for n in 0..20 {
+                let platform = run_cycles(&example(), &sequence, n)?;
+                assert_eq!(
+                    bitboard::run_cycles(&example(), &sequence, n)?,
+                    Bitboard::new(&platform.cells(&example()))?
+                );
+            }
+        }
+
+        assert!(Bitboard::new(&[vec![Cell::Empty; 129]]).is_err());
+        assert_eq!(Bitboard::new(&[vec![Cell::Rounded; 128]])?.load()?, 128);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let cells = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve1(cells)?;
+
+        assert_eq!(result, 110821);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_example() -> Result<(), Box<dyn Error>> {
+        let result = solve2(example())?;
+
+        assert_eq!(result, 64);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_sparse_example() -> Result<(), Box<dyn Error>> {
+        let result = solve2_sparse(example())?;
+
+        assert_eq!(result, solve2(example())?);
+        assert_eq!(result, 64);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_sparse_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let cells = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve2_sparse(cells)?;
+
+        assert_eq!(result, 83516);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let cells = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve2(cells)?;
+
+        assert_eq!(result, 83516);
+        Ok(())
+    }
+}
@@ -0,0 +1,383 @@
+use std::{error::Error, str::FromStr};
+
+fn hash(s: &str) -> u64 {
+    s.chars().filter(|c| *c != '\n').fold(0, |acc, c| {
+        let acc = acc + u64::from(c);
+        let acc = acc * 17;
+        let acc = acc % 256;
+        acc
+    })
+}
+
+pub fn solve1(s: &str) -> u64 {
+    s.split(',').map(|x| hash(x)).sum()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Step {
+    label: String,
+    operation: Operation,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Operation {
+    Remove,
+    Focal(u64),
+}
+
+impl FromStr for Operation {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "-" => Ok(Operation::Remove),
+            _ => {
+                let s = s.trim_start_matches('=');
+                Ok(Operation::Focal(s.parse::<u64>()?))
+            }
+        }
+    }
+}
+
+impl FromStr for Step {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.find(|c| c == '=' || c == '-') {
+            None => Err("Missing '=' or '-'")?,
+            Some(index) => {
+                let (label, operation) = s.split_at(index);
+                Ok(Step {
+                    label: label.to_string(),
+                    operation: operation.parse::<Operation>()?,
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Lens {
+    label: String,
+    focal: u64,
+}
+
+// the lenses of each box are kept in insertion order, their slot is their index in the box
+#[derive(Debug, PartialEq, Eq)]
+pub struct Boxes {
+    boxes: Vec<Vec<Lens>>,
+}
+
+impl Default for Boxes {
+    fn default() -> Self {
+        Boxes {
+            boxes: (0..256).map(|_| Vec::new()).collect(),
+        }
+    }
+}
+
+impl Boxes {
+    fn get_box(&mut self, label: &str) -> Result<&mut Vec<Lens>, Box<dyn Error>> {
+        let index = usize::try_from(hash(label))?;
+        self.boxes.get_mut(index).ok_or("Invalid box".into())
+    }
+
+    fn insert(&mut self, label: &str, focal: u64) -> Result<(), Box<dyn Error>> {
+        let lenses = self.get_box(label)?;
+        match lenses.iter_mut().find(|lens| lens.label == label) {
+            Some(lens) => lens.focal = focal,
+            None => lenses.push(Lens {
+                label: label.to_string(),
+                focal,
+            }),
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, label: &str) -> Result<(), Box<dyn Error>> {
+        self.get_box(label)?.retain(|lens| lens.label != label);
+        Ok(())
+    }
+
+    fn apply(&mut self, step: &Step) -> Result<(), Box<dyn Error>> {
+        match step.operation {
+            Operation::Remove => self.remove(&step.label),
+            Operation::Focal(focal) => self.insert(&step.label, focal),
+        }
+    }
+
+    fn focusing_power(&self) -> Result<u64, Box<dyn Error>> {
+        self.boxes
+            .iter()
+            .enumerate()
+            .flat_map(|(box_, lenses)| {
+                lenses
+                    .iter()
+                    .enumerate()
+                    .map(move |(slot, lens)| -> Result<_, Box<dyn Error>> {
+                        let box_ = u64::try_from(box_)? + 1;
+                        let slot = u64::try_from(slot)? + 1;
+                        Ok(box_ * slot * lens.focal)
+                    })
+            })
+            .sum()
+    }
+}
+
+pub fn fill(s: &str) -> Result<Boxes, Box<dyn Error>> {
+    let mut boxes = Boxes::default();
+
+    s.chars()
+        .filter(|c| *c != '\n')
+        .collect::<String>()
+        .split(',')
+        .try_for_each(|s| boxes.apply(&s.parse::<Step>()?))?;
+
+    Ok(boxes)
+}
+
+pub fn solve2(s: &str) -> Result<u64, Box<dyn Error>> {
+    fill(s)?.focusing_power()
+}
+
+// how the labels left in the boxes are spread by the hash, over holds the boxes with more labels
+// than the threshold
+#[derive(Debug, PartialEq)]
+pub struct Spread {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub over: Vec<usize>,
+}
+
+pub fn spread(boxes: &Boxes, threshold: usize) -> Result<Spread, Box<dyn Error>> {
+    let counts = boxes.boxes.iter().map(|lenses| lenses.len());
+    let total = counts.clone().sum::<usize>();
+
+    Ok(Spread {
+        min: counts.clone().min().ok_or("No boxes")?,
+        max: counts.clone().max().ok_or("No boxes")?,
+        mean: total as f64 / boxes.boxes.len() as f64,
+        over: counts
+            .enumerate()
+            .filter(|(_, count)| *count > threshold)
+            .map(|(box_, _)| box_)
+            .collect(),
+    })
+}
+
+// the number of boxes holding each number of labels, from none to the fullest box
+pub fn labels_histogram(boxes: &Boxes) -> Vec<(usize, u64)> {
+    let max = boxes.boxes.iter().map(|lenses| lenses.len()).max();
+
+    max.map_or(Vec::new(), |max| {
+        (0..=max)
+            .map(|labels| {
+                let count = boxes
+                    .boxes
+                    .iter()
+                    .filter(|lenses| lenses.len() == labels)
+                    .count();
+                (labels, count as u64)
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod day15 {
+    use std::{error::Error, fs::read_to_string};
+
+    use crate::{
+        fill, hash, labels_histogram, solve1, solve2, spread, Boxes, Lens, Operation, Spread, Step,
+    };
+
+    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn test_hash_simple() {
+        assert_eq!(hash("rn=1"), 30);
+        assert_eq!(hash("cm-"), 253);
+        assert_eq!(hash("qp=3"), 97);
+        assert_eq!(hash("cm=2"), 47);
+        assert_eq!(hash("qp-"), 14);
+        assert_eq!(hash("pc=4"), 180);
+        assert_eq!(hash("ot=9"), 9);
+        assert_eq!(hash("ab=5"), 197);
+        assert_eq!(hash("pc-"), 48);
+        assert_eq!(hash("pc=6"), 214);
+        assert_eq!(hash("ot=7"), 231);
+    }
+
+    #[test]
+    fn test_solve1_example() {
+        assert_eq!(solve1(EXAMPLE), 1320);
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let input = read_to_string("input")?;
+        assert_eq!(solve1(&input), 507769);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_example() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve2(EXAMPLE)?, 145);
+        Ok(())
+    }
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let input = read_to_string("input")?;
+        assert_eq!(solve2(&input)?, 269747);
+        Ok(())
+    }
+
+    fn lens(label: &str, focal: u64) -> Lens {
+        Lens {
+            label: label.to_string(),
+            focal,
+        }
+    }
+
+    #[test]
+    fn test_boxes_insert() -> Result<(), Box<dyn Error>> {
+        let mut boxes = Boxes::default();
+        boxes.insert("rn", 1)?;
+        boxes.insert("cm", 2)?;
+        boxes.insert("rn", 3)?;
+
+        assert_eq!(boxes.boxes[0], vec![lens("rn", 3), lens("cm", 2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_boxes_remove() -> Result<(), Box<dyn Error>> {
+        let mut boxes = Boxes::default();
+        boxes.insert("rn", 1)?;
+        boxes.insert("cm", 2)?;
+        boxes.remove("rn")?;
+        boxes.remove("qp")?;
+
+        assert_eq!(boxes.boxes[0], vec![lens("cm", 2)]);
+        assert_eq!(boxes.focusing_power()?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_example() -> Result<(), Box<dyn Error>> {
+        let boxes = fill(EXAMPLE)?;
+
+        assert_eq!(boxes.boxes[0], vec![lens("rn", 1), lens("cm", 2)]);
+        assert_eq!(boxes.boxes[1], vec![]);
+        assert_eq!(
+            boxes.boxes[3],
+            vec![lens("ot", 7), lens("ab", 5), lens("pc", 6)]
+        );
+        assert_eq!(boxes.focusing_power()?, 145);
+        Ok(())
+    }
+
+    #[test]
+    fn test_spread_example() -> Result<(), Box<dyn Error>> {
+        let boxes = fill(EXAMPLE)?;
+
+        assert_eq!(
+            labels_histogram(&boxes),
+            vec![(0, 254), (1, 0), (2, 1), (3, 1)]
+        );
+        assert_eq!(
+            spread(&boxes, 2)?,
+            Spread {
+                min: 0,
+                max: 3,
+                mean: 5.0 / 256.0,
+                over: vec![3]
+            }
+        );
+        assert_eq!(spread(&boxes, 3)?.over, Vec::<usize>::new());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            "rn=1".parse::<Step>()?,
+            Step {
+                label: "rn".to_string(),
+                operation: Operation::Focal(1)
+            }
+        );
+        assert_eq!(
+            "cm-".parse::<Step>()?,
+            Step {
+                label: "cm".to_string(),
+                operation: Operation::Remove
+            }
+        );
+        assert_eq!(
+            "qp=3".parse::<Step>()?,
+            Step {
+                label: "qp".to_string(),
+                operation: Operation::Focal(3)
+            }
+        );
+        assert_eq!(
+            "cm=2".parse::<Step>()?,
+            Step {
+                label: "cm".to_string(),
+                operation: Operation::Focal(2)
+            }
+        );
+        assert_eq!(
+            "qp-".parse::<Step>()?,
+            Step {
+                label: "qp".to_string(),
+                operation: Operation::Remove
+            }
+        );
+        assert_eq!(
+            "pc=4".parse::<Step>()?,
+            Step {
+                label: "pc".to_string(),
+                operation: Operation::Focal(4)
+            }
+        );
+        assert_eq!(
+            "ot=9".parse::<Step>()?,
+            Step {
+                label: "ot".to_string(),
+                operation: Operation::Focal(9)
+            }
+        );
+        assert_eq!(
+            "ab=5".parse::<Step>()?,
+            Step {
+                label: "ab".to_string(),
+                operation: Operation::Focal(5)
+            }
+        );
+        assert_eq!(
+            "pc-".parse::<Step>()?,
+            Step {
+                label: "pc".to_string(),
+                operation: Operation::Remove
+            }
+        );
+        assert_eq!(
+            "pc=6".parse::<Step>()?,
+            Step {
+                label: "pc".to_string(),
+                operation: Operation::Focal(6)
+            }
+        );
+        assert_eq!(
+            "ot=7".parse::<Step>()?,
+            Step {
+                label: "ot".to_string(),
+                operation: Operation::Focal(7)
+            }
+        );
+        Ok(())
+    }
+}
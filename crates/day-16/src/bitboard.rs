@@ -0,0 +1,191 @@
+// the beams as one bitmask per row and per direction, the bit x of a row being its column x. All
+// the beams going in a direction move together at each step, splitting on the masks of the
+// contraptions they enter
+
+use crate::{border_starts, moves, Contraption, Energized, Grid};
+use lib::par::map_indexed;
+use lib::vec2::{Dir4, Vec2};
+use std::{error::Error, mem::swap};
+
+pub struct Bitboard {
+    width: usize,
+    // for each incoming and outgoing directions, the tiles turning the first one into the second
+    // one
+    turns: [[Vec<u128>; 4]; 4],
+}
+
+impl Bitboard {
+    pub fn new(grid: &Grid) -> Result<Self, Box<dyn Error>> {
+        let width = usize::try_from(grid.width)?;
+        if width > 128 {
+            return Err(format!("Too wide for a bitboard: {}", width).into());
+        }
+
+        let masks = |accepted: &[Contraption]| {
+            grid.layout
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|(_, contraption)| accepted.contains(contraption))
+                        .fold(0u128, |mask, (x, _)| mask | 1 << x)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut turns: [[Vec<u128>; 4]; 4] = Default::default();
+        for from in Dir4::ALL {
+            for to in Dir4::ALL {
+                let accepted = CONTRAPTIONS
+                    .iter()
+                    .filter(|contraption| turn(contraption, from).contains(&to))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                turns[from.index()][to.index()] = masks(&accepted);
+            }
+        }
+
+        Ok(Bitboard { width, turns })
+    }
+
+    fn row_mask(&self) -> u128 {
+        u128::MAX.checked_shr(128 - self.width as u32).unwrap_or(0)
+    }
+
+    // the beams entering the tiles in the frontier are followed until they only enter tiles they
+    // already entered in the same direction. Only the rows with beams are looked at, the beams
+    // being usually spread on a few of them
+    pub fn simulate(&self, start: (Vec2, Dir4)) -> Result<Energized, Box<dyn Error>> {
+        let height = self.turns[0][0].len();
+        let (point, direction) = start;
+
+        let mut visited = [(); 4].map(|_| vec![0u128; height]);
+        let mut frontier = visited.clone();
+        let mut next = visited.clone();
+        let mut active: [Vec<usize>; 4] = Default::default();
+        let mut next_active: [Vec<usize>; 4] = Default::default();
+
+        // a start out of the grid energizes nothing
+        if let (Ok(x), Ok(y)) = (usize::try_from(point.x), usize::try_from(point.y)) {
+            if x < self.width && y < height {
+                visited[direction.index()][y] |= 1 << x;
+                frontier[direction.index()][y] |= 1 << x;
+                active[direction.index()].push(y);
+            }
+        }
+
+        let mask = self.row_mask();
+        while active.iter().any(|rows| !rows.is_empty()) {
+            for from in Dir4::ALL {
+                for y in active[from.index()].drain(..) {
+                    let beams = frontier[from.index()][y];
+                    frontier[from.index()][y] = 0;
+
+                    for to in Dir4::ALL {
+                        let leaving = beams & self.turns[from.index()][to.index()][y];
+                        let (row, entering) = match to {
+                            Dir4::Up if y > 0 => (y - 1, leaving),
+                            Dir4::Down if y + 1 < height => (y + 1, leaving),
+                            Dir4::Left => (y, leaving >> 1),
+                            Dir4::Right => (y, (leaving << 1) & mask),
+                            _ => continue,
+                        };
+                        if entering == 0 {
+                            continue;
+                        }
+
+                        if next[to.index()][row] == 0 {
+                            next_active[to.index()].push(row);
+                        }
+                        next[to.index()][row] |= entering;
+                    }
+                }
+            }
+
+            for to in Dir4::ALL {
+                for &y in &next_active[to.index()] {
+                    next[to.index()][y] &= !visited[to.index()][y];
+                    visited[to.index()][y] |= next[to.index()][y];
+                }
+            }
+
+            swap(&mut frontier, &mut next);
+            swap(&mut active, &mut next_active);
+        }
+
+        let energized = (0..height)
+            .map(|y| {
+                visited
+                    .iter()
+                    .fold(0, |row, rows| row | rows[y])
+                    .count_ones()
+            })
+            .sum::<u32>();
+        let steps = visited
+            .iter()
+            .flatten()
+            .map(|row| row.count_ones())
+            .sum::<u32>();
+
+        Ok(Energized {
+            count: i32::try_from(energized)?,
+            steps: usize::try_from(steps)?,
+            truncated: false,
+        })
+    }
+}
+
+const CONTRAPTIONS: [Contraption; 5] = [
+    Contraption::Empty,
+    Contraption::VerticalSplitter,
+    Contraption::HorizontalSplitter,
+    Contraption::MirrorSlash,
+    Contraption::MirrorBackslash,
+];
+
+// the directions of the beams leaving a contraption entered in a direction
+fn turn(contraption: &Contraption, direction: Dir4) -> Vec<Dir4> {
+    moves(contraption, &Vec2::ZERO, direction)
+        .into_iter()
+        .map(|(_, direction)| direction)
+        .collect()
+}
+
+fn check_no_budget(max_steps: Option<usize>) -> Result<(), Box<dyn Error>> {
+    match max_steps {
+        Some(_) => Err("The bitboard implementation has no step budget".into()),
+        None => Ok(()),
+    }
+}
+
+pub fn solve1_limited(grid: &Grid, max_steps: Option<usize>) -> Result<Energized, Box<dyn Error>> {
+    check_no_budget(max_steps)?;
+
+    Bitboard::new(grid)?.simulate((Vec2::ZERO, Dir4::Right))
+}
+
+pub fn solve2_limited(grid: &Grid, max_steps: Option<usize>) -> Result<Energized, Box<dyn Error>> {
+    check_no_budget(max_steps)?;
+
+    let bitboard = Bitboard::new(grid)?;
+    let positions = border_starts(grid)?;
+
+    let results = map_indexed(&positions, |point_and_direction| {
+        bitboard
+            .simulate(*point_and_direction)
+            .map_err(|e| e.to_string())
+    })
+    .into_iter()
+    .map(|timed| timed.value)
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Energized {
+        count: results
+            .iter()
+            .map(|result| result.count)
+            .max()
+            .ok_or("No solution")?,
+        steps: results.iter().map(|result| result.steps).sum(),
+        truncated: false,
+    })
+}
@@ -0,0 +1,1108 @@
+use itertools::Itertools;
+use lib::{
+    cli::Implementation,
+    grid::Symmetry,
+    par::map_indexed,
+    vec2::{Dir4, Vec2},
+};
+use std::{error::Error, iter::zip};
+
+#[cfg(feature = "simd")]
+pub mod bitboard;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Contraption {
+    Empty,
+    VerticalSplitter,
+    HorizontalSplitter,
+    MirrorSlash,
+    MirrorBackslash,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid {
+    width: i64,
+    height: i64,
+    layout: Vec<Vec<Contraption>>,
+}
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Grid, Box<dyn Error>> {
+    let mut width = 0;
+
+    let layout = itr
+        .map(|line| -> Result<Vec<Contraption>, Box<dyn Error>> {
+            let line_len = i64::try_from(line.len())?;
+            width = if width == 0 {
+                Ok(line_len)
+            } else if width != line_len {
+                Err(format!("Invalid line length: {}", line.len()))
+            } else {
+                Ok(width)
+            }?;
+
+            line.chars()
+                .map(|c| -> Result<_, Box<dyn Error>> {
+                    match c {
+                        '.' => Ok(Contraption::Empty),
+                        '|' => Ok(Contraption::VerticalSplitter),
+                        '-' => Ok(Contraption::HorizontalSplitter),
+                        '/' => Ok(Contraption::MirrorSlash),
+                        '\\' => Ok(Contraption::MirrorBackslash),
+                        _ => Err(format!("Invalid character: {}", c).into()),
+                    }
+                })
+                .collect()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Grid {
+        width,
+        height: i64::try_from(layout.len())?,
+        layout,
+    })
+}
+
+pub fn to_char(contraption: &Contraption) -> char {
+    match contraption {
+        Contraption::Empty => '.',
+        Contraption::VerticalSplitter => '|',
+        Contraption::HorizontalSplitter => '-',
+        Contraption::MirrorSlash => '/',
+        Contraption::MirrorBackslash => '\\',
+    }
+}
+
+// the splitters and the mirrors are segments turning with the grid: a splitter stays vertical
+// when the image of a vertical move is vertical, a backslash stays one when the image of its
+// diagonal is the same diagonal
+fn transform_contraption(contraption: &Contraption, symmetry: Symmetry) -> Contraption {
+    let keeps = |along: Vec2| symmetry.vector(along) == along || symmetry.vector(along) == -along;
+
+    match contraption {
+        Contraption::Empty => Contraption::Empty,
+        Contraption::VerticalSplitter if keeps(Dir4::Down.offset()) => {
+            Contraption::VerticalSplitter
+        }
+        Contraption::VerticalSplitter => Contraption::HorizontalSplitter,
+        Contraption::HorizontalSplitter if keeps(Dir4::Right.offset()) => {
+            Contraption::HorizontalSplitter
+        }
+        Contraption::HorizontalSplitter => Contraption::VerticalSplitter,
+        Contraption::MirrorBackslash if keeps(Vec2::new(1, 1)) => Contraption::MirrorBackslash,
+        Contraption::MirrorBackslash => Contraption::MirrorSlash,
+        Contraption::MirrorSlash if keeps(Vec2::new(1, -1)) => Contraption::MirrorSlash,
+        Contraption::MirrorSlash => Contraption::MirrorBackslash,
+    }
+}
+
+// the grid seen through a symmetry, the beams entering it at the images of the starts energize
+// as many tiles as in the grid
+fn transform_grid(grid: &Grid, symmetry: Symmetry) -> Grid {
+    let (width, height) = symmetry.size(grid.width, grid.height);
+    let layout = symmetry
+        .apply(&grid.layout)
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|contraption| transform_contraption(contraption, symmetry))
+                .collect()
+        })
+        .collect();
+
+    Grid {
+        width,
+        height,
+        layout,
+    }
+}
+
+fn transform_start(
+    grid: &Grid,
+    (point, direction): (Vec2, Dir4),
+    symmetry: Symmetry,
+) -> (Vec2, Dir4) {
+    (
+        symmetry.point(point, grid.width, grid.height),
+        symmetry.direction(direction),
+    )
+}
+
+// the grid turned so that the beam enters it going right, the starts entering the same tile in
+// different directions of different grids end up the same when their turned grids are the same
+fn canonical_start(grid: &Grid, start: (Vec2, Dir4)) -> (Grid, (Vec2, Dir4)) {
+    let symmetry = match start.1 {
+        Dir4::Right => Symmetry::Identity,
+        Dir4::Up => Symmetry::QuarterTurn,
+        Dir4::Left => Symmetry::HalfTurn,
+        Dir4::Down => Symmetry::ThreeQuarterTurn,
+    };
+
+    (
+        transform_grid(grid, symmetry),
+        transform_start(grid, start, symmetry),
+    )
+}
+
+// the part 1 count of each image of the grid, from the image of the start turned to the right,
+// which are all the same
+pub fn symmetries(grid: &Grid) -> Result<Vec<(Symmetry, i32)>, Box<dyn Error>> {
+    Symmetry::ALL
+        .into_iter()
+        .map(|symmetry| {
+            let start = transform_start(grid, (Vec2::ZERO, Dir4::Right), symmetry);
+            let (canonical, start) = canonical_start(&transform_grid(grid, symmetry), start);
+            Ok((symmetry, solve(&canonical, start)?))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct BeamOptions {
+    // the number of beam steps after which the simulation gives up
+    pub max_steps: Option<usize>,
+    pub json: bool,
+    pub implementation: Implementation,
+}
+
+pub fn parse_beam_options(args: &[String]) -> Result<BeamOptions, Box<dyn Error>> {
+    let mut options = BeamOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-steps" => {
+                options.max_steps = Some(args.next().ok_or("missing max steps")?.parse()?)
+            }
+            "--json" => options.json = true,
+            "--impl" => {
+                options.implementation = args
+                    .next()
+                    .ok_or("missing implementation")?
+                    .parse::<Implementation>()?
+                    .available(cfg!(feature = "simd"))?
+            }
+            _ => return Err(format!("unknown option: {}", arg).into()),
+        }
+    }
+
+    // the bitboard moves all the beams at once, it can't stop after a number of steps
+    if options.implementation == Implementation::Bitboard && options.max_steps.is_some() {
+        return Err("--max-steps only applies to the scalar implementation".into());
+    }
+    Ok(options)
+}
+
+// the result of a simulation, when truncated the count only includes the tiles energized before
+// the step budget ran out
+#[derive(Debug, PartialEq, Eq)]
+pub struct Energized {
+    pub count: i32,
+    pub steps: usize,
+    pub truncated: bool,
+}
+
+pub type LimitedSolver = fn(&Grid, Option<usize>) -> Result<Energized, Box<dyn Error>>;
+
+fn solve1(grid: &Grid) -> Result<i32, Box<dyn Error>> {
+    solve(grid, (Vec2::ZERO, Dir4::Right))
+}
+
+pub fn solve1_limited(grid: &Grid, max_steps: Option<usize>) -> Result<Energized, Box<dyn Error>> {
+    simulate(grid, (Vec2::ZERO, Dir4::Right), max_steps)
+}
+
+// the beams entering the grid from each tile of its border
+fn border_starts(grid: &Grid) -> Result<Vec<(Vec2, Dir4)>, Box<dyn Error>> {
+    let xs = 0..grid.width;
+    let last_x = if grid.width > 0 {
+        Ok(grid.width - 1)
+    } else {
+        Err("Invalid width")
+    }?;
+
+    let ys = 0..grid.height;
+    let last_y = if grid.height > 0 {
+        Ok(grid.height - 1)
+    } else {
+        Err("Invalid height")
+    }?;
+
+    Ok(xs
+        .clone()
+        .map(|x| (Vec2 { x, y: 0 }, Dir4::Down))
+        .chain(xs.map(|x| (Vec2 { x, y: last_y }, Dir4::Up)))
+        .chain(ys.clone().map(|y| (Vec2 { x: 0, y }, Dir4::Right)))
+        .chain(ys.map(|y| (Vec2 { x: last_x, y }, Dir4::Left)))
+        .collect())
+}
+
+// the budget applies to each start, the result is truncated when any of them is
+pub fn solve2_limited(grid: &Grid, max_steps: Option<usize>) -> Result<Energized, Box<dyn Error>> {
+    let positions = border_starts(grid)?;
+
+    // each start is independent, errors aren't Send so they cross the threads as strings
+    let results = map_indexed(&positions, |point_and_direction| {
+        simulate(grid, *point_and_direction, max_steps).map_err(|e| e.to_string())
+    })
+    .into_iter()
+    .map(|timed| timed.value)
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let truncated = results.iter().any(|result| result.truncated);
+    let steps = results.iter().map(|result| result.steps).sum();
+    let count = results
+        .iter()
+        .map(|result| result.count)
+        .max()
+        .ok_or("No solution")?;
+
+    Ok(Energized {
+        count,
+        steps,
+        truncated,
+    })
+}
+
+pub fn solve2(grid: &Grid) -> Result<i32, Box<dyn Error>> {
+    Ok(solve2_limited(grid, None)?.count)
+}
+
+// the strongly connected components of a graph with Tarjan's algorithm, run with an explicit stack
+// to cope with long beams. Returns the component of each node, the components are numbered in
+// reverse topological order: the successors of a component have a smaller number or the same one
+fn strongly_connected_components(successors: &[Vec<usize>]) -> Vec<usize> {
+    const UNVISITED: usize = usize::MAX;
+
+    let mut index = vec![UNVISITED; successors.len()];
+    let mut low = vec![0; successors.len()];
+    let mut on_stack = vec![false; successors.len()];
+    let mut stack = Vec::new();
+    let mut component = vec![UNVISITED; successors.len()];
+    let mut next_index = 0;
+    let mut next_component = 0;
+
+    for root in 0..successors.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        // the nodes being visited with the position of the next successor to look at
+        let mut visiting = vec![(root, 0)];
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, position)) = visiting.last().copied() {
+            if let Some(&successor) = successors[node].get(position) {
+                if let Some(last) = visiting.last_mut() {
+                    last.1 += 1;
+                }
+
+                if index[successor] == UNVISITED {
+                    index[successor] = next_index;
+                    low[successor] = next_index;
+                    next_index += 1;
+                    stack.push(successor);
+                    on_stack[successor] = true;
+                    visiting.push((successor, 0));
+                } else if on_stack[successor] {
+                    low[node] = low[node].min(index[successor]);
+                }
+            } else {
+                visiting.pop();
+                if let Some((parent, _)) = visiting.last() {
+                    low[*parent] = low[*parent].min(low[node]);
+                }
+
+                // node is the root of a component, pop its members
+                if low[node] == index[node] {
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component[member] = next_component;
+                        if member == node {
+                            break;
+                        }
+                    }
+                    next_component += 1;
+                }
+            }
+        }
+    }
+
+    component
+}
+
+// the tiles energized from every (tile, direction) state of the grid. The states are linked to
+// the states of the beams they emit, the states of a strongly connected component reach the same
+// tiles, so those are computed once per component, from the components it leads to
+struct BeamCache {
+    width: i64,
+    height: i64,
+    component: Vec<usize>,
+    // one bit per tile for each component
+    energized: Vec<Vec<u64>>,
+}
+
+impl BeamCache {
+    fn state(width: i64, height: i64, point: &Vec2, direction: Dir4) -> Option<usize> {
+        let in_grid = point.x >= 0 && point.x < width && point.y >= 0 && point.y < height;
+
+        in_grid
+            .then(|| usize::try_from((point.y * width + point.x) * 4).ok())
+            .flatten()
+            .map(|tile| tile + direction.index())
+    }
+
+    fn new(grid: &Grid) -> Result<Self, Box<dyn Error>> {
+        let (width, height) = (grid.width, grid.height);
+        let tiles = usize::try_from(width * height)?;
+
+        let successors = (0..height)
+            .cartesian_product(0..width)
+            .cartesian_product(Dir4::ALL)
+            .map(|((y, x), direction)| {
+                let contraption = &grid.layout[y as usize][x as usize];
+                moves(contraption, &Vec2 { x, y }, direction)
+                    .iter()
+                    .filter_map(|(point, direction)| Self::state(width, height, point, *direction))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let component = strongly_connected_components(&successors);
+        let components = component.iter().max().map_or(0, |max| max + 1);
+
+        let mut members = vec![Vec::new(); components];
+        component
+            .iter()
+            .enumerate()
+            .for_each(|(state, component)| members[*component].push(state));
+
+        // the successors of a component are done before it
+        let mut energized: Vec<Vec<u64>> = Vec::with_capacity(components);
+        for states in members {
+            let mut tiles_bits = vec![0u64; tiles.div_ceil(64)];
+            for state in &states {
+                let tile = state / 4;
+                tiles_bits[tile / 64] |= 1 << (tile % 64);
+
+                for successor in &successors[*state] {
+                    if let Some(reached) = energized.get(component[*successor]) {
+                        zip(tiles_bits.iter_mut(), reached).for_each(|(bits, reached)| {
+                            *bits |= reached;
+                        });
+                    }
+                }
+            }
+            energized.push(tiles_bits);
+        }
+
+        Ok(BeamCache {
+            width,
+            height,
+            component,
+            energized,
+        })
+    }
+
+    fn energized(&self, point: &Vec2, direction: Dir4) -> Option<i32> {
+        let state = Self::state(self.width, self.height, point, direction)?;
+
+        i32::try_from(
+            self.energized[self.component[state]]
+                .iter()
+                .map(|bits| bits.count_ones())
+                .sum::<u32>(),
+        )
+        .ok()
+    }
+}
+
+pub fn solve2_cached(grid: &Grid) -> Result<i32, Box<dyn Error>> {
+    let cache = BeamCache::new(grid)?;
+
+    border_starts(grid)?
+        .iter()
+        .map(|(point, direction)| cache.energized(point, *direction).ok_or("Invalid start"))
+        .process_results(|itr| itr.max())?
+        .ok_or("No solution".into())
+}
+
+// a tile replaced by another one and the resulting part 1 energized count
+#[derive(Debug, PartialEq, Eq)]
+pub struct Mutation {
+    pub point: Vec2,
+    pub contraption: Contraption,
+    pub energized: i32,
+}
+
+// tries every replacement of every tile in parallel, the first one in reading order wins on ties
+pub fn best_mutation(grid: &Grid) -> Result<Option<Mutation>, Box<dyn Error>> {
+    let contraptions = [
+        Contraption::Empty,
+        Contraption::VerticalSplitter,
+        Contraption::HorizontalSplitter,
+        Contraption::MirrorSlash,
+        Contraption::MirrorBackslash,
+    ];
+
+    let candidates = (0..grid.height)
+        .cartesian_product(0..grid.width)
+        .cartesian_product(contraptions)
+        .filter(|((y, x), contraption)| {
+            grid.layout
+                .get(*y as usize)
+                .and_then(|row| row.get(*x as usize))
+                .is_some_and(|current| current != contraption)
+        })
+        .collect::<Vec<_>>();
+
+    let mutations = map_indexed(
+        &candidates,
+        |((y, x), contraption)| -> Result<Mutation, String> {
+            let mut mutated = grid.clone();
+            mutated.layout[*y as usize][*x as usize] = contraption.clone();
+
+            let energized = solve1(&mutated).map_err(|e| e.to_string())?;
+            Ok(Mutation {
+                point: Vec2 { x: *x, y: *y },
+                contraption: contraption.clone(),
+                energized,
+            })
+        },
+    )
+    .into_iter()
+    .map(|timed| timed.value)
+    .collect::<Result<Vec<_>, _>>()?;
+
+    // max_by_key returns the last maximum
+    Ok(mutations
+        .into_iter()
+        .rev()
+        .max_by_key(|mutation| mutation.energized))
+}
+
+// the beam leaving a tile to its neighbor in a direction
+fn step(point: &Vec2, direction: Dir4) -> (Vec2, Dir4) {
+    (*point + direction.offset(), direction)
+}
+
+// the beams leaving a tile hit by a beam going in a direction
+fn moves(contraption: &Contraption, point: &Vec2, direction: Dir4) -> Vec<(Vec2, Dir4)> {
+    match contraption {
+        Contraption::Empty => vec![step(point, direction)],
+        Contraption::VerticalSplitter if direction.is_vertical() => vec![step(point, direction)],
+        Contraption::VerticalSplitter => vec![step(point, Dir4::Up), step(point, Dir4::Down)],
+        Contraption::HorizontalSplitter if direction.is_vertical() => {
+            vec![step(point, Dir4::Left), step(point, Dir4::Right)]
+        }
+        Contraption::HorizontalSplitter => vec![step(point, direction)],
+        // a beam going up leaves a / to the right, and one going right leaves it up
+        Contraption::MirrorSlash if direction.is_vertical() => {
+            vec![step(point, direction.rotate_cw())]
+        }
+        Contraption::MirrorSlash => vec![step(point, direction.rotate_ccw())],
+        Contraption::MirrorBackslash if direction.is_vertical() => {
+            vec![step(point, direction.rotate_ccw())]
+        }
+        Contraption::MirrorBackslash => vec![step(point, direction.rotate_cw())],
+    }
+}
+
+fn solve(grid: &Grid, start: (Vec2, Dir4)) -> Result<i32, Box<dyn Error>> {
+    Ok(simulate(grid, start, None)?.count)
+}
+
+fn simulate(
+    grid: &Grid,
+    start: (Vec2, Dir4),
+    max_steps: Option<usize>,
+) -> Result<Energized, Box<dyn Error>> {
+    Ok(simulate_map(grid, start, max_steps)?.1)
+}
+
+// the directions in which the beams entered each tile, as masks with the bit of Dir4::index set
+type EnergizeMap = Vec<Vec<u8>>;
+
+fn energize_map(grid: &Grid, start: (Vec2, Dir4)) -> Result<EnergizeMap, Box<dyn Error>> {
+    Ok(simulate_map(grid, start, None)?.0)
+}
+
+// a step is a beam entering a tile in a direction it didn't enter before
+fn simulate_map(
+    grid: &Grid,
+    start: (Vec2, Dir4),
+    max_steps: Option<usize>,
+) -> Result<(EnergizeMap, Energized), Box<dyn Error>> {
+    let mut visited: EnergizeMap =
+        vec![vec![0; usize::try_from(grid.width)?]; usize::try_from(grid.height)?];
+    let mut stack = vec![start];
+    let mut steps = 0;
+    let mut truncated = false;
+
+    while let Some((point, direction)) = stack.pop() {
+        // discard out of bound points
+        if point.x < 0 || point.x >= grid.width || point.y < 0 || point.y >= grid.height {
+            continue;
+        }
+
+        let point_x = usize::try_from(point.x)?;
+        let point_y = usize::try_from(point.y)?;
+        let bit = 1 << direction.index();
+        let cell_visited = visited
+            .get_mut(point_y)
+            .and_then(|row| row.get_mut(point_x));
+
+        // skip visited cells
+        if cell_visited
+            .as_ref()
+            .map_or(false, |directions| **directions & bit != 0)
+        {
+            continue;
+        }
+
+        if max_steps.is_some_and(|max_steps| steps >= max_steps) {
+            truncated = true;
+            break;
+        }
+        steps += 1;
+
+        // mark cell as visited
+        if let Some(directions) = cell_visited {
+            *directions |= bit;
+        }
+
+        // get the next moves
+        if let Some(contraption) = grid.layout.get(point_y).and_then(|row| row.get(point_x)) {
+            stack.extend(moves(contraption, &point, direction));
+        }
+    }
+
+    let count = i32::try_from(
+        visited
+            .iter()
+            .flatten()
+            .filter(|&&directions| directions != 0)
+            .count(),
+    )?;
+
+    Ok((
+        visited,
+        Energized {
+            count,
+            steps,
+            truncated,
+        },
+    ))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Stats {
+    // the border start energizing the most tiles, the first one in case of a tie
+    pub start: (Vec2, Dir4),
+    pub energized: i32,
+    // the number of tiles entered from 1, 2, 3 and 4 directions with this start
+    pub by_directions: [usize; 4],
+}
+
+pub fn stats(grid: &Grid) -> Result<Stats, Box<dyn Error>> {
+    let starts = border_starts(grid)?;
+    let counts = map_indexed(&starts, |start| {
+        solve(grid, *start).map_err(|e| e.to_string())
+    })
+    .into_iter()
+    .map(|timed| timed.value)
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let (start, energized) = zip(starts, counts)
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .ok_or("No solution")?;
+
+    let mut by_directions = [0; 4];
+    for directions in energize_map(grid, start)?.iter().flatten() {
+        if let Some(count) = usize::try_from(directions.count_ones())
+            .ok()
+            .and_then(|ones| ones.checked_sub(1))
+            .and_then(|i| by_directions.get_mut(i))
+        {
+            *count += 1;
+        }
+    }
+
+    Ok(Stats {
+        start,
+        energized,
+        by_directions,
+    })
+}
+
+#[cfg(test)]
+mod day16 {
+
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+    use lib::{
+        grid::Symmetry,
+        vec2::{Dir4, Vec2},
+    };
+
+    use crate::{
+        best_mutation, border_starts, canonical_start, energize_map, parse, parse_beam_options,
+        simulate, solve, solve1, solve1_limited, solve2, solve2_cached, solve2_limited, stats,
+        strongly_connected_components, symmetries, to_char, transform_grid, BeamCache, Contraption,
+        Energized, Grid, Implementation, Stats,
+    };
+
+    const EXAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    fn example_grid() -> Grid {
+        Grid {
+            width: 10,
+            height: 10,
+            layout: vec![
+                vec![
+                    Contraption::Empty,
+                    Contraption::VerticalSplitter,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::MirrorBackslash,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                ],
+                vec![
+                    Contraption::VerticalSplitter,
+                    Contraption::Empty,
+                    Contraption::HorizontalSplitter,
+                    Contraption::Empty,
+                    Contraption::MirrorBackslash,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                ],
+                vec![
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::VerticalSplitter,
+                    Contraption::HorizontalSplitter,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                ],
+                vec![
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::VerticalSplitter,
+                    Contraption::Empty,
+                ],
+                vec![
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                ],
+                vec![
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::MirrorBackslash,
+                ],
+                vec![
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::MirrorSlash,
+                    Contraption::Empty,
+                    Contraption::MirrorBackslash,
+                    Contraption::MirrorBackslash,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                ],
+                vec![
+                    Contraption::Empty,
+                    Contraption::HorizontalSplitter,
+                    Contraption::Empty,
+                    Contraption::HorizontalSplitter,
+                    Contraption::MirrorSlash,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::VerticalSplitter,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                ],
+                vec![
+                    Contraption::Empty,
+                    Contraption::VerticalSplitter,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::HorizontalSplitter,
+                    Contraption::VerticalSplitter,
+                    Contraption::Empty,
+                    Contraption::MirrorBackslash,
+                ],
+                vec![
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::MirrorSlash,
+                    Contraption::MirrorSlash,
+                    Contraption::Empty,
+                    Contraption::VerticalSplitter,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                    Contraption::Empty,
+                ],
+            ],
+        }
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            parse(EXAMPLE.lines().map(|s| s.to_string()))?,
+            example_grid()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_example() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve1(&example_grid())?, 46);
+        Ok(())
+    }
+
+    fn render(grid: &Grid) -> Vec<String> {
+        grid.layout
+            .iter()
+            .map(|row| row.iter().map(to_char).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_transform_grid() {
+        let grid = example_grid();
+
+        // the columns in reverse order, the mirrors turned the other way
+        let mirrored = transform_grid(&grid, Symmetry::MirrorX);
+        let expected = EXAMPLE
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .rev()
+                    .map(|c| match c {
+                        '/' => '\\',
+                        '\\' => '/',
+                        _ => c,
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(render(&mirrored), expected);
+
+        // the first column from the bottom, the splitters swapped by the quarter turn
+        let turned = transform_grid(&grid, Symmetry::QuarterTurn);
+        assert_eq!(render(&turned)[0], "........-.");
+        assert_eq!(render(&turned)[1], ".-|......-");
+
+        for symmetry in Symmetry::ALL {
+            let image = transform_grid(&grid, symmetry);
+            assert_eq!(transform_grid(&image, symmetry.inverse()), grid);
+        }
+    }
+
+    #[test]
+    fn test_symmetries_example() -> Result<(), Box<dyn Error>> {
+        let counts = symmetries(&example_grid())?;
+
+        assert_eq!(counts.len(), 8);
+        assert!(counts.iter().all(|(_, count)| *count == 46), "{:?}", counts);
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_start() -> Result<(), Box<dyn Error>> {
+        let grid = example_grid();
+
+        for start in border_starts(&grid)? {
+            let (canonical, canonical_start) = canonical_start(&grid, start);
+            assert_eq!(canonical_start.1, Dir4::Right);
+            assert_eq!(solve(&canonical, canonical_start)?, solve(&grid, start)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_mutation_example() -> Result<(), Box<dyn Error>> {
+        let grid = example_grid();
+        let mutation = best_mutation(&grid)?.ok_or("No mutation")?;
+
+        // no other single tile replacement energizes more tiles
+        let mut best = solve1(&grid)?;
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                for contraption in [
+                    Contraption::Empty,
+                    Contraption::VerticalSplitter,
+                    Contraption::HorizontalSplitter,
+                    Contraption::MirrorSlash,
+                    Contraption::MirrorBackslash,
+                ] {
+                    let mut mutated = grid.clone();
+                    mutated.layout[y as usize][x as usize] = contraption;
+                    best = best.max(solve1(&mutated)?);
+                }
+            }
+        }
+        assert_eq!(mutation.energized, best);
+
+        let mut mutated = grid.clone();
+        let Vec2 { x, y } = mutation.point;
+        mutated.layout[y as usize][x as usize] = mutation.contraption;
+        assert_eq!(solve1(&mutated)?, mutation.energized);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let grid = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve1(&grid)?;
+        assert_eq!(result, 7046);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_example() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve2(&example_grid())?, 51);
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_steps() -> Result<(), Box<dyn Error>> {
+        let grid = example_grid();
+        let full = solve1_limited(&grid, None)?;
+        assert_eq!(full.count, 46);
+        assert!(!full.truncated);
+
+        // exactly enough steps
+        assert_eq!(solve1_limited(&grid, Some(full.steps))?, full);
+
+        let partial = solve1_limited(&grid, Some(full.steps - 1))?;
+        assert!(partial.truncated);
+        assert!(partial.count <= full.count);
+
+        let partial = solve1_limited(&grid, Some(10))?;
+        assert!(partial.truncated);
+        assert_eq!(partial.steps, 10);
+        assert!(partial.count <= 10);
+
+        assert_eq!(
+            simulate(&grid, (Vec2::ZERO, Dir4::Right), Some(0))?,
+            Energized {
+                count: 0,
+                steps: 0,
+                truncated: true
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_energize_map() -> Result<(), Box<dyn Error>> {
+        let grid = example_grid();
+        let map = energize_map(&grid, (Vec2::ZERO, Dir4::Right))?;
+        assert_eq!(map.iter().flatten().filter(|&&mask| mask != 0).count(), 46);
+
+        // the maps are indexed by row, the | right of the first tile sends the beam down
+        assert_eq!(map[0][0], 1 << Dir4::Right.index());
+        // a beam coming back on the first row enters the splitter again going left
+        assert_eq!(
+            map[0][1],
+            1 << Dir4::Right.index() | 1 << Dir4::Left.index()
+        );
+        assert_eq!(map[1][1], 1 << Dir4::Down.index());
+        assert_eq!(map[0][6], 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_example() -> Result<(), Box<dyn Error>> {
+        let stats = stats(&example_grid())?;
+        assert_eq!(
+            stats,
+            Stats {
+                start: (Vec2 { x: 3, y: 0 }, Dir4::Down),
+                energized: 51,
+                by_directions: [44, 6, 1, 0],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_steps_solve2() -> Result<(), Box<dyn Error>> {
+        let grid = example_grid();
+        let full = solve2_limited(&grid, None)?;
+        assert_eq!(full.count, 51);
+        assert!(!full.truncated);
+
+        let partial = solve2_limited(&grid, Some(5))?;
+        assert!(partial.truncated);
+        assert!(partial.count <= 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_beam_options() -> Result<(), Box<dyn Error>> {
+        let args = |args: &[&str]| args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let options = parse_beam_options(&args(&[]))?;
+        assert_eq!((options.max_steps, options.json), (None, false));
+
+        let options = parse_beam_options(&args(&["--json", "--max-steps", "100"]))?;
+        assert_eq!((options.max_steps, options.json), (Some(100), true));
+
+        assert!(parse_beam_options(&args(&["--max-steps"])).is_err());
+        assert!(parse_beam_options(&args(&["--max-steps", "-1"])).is_err());
+        assert!(parse_beam_options(&args(&["--other"])).is_err());
+
+        let options = parse_beam_options(&args(&["--impl", "scalar"]))?;
+        assert_eq!(options.implementation, Implementation::Scalar);
+        assert!(parse_beam_options(&args(&["--impl"])).is_err());
+        assert!(parse_beam_options(&args(&["--impl", "gpu"])).is_err());
+        // only available with the simd feature
+        assert_eq!(
+            parse_beam_options(&args(&["--impl", "bitboard"])).is_ok(),
+            cfg!(feature = "simd")
+        );
+        // the step budget is only implemented by the scalar simulation
+        assert!(parse_beam_options(&args(&["--impl", "bitboard", "--max-steps", "10"])).is_err());
+        assert!(parse_beam_options(&args(&["--impl", "scalar", "--max-steps", "10"])).is_ok());
+        Ok(())
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn test_bitboard() -> Result<(), Box<dyn Error>> {
+        use crate::bitboard::{self, Bitboard};
+
+        let grid = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        assert_eq!(bitboard::solve1_limited(&grid, None)?.count, 46);
+        assert_eq!(bitboard::solve2_limited(&grid, None)?.count, 51);
+        assert!(bitboard::solve1_limited(&grid, Some(10)).is_err());
+
+        // same counts and steps as the scalar simulation from every start, inside the grid or not
+        let bitboard = Bitboard::new(&grid)?;
+        for start in border_starts(&grid)?
+            .into_iter()
+            .chain([(Vec2 { x: -1, y: 0 }, Dir4::Right)])
+        {
+            assert_eq!(bitboard.simulate(start)?, simulate(&grid, start, None)?);
+        }
+
+        let wide = parse([".".repeat(129)].into_iter())?;
+        assert!(Bitboard::new(&wide).is_err());
+        let wide = parse([".".repeat(128)].into_iter())?;
+        assert_eq!(bitboard::solve1_limited(&wide, None)?.count, 128);
+        Ok(())
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // 0 -> 1 -> 2 -> 1, 2 -> 3, 4 alone
+        let component =
+            strongly_connected_components(&[vec![1], vec![2], vec![1, 3], vec![], vec![4]]);
+
+        assert_eq!(component[1], component[2]);
+        assert_eq!(component.iter().unique().count(), 4);
+        // successors come first
+        assert!(component[3] < component[1]);
+        assert!(component[1] < component[0]);
+    }
+
+    #[test]
+    fn test_beam_cache() -> Result<(), Box<dyn Error>> {
+        let grid = example_grid();
+        let cache = BeamCache::new(&grid)?;
+
+        // every state of the grid, not only the border ones
+        (0..grid.height)
+            .cartesian_product(0..grid.width)
+            .cartesian_product(Dir4::ALL)
+            .try_for_each(|((y, x), direction)| -> Result<(), Box<dyn Error>> {
+                let point = Vec2 { x, y };
+                assert_eq!(
+                    cache.energized(&point, direction),
+                    Some(solve(&grid, (point, direction))?)
+                );
+                Ok(())
+            })?;
+
+        assert_eq!(cache.energized(&Vec2 { x: -1, y: 0 }, Dir4::Right), None);
+        assert_eq!(border_starts(&grid)?.len(), 40);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_cached_example() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve2_cached(&example_grid())?, 51);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let grid = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve2(&grid)?;
+        assert_eq!(solve2_cached(&grid)?, result);
+        assert_eq!(result, 7313);
+        Ok(())
+    }
+}
@@ -0,0 +1,706 @@
+use itertools::Itertools;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    error::Error,
+    str::FromStr,
+};
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Graph, Box<dyn Error>> {
+    let mut width = 0;
+    let graph: Vec<Vec<_>> = itr
+        .map(|line| -> Result<Vec<u32>, String> {
+            if width == 0 {
+                width = line.len();
+            } else if width != line.len() {
+                return Err("Invalid line length".to_string());
+            }
+            line.chars()
+                .map(|c| c.to_digit(10).ok_or("Invalid digit".to_string()))
+                .collect()
+        })
+        .process_results(|itr| itr.collect())?;
+    let height = graph.len();
+
+    Ok(Graph {
+        graph,
+        width,
+        height,
+    })
+}
+
+#[derive(Clone)]
+pub struct Graph {
+    graph: Vec<Vec<u32>>,
+    width: usize,
+    height: usize,
+}
+
+// the heat lost on a block as a function of its digit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cost {
+    Identity,
+    Square,
+    Cap(u32),
+}
+
+impl FromStr for Cost {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "identity" => Ok(Cost::Identity),
+            "square" => Ok(Cost::Square),
+            _ => {
+                let cap = s
+                    .strip_prefix("cap:")
+                    .ok_or(format!("Invalid cost: {}", s))?;
+                Ok(Cost::Cap(cap.parse()?))
+            }
+        }
+    }
+}
+
+impl Cost {
+    fn apply(&self, digit: u32) -> u32 {
+        match self {
+            Cost::Identity => digit,
+            Cost::Square => digit * digit,
+            Cost::Cap(cap) => digit.min(*cap),
+        }
+    }
+}
+
+pub fn with_cost(graph: Graph, cost: &Cost) -> Graph {
+    Graph {
+        graph: graph
+            .graph
+            .into_iter()
+            .map(|row| row.into_iter().map(|digit| cost.apply(digit)).collect())
+            .collect(),
+        ..graph
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Vertex {
+    x: usize,
+    y: usize,
+    orientation: Orientation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct QueueElement {
+    vertex: Vertex,
+    dist: u32,
+}
+
+impl PartialOrd for QueueElement {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.dist.cmp(&other.dist).reverse())
+    }
+}
+
+impl Ord for QueueElement {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist.cmp(&other.dist).reverse()
+    }
+}
+
+fn graph_get(
+    Graph {
+        graph,
+        width: _,
+        height: _,
+    }: &Graph,
+    x: usize,
+    y: usize,
+) -> Option<u32> {
+    graph.get(y).and_then(|row| row.get(x)).copied()
+}
+
+pub fn solve1(graph: Graph) -> Result<u32, Box<dyn Error>> {
+    solve_with_constraints(graph, CRUCIBLE_RUNS.0, CRUCIBLE_RUNS.1)
+}
+
+pub fn solve2(graph: Graph) -> Result<u32, Box<dyn Error>> {
+    solve_with_constraints(graph, ULTRA_CRUCIBLE_RUNS.0, ULTRA_CRUCIBLE_RUNS.1)
+}
+
+// the crucible has to go straight for at least min_run blocks and at most max_run blocks before
+// turning
+pub fn solve_with_constraints(
+    graph: Graph,
+    min_run: i32,
+    max_run: i32,
+) -> Result<u32, Box<dyn Error>> {
+    solve(&graph, min_run, max_run).map(|search| search.dist)
+}
+
+// the coordinates of a block
+type Block = (usize, usize);
+
+// the heat lost and the blocks of the path in order, the start block included
+pub fn solve_with_path(
+    graph: &Graph,
+    min_run: i32,
+    max_run: i32,
+) -> Result<(u32, Vec<Block>), Box<dyn Error>> {
+    let search = solve(graph, min_run, max_run)?;
+
+    Ok((search.dist, path_blocks(&search.vertices)))
+}
+
+// the blocks of the path in order, the start block included, from the vertices where it turns
+pub fn path_blocks(vertices: &[Vertex]) -> Vec<Block> {
+    let mut blocks = vec![(0, 0)];
+    vertices.iter().tuple_windows().for_each(|(from, to)| {
+        let (mut x, mut y) = (from.x, from.y);
+        while (x, y) != (to.x, to.y) {
+            (x, y) = (step(x, to.x), step(y, to.y));
+            blocks.push((x, y));
+        }
+    });
+
+    blocks
+}
+
+fn step(from: usize, to: usize) -> usize {
+    match from.cmp(&to) {
+        Ordering::Less => from + 1,
+        Ordering::Greater => from - 1,
+        Ordering::Equal => from,
+    }
+}
+
+// the grid with an arrow on each block of the path showing where the crucible comes from
+pub fn render_path(graph: &Graph, blocks: &[Block]) -> String {
+    let mut cells = graph
+        .graph
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|digit| char::from_digit(*digit, 10).unwrap_or('?'))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    blocks.iter().tuple_windows().for_each(|(from, to)| {
+        let arrow = match (to.0.cmp(&from.0), to.1.cmp(&from.1)) {
+            (Ordering::Greater, _) => '>',
+            (Ordering::Less, _) => '<',
+            (_, Ordering::Greater) => 'v',
+            _ => '^',
+        };
+        cells[to.1][to.0] = arrow;
+    });
+
+    cells
+        .iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+// the grid with the order in which the search settles each block, from 0 for the start. A block is
+// settled with the first of its vertices, the blocks never settled are shown with a dot
+pub fn render_explored(graph: &Graph, settled: &[Vertex]) -> String {
+    let mut order: Vec<Vec<Option<usize>>> = vec![vec![None; graph.width]; graph.height];
+    let mut count = 0;
+    for vertex in settled {
+        if let Some(cell @ None) = order
+            .get_mut(vertex.y)
+            .and_then(|row| row.get_mut(vertex.x))
+        {
+            *cell = Some(count);
+            count += 1;
+        }
+    }
+
+    let width = count.saturating_sub(1).to_string().len();
+    order
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    Some(n) => format!("{:>width$}", n),
+                    None => format!("{:>width$}", "."),
+                })
+                .join(" ")
+                + "\n"
+        })
+        .collect()
+}
+
+// one value per block for the heatmap export: the least heat lost to reach it, scaled from 0 for the
+// start to 254 for the block the farthest away, 255 for the blocks never reached
+pub fn heat_rows(graph: &Graph, distances: &HashMap<Vertex, u32>) -> Vec<Vec<u8>> {
+    let mut heat: Vec<Vec<Option<u32>>> = vec![vec![None; graph.width]; graph.height];
+    for (vertex, dist) in distances {
+        if let Some(cell) = heat.get_mut(vertex.y).and_then(|row| row.get_mut(vertex.x)) {
+            *cell = Some(cell.map_or(*dist, |heat| heat.min(*dist)));
+        }
+    }
+
+    let max = heat.iter().flatten().flatten().max().copied().unwrap_or(0);
+    heat.iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| {
+                    cell.map_or(255, |heat| {
+                        u8::try_from(u64::from(heat) * 254 / u64::from(max.max(1))).unwrap_or(254)
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// the minimum and maximum lengths of the straight runs of the crucibles of both parts
+pub const CRUCIBLE_RUNS: (i32, i32) = (1, 3);
+pub const ULTRA_CRUCIBLE_RUNS: (i32, i32) = (4, 10);
+
+pub fn parse_runs(s: &str) -> Result<(i32, i32), Box<dyn Error>> {
+    let (min_run, max_run) = s.split_once(':').ok_or(format!("Invalid runs: {}", s))?;
+
+    Ok((min_run.parse()?, max_run.parse()?))
+}
+
+fn get_neighbors(graph: &Graph, vertex: Vertex, min_run: i32, max_run: i32) -> Vec<(Vertex, u32)> {
+    // we start at 1 and -1 to rightly compute the distance on the way
+    [(1..=max_run).collect(), (-max_run..=-1).rev().collect()]
+        .iter()
+        .flat_map(|range: &Vec<i32>| -> Vec<(Vertex, u32)> {
+            let mut dist: u32 = 0;
+            range
+                .iter()
+                .filter_map(|offset| {
+                    move_(vertex, graph.width, graph.height, *offset).and_then(|next| {
+                        dist += graph_get(graph, next.x, next.y)?;
+
+                        // discard vertices that are too close
+                        (offset.abs() >= min_run).then_some((next, dist))
+                    })
+                })
+                .collect::<Vec<(Vertex, u32)>>()
+        })
+        .collect()
+}
+
+fn move_(vertex: Vertex, width: usize, height: usize, d: i32) -> Option<Vertex> {
+    match vertex.orientation {
+        Orientation::Horizontal => {
+            let nx = i32::try_from(vertex.x).ok()? + d;
+            (nx >= 0 && nx < i32::try_from(width).ok()?).then_some(Vertex {
+                x: usize::try_from(nx).ok()?,
+                y: vertex.y,
+                orientation: Orientation::Vertical,
+            })
+        }
+        Orientation::Vertical => {
+            let ny = i32::try_from(vertex.y).ok()? + d;
+            (ny >= 0 && ny < i32::try_from(height).ok()?).then_some(Vertex {
+                x: vertex.x,
+                y: usize::try_from(ny).ok()?,
+                orientation: Orientation::Horizontal,
+            })
+        }
+    }
+}
+
+// what the search did, to compare the ways to explore the grid
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub pushed: usize,
+    pub popped: usize,
+    // popped after a shorter distance was found for their vertex, they are skipped
+    pub stale: usize,
+    // the vertices in the order their distance became final
+    pub settled: Vec<Vertex>,
+}
+
+pub struct Search {
+    // the heat lost on the best path
+    pub dist: u32,
+    // the vertices where the best path turns, from the start to the end
+    pub vertices: Vec<Vertex>,
+    // the least heat lost to reach each vertex found by the search
+    pub distances: HashMap<Vertex, u32>,
+    pub stats: SearchStats,
+}
+
+pub fn solve(graph: &Graph, min_run: i32, max_run: i32) -> Result<Search, Box<dyn Error>> {
+    if min_run < 1 || max_run < min_run {
+        return Err(format!("Invalid runs: {}:{}", min_run, max_run).into());
+    }
+
+    let mut queue: BinaryHeap<QueueElement> = BinaryHeap::new();
+    queue.push(QueueElement {
+        vertex: Vertex {
+            x: 0,
+            y: 0,
+            orientation: Orientation::Horizontal,
+        },
+        dist: 0,
+    });
+    queue.push(QueueElement {
+        vertex: Vertex {
+            x: 0,
+            y: 0,
+            orientation: Orientation::Vertical,
+        },
+        dist: 0,
+    });
+
+    let mut distances: HashMap<Vertex, u32> = HashMap::new();
+    distances.insert(
+        Vertex {
+            x: 0,
+            y: 0,
+            orientation: Orientation::Vertical,
+        },
+        0,
+    );
+    distances.insert(
+        Vertex {
+            x: 0,
+            y: 0,
+            orientation: Orientation::Horizontal,
+        },
+        0,
+    );
+
+    // track of the previous vertices to rebuild the path
+    let mut prev: HashMap<Vertex, Vertex> = HashMap::new();
+
+    let mut result: Option<(u32, Vertex)> = None;
+    let mut stats = SearchStats {
+        pushed: queue.len(),
+        ..Default::default()
+    };
+
+    while let Some(QueueElement {
+        vertex: current,
+        dist,
+    }) = queue.pop()
+    {
+        stats.popped += 1;
+        if distances.get(&current).is_some_and(|best| dist > *best) {
+            stats.stale += 1;
+            continue;
+        }
+        stats.settled.push(current);
+
+        if current.x == graph.width - 1 && current.y == graph.height - 1 {
+            result = result.map_or(Some((dist, current)), |result| {
+                Some(if dist < result.0 {
+                    (dist, current)
+                } else {
+                    result
+                })
+            });
+        }
+
+        get_neighbors(graph, current, min_run, max_run)
+            .iter()
+            .for_each(|(neighbor, relative_dist)| {
+                let dist = dist + relative_dist;
+                let prev_dist = distances.get(neighbor).unwrap_or(&u32::MAX);
+                if dist < *prev_dist {
+                    queue.push(QueueElement {
+                        vertex: *neighbor,
+                        dist,
+                    });
+                    distances.insert(*neighbor, dist);
+                    prev.insert(*neighbor, current);
+                    stats.pushed += 1;
+                }
+            });
+    }
+
+    let (dist, end) = result.ok_or("No path found")?;
+
+    // the start vertices are the only ones without a previous vertex
+    let mut vertices = vec![end];
+    while let Some(vertex) = prev.get(vertices.last().ok_or("Empty path")?) {
+        vertices.push(*vertex);
+    }
+    vertices.reverse();
+
+    Ok(Search {
+        dist,
+        vertices,
+        distances,
+        stats,
+    })
+}
+
+#[cfg(test)]
+mod day17 {
+    use std::{error::Error, ops::RangeInclusive};
+
+    use itertools::Itertools;
+    use lib::aoc_tests;
+
+    use crate::{
+        get_neighbors, graph_get, heat_rows, parse, parse_runs, render_explored, render_path,
+        solve, solve1, solve2, solve_with_constraints, solve_with_path, with_cost, Cost, Graph,
+        Orientation, Vertex,
+    };
+
+    const EXAMPLE1: &str = "\
+        2413432311323\n\
+        3215453535623\n\
+        3255245654254\n\
+        3446585845452\n\
+        4546657867536\n\
+        1438598798454\n\
+        4457876987766\n\
+        3637877979653\n\
+        4654967986887\n\
+        4564679986453\n\
+        1224686865563\n\
+        2546548887735\n\
+        4322674655533";
+
+    const EXAMPLE2: &str = "\
+        111111111111\n\
+        999999999991\n\
+        999999999991\n\
+        999999999991\n\
+        999999999991";
+
+    aoc_tests! {
+        test_solve1_example1: example(EXAMPLE1), parse, solve1 => 102;
+        test_solve2_example1: example(EXAMPLE1), parse, solve2 => 94;
+        test_solve2_example2: example(EXAMPLE2), parse, solve2 => 71;
+        test_solve1_input: file("input"), parse, solve1 => 722;
+        test_solve2_input: file("input"), parse, solve2 => 894;
+    }
+
+    #[test]
+    fn test_parse_cost() -> Result<(), Box<dyn Error>> {
+        assert_eq!("identity".parse::<Cost>()?, Cost::Identity);
+        assert_eq!("square".parse::<Cost>()?, Cost::Square);
+        assert_eq!("cap:5".parse::<Cost>()?, Cost::Cap(5));
+        assert!("cap:".parse::<Cost>().is_err());
+        assert!("cube".parse::<Cost>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_identity_cost() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        assert_eq!(solve1(with_cost(graph, &Cost::Identity))?, 102);
+
+        let graph = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        assert_eq!(solve2(with_cost(graph, &Cost::Identity))?, 94);
+
+        let graph = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        assert_eq!(solve2(with_cost(graph, &Cost::Identity))?, 71);
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_costs() -> Result<(), Box<dyn Error>> {
+        // every block costs 1, the length of the shortest path allowed is left
+        let graph = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        assert_eq!(solve1(with_cost(graph, &Cost::Cap(1)))?, 24);
+
+        let graph = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        assert_eq!(solve2(with_cost(graph, &Cost::Cap(1)))?, 15);
+
+        // 7 ones to the right, 4 nines down, then 3 nines and a one to the right
+        let graph = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        assert_eq!(solve2(with_cost(graph, &Cost::Square))?, 7 + 7 * 81 + 1);
+        Ok(())
+    }
+
+    // the path goes from the start to the end through adjacent blocks, never turns back and the
+    // lengths of its straight runs are in the range allowed
+    fn check_path(graph: &Graph, runs: RangeInclusive<usize>) -> Result<u32, Box<dyn Error>> {
+        let min_run = i32::try_from(*runs.start())?;
+        let max_run = i32::try_from(*runs.end())?;
+        let (dist, blocks) = solve_with_path(graph, min_run, max_run)?;
+        assert_eq!(
+            solve_with_constraints(graph.clone(), min_run, max_run)?,
+            dist
+        );
+
+        assert_eq!(blocks.first(), Some(&(0, 0)));
+        assert_eq!(blocks.last(), Some(&(graph.width - 1, graph.height - 1)));
+
+        let moves = blocks
+            .iter()
+            .tuple_windows()
+            .map(|(from, to)| {
+                let dx = i64::try_from(to.0)? - i64::try_from(from.0)?;
+                let dy = i64::try_from(to.1)? - i64::try_from(from.1)?;
+                assert_eq!(dx.abs() + dy.abs(), 1);
+                Ok((dx, dy))
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        moves.iter().dedup_with_count().tuple_windows().for_each(
+            |((count, (dx, dy)), (_, next))| {
+                assert!(runs.contains(&count));
+                assert_ne!((-dx, -dy), *next);
+            },
+        );
+        let last_run = moves
+            .iter()
+            .dedup_with_count()
+            .last()
+            .map(|(count, _)| count);
+        assert!(last_run.is_some_and(|count| runs.contains(&count)));
+
+        // the heat lost is the one of the blocks entered
+        let heat = blocks[1..]
+            .iter()
+            .map(|(x, y)| graph_get(graph, *x, *y).ok_or("Out of the graph"))
+            .sum::<Result<u32, _>>()?;
+        assert_eq!(heat, dist);
+
+        Ok(dist)
+    }
+
+    #[test]
+    fn test_path() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        assert_eq!(check_path(&graph, 1..=3)?, 102);
+        assert_eq!(check_path(&graph, 4..=10)?, 94);
+
+        let graph = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        assert_eq!(check_path(&graph, 1..=3)?, 59);
+        assert_eq!(check_path(&graph, 4..=10)?, 71);
+
+        let graph = with_cost(graph, &Cost::Square);
+        check_path(&graph, 4..=10)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_runs() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        assert_eq!(check_path(&graph, 1..=1)?, 133);
+        assert_eq!(check_path(&graph, 2..=5)?, 101);
+
+        // a staircase, then a detour going back up to avoid the nines
+        let graph = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        assert_eq!(check_path(&graph, 1..=1)?, 125);
+        assert_eq!(check_path(&graph, 2..=5)?, 59);
+
+        // the parts are the constraints 1 to 3 and 4 to 10
+        let graph = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        assert_eq!(
+            solve_with_constraints(graph.clone(), 1, 3)?,
+            solve1(graph.clone())?
+        );
+        assert_eq!(
+            solve_with_constraints(graph.clone(), 4, 10)?,
+            solve2(graph.clone())?
+        );
+
+        assert!(solve_with_constraints(graph.clone(), 0, 3).is_err());
+        assert!(solve_with_constraints(graph, 3, 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_neighbors() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        let vertex = Vertex {
+            x: 5,
+            y: 0,
+            orientation: Orientation::Horizontal,
+        };
+
+        let xs = |min_run, max_run| {
+            get_neighbors(&graph, vertex, min_run, max_run)
+                .iter()
+                .map(|(vertex, _)| vertex.x)
+                .sorted()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(xs(1, 1), vec![4, 6]);
+        assert_eq!(xs(2, 5), vec![0, 1, 2, 3, 7, 8, 9, 10]);
+        assert_eq!(xs(4, 10), vec![0, 1, 9, 10, 11, 12]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_runs() -> Result<(), Box<dyn Error>> {
+        assert_eq!(parse_runs("2:5")?, (2, 5));
+        assert!(parse_runs("2").is_err());
+        assert!(parse_runs("2:x").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_path() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        let (_, blocks) = solve_with_path(&graph, 4, 10)?;
+
+        assert_eq!(
+            render_path(&graph, &blocks),
+            "\
+            1>>>>>>>1111\n\
+            9999999v9991\n\
+            9999999v9991\n\
+            9999999v9991\n\
+            9999999v>>>>\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_explored() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        let stats = solve(&graph, 4, 10)?.stats;
+
+        // the queue is drained
+        assert_eq!(stats.popped, stats.pushed);
+        assert_eq!(stats.settled.len() + stats.stale, stats.popped);
+        // the middle rows can't be reached with runs of at least 4 blocks
+        assert_eq!(stats.settled.len(), 48);
+
+        assert_eq!(
+            render_explored(&graph, &stats.settled),
+            [
+                " 0 23 22 21  1  2  3  4  5  6  7 17",
+                " .  .  .  .  .  .  .  .  .  .  .  .",
+                " .  .  .  .  .  .  .  .  .  .  .  .",
+                " .  .  .  .  .  .  .  .  .  .  .  .",
+                " 8 18 19 20  9 10 11 12 13 14 15 16",
+            ]
+            .map(|line| line.to_string() + "\n")
+            .concat()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_heat_rows() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        let search = solve(&graph, 4, 10)?;
+        let rows = heat_rows(&graph, &search.distances);
+
+        assert_eq!(rows.len(), 5);
+        assert!(rows.iter().all(|row| row.len() == 12));
+        assert_eq!(rows[0][0], 0);
+        assert_eq!(
+            rows.iter().flatten().filter(|&&heat| heat < 255).max(),
+            Some(&254)
+        );
+        // the middle rows can't be reached with runs of at least 4 blocks
+        assert!(rows[1..4].iter().flatten().all(|&heat| heat == 255));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use rand::Rng;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+};
+
+use lib::vec2::Dir4;
+
+// maximum number of cells added when growing a region before giving up
+const MAX_ATTEMPTS: usize = 10_000;
+
+type Cell = (i64, i64);
+
+fn neighbors((x, y): Cell) -> [Cell; 4] {
+    [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+}
+
+// the region has no hole when all the cells outside of it can be reached from the border
+fn has_hole(region: &HashSet<Cell>, extent: i64) -> bool {
+    let inside = |(x, y): Cell| (-1..=extent).contains(&x) && (-1..=extent).contains(&y);
+
+    let mut seen = HashSet::from([(-1, -1)]);
+    let mut queue = VecDeque::from([(-1, -1)]);
+    while let Some(cell) = queue.pop_front() {
+        for next in neighbors(cell) {
+            if inside(next) && !region.contains(&next) && seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    seen.len() + region.len() != ((extent + 2) * (extent + 2)) as usize
+}
+
+// two cells touching only by a corner make the boundary go twice through that corner
+fn has_pinch(region: &HashSet<Cell>, extent: i64) -> bool {
+    (-1..extent).any(|x| {
+        (-1..extent).any(|y| {
+            let [a, b, c, d] =
+                [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)].map(|cell| region.contains(&cell));
+            a == d && b == c && a != b
+        })
+    })
+}
+
+// the boundary of the region going clockwise, each cell side not shared with another cell of the
+// region being a unit move. Without hole nor pinch, a corner starts at most one move.
+fn boundary(region: &HashSet<Cell>) -> Result<Vec<(Dir4, i64)>, Box<dyn Error>> {
+    let moves = region
+        .iter()
+        .flat_map(|&(x, y)| {
+            [
+                ((x, y - 1), (x, y), (x + 1, y), Dir4::Right),
+                ((x + 1, y), (x + 1, y), (x + 1, y + 1), Dir4::Down),
+                ((x, y + 1), (x + 1, y + 1), (x, y + 1), Dir4::Left),
+                ((x - 1, y), (x, y + 1), (x, y), Dir4::Up),
+            ]
+        })
+        .filter(|(neighbor, _, _, _)| !region.contains(neighbor))
+        .map(|(_, from, to, direction)| (from, (to, direction)))
+        .collect::<HashMap<_, _>>();
+
+    let start = *moves.keys().min().ok_or("empty region")?;
+    let mut point = start;
+    let mut plan: Vec<(Dir4, i64)> = Vec::new();
+    loop {
+        let (next, direction) = *moves.get(&point).ok_or("open boundary")?;
+        match plan.last_mut() {
+            Some((last, dist)) if *last == direction => *dist += 1,
+            _ => plan.push((direction, 1)),
+        }
+        point = next;
+        if point == start || plan.iter().map(|(_, dist)| dist).sum::<i64>() > moves.len() as i64 {
+            break;
+        }
+    }
+
+    if plan.iter().map(|(_, dist)| dist).sum::<i64>() != moves.len() as i64 {
+        return Err("the boundary is not a single loop".into());
+    }
+
+    // the start is the smallest corner, so the first and last moves can't be in the same
+    // direction
+    Ok(plan)
+}
+
+// a random rectilinear simple polygon with the given number of vertices fitting in a square of
+// size extent. A region of cells is grown from a single cell, keeping it free of holes and
+// pinches so that its boundary never touches itself.
+pub fn generate_plan(
+    vertices: usize,
+    extent: i64,
+    rng: &mut impl Rng,
+) -> Result<Vec<(Dir4, i64)>, Box<dyn Error>> {
+    if vertices < 4 || !vertices.is_multiple_of(2) {
+        return Err(format!("invalid number of vertices: {}", vertices).into());
+    }
+    if extent < 1 {
+        return Err(format!("invalid extent: {}", extent).into());
+    }
+
+    let mut region = HashSet::from([(rng.gen_range(0..extent), rng.gen_range(0..extent))]);
+    let mut plan = boundary(&region)?;
+
+    for _ in 0..MAX_ATTEMPTS {
+        if plan.len() == vertices {
+            return Ok(plan);
+        }
+
+        let candidates = region
+            .iter()
+            .flat_map(|cell| neighbors(*cell))
+            .filter(|(x, y)| (0..extent).contains(x) && (0..extent).contains(y))
+            .filter(|cell| !region.contains(cell))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            break;
+        }
+
+        let cell = candidates[rng.gen_range(0..candidates.len())];
+        region.insert(cell);
+        if has_hole(&region, extent) || has_pinch(&region, extent) {
+            region.remove(&cell);
+            continue;
+        }
+
+        let grown = boundary(&region)?;
+        if grown.len() > vertices {
+            region.remove(&cell);
+        } else {
+            plan = grown;
+        }
+    }
+
+    Err(format!(
+        "unable to build a polygon with {} vertices in an extent of {}",
+        vertices, extent
+    )
+    .into())
+}
+
+// the plan in the puzzle format, the colors encoding the same instructions as the plain fields
+pub fn to_lines(plan: &[(Dir4, i64)]) -> Vec<String> {
+    plan.iter()
+        .map(|(direction, dist)| {
+            let (letter, digit) = match direction {
+                Dir4::Right => ("R", 0),
+                Dir4::Down => ("D", 1),
+                Dir4::Left => ("L", 2),
+                Dir4::Up => ("U", 3),
+            };
+            format!("{} {} (#{:05x}{})", letter, dist, dist, digit)
+        })
+        .collect()
+}
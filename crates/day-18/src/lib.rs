@@ -0,0 +1,324 @@
+use lib::vec2::{Dir4, Vec2};
+use std::error::Error;
+
+pub mod genfuzz;
+
+pub fn parse_direction(s: &str) -> Result<Dir4, Box<dyn Error>> {
+    match s {
+        "U" => Ok(Dir4::Up),
+        "D" => Ok(Dir4::Down),
+        "L" => Ok(Dir4::Left),
+        "R" => Ok(Dir4::Right),
+        _ => Err(format!("Invalid direction: {}", s).into()),
+    }
+}
+
+pub fn parse_color(hex: &str) -> Result<(Dir4, i64), Box<dyn Error>> {
+    let hex_str = hex
+        .strip_prefix("(#")
+        .and_then(|s| s.strip_suffix(")"))
+        .ok_or("Invalid hex")?;
+
+    let hex_dist = hex_str.get(0..5).ok_or("Invalid distance")?;
+    let dist = i64::from_str_radix(hex_dist, 16)?;
+
+    let hex_dir = hex_str
+        .get(5..)
+        .and_then(|s| s.chars().next())
+        .ok_or("Invalid direction")?;
+    let dir = match hex_dir {
+        '0' => Dir4::Right,
+        '1' => Dir4::Down,
+        '2' => Dir4::Left,
+        '3' => Dir4::Up,
+        _ => return Err("Invalid direction".into()),
+    };
+
+    Ok((dir, dist))
+}
+
+// a dig plan is made of lines with a direction, a distance and a color. The two halves of the
+// puzzle read the instruction from different fields.
+pub trait DigPlanParser {
+    fn parse_line(&self, line: &str) -> Result<(Dir4, i64), Box<dyn Error>>;
+
+    fn parse(&self, lines: &[String]) -> Result<Vec<(Dir4, i64)>, Box<dyn Error>> {
+        lines.iter().map(|line| self.parse_line(line)).collect()
+    }
+}
+
+// the instruction is given by the direction and the distance, as in part 1
+pub struct Plain;
+
+impl DigPlanParser for Plain {
+    fn parse_line(&self, line: &str) -> Result<(Dir4, i64), Box<dyn Error>> {
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+
+        let dir_str = parts.first().ok_or("Missing direction")?;
+        let dir = parse_direction(dir_str)?;
+
+        let dist_str = parts.get(1).ok_or("Missing distance")?;
+        let dist = dist_str.parse::<i64>()?;
+
+        Ok((dir, dist))
+    }
+}
+
+// the instruction is encoded in the color, as in part 2
+pub struct Color;
+
+impl DigPlanParser for Color {
+    fn parse_line(&self, line: &str) -> Result<(Dir4, i64), Box<dyn Error>> {
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+        let hex_str = parts.get(2).ok_or("Missing hex")?;
+
+        parse_color(hex_str)
+    }
+}
+
+// reads the colors when they all look like the part 2 encoding, falls back to the plain format
+// otherwise
+pub struct Auto;
+
+impl DigPlanParser for Auto {
+    fn parse_line(&self, line: &str) -> Result<(Dir4, i64), Box<dyn Error>> {
+        Color.parse_line(line).or_else(|_| Plain.parse_line(line))
+    }
+
+    fn parse(&self, lines: &[String]) -> Result<Vec<(Dir4, i64)>, Box<dyn Error>> {
+        if lines.iter().all(|line| Color.parse_line(line).is_ok()) {
+            Color.parse(lines)
+        } else {
+            Plain.parse(lines)
+        }
+    }
+}
+
+pub fn draw(directions: &[(Dir4, i64)]) -> Vec<(i64, i64)> {
+    let mut point = Vec2::ZERO;
+
+    directions
+        .iter()
+        .map(|(dir, dist)| {
+            point += dir.offset() * *dist;
+            point.into()
+        })
+        .collect::<Vec<_>>()
+}
+
+#[cfg(test)]
+mod day18 {
+    use std::{
+        collections::{HashSet, VecDeque},
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+    use lib::{
+        geom::{boundary_point_count, lattice_point_count},
+        vec2::{Dir4, Vec2},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{draw, genfuzz, parse_color, Auto, Color, DigPlanParser, Plain};
+
+    const EXAMPLE1: &str = "\
+        R 6 (#70c710)
+        D 5 (#0dc571)
+        L 2 (#5713f0)
+        D 2 (#d2c081)
+        R 2 (#59c680)
+        D 2 (#411b91)
+        L 5 (#8ceee2)
+        U 2 (#caa173)
+        L 1 (#1b58a2)
+        U 2 (#caa171)
+        R 2 (#7807d2)
+        U 3 (#a77fa3)
+        L 2 (#015232)
+        U 2 (#7a21e3)";
+
+    fn example1() -> Vec<String> {
+        EXAMPLE1.lines().map(|s| s.trim().to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse1() -> Result<(), Box<dyn Error>> {
+        let directions = Plain.parse(&example1())?;
+        let result = draw(&directions);
+
+        assert_eq!(result.last().ok_or("No last elemtn")?, &(0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_perimeter() -> Result<(), Box<dyn Error>> {
+        let directions = Plain.parse(&example1())?;
+        let points = draw(&directions);
+        let perimeter = boundary_point_count(&points);
+
+        assert_eq!(perimeter, 38);
+        Ok(())
+    }
+
+    #[test]
+    fn test_num_points_parse1() -> Result<(), Box<dyn Error>> {
+        let directions = Plain.parse(&example1())?;
+        let points = draw(&directions);
+        let area = lattice_point_count(&points);
+
+        assert_eq!(area, 62);
+        Ok(())
+    }
+
+    #[test]
+    fn test_num_points_parse2() -> Result<(), Box<dyn Error>> {
+        let directions = Color.parse(&example1())?;
+        let points = draw(&directions);
+        let area = lattice_point_count(&points);
+
+        assert_eq!(area, 952408144115);
+        Ok(())
+    }
+
+    #[test]
+    fn test_num_points_parse1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let directions = Plain.parse(&reader.lines().collect::<Result<Vec<_>, _>>()?)?;
+        let points = draw(&directions);
+        let area = lattice_point_count(&points);
+
+        assert_eq!(area, 47527);
+        Ok(())
+    }
+
+    #[test]
+    fn test_num_points_parse2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let directions = Color.parse(&reader.lines().collect::<Result<Vec<_>, _>>()?)?;
+        let points = draw(&directions);
+        let area = lattice_point_count(&points);
+
+        assert_eq!(area, 52240187443190);
+        Ok(())
+    }
+
+    // digs the trench one cube at a time then counts the cubes that can't be reached from the
+    // outside. The coordinates are doubled so that the outside can flow between two parallel
+    // trenches next to each other, only the even points being actual cubes.
+    fn flood_fill_count(directions: &[(Dir4, i64)]) -> usize {
+        let mut trench = HashSet::from([(0, 0)]);
+        let mut point = Vec2::ZERO;
+        for (direction, dist) in directions {
+            for _ in 0..2 * dist {
+                point += direction.offset();
+                trench.insert(point.into());
+            }
+        }
+
+        let (min_x, max_x) = trench.iter().map(|p| p.0).minmax().into_option().unwrap();
+        let (min_y, max_y) = trench.iter().map(|p| p.1).minmax().into_option().unwrap();
+        let (min_x, max_x, min_y, max_y) = (min_x - 2, max_x + 2, min_y - 2, max_y + 2);
+
+        let mut outside = HashSet::from([(min_x, min_y)]);
+        let mut queue = VecDeque::from([(min_x, min_y)]);
+        while let Some((x, y)) = queue.pop_front() {
+            for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                if (min_x..=max_x).contains(&next.0)
+                    && (min_y..=max_y).contains(&next.1)
+                    && !trench.contains(&next)
+                    && outside.insert(next)
+                {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        (min_x..=max_x)
+            .step_by(2)
+            .cartesian_product((min_y..=max_y).step_by(2))
+            .filter(|point| !outside.contains(point))
+            .count()
+    }
+
+    #[test]
+    fn test_flood_fill_example() -> Result<(), Box<dyn Error>> {
+        assert_eq!(flood_fill_count(&Plain.parse(&example1())?), 62);
+        Ok(())
+    }
+
+    #[test]
+    fn test_generated_plans() -> Result<(), Box<dyn Error>> {
+        let mut rng = StdRng::seed_from_u64(18);
+
+        for vertices in (4..=24).step_by(2) {
+            for _ in 0..10 {
+                let plan = genfuzz::generate_plan(vertices, 12, &mut rng)?;
+                assert_eq!(plan.len(), vertices);
+
+                // closed and turning at every vertex
+                assert_eq!(draw(&plan).last(), Some(&(0, 0)));
+                assert!(plan
+                    .iter()
+                    .zip(plan.iter().cycle().skip(1))
+                    .all(|((d1, _), (d2, _))| d1 != d2));
+
+                let lines = genfuzz::to_lines(&plan);
+                assert_eq!(Plain.parse(&lines)?, plan);
+                assert_eq!(Color.parse(&lines)?, plan);
+
+                let area = lattice_point_count(&draw(&plan));
+                assert_eq!(area as usize, flood_fill_count(&plan));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_plan_invalid() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(genfuzz::generate_plan(5, 10, &mut rng).is_err());
+        assert!(genfuzz::generate_plan(2, 10, &mut rng).is_err());
+        assert!(genfuzz::generate_plan(4, 0, &mut rng).is_err());
+        // a single cell can't have more than 4 vertices
+        assert!(genfuzz::generate_plan(6, 1, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_parse_color() -> Result<(), Box<dyn Error>> {
+        assert_eq!(parse_color("(#70c710)")?, (Dir4::Right, 461937));
+        assert_eq!(parse_color("(#0dc571)")?, (Dir4::Down, 56407));
+        assert_eq!(parse_color("(#5713f0)")?, (Dir4::Right, 356671));
+        assert_eq!(parse_color("(#d2c081)")?, (Dir4::Down, 863240));
+        assert_eq!(parse_color("(#59c680)")?, (Dir4::Right, 367720));
+        assert_eq!(parse_color("(#411b91)")?, (Dir4::Down, 266681));
+        assert_eq!(parse_color("(#8ceee2)")?, (Dir4::Left, 577262));
+        assert_eq!(parse_color("(#caa173)")?, (Dir4::Up, 829975));
+        assert_eq!(parse_color("(#1b58a2)")?, (Dir4::Left, 112010));
+        assert_eq!(parse_color("(#caa171)")?, (Dir4::Down, 829975));
+        assert_eq!(parse_color("(#7807d2)")?, (Dir4::Left, 491645));
+        assert_eq!(parse_color("(#a77fa3)")?, (Dir4::Up, 686074));
+        assert_eq!(parse_color("(#015232)")?, (Dir4::Left, 5411));
+        assert_eq!(parse_color("(#7a21e3)")?, (Dir4::Up, 500254));
+        Ok(())
+    }
+
+    #[test]
+    fn test_auto() -> Result<(), Box<dyn Error>> {
+        assert_eq!(Auto.parse(&example1())?, Color.parse(&example1())?);
+
+        // an invalid color on one line switches the whole plan to the plain format
+        let mut lines = example1();
+        lines[0] = "R 6 (#70c714)".to_string();
+        assert_eq!(Auto.parse(&lines)?, Plain.parse(&lines)?);
+
+        // so does a plan without color
+        let lines = vec!["R 2".to_string(), "D 2".to_string(), "L 2".to_string()];
+        assert_eq!(Auto.parse(&lines)?.len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use rand::Rng;
+use std::{error::Error, iter::once};
+
+use crate::{
+    accepted_regions, apply_workflows, possibilities, possibilities_ranges, Category, Comparison,
+    Condition, Part, PartRanges, Range, Workflow, MAX_RANGE, MIN_RANGE,
+};
+
+// maximum number of draws when looking for a rejected part before giving up
+const MAX_ATTEMPTS: usize = 10_000;
+
+// picks the index of the item containing the value in the cumulative sums of the weights
+fn inverse_transform(cumulative: &[i64], value: i64) -> usize {
+    cumulative.partition_point(|&sum| sum <= value)
+}
+
+fn cumulative_sums(weights: impl Iterator<Item = i64>) -> Vec<i64> {
+    weights
+        .scan(0, |sum, weight| {
+            *sum += weight;
+            Some(*sum)
+        })
+        .collect()
+}
+
+fn sample_ranges(ranges: &[Range], rng: &mut impl Rng) -> Result<i64, Box<dyn Error>> {
+    let cumulative = cumulative_sums(ranges.iter().map(possibilities));
+    let total = *cumulative.last().ok_or("empty ranges")?;
+
+    let value = rng.gen_range(0..total);
+    let index = inverse_transform(&cumulative, value);
+    let range = ranges.get(index).ok_or("invalid range index")?;
+    let before = index
+        .checked_sub(1)
+        .and_then(|i| cumulative.get(i))
+        .unwrap_or(&0);
+
+    Ok(range.min + value - before)
+}
+
+fn sample_region(region: &PartRanges, rng: &mut impl Rng) -> Result<Part, Box<dyn Error>> {
+    Ok(Part {
+        x: sample_ranges(&region.x, rng)?,
+        m: sample_ranges(&region.m, rng)?,
+        a: sample_ranges(&region.a, rng)?,
+        s: sample_ranges(&region.s, rng)?,
+    })
+}
+
+// draws parts uniformly among all the accepted parts: a region is first chosen with a
+// probability proportional to its volume, then a part is drawn uniformly inside it
+pub fn sample_accepted(regions: &[PartRanges], rng: &mut impl Rng) -> Result<Part, Box<dyn Error>> {
+    let cumulative = cumulative_sums(regions.iter().map(possibilities_ranges));
+    let total = *cumulative.last().ok_or("no accepted part")?;
+
+    let index = inverse_transform(&cumulative, rng.gen_range(0..total));
+    sample_region(regions.get(index).ok_or("invalid region index")?, rng)
+}
+
+fn sample_rejected(workflows: &Vec<Workflow>, rng: &mut impl Rng) -> Result<Part, Box<dyn Error>> {
+    let range = MIN_RANGE..=MAX_RANGE;
+    for _ in 0..MAX_ATTEMPTS {
+        let part = Part {
+            x: rng.gen_range(range.clone()),
+            m: rng.gen_range(range.clone()),
+            a: rng.gen_range(range.clone()),
+            s: rng.gen_range(range.clone()),
+        };
+        if !apply_workflows(&part, workflows)? {
+            return Ok(part);
+        }
+    }
+
+    Err("unable to find a rejected part".into())
+}
+
+// generates parts among which the given rate is accepted by the workflows
+pub fn generate_parts(
+    workflows: &Vec<Workflow>,
+    count: usize,
+    rate: f64,
+    rng: &mut impl Rng,
+) -> Result<Vec<Part>, Box<dyn Error>> {
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(format!("invalid acceptance rate: {}", rate).into());
+    }
+
+    let regions = accepted_regions(workflows)?;
+
+    (0..count)
+        .map(|_| {
+            if rng.gen_bool(rate) {
+                sample_accepted(&regions, rng)
+            } else {
+                sample_rejected(workflows, rng)
+            }
+        })
+        .collect()
+}
+
+// a random set of workflows starting with "in". A workflow only sends parts to the ones after it,
+// so there is no cycle. The values include the bounds of the ranges, which gives conditions that
+// never or always hold.
+pub fn generate_workflows(
+    count: usize,
+    rng: &mut impl Rng,
+) -> Result<Vec<Workflow>, Box<dyn Error>> {
+    if count == 0 {
+        return Err("at least one workflow is needed".into());
+    }
+
+    let names = once("in".to_string())
+        .chain((1..count).map(|i| format!("w{}", i)))
+        .collect::<Vec<_>>();
+    let categories = [Category::X, Category::M, Category::A, Category::S];
+
+    Ok(names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let targets = ["A".to_string(), "R".to_string()]
+                .into_iter()
+                .chain(names[i + 1..].iter().cloned())
+                .collect::<Vec<_>>();
+            let conditions = (0..rng.gen_range(0..=3))
+                .map(|_| {
+                    let condition = Condition {
+                        category: categories[rng.gen_range(0..categories.len())],
+                        comparison: if rng.gen_bool(0.5) {
+                            Comparison::LessThan
+                        } else {
+                            Comparison::GreaterThan
+                        },
+                        value: rng.gen_range(MIN_RANGE..=MAX_RANGE),
+                    };
+                    (condition, targets[rng.gen_range(0..targets.len())].clone())
+                })
+                .collect();
+
+            Workflow {
+                name: name.clone(),
+                conditions,
+                fallback: targets[rng.gen_range(0..targets.len())].clone(),
+            }
+        })
+        .collect())
+}
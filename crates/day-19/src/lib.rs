@@ -0,0 +1,1825 @@
+use itertools::{Itertools, Position};
+use lib::parse::{Cursor, ParseError};
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::{self, Display},
+    iter::once,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+// the four ratings are required in json as well, as in {"x":1,"m":2,"a":3,"s":4}
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Part {
+    pub x: i64,
+    pub m: i64,
+    pub a: i64,
+    pub s: i64,
+}
+
+impl FromStr for Part {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        cursor.tag("{")?;
+        let ratings = cursor.until("}")?;
+        cursor.end()?;
+
+        Ok(ratings.split(",").try_fold(
+            Part::default(),
+            |part, rating| -> Result<_, ParseError> {
+                let (mut category, value) = rating.split_once("=")?;
+                let category = next_char::<Category>(&mut category)?;
+                let value = value.value::<i64>()?;
+                match category {
+                    Category::X => Ok(Part { x: value, ..part }),
+                    Category::M => Ok(Part { m: value, ..part }),
+                    Category::A => Ok(Part { a: value, ..part }),
+                    Category::S => Ok(Part { s: value, ..part }),
+                }
+            },
+        )?)
+    }
+}
+
+impl Index<Category> for Part {
+    type Output = i64;
+
+    fn index(&self, category: Category) -> &Self::Output {
+        match category {
+            Category::X => &self.x,
+            Category::M => &self.m,
+            Category::A => &self.a,
+            Category::S => &self.s,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Category {
+    X,
+    M,
+    A,
+    S,
+}
+
+impl TryFrom<char> for Category {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: char) -> Result<Self, Box<dyn Error>> {
+        match value {
+            'x' => Ok(Category::X),
+            'm' => Ok(Category::M),
+            'a' => Ok(Category::A),
+            's' => Ok(Category::S),
+            _ => Err("invalid category".into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Comparison {
+    LessThan,
+    GreaterThan,
+}
+
+impl TryFrom<char> for Comparison {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: char) -> Result<Self, Box<dyn Error>> {
+        match value {
+            '<' => Ok(Comparison::LessThan),
+            '>' => Ok(Comparison::GreaterThan),
+            _ => Err("invalid comparison".into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Condition {
+    pub category: Category,
+    pub comparison: Comparison,
+    pub value: i64,
+}
+
+// the next char of the cursor as a category or a comparison, the error located at it
+fn next_char<T: TryFrom<char, Error = Box<dyn Error>>>(
+    cursor: &mut Cursor,
+) -> Result<T, ParseError> {
+    let at = *cursor;
+    T::try_from(cursor.char()?).map_err(|e| at.error(e.to_string()))
+}
+
+impl Condition {
+    fn from_cursor(cursor: Cursor) -> Result<Self, ParseError> {
+        let mut cursor = cursor;
+        let category = next_char::<Category>(&mut cursor)?;
+        let comparison = next_char::<Comparison>(&mut cursor)?;
+
+        Ok(Condition {
+            category,
+            comparison,
+            value: cursor.value::<i64>()?,
+        })
+    }
+}
+
+impl FromStr for Condition {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Condition::from_cursor(Cursor::new(s))?)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Workflow {
+    pub name: String,
+    pub conditions: Vec<(Condition, String)>,
+    pub fallback: String,
+}
+
+impl FromStr for Workflow {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        let name = cursor.until("{")?;
+        if name.is_empty() {
+            return Err(name.error("missing name").into());
+        }
+        let rules = cursor.until("}")?;
+        cursor.end()?;
+
+        let mut rules_iter = rules.split(",").with_position();
+        let conditions = rules_iter
+            .take_while_ref(|(position, _)| {
+                *position != Position::Last && *position != Position::Only
+            })
+            .map(|(_, rule)| {
+                let (condition, name) = rule.split_once(":")?;
+                if name.is_empty() {
+                    return Err(name.error("missing name"));
+                }
+
+                Ok((Condition::from_cursor(condition)?, name.rest().to_string()))
+            })
+            .collect::<Result<Vec<(Condition, String)>, ParseError>>()?;
+
+        let fallback = rules_iter.next().ok_or("missing fallback")?.1;
+        // a last rule with a condition means the fallback is missing
+        if fallback.is_empty() || fallback.rest().contains(':') {
+            return Err(fallback.error("missing fallback").into());
+        }
+
+        Ok(Workflow {
+            name: name.rest().to_string(),
+            conditions,
+            fallback: fallback.rest().to_string(),
+        })
+    }
+}
+
+// the same format as the input
+impl Display for Workflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = self
+            .conditions
+            .iter()
+            .map(|(condition, next)| format!("{}:{}", condition_label(condition), next))
+            .chain(once(self.fallback.clone()))
+            .join(",");
+        write!(f, "{}{{{}}}", self.name, rules)
+    }
+}
+
+pub fn condition_label(condition: &Condition) -> String {
+    let category = match condition.category {
+        Category::X => 'x',
+        Category::M => 'm',
+        Category::A => 'a',
+        Category::S => 's',
+    };
+    let comparison = match condition.comparison {
+        Comparison::LessThan => '<',
+        Comparison::GreaterThan => '>',
+    };
+
+    format!("{}{}{}", category, comparison, condition.value)
+}
+
+pub fn parse(
+    itr: impl Iterator<Item = String>,
+) -> Result<(Vec<Workflow>, Vec<Part>), Box<dyn Error>> {
+    let mut itr = itr;
+    let workflows = itr
+        .by_ref()
+        .take_while(|s| !s.is_empty())
+        .map(|s| s.parse::<Workflow>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let parts = itr
+        .map(|s| s.parse::<Part>())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((workflows, parts))
+}
+
+// the parts as a json array of objects, for the parts generated by other programs
+pub fn parse_parts_json(s: &str) -> Result<Vec<Part>, Box<dyn Error>> {
+    Ok(serde_json::from_str(s)?)
+}
+
+pub mod genfuzz;
+
+const MIN_RANGE: i64 = 1;
+const MAX_RANGE: i64 = 4000;
+
+// min and max are included in the range
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Range {
+    min: i64,
+    max: i64,
+}
+
+impl Default for Range {
+    fn default() -> Self {
+        Range {
+            min: MIN_RANGE,
+            max: MAX_RANGE,
+        }
+    }
+}
+
+fn possibilities(range: &Range) -> i64 {
+    range.max - range.min + 1
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartRanges {
+    x: Vec<Range>,
+    m: Vec<Range>,
+    a: Vec<Range>,
+    s: Vec<Range>,
+}
+
+impl Default for PartRanges {
+    fn default() -> Self {
+        PartRanges {
+            x: vec![Default::default()],
+            m: vec![Default::default()],
+            a: vec![Default::default()],
+            s: vec![Default::default()],
+        }
+    }
+}
+
+pub fn possibilities_ranges(ranges: &PartRanges) -> i64 {
+    [&ranges.x, &ranges.m, &ranges.a, &ranges.s]
+        .iter()
+        .map(|ranges| ranges.iter().map(possibilities).sum::<i64>())
+        .product::<i64>()
+}
+
+impl Index<Category> for PartRanges {
+    type Output = Vec<Range>;
+
+    fn index(&self, category: Category) -> &Self::Output {
+        match category {
+            Category::X => &self.x,
+            Category::M => &self.m,
+            Category::A => &self.a,
+            Category::S => &self.s,
+        }
+    }
+}
+
+impl IndexMut<Category> for PartRanges {
+    fn index_mut(&mut self, category: Category) -> &mut Self::Output {
+        match category {
+            Category::X => &mut self.x,
+            Category::M => &mut self.m,
+            Category::A => &mut self.a,
+            Category::S => &mut self.s,
+        }
+    }
+}
+
+fn to_range(condition: &Condition) -> Range {
+    match condition.comparison {
+        Comparison::LessThan => Range {
+            min: MIN_RANGE,
+            max: condition.value.saturating_sub(1),
+        },
+        Comparison::GreaterThan => Range {
+            min: condition.value.saturating_add(1),
+            max: MAX_RANGE,
+        },
+    }
+}
+
+// the bounds of the walks through the workflows, which would never end on workflows sending the
+// parts around a cycle, as generated inputs may. The real inputs stay far below them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    // the workflows a part, or a box of parts, goes through before reaching A or R
+    max_hops: usize,
+    // the boxes of parts waiting for their next workflow in part 2
+    max_stack: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_hops: 10_000,
+            max_stack: 1_000_000,
+        }
+    }
+}
+
+pub fn parse_limits(args: &[String]) -> Result<Limits, Box<dyn Error>> {
+    let mut limits = Limits::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-hops" => limits.max_hops = args.next().ok_or("missing max hops")?.parse()?,
+            "--max-stack" => limits.max_stack = args.next().ok_or("missing max stack")?.parse()?,
+            _ => return Err(format!("unknown option: {}", arg).into()),
+        }
+    }
+
+    Ok(limits)
+}
+
+// a walk through the workflows stopped by the limits, with the part or the box of parts it was
+// about and the workflow it was going to
+#[derive(Debug, PartialEq, Eq)]
+enum LimitError {
+    PartHops {
+        part: String,
+        workflow: String,
+        hops: usize,
+    },
+    RegionHops {
+        region: String,
+        workflow: String,
+        hops: usize,
+    },
+    Stack {
+        region: String,
+        workflow: String,
+        size: usize,
+    },
+}
+
+impl Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::PartHops {
+                part,
+                workflow,
+                hops,
+            } => write!(
+                f,
+                "part {} still going to {} after {} workflows, they may loop",
+                part, workflow, hops
+            ),
+            LimitError::RegionHops {
+                region,
+                workflow,
+                hops,
+            } => write!(
+                f,
+                "parts {} still going to {} after {} workflows, they may loop",
+                region, workflow, hops
+            ),
+            LimitError::Stack {
+                region,
+                workflow,
+                size,
+            } => write!(
+                f,
+                "{} boxes of parts waiting, the last one {} going to {}",
+                size, region, workflow
+            ),
+        }
+    }
+}
+
+impl Error for LimitError {}
+
+fn part_label(part: &Part) -> String {
+    format!("{{x={},m={},a={},s={}}}", part.x, part.m, part.a, part.s)
+}
+
+fn apply_a_workflow1(part: &Part, workflow: &Workflow) -> String {
+    workflow
+        .conditions
+        .iter()
+        .find(|(condition, _)| match condition.comparison {
+            Comparison::LessThan => part[condition.category] < condition.value,
+            Comparison::GreaterThan => part[condition.category] > condition.value,
+        })
+        .map(|(_, name)| name)
+        .unwrap_or(&workflow.fallback)
+        .clone()
+}
+
+fn apply_workflows_limited(
+    part: &Part,
+    workflows: &Vec<Workflow>,
+    limits: &Limits,
+) -> Result<bool, Box<dyn Error>> {
+    let mut stack: Vec<String> = Vec::new();
+    stack.push("in".to_string());
+
+    let name_to_workflow = workflow_get_map(workflows);
+    let mut hops = 0;
+
+    while let Some(name) = stack.pop() {
+        match name.as_str() {
+            "R" => return Ok(false),
+            "A" => return Ok(true),
+            _ if hops == limits.max_hops => {
+                return Err(LimitError::PartHops {
+                    part: part_label(part),
+                    workflow: name,
+                    hops,
+                }
+                .into())
+            }
+            _ => {
+                hops += 1;
+                let workflow = name_to_workflow.get(&name).ok_or("missing workflow")?;
+                let next_workflow = apply_a_workflow1(&part, workflow);
+                stack.push(next_workflow);
+            }
+        }
+    }
+
+    Err("no workflow found".into())
+}
+
+pub fn apply_workflows(part: &Part, workflows: &Vec<Workflow>) -> Result<bool, Box<dyn Error>> {
+    apply_workflows_limited(part, workflows, &Limits::default())
+}
+
+fn apply_a_workflow2(workflow: &Workflow) -> Vec<(String, PartRanges)> {
+    // while we walk through the conditions, this variable stores the ranges that correspond to
+    // the negated conditions
+    let mut invalid_ranges: PartRanges = Default::default();
+
+    let mut results = workflow
+        .conditions
+        .iter()
+        .map(|(condition, next_workflow)| {
+            let range = to_range(&condition);
+
+            let ranges = intersect_ranges_range(&invalid_ranges[condition.category], &range);
+
+            let part_ranges = match condition.category {
+                Category::X => PartRanges {
+                    x: ranges,
+                    ..invalid_ranges.clone()
+                },
+                Category::M => PartRanges {
+                    m: ranges,
+                    ..invalid_ranges.clone()
+                },
+                Category::A => PartRanges {
+                    a: ranges,
+                    ..invalid_ranges.clone()
+                },
+                Category::S => PartRanges {
+                    s: ranges,
+                    ..invalid_ranges.clone()
+                },
+            };
+
+            invalid_ranges[condition.category] =
+                intersect_ranges_ranges(&invalid_ranges[condition.category], &opposite(&range));
+
+            (next_workflow.clone(), part_ranges)
+        })
+        .collect::<Vec<(String, PartRanges)>>();
+
+    results.push((workflow.fallback.clone(), invalid_ranges));
+
+    results
+}
+
+fn range_valid(range: &Range) -> bool {
+    range.min <= range.max
+}
+
+fn opposite(range: &Range) -> Vec<Range> {
+    vec![
+        Range {
+            min: MIN_RANGE,
+            max: range.min - 1,
+        },
+        Range {
+            min: range.max + 1,
+            max: MAX_RANGE,
+        },
+    ]
+    .into_iter()
+    .filter(range_valid)
+    .collect()
+}
+
+fn intersect_part_ranges(ranges1: &PartRanges, ranges2: &PartRanges) -> PartRanges {
+    PartRanges {
+        x: intersect_ranges_ranges(&ranges1.x, &ranges2.x),
+        m: intersect_ranges_ranges(&ranges1.m, &ranges2.m),
+        a: intersect_ranges_ranges(&ranges1.a, &ranges2.a),
+        s: intersect_ranges_ranges(&ranges1.s, &ranges2.s),
+    }
+}
+
+fn intersect_ranges_ranges(ranges1: &Vec<Range>, ranges2: &Vec<Range>) -> Vec<Range> {
+    ranges1
+        .iter()
+        .flat_map(|range1| intersect_ranges_range(ranges2, range1))
+        .collect()
+}
+
+fn intersect_ranges_range(ranges: &Vec<Range>, range: &Range) -> Vec<Range> {
+    ranges
+        .iter()
+        .filter_map(|range_| intersect_range_range(range_, range))
+        .collect()
+}
+
+fn intersect_range_range(range1: &Range, range2: &Range) -> Option<Range> {
+    let range = Range {
+        min: range1.min.max(range2.min),
+        max: range1.max.min(range2.max),
+    };
+    range_valid(&range).then_some(range)
+}
+
+fn workflow_get_map(workflows: &Vec<Workflow>) -> HashMap<String, &Workflow> {
+    HashMap::from_iter(
+        workflows
+            .iter()
+            .map(|workflow| (workflow.name.clone(), workflow)),
+    )
+}
+
+pub fn solve1_limited(
+    workflows: &Vec<Workflow>,
+    parts: &Vec<Part>,
+    limits: &Limits,
+) -> Result<i64, Box<dyn Error>> {
+    parts
+        .iter()
+        .filter_map(|part| {
+            let accepted = apply_workflows_limited(part, workflows, limits);
+            match accepted {
+                Err(e) => Some(Err(e)),
+                Ok(false) => None,
+                Ok(true) => Some(Ok(part.x + part.m + part.a + part.s)),
+            }
+        })
+        .sum()
+}
+
+pub fn solve1(workflows: &Vec<Workflow>, parts: &Vec<Part>) -> Result<i64, Box<dyn Error>> {
+    solve1_limited(workflows, parts, &Limits::default())
+}
+
+// the disjoint boxes of parts that reach A
+pub fn accepted_regions_limited(
+    workflows: &Vec<Workflow>,
+    limits: &Limits,
+) -> Result<Vec<PartRanges>, Box<dyn Error>> {
+    regions_reaching_limited(workflows, "A", limits)
+}
+
+pub fn accepted_regions(workflows: &Vec<Workflow>) -> Result<Vec<PartRanges>, Box<dyn Error>> {
+    accepted_regions_limited(workflows, &Limits::default())
+}
+
+// the disjoint boxes of parts that reach the end, A or R
+fn regions_reaching_limited(
+    workflows: &Vec<Workflow>,
+    end: &str,
+    limits: &Limits,
+) -> Result<Vec<PartRanges>, Box<dyn Error>> {
+    // the boxes come with the number of workflows they went through
+    let mut stack: Vec<(String, PartRanges, usize)> = Vec::new();
+    stack.push(("in".to_string(), Default::default(), 0));
+
+    let name_to_workflow = workflow_get_map(workflows);
+
+    let mut regions = Vec::new();
+
+    while let Some((name, ranges, hops)) = stack.pop() {
+        match name.as_str() {
+            _ if name == end => regions.push(ranges),
+            "A" | "R" => continue,
+            _ if hops == limits.max_hops => {
+                return Err(LimitError::RegionHops {
+                    region: region_label(&ranges),
+                    workflow: name,
+                    hops,
+                }
+                .into())
+            }
+            _ => {
+                let workflow = name_to_workflow.get(&name).ok_or("missing workflow")?;
+                let workflows_and_ranges = apply_a_workflow2(workflow);
+
+                for (next_workflow, next_ranges) in workflows_and_ranges {
+                    let new_ranges = intersect_part_ranges(&ranges, &next_ranges);
+
+                    if possibilities_ranges(&new_ranges) != 0 {
+                        if stack.len() == limits.max_stack {
+                            return Err(LimitError::Stack {
+                                region: region_label(&new_ranges),
+                                workflow: next_workflow,
+                                size: stack.len(),
+                            }
+                            .into());
+                        }
+                        stack.push((next_workflow, new_ranges, hops + 1));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(regions)
+}
+
+fn regions_reaching(
+    workflows: &Vec<Workflow>,
+    end: &str,
+) -> Result<Vec<PartRanges>, Box<dyn Error>> {
+    regions_reaching_limited(workflows, end, &Limits::default())
+}
+
+fn contains(region: &PartRanges, part: &Part) -> bool {
+    [Category::X, Category::M, Category::A, Category::S]
+        .iter()
+        .all(|&category| {
+            region[category]
+                .iter()
+                .any(|range| range.min <= part[category] && part[category] <= range.max)
+        })
+}
+
+// same as solve1 but a part is accepted if it lies in one of the accepted regions
+pub fn solve1_regions_limited(
+    workflows: &Vec<Workflow>,
+    parts: &[Part],
+    limits: &Limits,
+) -> Result<i64, Box<dyn Error>> {
+    let regions = accepted_regions_limited(workflows, limits)?;
+
+    Ok(parts
+        .iter()
+        .filter(|part| regions.iter().any(|region| contains(region, part)))
+        .map(|part| part.x + part.m + part.a + part.s)
+        .sum())
+}
+
+pub fn solve1_regions(workflows: &Vec<Workflow>, parts: &[Part]) -> Result<i64, Box<dyn Error>> {
+    solve1_regions_limited(workflows, parts, &Limits::default())
+}
+
+pub fn solve2_limited(workflows: &Vec<Workflow>, limits: &Limits) -> Result<i64, Box<dyn Error>> {
+    Ok(accepted_regions_limited(workflows, limits)?
+        .iter()
+        .map(possibilities_ranges)
+        .sum())
+}
+
+pub fn solve2(workflows: &Vec<Workflow>) -> Result<i64, Box<dyn Error>> {
+    solve2_limited(workflows, &Limits::default())
+}
+
+pub fn region_label(region: &PartRanges) -> String {
+    [
+        ('x', &region.x),
+        ('m', &region.m),
+        ('a', &region.a),
+        ('s', &region.s),
+    ]
+    .iter()
+    .map(|(name, ranges)| {
+        let ranges = ranges
+            .iter()
+            .map(|range| format!("{}..{}", range.min, range.max))
+            .join(",");
+        format!("{}=[{}]", name, ranges)
+    })
+    .join(" ")
+}
+
+// which of the two sets of workflows accepts the parts of a disagreement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    First,
+    Second,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Disagreement {
+    pub accepted_by: Side,
+    pub region: PartRanges,
+    // the part with the smallest ratings of the region
+    pub witness: Part,
+}
+
+// the boxes of parts accepted by one set of workflows and rejected by the other one. Every part
+// ends in A or R, so the parts accepted by the first set only are the intersections of its
+// accepted regions with the rejected regions of the second one, and the other way around
+pub fn diff(
+    first: &Vec<Workflow>,
+    second: &Vec<Workflow>,
+) -> Result<Vec<Disagreement>, Box<dyn Error>> {
+    let sides = [(Side::First, first, second), (Side::Second, second, first)];
+
+    let mut disagreements = Vec::new();
+    for (side, accepting, rejecting) in sides {
+        let rejected = regions_reaching(rejecting, "R")?;
+
+        for accepted in accepted_regions(accepting)? {
+            for region in rejected
+                .iter()
+                .map(|rejected| intersect_part_ranges(&accepted, rejected))
+                .filter(|region| possibilities_ranges(region) != 0)
+            {
+                let witness = witness(&region).ok_or("Empty region")?;
+                disagreements.push(Disagreement {
+                    accepted_by: side,
+                    region,
+                    witness,
+                });
+            }
+        }
+    }
+
+    Ok(disagreements)
+}
+
+fn witness(region: &PartRanges) -> Option<Part> {
+    let min = |ranges: &Vec<Range>| ranges.iter().map(|range| range.min).min();
+
+    Some(Part {
+        x: min(&region.x)?,
+        m: min(&region.m)?,
+        a: min(&region.a)?,
+        s: min(&region.s)?,
+    })
+}
+
+// the number of workflows and of rules, the fallbacks being rules too
+#[derive(Debug, PartialEq, Eq)]
+pub struct WorkflowStats {
+    pub workflows: usize,
+    pub rules: usize,
+}
+
+pub fn workflow_stats(workflows: &[Workflow]) -> WorkflowStats {
+    WorkflowStats {
+        workflows: workflows.len(),
+        rules: workflows
+            .iter()
+            .map(|workflow| workflow.conditions.len() + 1)
+            .sum(),
+    }
+}
+
+// the same workflows accepting the same parts with fewer rules: the rules no part can reach are
+// removed, as well as the last rules going to the fallback. The workflows left with the fallback
+// only are inlined where they are used, and the workflows unreachable from "in" are pruned. Each
+// change can make another one possible, so this goes on until nothing changes
+pub fn simplify(workflows: &[Workflow]) -> Vec<Workflow> {
+    let mut workflows = workflows.to_vec();
+
+    loop {
+        let before = workflows.clone();
+
+        for workflow in workflows.iter_mut() {
+            let matched = apply_a_workflow2(workflow);
+            let mut conditions = workflow
+                .conditions
+                .iter()
+                .zip(&matched)
+                .filter(|(_, (_, ranges))| possibilities_ranges(ranges) != 0)
+                .map(|(condition, _)| condition.clone())
+                .collect::<Vec<_>>();
+            while conditions
+                .last()
+                .is_some_and(|(_, next)| *next == workflow.fallback)
+            {
+                conditions.pop();
+            }
+            workflow.conditions = conditions;
+        }
+
+        // "in" is where the parts start, it stays even when it always goes to the same place
+        let inlined = workflows
+            .iter()
+            .filter(|workflow| {
+                workflow.conditions.is_empty()
+                    && workflow.name != "in"
+                    && workflow.name != workflow.fallback
+            })
+            .map(|workflow| (workflow.name.clone(), workflow.fallback.clone()))
+            .collect::<HashMap<_, _>>();
+        let replace = |next: &mut String| {
+            if let Some(target) = inlined.get(next) {
+                *next = target.clone();
+            }
+        };
+        for workflow in workflows.iter_mut() {
+            workflow
+                .conditions
+                .iter_mut()
+                .for_each(|(_, next)| replace(next));
+            replace(&mut workflow.fallback);
+        }
+
+        workflows = reachable(workflows);
+
+        if workflows == before {
+            return workflows;
+        }
+    }
+}
+
+// the workflows that can be reached from "in", in their original order
+fn reachable(workflows: Vec<Workflow>) -> Vec<Workflow> {
+    let name_to_workflow = workflow_get_map(&workflows);
+
+    let mut names = HashSet::from(["in".to_string()]);
+    let mut stack = vec!["in".to_string()];
+    while let Some(name) = stack.pop() {
+        let nexts = name_to_workflow
+            .get(&name)
+            .into_iter()
+            .flat_map(|workflow| {
+                workflow
+                    .conditions
+                    .iter()
+                    .map(|(_, next)| next)
+                    .chain(once(&workflow.fallback))
+            });
+        for next in nexts {
+            if names.insert(next.clone()) {
+                stack.push(next.clone());
+            }
+        }
+    }
+
+    workflows
+        .iter()
+        .filter(|workflow| names.contains(&workflow.name))
+        .cloned()
+        .collect()
+}
+
+// the workflows as a graphviz graph, the conditions label the edges and the fallbacks are
+// unlabeled
+pub fn to_dot(workflows: &[Workflow]) -> String {
+    let edges = workflows.iter().flat_map(|workflow| {
+        workflow
+            .conditions
+            .iter()
+            .map(|(condition, next)| {
+                format!(
+                    "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                    workflow.name,
+                    next,
+                    condition_label(condition)
+                )
+            })
+            .chain(once(format!(
+                "    \"{}\" -> \"{}\";\n",
+                workflow.name, workflow.fallback
+            )))
+    });
+
+    once("digraph workflows {\n".to_string())
+        .chain(once(
+            "    \"A\" [shape=box, style=filled, fillcolor=green];\n".to_string(),
+        ))
+        .chain(once(
+            "    \"R\" [shape=box, style=filled, fillcolor=red];\n".to_string(),
+        ))
+        .chain(edges)
+        .chain(once("}\n".to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod day19 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+    use lib::alloc::{count_allocations, CountingAllocator};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        accepted_regions, apply_workflows, contains, diff, genfuzz, intersect_part_ranges, parse,
+        parse_limits, parse_parts_json, possibilities_ranges, reachable, simplify, solve1,
+        solve1_limited, solve1_regions, solve2, solve2_limited, to_dot, workflow_stats, Category,
+        Comparison, Condition, LimitError, Limits, Part, PartRanges, Range, Side, Workflow,
+        WorkflowStats, MAX_RANGE, MIN_RANGE,
+    };
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    const WORKFLOW: &str = "\
+        px{a<2006:qkq,m>2090:A,rfg}\n\
+        pv{a>1716:R,A}\n\
+        lnx{m>1548:A,A}\n\
+        rfg{s<537:gd,x>2440:R,A}\n\
+        qs{s>3448:A,lnx}\n\
+        qkq{x<1416:A,crn}\n\
+        crn{x>2662:A,R}\n\
+        in{s<1351:px,qqz}\n\
+        qqz{s>2770:qs,m<1801:hdj,R}\n\
+        gd{a>3333:R,R}\n\
+        hdj{m>838:A,pv}";
+
+    fn workflows() -> Vec<Workflow> {
+        vec![
+            Workflow {
+                name: "px".to_string(),
+                conditions: vec![
+                    (
+                        Condition {
+                            category: Category::A,
+                            comparison: Comparison::LessThan,
+                            value: 2006,
+                        },
+                        "qkq".to_string(),
+                    ),
+                    (
+                        Condition {
+                            category: Category::M,
+                            comparison: Comparison::GreaterThan,
+                            value: 2090,
+                        },
+                        "A".to_string(),
+                    ),
+                ],
+                fallback: "rfg".to_string(),
+            },
+            Workflow {
+                name: "pv".to_string(),
+                conditions: vec![(
+                    Condition {
+                        category: Category::A,
+                        comparison: Comparison::GreaterThan,
+                        value: 1716,
+                    },
+                    "R".to_string(),
+                )],
+                fallback: "A".to_string(),
+            },
+            Workflow {
+                name: "lnx".to_string(),
+                conditions: vec![(
+                    Condition {
+                        category: Category::M,
+                        comparison: Comparison::GreaterThan,
+                        value: 1548,
+                    },
+                    "A".to_string(),
+                )],
+                fallback: "A".to_string(),
+            },
+            Workflow {
+                name: "rfg".to_string(),
+                conditions: vec![
+                    (
+                        Condition {
+                            category: Category::S,
+                            comparison: Comparison::LessThan,
+                            value: 537,
+                        },
+                        "gd".to_string(),
+                    ),
+                    (
+                        Condition {
+                            category: Category::X,
+                            comparison: Comparison::GreaterThan,
+                            value: 2440,
+                        },
+                        "R".to_string(),
+                    ),
+                ],
+                fallback: "A".to_string(),
+            },
+            Workflow {
+                name: "qs".to_string(),
+                conditions: vec![(
+                    Condition {
+                        category: Category::S,
+                        comparison: Comparison::GreaterThan,
+                        value: 3448,
+                    },
+                    "A".to_string(),
+                )],
+                fallback: "lnx".to_string(),
+            },
+            Workflow {
+                name: "qkq".to_string(),
+                conditions: vec![(
+                    Condition {
+                        category: Category::X,
+                        comparison: Comparison::LessThan,
+                        value: 1416,
+                    },
+                    "A".to_string(),
+                )],
+                fallback: "crn".to_string(),
+            },
+            Workflow {
+                name: "crn".to_string(),
+                conditions: vec![(
+                    Condition {
+                        category: Category::X,
+                        comparison: Comparison::GreaterThan,
+                        value: 2662,
+                    },
+                    "A".to_string(),
+                )],
+                fallback: "R".to_string(),
+            },
+            Workflow {
+                name: "in".to_string(),
+                conditions: vec![(
+                    Condition {
+                        category: Category::S,
+                        comparison: Comparison::LessThan,
+                        value: 1351,
+                    },
+                    "px".to_string(),
+                )],
+                fallback: "qqz".to_string(),
+            },
+            Workflow {
+                name: "qqz".to_string(),
+                conditions: vec![
+                    (
+                        Condition {
+                            category: Category::S,
+                            comparison: Comparison::GreaterThan,
+                            value: 2770,
+                        },
+                        "qs".to_string(),
+                    ),
+                    (
+                        Condition {
+                            category: Category::M,
+                            comparison: Comparison::LessThan,
+                            value: 1801,
+                        },
+                        "hdj".to_string(),
+                    ),
+                ],
+                fallback: "R".to_string(),
+            },
+            Workflow {
+                name: "gd".to_string(),
+                conditions: vec![(
+                    Condition {
+                        category: Category::A,
+                        comparison: Comparison::GreaterThan,
+                        value: 3333,
+                    },
+                    "R".to_string(),
+                )],
+                fallback: "R".to_string(),
+            },
+            Workflow {
+                name: "hdj".to_string(),
+                conditions: vec![(
+                    Condition {
+                        category: Category::M,
+                        comparison: Comparison::GreaterThan,
+                        value: 838,
+                    },
+                    "A".to_string(),
+                )],
+                fallback: "pv".to_string(),
+            },
+        ]
+    }
+
+    const PARTS: &str = "\
+        {x=787,m=2655,a=1222,s=2876}\n\
+        {x=1679,m=44,a=2067,s=496}\n\
+        {x=2036,m=264,a=79,s=2244}\n\
+        {x=2461,m=1339,a=466,s=291}\n\
+        {x=2127,m=1623,a=2188,s=1013}";
+
+    fn parts() -> Vec<Part> {
+        vec![
+            Part {
+                x: 787,
+                m: 2655,
+                a: 1222,
+                s: 2876,
+            },
+            Part {
+                x: 1679,
+                m: 44,
+                a: 2067,
+                s: 496,
+            },
+            Part {
+                x: 2036,
+                m: 264,
+                a: 79,
+                s: 2244,
+            },
+            Part {
+                x: 2461,
+                m: 1339,
+                a: 466,
+                s: 291,
+            },
+            Part {
+                x: 2127,
+                m: 1623,
+                a: 2188,
+                s: 1013,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parse_workflows() -> Result<(), Box<dyn Error>> {
+        let workflows_ = WORKFLOW
+            .lines()
+            .map(|s| s.parse::<Workflow>())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(workflows_, workflows());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_parts() -> Result<(), Box<dyn Error>> {
+        let parts_ = PARTS
+            .lines()
+            .map(|s| s.parse::<Part>())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(parts_, parts());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_parts_json() -> Result<(), Box<dyn Error>> {
+        let json = parts()
+            .iter()
+            .map(|part| {
+                format!(
+                    "{{\"x\":{},\"m\":{},\"a\":{},\"s\":{}}}",
+                    part.x, part.m, part.a, part.s
+                )
+            })
+            .join(", ");
+        let parsed = parse_parts_json(&format!("[{}]", json))?;
+        assert_eq!(parsed, parts());
+        assert_eq!(solve1(&workflows(), &parsed)?, 19114);
+
+        assert_eq!(parse_parts_json("[]")?, vec![]);
+        for json in [
+            "",
+            "{\"x\":1,\"m\":2,\"a\":3,\"s\":4}",
+            "[{\"x\":1,\"m\":2,\"a\":3}]",
+            "[{\"x\":1,\"m\":2,\"a\":3,\"s\":4,\"y\":5}]",
+            "[{\"x\":1,\"m\":2,\"a\":3,\"s\":\"4\"}]",
+        ] {
+            assert!(parse_parts_json(json).is_err(), "{:?}", json);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        let input = format!("{}\n\n{}", WORKFLOW, PARTS);
+        let (workflows_, parts_) = parse(input.lines().map(|s| s.to_string()))?;
+        assert_eq!(workflows_, workflows());
+        assert_eq!(parts_, parts());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_allocations() -> Result<(), Box<dyn Error>> {
+        let input = format!("{}\n\n{}", WORKFLOW, PARTS);
+        let lines = input.lines().map(|s| s.to_string()).collect::<Vec<_>>();
+        let (parsed, allocations) = count_allocations(|| parse(lines.into_iter()));
+
+        // the names and the rules of the workflows, 66 when the value of each condition was
+        // collected into a string before being parsed
+        assert_eq!(parsed?, (workflows(), parts()));
+        assert_eq!(allocations, 52);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_dot() -> Result<(), Box<dyn Error>> {
+        let workflows = ["in{s<1351:px,R}", "px{a>2006:A,m<10:R,A}"]
+            .iter()
+            .map(|s| s.parse::<Workflow>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(
+            to_dot(&workflows),
+            "\
+            digraph workflows {\n    \
+                \"A\" [shape=box, style=filled, fillcolor=green];\n    \
+                \"R\" [shape=box, style=filled, fillcolor=red];\n    \
+                \"in\" -> \"px\" [label=\"s<1351\"];\n    \
+                \"in\" -> \"R\";\n    \
+                \"px\" -> \"A\" [label=\"a>2006\"];\n    \
+                \"px\" -> \"R\" [label=\"m<10\"];\n    \
+                \"px\" -> \"A\";\n\
+            }\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_dot_example() {
+        let dot = to_dot(&workflows());
+        assert!(dot.contains("    \"in\" -> \"px\" [label=\"s<1351\"];\n"));
+        assert!(dot.contains("    \"in\" -> \"qqz\";\n"));
+        assert!(dot.contains("    \"px\" -> \"qkq\" [label=\"a<2006\"];\n"));
+        // one edge per condition and one per fallback
+        let edges = workflows()
+            .iter()
+            .map(|workflow| workflow.conditions.len() + 1)
+            .sum::<usize>();
+        assert_eq!(dot.matches(" -> ").count(), edges);
+    }
+
+    #[test]
+    fn test_solve1_example() -> Result<(), Box<dyn Error>> {
+        let result = solve1(&workflows(), &parts())?;
+        assert_eq!(result, 19114);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_example() -> Result<(), Box<dyn Error>> {
+        let result = solve2(&workflows())?;
+        assert_eq!(result, 167409079868000);
+        Ok(())
+    }
+
+    // the volume of a box sums the ranges of each category before multiplying the categories, and
+    // the conditions at the bounds of the ratings leave no part on their side
+    #[test]
+    fn test_solve2_split_ranges() -> Result<(), Box<dyn Error>> {
+        let others = 4000 * 4000 * 4000;
+        let split = PartRanges {
+            x: vec![Range { min: 1, max: 10 }, Range { min: 21, max: 30 }],
+            ..PartRanges::default()
+        };
+        assert_eq!(possibilities_ranges(&split), 20 * others);
+
+        let volume = |lines: &[&str]| -> Result<i64, Box<dyn Error>> {
+            let workflows = lines
+                .iter()
+                .map(|line| line.parse())
+                .collect::<Result<Vec<Workflow>, _>>()?;
+            solve2(&workflows)
+        };
+        assert_eq!(volume(&["in{x<1:A,R}"])?, 0);
+        assert_eq!(volume(&["in{x>4000:A,R}"])?, 0);
+        assert_eq!(volume(&["in{x>1:R,A}"])?, others);
+        assert_eq!(volume(&["in{x<4000:R,A}"])?, others);
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits() -> Result<(), Box<dyn Error>> {
+        let looping = ["in{x<10:A,loop}", "loop{in}"]
+            .iter()
+            .map(|line| line.parse())
+            .collect::<Result<Vec<Workflow>, _>>()?;
+        let limits = Limits {
+            max_hops: 3,
+            ..Limits::default()
+        };
+        let limit_error = |error: Box<dyn Error>| error.downcast::<LimitError>().map(|e| *e).ok();
+
+        // the parts below x=10 leave the loop
+        let part = Part {
+            x: 10,
+            m: 2,
+            a: 3,
+            s: 4,
+        };
+        assert_eq!(
+            solve1_limited(&looping, &vec![Part { x: 9, ..part }], &limits)?,
+            9 + 2 + 3 + 4
+        );
+        let error = solve1_limited(&looping, &vec![part], &limits)
+            .err()
+            .ok_or("no error")?;
+        assert_eq!(
+            error.to_string(),
+            "part {x=10,m=2,a=3,s=4} still going to loop after 3 workflows, they may loop"
+        );
+
+        let error = solve2_limited(&looping, &limits)
+            .err()
+            .and_then(limit_error);
+        assert_eq!(
+            error,
+            Some(LimitError::RegionHops {
+                region: "x=[10..4000] m=[1..4000] a=[1..4000] s=[1..4000]".to_string(),
+                workflow: "loop".to_string(),
+                hops: 3
+            })
+        );
+
+        // the example stays within the default limits but not within a stack of one box
+        assert_eq!(solve2(&workflows())?, 167409079868000);
+        let limits = Limits {
+            max_stack: 1,
+            ..Limits::default()
+        };
+        let error = solve2_limited(&workflows(), &limits)
+            .err()
+            .and_then(limit_error);
+        assert!(matches!(error, Some(LimitError::Stack { size: 1, .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_limits() -> Result<(), Box<dyn Error>> {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(parse_limits(&[])?, Limits::default());
+        assert_eq!(
+            parse_limits(&args(&["--max-stack", "5", "--max-hops", "7"]))?,
+            Limits {
+                max_hops: 7,
+                max_stack: 5
+            }
+        );
+        assert!(parse_limits(&args(&["--max-hops"])).is_err());
+        assert!(parse_limits(&args(&["--max-hops", "-1"])).is_err());
+        assert!(parse_limits(&args(&["--depth", "1"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_regions_example() -> Result<(), Box<dyn Error>> {
+        let result = solve1_regions(&workflows(), &parts())?;
+        assert_eq!(result, 19114);
+        Ok(())
+    }
+
+    #[test]
+    fn test_accepted_regions_disjoint() -> Result<(), Box<dyn Error>> {
+        let regions = accepted_regions(&workflows())?;
+        regions.iter().tuple_combinations().for_each(
+            |(region1, region2): (&PartRanges, &PartRanges)| {
+                assert_eq!(
+                    possibilities_ranges(&intersect_part_ranges(region1, region2)),
+                    0
+                )
+            },
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_contains_matches_workflows() -> Result<(), Box<dyn Error>> {
+        let workflows = workflows();
+        let regions = accepted_regions(&workflows)?;
+        let mut rng = StdRng::seed_from_u64(13);
+
+        for part in genfuzz::generate_parts(&workflows, 1000, 0.5, &mut rng)? {
+            let in_regions = regions
+                .iter()
+                .filter(|region| contains(region, &part))
+                .count();
+            let accepted = apply_workflows(&part, &workflows)?;
+            assert_eq!(in_regions, if accepted { 1 } else { 0 });
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sample_accepted_example() -> Result<(), Box<dyn Error>> {
+        let workflows = workflows();
+        let regions = accepted_regions(&workflows)?;
+
+        let mut rng = StdRng::seed_from_u64(19);
+        let count = 20000;
+        let mut hits = vec![0; regions.len()];
+        for _ in 0..count {
+            let part = genfuzz::sample_accepted(&regions, &mut rng)?;
+            assert!(apply_workflows(&part, &workflows)?);
+
+            let index = regions
+                .iter()
+                .position(|region| contains(region, &part))
+                .ok_or("part outside the accepted regions")?;
+            hits[index] += 1;
+        }
+
+        // each region should be hit proportionally to its volume, within 5 standard deviations
+        let volume = solve2(&workflows)? as f64;
+        regions.iter().zip(hits).for_each(|(region, hit)| {
+            let p = possibilities_ranges(region) as f64 / volume;
+            let expected = p * count as f64;
+            let sigma = (count as f64 * p * (1.0 - p)).sqrt();
+            assert!((hit as f64 - expected).abs() <= 5.0 * sigma + 1.0);
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff() -> Result<(), Box<dyn Error>> {
+        let workflows = workflows();
+        assert!(diff(&workflows, &workflows)?.is_empty());
+
+        // crn accepts more parts, pv rejects more parts
+        let others = parse(
+            WORKFLOW
+                .replace("crn{x>2662:A,R}", "crn{x>2000:A,R}")
+                .replace("pv{a>1716:R,A}", "pv{a>1716:R,m<100:R,A}")
+                .lines()
+                .map(|s| s.to_string()),
+        )?
+        .0;
+        let disagreements = diff(&workflows, &others)?;
+
+        let volume = |side| {
+            disagreements
+                .iter()
+                .filter(|disagreement| disagreement.accepted_by == side)
+                .map(|disagreement| possibilities_ranges(&disagreement.region))
+                .sum::<i64>()
+        };
+        assert_eq!(volume(Side::First), 4000 * 99 * 1716 * 1420);
+        assert_eq!(volume(Side::Second), 662 * 4000 * 2005 * 1350);
+        assert_eq!(
+            solve2(&others)? - solve2(&workflows)?,
+            volume(Side::Second) - volume(Side::First)
+        );
+
+        for disagreement in disagreements {
+            let part = &disagreement.witness;
+            assert!(contains(&disagreement.region, part));
+            assert_eq!(
+                apply_workflows(part, &workflows)?,
+                disagreement.accepted_by == Side::First
+            );
+            assert_eq!(
+                apply_workflows(part, &others)?,
+                disagreement.accepted_by == Side::Second
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_parts_rate() -> Result<(), Box<dyn Error>> {
+        let workflows = workflows();
+        let mut rng = StdRng::seed_from_u64(2023);
+        let count = 2000;
+        let parts = genfuzz::generate_parts(&workflows, count, 0.25, &mut rng)?;
+
+        let accepted = parts
+            .iter()
+            .map(|part| apply_workflows(part, &workflows))
+            .process_results(|itr| itr.filter(|&accepted| accepted).count())?;
+        let rate = accepted as f64 / count as f64;
+        assert!((rate - 0.25).abs() < 0.05);
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_parts_invalid_rate() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(genfuzz::generate_parts(&workflows(), 1, 1.5, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_display_workflows() -> Result<(), Box<dyn Error>> {
+        let lines = workflows()
+            .iter()
+            .map(|workflow| workflow.to_string())
+            .join("\n");
+        assert_eq!(lines, WORKFLOW);
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_workflows() -> Result<(), Box<dyn Error>> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let workflows = genfuzz::generate_workflows(10, &mut rng)?;
+        assert_eq!(workflows.len(), 10);
+        assert_eq!(workflows[0].name, "in");
+
+        // printed then parsed back unchanged, and without cycle
+        let lines = workflows.iter().map(|workflow| workflow.to_string());
+        let (parsed, _) = parse(lines)?;
+        assert_eq!(parsed, workflows);
+        solve2(&workflows)?;
+
+        assert!(genfuzz::generate_workflows(0, &mut rng).is_err());
+        Ok(())
+    }
+
+    // a pass rewriting workflows, the simplifications are checked against this signature
+    type Pass = fn(&[Workflow]) -> Vec<Workflow>;
+
+    // parts on both sides of every condition, plus random ones
+    fn sample_parts(workflows: &[Workflow], rng: &mut StdRng) -> Vec<Part> {
+        let mut random = || Part {
+            x: rng.gen_range(MIN_RANGE..=MAX_RANGE),
+            m: rng.gen_range(MIN_RANGE..=MAX_RANGE),
+            a: rng.gen_range(MIN_RANGE..=MAX_RANGE),
+            s: rng.gen_range(MIN_RANGE..=MAX_RANGE),
+        };
+
+        let mut parts = (0..100).map(|_| random()).collect::<Vec<_>>();
+        for (condition, _) in workflows.iter().flat_map(|workflow| &workflow.conditions) {
+            for value in condition.value - 1..=condition.value + 1 {
+                let mut part = random();
+                let value = value.clamp(MIN_RANGE, MAX_RANGE);
+                match condition.category {
+                    Category::X => part.x = value,
+                    Category::M => part.m = value,
+                    Category::A => part.a = value,
+                    Category::S => part.s = value,
+                }
+                parts.push(part);
+            }
+        }
+        parts
+    }
+
+    // both sets accept the same parts and the same number of combinations
+    fn equivalent(original: &[Workflow], transformed: &[Workflow], parts: &[Part]) -> bool {
+        let (original, transformed) = (original.to_vec(), transformed.to_vec());
+        let same_parts = parts.iter().all(|part| {
+            apply_workflows(part, &original).ok() == apply_workflows(part, &transformed).ok()
+        });
+        same_parts && solve2(&original).ok() == solve2(&transformed).ok()
+    }
+
+    // smaller workflow sets: one condition less, or one jump replaced by an accept or a reject
+    fn shrink(workflows: &[Workflow]) -> Vec<Vec<Workflow>> {
+        let mut candidates = Vec::new();
+        for (i, workflow) in workflows.iter().enumerate() {
+            for j in 0..workflow.conditions.len() {
+                let mut candidate = workflows.to_vec();
+                candidate[i].conditions.remove(j);
+                candidates.push(candidate);
+            }
+            for j in 0..=workflow.conditions.len() {
+                for terminal in ["A", "R"] {
+                    let mut candidate = workflows.to_vec();
+                    let next = match candidate[i].conditions.get_mut(j) {
+                        Some((_, next)) => next,
+                        None => &mut candidate[i].fallback,
+                    };
+                    if next != "A" && next != "R" {
+                        *next = terminal.to_string();
+                        candidates.push(reachable(candidate));
+                    }
+                }
+            }
+        }
+        candidates
+    }
+
+    // the smallest set of workflows still breaking the pass that can be reached by shrinking
+    fn minimize(workflows: Vec<Workflow>, fails: impl Fn(&[Workflow]) -> bool) -> Vec<Workflow> {
+        let mut workflows = workflows;
+        while let Some(smaller) = shrink(&workflows)
+            .into_iter()
+            .find(|candidate| fails(candidate))
+        {
+            workflows = smaller;
+        }
+        workflows
+    }
+
+    // runs the pass on random workflow sets, returns the minimized first one it breaks
+    fn check_pass(pass: Pass, seed: u64, cases: usize) -> Result<Option<String>, Box<dyn Error>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..cases {
+            let count = rng.gen_range(1..=8);
+            let workflows = genfuzz::generate_workflows(count, &mut rng)?;
+            let parts = sample_parts(&workflows, &mut rng);
+
+            let fails = |workflows: &[Workflow]| !equivalent(workflows, &pass(workflows), &parts);
+            if fails(&workflows) {
+                let minimized = minimize(workflows, fails);
+                return Ok(Some(minimized.iter().join("\n")));
+            }
+        }
+        Ok(None)
+    }
+
+    // the names of the workflows but "in" are changed and the order of the workflows is reversed,
+    // which doesn't change anything
+    fn rename(workflows: &[Workflow]) -> Vec<Workflow> {
+        let renamed = |name: &String| match name.as_str() {
+            "in" | "A" | "R" => name.clone(),
+            _ => format!("renamed_{}", name),
+        };
+        workflows
+            .iter()
+            .rev()
+            .map(|workflow| Workflow {
+                name: renamed(&workflow.name),
+                conditions: workflow
+                    .conditions
+                    .iter()
+                    .map(|(condition, next)| (condition.clone(), renamed(next)))
+                    .collect(),
+                fallback: renamed(&workflow.fallback),
+            })
+            .collect()
+    }
+
+    // wrong on purpose, the last condition of each workflow is dropped
+    fn drop_last_condition(workflows: &[Workflow]) -> Vec<Workflow> {
+        workflows
+            .iter()
+            .map(|workflow| {
+                let mut workflow = workflow.clone();
+                workflow.conditions.pop();
+                workflow
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pass_preserves_semantics() -> Result<(), Box<dyn Error>> {
+        let passes: [(&str, Pass); 2] = [("rename", rename), ("simplify", simplify)];
+        for (name, pass) in passes {
+            if let Some(workflows) = check_pass(pass, 19, 200)? {
+                panic!("{} changes the meaning of:\n{}", name, workflows);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_pass_minimizes() -> Result<(), Box<dyn Error>> {
+        let workflows = check_pass(drop_last_condition, 19, 200)?.ok_or("no failure found")?;
+
+        // a single condition is enough to tell the difference
+        let (workflows, _) = parse(workflows.lines().map(|s| s.to_string()))?;
+        let conditions = workflows
+            .iter()
+            .map(|workflow| workflow.conditions.len())
+            .sum::<usize>();
+        assert_eq!(conditions, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_simplify_example() -> Result<(), Box<dyn Error>> {
+        let workflows = workflows();
+        let simplified = simplify(&workflows);
+
+        // lnx and gd always go to the same place, once they are inlined qs does too
+        assert_eq!(
+            simplified.iter().join("\n"),
+            "px{a<2006:qkq,m>2090:A,rfg}\n\
+            pv{a>1716:R,A}\n\
+            rfg{s<537:R,x>2440:R,A}\n\
+            qkq{x<1416:A,crn}\n\
+            crn{x>2662:A,R}\n\
+            in{s<1351:px,qqz}\n\
+            qqz{s>2770:A,m<1801:hdj,R}\n\
+            hdj{m>838:A,pv}"
+        );
+        assert_eq!(
+            workflow_stats(&workflows),
+            WorkflowStats {
+                workflows: 11,
+                rules: 25
+            }
+        );
+        assert_eq!(
+            workflow_stats(&simplified),
+            WorkflowStats {
+                workflows: 8,
+                rules: 19
+            }
+        );
+
+        assert_eq!(
+            solve1(&simplified, &parts())?,
+            solve1(&workflows, &parts())?
+        );
+        assert_eq!(solve2(&simplified)?, solve2(&workflows)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_simplify_rules() -> Result<(), Box<dyn Error>> {
+        // x<1 matches nothing, x>10 nothing left by x>5, and the last rules of b go to its
+        // fallback. b is then inlined, and c is never reached
+        let (workflows, _) = parse(
+            "in{x<1:R,x>5:b,x>10:R,A}\n\
+            b{m<5:R,a<3:A,A}\n\
+            c{x<1:A,R}"
+                .lines()
+                .map(|s| s.to_string()),
+        )?;
+        let simplified = simplify(&workflows);
+        assert_eq!(simplified.iter().join("\n"), "in{x>5:b,A}\nb{m<5:R,A}");
+
+        // the workflows jumping to each other end up as a single one looping on itself
+        let (workflows, _) = parse("in{a}\na{b}\nb{a}".lines().map(|s| s.to_string()))?;
+        assert_eq!(simplify(&workflows).iter().join("\n"), "in{b}\nb{b}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_simplify_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let (workflows, parts) = reader.lines().process_results(|itr| parse(itr))??;
+        let simplified = simplify(&workflows);
+
+        assert_eq!(solve1(&simplified, &parts)?, 432434);
+        assert_eq!(solve2(&simplified)?, 132557544578569);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let (workflows, parts) = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve1(&workflows, &parts)?;
+
+        assert_eq!(result, 432434);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let (workflows, _) = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve2(&workflows)?;
+
+        assert_eq!(result, 132557544578569);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        for s in [
+            "{x<1:A,R}",
+            "in",
+            "in{R",
+            "in{}",
+            "in{x<1:A}",
+            "in{x<1:,R}",
+            "in{x<1:A,}",
+            "in{x<:A,R}",
+            "in{q<1:A,R}",
+            "in{x=1:A,R}",
+            "in{R}\n\nx=1",
+            "in{R}\n\n{x=}",
+            "in{R}\n\n{x1}",
+            "in{R}\n\n{q=1}",
+            "in{R}\n\n{x=1",
+        ] {
+            assert!(parse(s.lines().map(|s| s.to_string())).is_err(), "{:?}", s);
+        }
+
+        assert_eq!(
+            "px{a<2006:qkq,m?2090:A,rfg}"
+                .parse::<Workflow>()
+                .map_err(|e| e.to_string()),
+            Err("column 16: invalid comparison".to_string())
+        );
+    }
+}
@@ -0,0 +1,1335 @@
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Pulse {
+    High,
+    Low,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlipFlopState {
+    On,
+    Off,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NodeType {
+    Broadcast,
+    FlipFlop { state: FlipFlopState },
+    Conjunction { inputs: HashMap<String, Pulse> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node {
+    pub name: String,
+    pub node_type: NodeType,
+    pub outputs: Vec<String>,
+}
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Vec<Node>, Box<dyn Error>> {
+    itr.map(|line| {
+        let (name_str, outputs_str) = line.split_once(" -> ").ok_or("Invalid line")?;
+
+        let outputs = outputs_str
+            .split(", ")
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        if outputs.iter().any(|output| output.is_empty()) {
+            return Err("Missing output".into());
+        }
+
+        let node_type = match name_str.chars().next().ok_or("Invalid line")? {
+            '%' => NodeType::FlipFlop {
+                state: FlipFlopState::Off,
+            },
+            '&' => NodeType::Conjunction {
+                inputs: HashMap::new(),
+            },
+            _ => NodeType::Broadcast,
+        };
+
+        let name_str = name_str.trim_start_matches("&").trim_start_matches("%");
+        if name_str.is_empty() {
+            return Err("Missing name".into());
+        }
+
+        Ok(Node {
+            name: name_str.to_string(),
+            node_type,
+            outputs,
+        })
+    })
+    .collect()
+}
+
+fn to_map(nodes: Vec<Node>) -> HashMap<String, Node> {
+    nodes
+        .into_iter()
+        .map(|node| (node.name.clone(), node))
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SendPulse {
+    from: String,
+    to: String,
+    pulse: Pulse,
+}
+
+// same format as in the puzzle text: from -pulse-> to
+impl Display for SendPulse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -{}-> {}", self.from, pulse_name(self.pulse), self.to)
+    }
+}
+
+fn init_conjunctions(nodes: &mut HashMap<String, Node>) -> () {
+    // updating the hashmap while iterating over it is not possible in Rust that's why we clone it
+    nodes.clone().iter().for_each(|(name, node)| {
+        node.outputs.iter().for_each(|output| {
+            nodes
+                .get_mut(output)
+                .map(|output_node| match &mut output_node.node_type {
+                    NodeType::Conjunction { inputs } => {
+                        inputs.insert(name.clone(), Pulse::Low);
+                    }
+                    _ => {}
+                });
+        })
+    })
+}
+
+fn init(nodes: Vec<Node>) -> HashMap<String, Node> {
+    let mut nodes = to_map(nodes);
+    init_conjunctions(&mut nodes);
+
+    nodes
+}
+
+fn solve(nodes: Vec<Node>, count: i32) -> Result<PulseCount, Box<dyn Error>> {
+    let mut machine = Machine::new(nodes);
+    for _ in 0..count {
+        machine.press()?;
+    }
+
+    Ok(machine.pulses)
+}
+
+// the modules with their states, along with the presses and the pulses sent since the start
+pub struct Machine {
+    nodes: HashMap<String, Node>,
+    pub presses: i64,
+    pulses: PulseCount,
+}
+
+// what a module remembers between two pulses
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModuleState {
+    Broadcast,
+    FlipFlop(FlipFlopState),
+    // the last pulse received from each input, sorted by input
+    Conjunction(Vec<(String, Pulse)>),
+    // an untyped module, only receiving pulses like rx
+    Untyped,
+}
+
+impl Display for ModuleState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleState::Broadcast => write!(f, "broadcaster"),
+            ModuleState::FlipFlop(FlipFlopState::On) => write!(f, "flip-flop on"),
+            ModuleState::FlipFlop(FlipFlopState::Off) => write!(f, "flip-flop off"),
+            ModuleState::Conjunction(inputs) => write!(
+                f,
+                "conjunction {}",
+                inputs
+                    .iter()
+                    .map(|(input, pulse)| format!("{}:{}", input, pulse_name(*pulse)))
+                    .join(" ")
+            ),
+            ModuleState::Untyped => write!(f, "untyped"),
+        }
+    }
+}
+
+fn pulse_name(pulse: Pulse) -> &'static str {
+    match pulse {
+        Pulse::High => "high",
+        Pulse::Low => "low",
+    }
+}
+
+impl Machine {
+    pub fn new(nodes: Vec<Node>) -> Self {
+        Machine {
+            nodes: init(nodes),
+            presses: 0,
+            pulses: PulseCount::default(),
+        }
+    }
+
+    pub fn press(&mut self) -> Result<PulseCount, Box<dyn Error>> {
+        self.press_traced(&mut |_| {})
+    }
+
+    // the pulses sent by this press, trace is called on each of them when it reaches its
+    // destination
+    pub fn press_traced(
+        &mut self,
+        trace: &mut impl FnMut(&SendPulse),
+    ) -> Result<PulseCount, Box<dyn Error>> {
+        let (pulses, _) = push_button_traced(&mut self.nodes, &HashSet::new(), trace)?;
+
+        self.presses += 1;
+        self.pulses.high += pulses.high;
+        self.pulses.low += pulses.low;
+
+        Ok(pulses)
+    }
+
+    fn inspect(&self, name: &str) -> Result<ModuleState, Box<dyn Error>> {
+        match self.nodes.get(name) {
+            Some(node) => Ok(match &node.node_type {
+                NodeType::Broadcast => ModuleState::Broadcast,
+                NodeType::FlipFlop { state } => ModuleState::FlipFlop(*state),
+                NodeType::Conjunction { inputs } => ModuleState::Conjunction(
+                    inputs
+                        .iter()
+                        .map(|(input, pulse)| (input.clone(), *pulse))
+                        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+                        .collect(),
+                ),
+            }),
+            None if self
+                .nodes
+                .values()
+                .any(|node| node.outputs.iter().any(|output| output == name)) =>
+            {
+                Ok(ModuleState::Untyped)
+            }
+            None => Err(format!("Unknown module: {}", name).into()),
+        }
+    }
+
+    // the presses, the pulses and the states of the flip-flops and the conjunctions. The modules
+    // and the inputs are sorted by name so that the same machine always gives the same bytes
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        for value in [self.presses, self.pulses.high, self.pulses.low] {
+            bytes.extend(value.to_le_bytes());
+        }
+
+        for node in self.nodes.values().sorted_by(|a, b| a.name.cmp(&b.name)) {
+            match &node.node_type {
+                NodeType::Broadcast => {}
+                NodeType::FlipFlop { state } => {
+                    write_name(&mut bytes, &node.name);
+                    bytes.push(FLIP_FLOP);
+                    bytes.push(u8::from(*state == FlipFlopState::On));
+                }
+                NodeType::Conjunction { inputs } => {
+                    write_name(&mut bytes, &node.name);
+                    bytes.push(CONJUNCTION);
+                    bytes.extend((inputs.len() as u32).to_le_bytes());
+                    for (input, pulse) in inputs.iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
+                        write_name(&mut bytes, input);
+                        bytes.push(u8::from(*pulse == Pulse::High));
+                    }
+                }
+            }
+        }
+
+        bytes
+    }
+
+    // the snapshot must come from a machine with the same modules, the machine is left untouched
+    // otherwise
+    pub fn restore(&mut self, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        let mut reader = SnapshotReader { bytes };
+        if reader.take(SNAPSHOT_MAGIC.len())? != SNAPSHOT_MAGIC {
+            return Err("Not a snapshot".into());
+        }
+        let presses = reader.i64()?;
+        let pulses = PulseCount {
+            high: reader.i64()?,
+            low: reader.i64()?,
+        };
+
+        let mut nodes = self.nodes.clone();
+        let mut restored = HashSet::new();
+        while !reader.bytes.is_empty() {
+            let name = reader.name()?;
+            let node = nodes
+                .get_mut(&name)
+                .ok_or(format!("Unknown module in the snapshot: {}", name))?;
+
+            match (reader.u8()?, &mut node.node_type) {
+                (FLIP_FLOP, NodeType::FlipFlop { state }) => {
+                    *state = match reader.u8()? {
+                        0 => FlipFlopState::Off,
+                        _ => FlipFlopState::On,
+                    };
+                }
+                (CONJUNCTION, NodeType::Conjunction { inputs }) => {
+                    let count = reader.u32()?;
+                    if usize::try_from(count)? != inputs.len() {
+                        return Err(format!("Different inputs for {}", name).into());
+                    }
+                    for _ in 0..count {
+                        let input = reader.name()?;
+                        *inputs
+                            .get_mut(&input)
+                            .ok_or(format!("Unknown input of {}: {}", name, input))? =
+                            match reader.u8()? {
+                                0 => Pulse::Low,
+                                _ => Pulse::High,
+                            };
+                    }
+                }
+                _ => return Err(format!("Different type for {}", name).into()),
+            }
+            restored.insert(name);
+        }
+
+        let stateful = nodes
+            .values()
+            .filter(|node| node.node_type != NodeType::Broadcast)
+            .count();
+        if restored.len() != stateful {
+            return Err("Missing modules in the snapshot".into());
+        }
+
+        self.nodes = nodes;
+        self.presses = presses;
+        self.pulses = pulses;
+
+        Ok(())
+    }
+}
+
+const SNAPSHOT_MAGIC: &[u8; 4] = b"D20S";
+const FLIP_FLOP: u8 = 0;
+const CONJUNCTION: u8 = 1;
+
+// the names are prefixed by their length
+fn write_name(bytes: &mut Vec<u8>, name: &str) {
+    bytes.extend((name.len() as u32).to_le_bytes());
+    bytes.extend(name.as_bytes());
+}
+
+struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Box<dyn Error>> {
+        if count > self.bytes.len() {
+            return Err("Truncated snapshot".into());
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, Box<dyn Error>> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, Box<dyn Error>> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, Box<dyn Error>> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn name(&mut self) -> Result<String, Box<dyn Error>> {
+        let length = usize::try_from(self.u32()?)?;
+
+        Ok(String::from_utf8(self.take(length)?.to_vec())?)
+    }
+}
+
+// a module with its state in two machines
+type StateChange = (String, ModuleState, ModuleState);
+
+// the modules whose states differ between two machines with the same modules, sorted by name
+pub fn diff_states(
+    machine1: &Machine,
+    machine2: &Machine,
+) -> Result<Vec<StateChange>, Box<dyn Error>> {
+    machine1
+        .nodes
+        .keys()
+        .sorted()
+        .map(|name| {
+            Ok((
+                name.clone(),
+                machine1.inspect(name)?,
+                machine2.inspect(name)?,
+            ))
+        })
+        .filter_ok(|(_, state1, state2)| state1 != state2)
+        .collect()
+}
+
+// the commands typed in the repl, one per line
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Command {
+    // push the button a number of times, at least once
+    Push(u64),
+    State(String),
+    // report the pulses received by a module after each push command
+    Watch(String),
+    Help,
+}
+
+impl FromStr for Command {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["push"] => Ok(Command::Push(1)),
+            ["push", count] => match count.parse()? {
+                0 => Err("Invalid count: 0, the button is pushed at least once".into()),
+                count => Ok(Command::Push(count)),
+            },
+            ["state", name] => Ok(Command::State(name.to_string())),
+            ["watch", name] => Ok(Command::Watch(name.to_string())),
+            ["help"] => Ok(Command::Help),
+            _ => Err(format!("Invalid command: {}, try help", s.trim()).into()),
+        }
+    }
+}
+
+const REPL_HELP: &str = "\
+    push [N]     push the button N times, once by default\n\
+    state NAME   show the state of a module\n\
+    watch NAME   count the pulses received by a module on each push\n\
+    help         show this help\n";
+
+pub struct Repl {
+    machine: Machine,
+    watched: Vec<String>,
+}
+
+impl Repl {
+    pub fn new(machine: Machine) -> Self {
+        Repl {
+            machine,
+            watched: Vec::new(),
+        }
+    }
+
+    // what the command prints, empty lines are ignored
+    pub fn execute(&mut self, line: &str) -> Result<String, Box<dyn Error>> {
+        if line.trim().is_empty() {
+            return Ok(String::new());
+        }
+
+        match line.parse()? {
+            Command::Push(count) => {
+                let mut received: HashMap<&str, PulseCount> = HashMap::new();
+                for _ in 0..count {
+                    let watched = &self.watched;
+                    self.machine.press_traced(&mut |send_pulse| {
+                        if let Some(name) = watched.iter().find(|name| **name == send_pulse.to) {
+                            received.entry(name).or_default()[send_pulse.pulse] += 1;
+                        }
+                    })?;
+                }
+
+                let pulses = &self.machine.pulses;
+                let mut output = format!(
+                    "press {}: {} high, {} low\n",
+                    self.machine.presses, pulses.high, pulses.low
+                );
+                for name in &self.watched {
+                    let count = received.get(name.as_str()).cloned().unwrap_or_default();
+                    output += &format!(
+                        "{}: {}, received {} high, {} low\n",
+                        name,
+                        self.machine.inspect(name)?,
+                        count.high,
+                        count.low
+                    );
+                }
+                Ok(output)
+            }
+            Command::State(name) => Ok(format!("{}: {}\n", name, self.machine.inspect(&name)?)),
+            Command::Watch(name) => {
+                self.machine.inspect(&name)?;
+                if !self.watched.contains(&name) {
+                    self.watched.push(name.clone());
+                }
+                Ok(format!("watching {}\n", name))
+            }
+            Command::Help => Ok(REPL_HELP.to_string()),
+        }
+    }
+}
+
+pub fn solve1(nodes: Vec<Node>) -> Result<i64, Box<dyn Error>> {
+    let result = solve(nodes, 1000)?;
+
+    Ok(result.high * result.low)
+}
+
+fn get_parents(nodes: &HashMap<String, Node>, name: &str) -> Vec<String> {
+    nodes
+        .iter()
+        .filter_map(|(node_name, node)| {
+            node.outputs
+                .contains(&name.to_string())
+                .then_some(node_name.clone())
+        })
+        .collect()
+}
+
+pub fn solve2(nodes: Vec<Node>) -> Result<i64, Box<dyn Error>> {
+    let parents = to_map(nodes.clone());
+
+    // the grand parents of rx send a high pulse to its parent once in a while
+    let rx_parents = get_parents(&parents, "rx");
+    let targets = rx_parents
+        .iter()
+        .flat_map(|name| get_parents(&parents, name))
+        .unique()
+        .map(|module| Target {
+            module,
+            pulse: Pulse::High,
+        })
+        .collect::<Vec<_>>();
+
+    let presses = first_presses(nodes, &targets, None)?;
+
+    // we assume that this number of pushes happen in a cycle then the result might be the product
+    // of all these cycles (or the LCM of all these numbers)
+    Ok(presses.values().product::<i64>())
+}
+
+// a module and a pulse it sends
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Target {
+    module: String,
+    pulse: Pulse,
+}
+
+impl FromStr for Target {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (module, pulse) = s.split_once(':').ok_or(format!("Invalid target: {}", s))?;
+        let pulse = match pulse {
+            "high" => Pulse::High,
+            "low" => Pulse::Low,
+            _ => return Err(format!("Invalid pulse: {}", pulse).into()),
+        };
+
+        Ok(Target {
+            module: module.to_string(),
+            pulse,
+        })
+    }
+}
+
+// same format as on the command line
+impl Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.module, pulse_name(self.pulse))
+    }
+}
+
+// the presses simulated when no maximum is given, the cycles of the inputs are about four thousand
+// presses long
+const DEFAULT_MAX_PRESSES: i64 = 10_000;
+
+// the first press on which each target sends its pulse, the presses are simulated once for all of
+// them. The presses stop after max_presses, the targets not reached yet are then left out. Without
+// a maximum, the targets must all be reached within DEFAULT_MAX_PRESSES
+pub fn first_presses(
+    nodes: Vec<Node>,
+    targets: &[Target],
+    max_presses: Option<i64>,
+) -> Result<HashMap<Target, i64>, Box<dyn Error>> {
+    let mut machine = Machine::new(nodes);
+
+    // a target that isn't a module would never be reached
+    if let Some(target) = targets
+        .iter()
+        .find(|target| target.module != "button" && !machine.nodes.contains_key(&target.module))
+    {
+        return Err(format!("Unknown module: {}", target.module).into());
+    }
+
+    let searched = targets.iter().cloned().collect::<HashSet<_>>();
+    let mut found: HashMap<Target, i64> = HashMap::new();
+
+    let max = max_presses.unwrap_or(DEFAULT_MAX_PRESSES);
+    while found.len() != searched.len() && machine.presses < max {
+        let i = machine.presses + 1;
+        machine.press_traced(&mut |send_pulse| {
+            let target = Target {
+                module: send_pulse.from.clone(),
+                pulse: send_pulse.pulse,
+            };
+            if searched.contains(&target) {
+                found.entry(target).or_insert(i);
+            }
+        })?;
+    }
+
+    if max_presses.is_none() {
+        if let Some(target) = targets.iter().find(|target| !found.contains_key(target)) {
+            return Err(format!("{} not reached after {} presses", target, max).into());
+        }
+    }
+
+    Ok(found)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PulseCount {
+    high: i64,
+    low: i64,
+}
+
+impl Default for PulseCount {
+    fn default() -> Self {
+        Self { high: 0, low: 0 }
+    }
+}
+
+impl Index<Pulse> for PulseCount {
+    type Output = i64;
+
+    fn index(&self, pulse: Pulse) -> &Self::Output {
+        match pulse {
+            Pulse::High => &self.high,
+            Pulse::Low => &self.low,
+        }
+    }
+}
+
+impl IndexMut<Pulse> for PulseCount {
+    fn index_mut(&mut self, pulse: Pulse) -> &mut Self::Output {
+        match pulse {
+            Pulse::High => &mut self.high,
+            Pulse::Low => &mut self.low,
+        }
+    }
+}
+
+fn push_button(
+    nodes: &mut HashMap<String, Node>,
+    searched_conjunctions: &HashSet<String>,
+) -> Result<(PulseCount, HashSet<String>), Box<dyn Error>> {
+    push_button_traced(nodes, searched_conjunctions, &mut |_| {})
+}
+
+// the pulses are processed in the order they are sent, trace is called on each of them when it
+// reaches its destination
+fn push_button_traced(
+    nodes: &mut HashMap<String, Node>,
+    searched_conjunctions: &HashSet<String>,
+    trace: &mut impl FnMut(&SendPulse),
+) -> Result<(PulseCount, HashSet<String>), Box<dyn Error>> {
+    let mut stack = VecDeque::new();
+    stack.push_back(SendPulse {
+        from: "button".to_string(),
+        to: "broadcaster".to_string(),
+        pulse: Pulse::Low,
+    });
+
+    let mut pulse_count = PulseCount::default();
+    pulse_count[Pulse::Low] += 1;
+
+    let mut found_conjunctions = HashSet::new();
+
+    while let Some(send_pulse) = stack.pop_front() {
+        trace(&send_pulse);
+        let SendPulse { from, to, pulse } = send_pulse;
+        nodes
+            .get_mut(&to)
+            .map_or(Ok(()), |node| -> Result<_, Box<dyn Error>> {
+                match &mut node.node_type {
+                    NodeType::Broadcast => {
+                        node.outputs.iter().for_each(|output| {
+                            stack.push_back(SendPulse {
+                                from: to.clone(),
+                                to: output.clone(),
+                                pulse,
+                            });
+                        });
+                        pulse_count[pulse] += i64::try_from(node.outputs.len())?;
+                    }
+                    NodeType::FlipFlop { ref mut state } => match pulse {
+                        Pulse::High => {}
+                        Pulse::Low => {
+                            *state = flip(state);
+                            let pulse = match state {
+                                FlipFlopState::On => Pulse::High,
+                                FlipFlopState::Off => Pulse::Low,
+                            };
+
+                            node.outputs.iter().for_each(|output| {
+                                stack.push_back(SendPulse {
+                                    from: to.clone(),
+                                    to: output.clone(),
+                                    pulse,
+                                });
+                            });
+                            pulse_count[pulse] += i64::try_from(node.outputs.len())?;
+                        }
+                    },
+                    NodeType::Conjunction { inputs } => {
+                        *inputs.get_mut(&from).ok_or("Invalid input")? = pulse;
+                        let all_high = inputs.values().all(|&p| p == Pulse::High);
+                        let pulse = if all_high { Pulse::Low } else { Pulse::High };
+
+                        node.outputs.iter().for_each(|output| {
+                            stack.push_back(SendPulse {
+                                from: to.clone(),
+                                to: output.clone(),
+                                pulse,
+                            });
+                        });
+
+                        pulse_count[pulse] += i64::try_from(node.outputs.len())?;
+
+                        if (pulse == Pulse::High) && searched_conjunctions.contains(&node.name) {
+                            found_conjunctions.insert(node.name.clone());
+                        }
+                    }
+                }
+                Ok(())
+            })?;
+    }
+
+    Ok((pulse_count, found_conjunctions))
+}
+
+// the chains of flip-flops fed by the broadcaster behave as binary counters: the flip-flops
+// connected to the hub conjunction are the bits set in the period, the hub then sends a low pulse
+// to its output which emits a high pulse once every period
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counter {
+    pub start: String,
+    hub: String,
+    pub output: String,
+    pub period: i64,
+}
+
+fn decode_counter(nodes: &HashMap<String, Node>, start: &str) -> Result<Counter, Box<dyn Error>> {
+    let mut current = Some(start.to_string());
+    let mut hub: Option<String> = None;
+    let mut period = 0;
+    let mut bit = 0;
+
+    while let Some(name) = current {
+        let node = nodes.get(&name).ok_or("Unknown node in counter")?;
+        if !matches!(node.node_type, NodeType::FlipFlop { .. }) {
+            return Err(format!("{} is not a flip-flop", name).into());
+        }
+
+        let (flip_flops, conjunctions): (Vec<_>, Vec<_>) =
+            node.outputs.iter().partition(|output| {
+                nodes.get(*output).is_some_and(|output_node| {
+                    matches!(output_node.node_type, NodeType::FlipFlop { .. })
+                })
+            });
+
+        match conjunctions.as_slice() {
+            [] => {}
+            [conjunction] => {
+                if hub.as_ref().is_some_and(|hub| hub != *conjunction) {
+                    return Err(format!("{} is connected to several hubs", name).into());
+                }
+                hub = Some(conjunction.to_string());
+                period += 1 << bit;
+            }
+            _ => return Err(format!("{} is connected to several hubs", name).into()),
+        }
+
+        current = match flip_flops.as_slice() {
+            [] => None,
+            [next] => Some(next.to_string()),
+            _ => return Err(format!("{} is connected to several flip-flops", name).into()),
+        };
+        bit += 1;
+    }
+
+    let hub = hub.ok_or(format!("Counter starting at {} has no hub", start))?;
+    let outputs = nodes
+        .get(&hub)
+        .ok_or("Unknown hub")?
+        .outputs
+        .iter()
+        .filter(|output| {
+            nodes.get(*output).is_some_and(|output_node| {
+                matches!(output_node.node_type, NodeType::Conjunction { .. })
+            })
+        })
+        .collect::<Vec<_>>();
+    let output = match outputs.as_slice() {
+        [output] => output.to_string(),
+        _ => return Err(format!("Hub {} should have a single output", hub).into()),
+    };
+
+    Ok(Counter {
+        start: start.to_string(),
+        hub,
+        output,
+        period,
+    })
+}
+
+fn decode_counters(nodes: &HashMap<String, Node>) -> Result<Vec<Counter>, Box<dyn Error>> {
+    nodes
+        .get("broadcaster")
+        .ok_or("No broadcaster")?
+        .outputs
+        .iter()
+        .map(|start| decode_counter(nodes, start))
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub output: String,
+    pub predicted: Vec<i64>,
+    pub observed: Vec<i64>,
+}
+
+pub fn verify(
+    nodes: Vec<Node>,
+    presses: i64,
+) -> Result<(Vec<Counter>, Vec<Mismatch>), Box<dyn Error>> {
+    let mut nodes = init(nodes);
+    let counters = decode_counters(&nodes)?;
+
+    let outputs = counters
+        .iter()
+        .map(|counter| counter.output.clone())
+        .collect::<HashSet<_>>();
+
+    let mut observed: HashMap<String, Vec<i64>> = HashMap::new();
+    for i in 1..=presses {
+        let (_, found_conjunctions) = push_button(&mut nodes, &outputs)?;
+        found_conjunctions.into_iter().for_each(|name| {
+            observed.entry(name).or_default().push(i);
+        });
+    }
+
+    let mismatches = counters
+        .iter()
+        .filter_map(|counter| {
+            let predicted = (1..=presses / counter.period)
+                .map(|k| k * counter.period)
+                .collect::<Vec<_>>();
+            let observed = observed.get(&counter.output).cloned().unwrap_or_default();
+
+            (predicted != observed).then_some(Mismatch {
+                output: counter.output.clone(),
+                predicted,
+                observed,
+            })
+        })
+        .collect();
+
+    Ok((counters, mismatches))
+}
+
+fn flip(state: &FlipFlopState) -> FlipFlopState {
+    match state {
+        FlipFlopState::On => FlipFlopState::Off,
+        FlipFlopState::Off => FlipFlopState::On,
+    }
+}
+
+#[cfg(test)]
+mod day20 {
+    use std::{
+        collections::{HashMap, HashSet},
+        error::Error,
+    };
+
+    use itertools::Itertools;
+    use lib::aoc_tests;
+
+    use crate::{
+        decode_counters, diff_states, first_presses, init, parse, push_button_traced, solve,
+        solve1, solve2, verify, Counter, FlipFlopState, Machine, ModuleState, Node, NodeType,
+        Pulse, PulseCount, Repl, Target,
+    };
+
+    const EXAMPLE1: &str = "\
+        broadcaster -> a, b, c\n\
+        %a -> b\n\
+        %b -> c\n\
+        %c -> inv\n\
+        &inv -> a";
+
+    fn example1() -> Vec<Node> {
+        vec![
+            Node {
+                name: "broadcaster".to_string(),
+                node_type: NodeType::Broadcast,
+                outputs: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            },
+            Node {
+                name: "a".to_string(),
+                node_type: NodeType::FlipFlop {
+                    state: FlipFlopState::Off,
+                },
+                outputs: vec!["b".to_string()],
+            },
+            Node {
+                name: "b".to_string(),
+                node_type: NodeType::FlipFlop {
+                    state: FlipFlopState::Off,
+                },
+                outputs: vec!["c".to_string()],
+            },
+            Node {
+                name: "c".to_string(),
+                node_type: NodeType::FlipFlop {
+                    state: FlipFlopState::Off,
+                },
+                outputs: vec!["inv".to_string()],
+            },
+            Node {
+                name: "inv".to_string(),
+                node_type: NodeType::Conjunction {
+                    inputs: HashMap::new(),
+                },
+                outputs: vec!["a".to_string()],
+            },
+        ]
+    }
+
+    const EXAMPLE2: &str = "\
+        broadcaster -> a\n\
+        %a -> inv, con\n\
+        &inv -> b\n\
+        %b -> con\n\
+        &con -> output";
+
+    fn example2() -> Vec<Node> {
+        vec![
+            Node {
+                name: "broadcaster".to_string(),
+                node_type: NodeType::Broadcast,
+                outputs: vec!["a".to_string()],
+            },
+            Node {
+                name: "a".to_string(),
+                node_type: NodeType::FlipFlop {
+                    state: FlipFlopState::Off,
+                },
+                outputs: vec!["inv".to_string(), "con".to_string()],
+            },
+            Node {
+                name: "inv".to_string(),
+                node_type: NodeType::Conjunction {
+                    inputs: HashMap::new(),
+                },
+                outputs: vec!["b".to_string()],
+            },
+            Node {
+                name: "b".to_string(),
+                node_type: NodeType::FlipFlop {
+                    state: FlipFlopState::Off,
+                },
+                outputs: vec!["con".to_string()],
+            },
+            Node {
+                name: "con".to_string(),
+                node_type: NodeType::Conjunction {
+                    inputs: HashMap::new(),
+                },
+                outputs: vec!["output".to_string()],
+            },
+        ]
+    }
+
+    // two counters with periods 5 and 3 feeding rx like in the real input
+    const COUNTERS: &str = "\
+        broadcaster -> a0, b0\n\
+        %a0 -> a1, ha\n\
+        %a1 -> a2\n\
+        %a2 -> ha\n\
+        &ha -> oa, a0, a1\n\
+        &oa -> fin\n\
+        %b0 -> b1, hb\n\
+        %b1 -> hb\n\
+        &hb -> ob, b0\n\
+        &ob -> fin\n\
+        &fin -> rx";
+
+    #[test]
+    fn test_decode_counters() -> Result<(), Box<dyn Error>> {
+        let nodes = init(parse(COUNTERS.lines().map(|s| s.to_string()))?);
+        let result = decode_counters(&nodes)?;
+        assert_eq!(
+            result,
+            vec![
+                Counter {
+                    start: "a0".to_string(),
+                    hub: "ha".to_string(),
+                    output: "oa".to_string(),
+                    period: 5
+                },
+                Counter {
+                    start: "b0".to_string(),
+                    hub: "hb".to_string(),
+                    output: "ob".to_string(),
+                    period: 3
+                }
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_counters() -> Result<(), Box<dyn Error>> {
+        let (_, mismatches) = verify(parse(COUNTERS.lines().map(|s| s.to_string()))?, 100)?;
+        assert_eq!(mismatches, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_broken_counter() -> Result<(), Box<dyn Error>> {
+        // a2 doesn't reset anymore, the counter decoded with period 5 fires only once
+        let broken = COUNTERS.replace("&ha -> oa, a0, a1", "&ha -> oa");
+        let (_, mismatches) = verify(parse(broken.lines().map(|s| s.to_string()))?, 20)?;
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].output, "oa");
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_presses() -> Result<(), Box<dyn Error>> {
+        let nodes = || parse(COUNTERS.lines().map(|s| s.to_string()));
+        let targets = [
+            "oa:high",
+            "ob:high",
+            "ha:low",
+            "fin:low",
+            "button:low",
+            "a1:high",
+        ]
+        .iter()
+        .map(|target| target.parse::<Target>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let presses = first_presses(nodes()?, &targets, None)?;
+        let press = |target: &str| -> Result<Option<i64>, Box<dyn Error>> {
+            Ok(presses.get(&target.parse::<Target>()?).copied())
+        };
+        assert_eq!(press("oa:high")?, Some(5));
+        assert_eq!(press("ob:high")?, Some(3));
+        // once both outputs are high together, the first time is the product of the periods
+        assert_eq!(press("fin:low")?, Some(15));
+        assert_eq!(press("ha:low")?, Some(5));
+        assert_eq!(press("button:low")?, Some(1));
+        assert_eq!(press("a1:high")?, Some(2));
+
+        // each target reached within the presses has the same press as when searched alone
+        let presses = first_presses(nodes()?, &targets, Some(4))?;
+        assert_eq!(
+            presses,
+            [("ob:high", 3), ("button:low", 1), ("a1:high", 2)]
+                .into_iter()
+                .map(|(target, press)| Ok((target.parse::<Target>()?, press)))
+                .collect::<Result<_, Box<dyn Error>>>()?
+        );
+
+        assert!(first_presses(nodes()?, &["rx:low".parse()?], Some(10)).is_err());
+
+        // the broadcaster only forwards the low pulses of the button
+        let never = ["broadcaster:high".parse()?];
+        assert!(first_presses(nodes()?, &never, Some(10))?.is_empty());
+        assert_eq!(
+            first_presses(nodes()?, &never, None)
+                .err()
+                .map(|e| e.to_string()),
+            Some("broadcaster:high not reached after 10000 presses".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_target() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            "oa:high".parse::<Target>()?,
+            Target {
+                module: "oa".to_string(),
+                pulse: Pulse::High
+            }
+        );
+        assert_eq!("oa:low".parse::<Target>()?.to_string(), "oa:low");
+        assert!("oa".parse::<Target>().is_err());
+        assert!("oa:medium".parse::<Target>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_counters() -> Result<(), Box<dyn Error>> {
+        let result = solve2(parse(COUNTERS.lines().map(|s| s.to_string()))?)?;
+        assert_eq!(result, 15);
+        Ok(())
+    }
+
+    #[test]
+    fn test_machine() -> Result<(), Box<dyn Error>> {
+        let mut machine = Machine::new(example2());
+        assert_eq!(
+            machine.inspect("con")?,
+            ModuleState::Conjunction(vec![("a".into(), Pulse::Low), ("b".into(), Pulse::Low)])
+        );
+
+        assert_eq!(machine.press()?, PulseCount { high: 4, low: 4 });
+        assert_eq!(
+            machine.inspect("a")?,
+            ModuleState::FlipFlop(FlipFlopState::On)
+        );
+        assert_eq!(
+            machine.inspect("b")?,
+            ModuleState::FlipFlop(FlipFlopState::On)
+        );
+        assert_eq!(machine.inspect("broadcaster")?, ModuleState::Broadcast);
+        assert_eq!(machine.inspect("output")?, ModuleState::Untyped);
+        assert!(machine.inspect("rx").is_err());
+
+        // the same count as solve on 4 presses, the circuit being back in its initial state
+        (0..3).try_for_each(|_| machine.press().map(|_| ()))?;
+        assert_eq!(machine.presses, 4);
+        assert_eq!(machine.pulses, solve(example2(), 4)?);
+        assert_eq!(
+            machine.inspect("a")?,
+            ModuleState::FlipFlop(FlipFlopState::Off)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<(), Box<dyn Error>> {
+        let mut machine = Machine::new(example2());
+        machine.press()?;
+        let snapshot = machine.snapshot();
+        assert_eq!(Machine::new(example2()).snapshot().len(), snapshot.len());
+
+        // resuming from the snapshot is the same as pressing all along
+        let mut resumed = Machine::new(example2());
+        resumed.restore(&snapshot)?;
+        assert_eq!(resumed.snapshot(), snapshot);
+        for _ in 0..2 {
+            machine.press()?;
+            resumed.press()?;
+        }
+        assert_eq!(resumed.snapshot(), machine.snapshot());
+        assert_eq!(resumed.pulses, solve(example2(), 3)?);
+
+        let changes = diff_states(&Machine::new(example2()), &resumed)?;
+        assert_eq!(
+            changes
+                .iter()
+                .map(|(name, _, _)| name.as_str())
+                .collect_vec(),
+            vec!["a", "con", "inv"]
+        );
+        assert_eq!(
+            changes[0],
+            (
+                "a".to_string(),
+                ModuleState::FlipFlop(FlipFlopState::Off),
+                ModuleState::FlipFlop(FlipFlopState::On)
+            )
+        );
+
+        // the snapshot of another machine, or a broken one, is rejected and changes nothing
+        let mut other = Machine::new(example1());
+        let before = other.snapshot();
+        assert!(other.restore(&snapshot).is_err());
+        assert!(other.restore(&snapshot[..snapshot.len() - 1]).is_err());
+        assert!(other.restore(b"nope").is_err());
+        assert_eq!(other.snapshot(), before);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_repl() -> Result<(), Box<dyn Error>> {
+        let mut repl = Repl::new(Machine::new(example2()));
+
+        assert_eq!(repl.execute("watch output")?, "watching output\n");
+        assert_eq!(repl.execute("")?, "");
+        assert_eq!(
+            repl.execute("push")?,
+            "press 1: 4 high, 4 low\noutput: untyped, received 1 high, 1 low\n"
+        );
+        assert_eq!(
+            repl.execute("  state   con ")?,
+            "con: conjunction a:high b:high\n"
+        );
+        assert_eq!(
+            repl.execute("push 3")?,
+            "press 4: 11 high, 17 low\noutput: untyped, received 3 high, 1 low\n"
+        );
+
+        for line in [
+            "state zz", "watch zz", "push x", "push -1", "push 0", "pull", "state",
+        ] {
+            assert!(repl.execute(line).is_err(), "{}", line);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_example1() -> Result<(), Box<dyn Error>> {
+        let result = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
+        assert_eq!(result, example1());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_example2() -> Result<(), Box<dyn Error>> {
+        let result = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
+        assert_eq!(result, example2());
+        Ok(())
+    }
+
+    fn trace(nodes: Vec<Node>, presses: usize) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut nodes = init(nodes);
+        let mut pulses = Vec::new();
+        for _ in 0..presses {
+            push_button_traced(&mut nodes, &HashSet::new(), &mut |send_pulse| {
+                pulses.push(send_pulse.to_string())
+            })?;
+        }
+        Ok(pulses)
+    }
+
+    // the pulse sequences below are the ones given in the puzzle text
+    const TRACE1: &str = "\
+        button -low-> broadcaster\n\
+        broadcaster -low-> a\n\
+        broadcaster -low-> b\n\
+        broadcaster -low-> c\n\
+        a -high-> b\n\
+        b -high-> c\n\
+        c -high-> inv\n\
+        inv -low-> a\n\
+        a -low-> b\n\
+        b -low-> c\n\
+        c -low-> inv\n\
+        inv -high-> a";
+
+    #[test]
+    fn test_trace_example1() -> Result<(), Box<dyn Error>> {
+        assert_eq!(trace(example1(), 1)?, TRACE1.lines().collect::<Vec<_>>());
+
+        // the circuit is back in its initial state after one push
+        let twice = TRACE1.lines().chain(TRACE1.lines()).collect::<Vec<_>>();
+        assert_eq!(trace(example1(), 2)?, twice);
+        Ok(())
+    }
+
+    const TRACE2: [&str; 4] = [
+        "\
+        button -low-> broadcaster\n\
+        broadcaster -low-> a\n\
+        a -high-> inv\n\
+        a -high-> con\n\
+        inv -low-> b\n\
+        con -high-> output\n\
+        b -high-> con\n\
+        con -low-> output",
+        "\
+        button -low-> broadcaster\n\
+        broadcaster -low-> a\n\
+        a -low-> inv\n\
+        a -low-> con\n\
+        inv -high-> b\n\
+        con -high-> output",
+        "\
+        button -low-> broadcaster\n\
+        broadcaster -low-> a\n\
+        a -high-> inv\n\
+        a -high-> con\n\
+        inv -low-> b\n\
+        con -low-> output\n\
+        b -low-> con\n\
+        con -high-> output",
+        "\
+        button -low-> broadcaster\n\
+        broadcaster -low-> a\n\
+        a -low-> inv\n\
+        a -low-> con\n\
+        inv -high-> b\n\
+        con -high-> output",
+    ];
+
+    #[test]
+    fn test_trace_example2() -> Result<(), Box<dyn Error>> {
+        let expected = TRACE2
+            .iter()
+            .flat_map(|press| press.lines())
+            .collect::<Vec<_>>();
+        assert_eq!(trace(example2(), 4)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_example1() -> Result<(), Box<dyn Error>> {
+        let result = solve(example1(), 1)?;
+        assert_eq!(result, PulseCount { high: 4, low: 8 });
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_example2() -> Result<(), Box<dyn Error>> {
+        let result = solve(example2(), 1000)?;
+        assert_eq!(
+            result,
+            PulseCount {
+                high: 2750,
+                low: 4250
+            }
+        );
+        Ok(())
+    }
+
+    aoc_tests! {
+        test_solve1_example1: example(EXAMPLE1), parse, solve1 => 32000000;
+        test_solve1_example2: example(EXAMPLE2), parse, solve1 => 11687500;
+        test_solve1_input: file("input"), parse, solve1 => 944750144;
+        test_solve2_input: file("input"), parse, solve2 => 222718819437131;
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        for s in [
+            "broadcaster",
+            "-> a",
+            "% -> a",
+            "& -> a",
+            "%a -> ",
+            "&a -> b, ",
+            "broadcaster -> a, , b",
+        ] {
+            assert!(parse(s.lines().map(|s| s.to_string())).is_err(), "{:?}", s);
+        }
+    }
+}
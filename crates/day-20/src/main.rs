@@ -1,25 +1,45 @@
+use day_20::{diff_states, first_presses, parse, solve1, solve2, verify, Machine, Repl};
 use itertools::Itertools;
-use lib::get_args;
+use lib::{
+    cli::Options,
+    validate::{check_lines, check_not_empty, Problem},
+};
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
     error::Error,
+    fs::{create_dir_all, read, write},
     io::{stdin, BufRead},
-    ops::{Index, IndexMut},
+    iter::once,
+    path::Path,
     process::exit,
 };
 
-fn usage(prog_name: String) {
-    println!("Usage: {} [-1|-2|-h]", prog_name);
+fn usage(options: &Options) {
+    println!(
+        "{}",
+        options.usage(
+            "[-1|-2|--verify N|--trace N|--until MODULE:high|low... [--max-presses N]|--repl|\
+             --snapshot-every N --presses M [--resume FILE]|--diff-snapshots FILE FILE|--validate|-h]",
+        )
+    );
     exit(0)
 }
 
+fn validate(lines: &[String]) -> Vec<Problem> {
+    check_not_empty(lines)
+        .into_iter()
+        .chain(check_lines(lines, 1, |line| parse(once(line.to_string()))))
+        .collect()
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let (prog_name, args) = get_args()?;
+    let options = Options::parse()?;
+    let args = &options.args;
+    let _timer = options.timer();
 
-    match args.get(0) {
+    match options.mode() {
         Some(arg) if arg == "-1" || arg == "-2" => {
-            let nodes = stdin()
-                .lock()
+            let nodes = options
+                .input()?
                 .lines()
                 .process_results(|lines| parse(lines))??;
 
@@ -31,461 +51,162 @@ fn main() -> Result<(), Box<dyn Error>> {
 
             println!("{}", result);
         }
-        _ => usage(prog_name),
-    }
-
-    Ok(())
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Pulse {
-    High,
-    Low,
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum FlipFlopState {
-    On,
-    Off,
-}
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-enum NodeType {
-    Broadcast,
-    FlipFlop { state: FlipFlopState },
-    Conjunction { inputs: HashMap<String, Pulse> },
-}
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct Node {
-    name: String,
-    node_type: NodeType,
-    outputs: Vec<String>,
-}
-
-fn parse(itr: impl Iterator<Item = String>) -> Result<Vec<Node>, Box<dyn Error>> {
-    itr.map(|line| {
-        let (name_str, outputs_str) = line.split_once(" -> ").ok_or("Invalid line")?;
-
-        let outputs = outputs_str
-            .split(", ")
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>();
-
-        let node_type = match name_str.chars().next().ok_or("Invalid line")? {
-            '%' => NodeType::FlipFlop {
-                state: FlipFlopState::Off,
-            },
-            '&' => NodeType::Conjunction {
-                inputs: HashMap::new(),
-            },
-            _ => NodeType::Broadcast,
-        };
-
-        let name_str = name_str.trim_start_matches("&").trim_start_matches("%");
-
-        Ok(Node {
-            name: name_str.to_string(),
-            node_type,
-            outputs,
-        })
-    })
-    .collect()
-}
-
-fn to_map(nodes: Vec<Node>) -> HashMap<String, Node> {
-    nodes
-        .into_iter()
-        .map(|node| (node.name.clone(), node))
-        .collect()
-}
+        Some(arg) if arg == "--verify" => {
+            let presses = args
+                .get(1)
+                .ok_or("Missing number of presses")?
+                .parse::<i64>()?;
+            let nodes = options
+                .input()?
+                .lines()
+                .process_results(|lines| parse(lines))??;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct SendPulse {
-    from: String,
-    to: String,
-    pulse: Pulse,
-}
+            let (counters, mismatches) = verify(nodes, presses)?;
+
+            counters.iter().for_each(|counter| {
+                println!(
+                    "{} -> {}: period {}",
+                    counter.start, counter.output, counter.period
+                )
+            });
+            mismatches.iter().for_each(|mismatch| {
+                println!(
+                    "mismatch on {}: predicted {:?}, observed {:?}",
+                    mismatch.output, mismatch.predicted, mismatch.observed
+                )
+            });
+
+            if !mismatches.is_empty() {
+                return Err("Decoded counters disagree with the simulation".into());
+            }
+        }
+        Some(arg) if arg == "--trace" => {
+            let presses = args
+                .get(1)
+                .ok_or("Missing number of presses")?
+                .parse::<i64>()?;
+            let nodes = options
+                .input()?
+                .lines()
+                .process_results(|lines| parse(lines))??;
 
-fn init_conjunctions(nodes: &mut HashMap<String, Node>) -> () {
-    // updating the hashmap while iterating over it is not possible in Rust that's why we clone it
-    nodes.clone().iter().for_each(|(name, node)| {
-        node.outputs.iter().for_each(|output| {
-            nodes
-                .get_mut(output)
-                .map(|output_node| match &mut output_node.node_type {
-                    NodeType::Conjunction { inputs } => {
-                        inputs.insert(name.clone(), Pulse::Low);
+            let mut machine = Machine::new(nodes);
+            for _ in 0..presses {
+                machine.press_traced(&mut |send_pulse| println!("{}", send_pulse))?;
+            }
+        }
+        Some(arg) if arg == "--until" => {
+            let mut targets = Vec::new();
+            let mut max_presses = None;
+            let mut args = args.iter();
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--until" => targets.push(args.next().ok_or("Missing target")?.parse()?),
+                    "--max-presses" => {
+                        max_presses = Some(args.next().ok_or("Missing presses")?.parse::<i64>()?)
                     }
-                    _ => {}
-                });
-        })
-    })
-}
-
-fn init(nodes: Vec<Node>) -> HashMap<String, Node> {
-    let mut nodes = to_map(nodes);
-    init_conjunctions(&mut nodes);
-
-    nodes
-}
-
-fn solve(nodes: Vec<Node>, count: i32) -> Result<PulseCount, Box<dyn Error>> {
-    let mut nodes = init(nodes);
-
-    push_button_count(&mut nodes, count)
-}
-
-fn solve1(nodes: Vec<Node>) -> Result<i64, Box<dyn Error>> {
-    let result = solve(nodes, 1000)?;
-
-    Ok(result.high * result.low)
-}
-
-fn get_parents(nodes: &HashMap<String, Node>, name: &str) -> Vec<String> {
-    nodes
-        .iter()
-        .filter_map(|(node_name, node)| {
-            node.outputs
-                .contains(&name.to_string())
-                .then_some(node_name.clone())
-        })
-        .collect()
-}
-
-fn solve2(nodes: Vec<Node>) -> Result<i64, Box<dyn Error>> {
-    let mut nodes = init(nodes);
-
-    let rx_parents = get_parents(&nodes, "rx");
-    let rx_grand_parents = rx_parents
-        .iter()
-        .flat_map(|name| get_parents(&nodes, name))
-        .collect::<HashSet<_>>();
-
-    // we will record in this hash map the number of pushes on the button that triggers a high
-    // pulse to the grand parents of rx
-    let mut found_conjunctions: HashMap<String, i64> = HashMap::new();
-    let mut i = 0;
-
-    while found_conjunctions.len() != rx_grand_parents.len() {
-        i += 1;
-        let (_, new_found_conjunctions) = push_button(&mut nodes, &rx_grand_parents)?;
-
-        new_found_conjunctions.iter().for_each(|name| {
-            found_conjunctions.entry(name.clone()).or_insert(i);
-        });
-    }
-
-    // we assume that this number of pushes happen in a cycle then the result might be the product
-    // of all these cycles (or the LCM of all these numbers)
-    Ok(found_conjunctions
-        .iter()
-        .map(|(_, count)| *count)
-        .product::<i64>())
-}
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct PulseCount {
-    high: i64,
-    low: i64,
-}
-
-impl Default for PulseCount {
-    fn default() -> Self {
-        Self { high: 0, low: 0 }
-    }
-}
+                    _ => return Err(format!("Invalid argument: {}", arg).into()),
+                }
+            }
+            let nodes = options
+                .input()?
+                .lines()
+                .process_results(|lines| parse(lines))??;
 
-impl Index<Pulse> for PulseCount {
-    type Output = i64;
+            let presses = first_presses(nodes, &targets, max_presses)?;
 
-    fn index(&self, pulse: Pulse) -> &Self::Output {
-        match pulse {
-            Pulse::High => &self.high,
-            Pulse::Low => &self.low,
+            targets.iter().for_each(|target| match presses.get(target) {
+                Some(press) => println!("{} {}", target, press),
+                None => println!("{} not reached", target),
+            });
         }
-    }
-}
+        Some(arg) if arg == "--repl" => {
+            // stdin is used for the commands
+            if options.input.is_none() {
+                return Err("The input must be given with --input to read the commands".into());
+            }
+            let nodes = options
+                .input()?
+                .lines()
+                .process_results(|lines| parse(lines))??;
 
-impl IndexMut<Pulse> for PulseCount {
-    fn index_mut(&mut self, pulse: Pulse) -> &mut Self::Output {
-        match pulse {
-            Pulse::High => &mut self.high,
-            Pulse::Low => &mut self.low,
+            let mut repl = Repl::new(Machine::new(nodes));
+            stdin().lock().lines().try_for_each(|line| {
+                match repl.execute(&line?) {
+                    Ok(output) => print!("{}", output),
+                    Err(e) => println!("{}", e),
+                }
+                Ok::<(), Box<dyn Error>>(())
+            })?;
         }
-    }
-}
-
-fn push_button(
-    nodes: &mut HashMap<String, Node>,
-    searched_conjunctions: &HashSet<String>,
-) -> Result<(PulseCount, HashSet<String>), Box<dyn Error>> {
-    let mut stack = VecDeque::new();
-    stack.push_back(SendPulse {
-        from: "button".to_string(),
-        to: "broadcaster".to_string(),
-        pulse: Pulse::Low,
-    });
-
-    let mut pulse_count = PulseCount::default();
-    pulse_count[Pulse::Low] += 1;
-
-    let mut found_conjunctions = HashSet::new();
-
-    while let Some(SendPulse { from, to, pulse }) = stack.pop_front() {
-        nodes
-            .get_mut(&to)
-            .map_or(Ok(()), |node| -> Result<_, Box<dyn Error>> {
-                match &mut node.node_type {
-                    NodeType::Broadcast => {
-                        node.outputs.iter().for_each(|output| {
-                            stack.push_back(SendPulse {
-                                from: to.clone(),
-                                to: output.clone(),
-                                pulse,
-                            });
-                        });
-                        pulse_count[pulse] += i64::try_from(node.outputs.len())?;
+        Some(arg) if arg == "--snapshot-every" => {
+            let mut every = None;
+            let mut presses = None;
+            let mut resume = None;
+            let mut args = args.iter();
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--snapshot-every" => {
+                        every = Some(args.next().ok_or("Missing period")?.parse::<i64>()?)
                     }
-                    NodeType::FlipFlop { ref mut state } => match pulse {
-                        Pulse::High => {}
-                        Pulse::Low => {
-                            *state = flip(state);
-                            let pulse = match state {
-                                FlipFlopState::On => Pulse::High,
-                                FlipFlopState::Off => Pulse::Low,
-                            };
-
-                            node.outputs.iter().for_each(|output| {
-                                stack.push_back(SendPulse {
-                                    from: to.clone(),
-                                    to: output.clone(),
-                                    pulse,
-                                });
-                            });
-                            pulse_count[pulse] += i64::try_from(node.outputs.len())?;
-                        }
-                    },
-                    NodeType::Conjunction { inputs } => {
-                        *inputs.get_mut(&from).ok_or("Invalid input")? = pulse;
-                        let all_high = inputs.values().all(|&p| p == Pulse::High);
-                        let pulse = if all_high { Pulse::Low } else { Pulse::High };
-
-                        node.outputs.iter().for_each(|output| {
-                            stack.push_back(SendPulse {
-                                from: to.clone(),
-                                to: output.clone(),
-                                pulse,
-                            });
-                        });
-
-                        pulse_count[pulse] += i64::try_from(node.outputs.len())?;
-
-                        if (pulse == Pulse::High) && searched_conjunctions.contains(&node.name) {
-                            found_conjunctions.insert(node.name.clone());
-                        }
+                    "--presses" => {
+                        presses = Some(args.next().ok_or("Missing presses")?.parse::<i64>()?)
                     }
+                    "--resume" => resume = Some(args.next().ok_or("Missing snapshot")?),
+                    _ => return Err(format!("Invalid argument: {}", arg).into()),
                 }
-                Ok(())
-            })?;
-    }
-
-    Ok((pulse_count, found_conjunctions))
-}
-
-fn push_button_count(
-    nodes: &mut HashMap<String, Node>,
-    count: i32,
-) -> Result<PulseCount, Box<dyn Error>> {
-    (0..count).try_fold(
-        PulseCount::default(),
-        |mut acc, _| -> Result<PulseCount, Box<dyn Error>> {
-            let (new_result, _) = push_button(nodes, &HashSet::new())?;
-            acc.high += new_result.high;
-            acc.low += new_result.low;
-
-            Ok(acc)
-        },
-    )
-}
-
-fn flip(state: &FlipFlopState) -> FlipFlopState {
-    match state {
-        FlipFlopState::On => FlipFlopState::Off,
-        FlipFlopState::Off => FlipFlopState::On,
-    }
-}
-
-#[cfg(test)]
-mod day20 {
-    use std::{
-        collections::HashMap,
-        error::Error,
-        fs::File,
-        io::{BufRead, BufReader},
-    };
-
-    use itertools::Itertools;
-
-    use crate::{parse, solve, solve1, solve2, FlipFlopState, Node, NodeType, PulseCount};
-
-    const EXAMPLE1: &str = "\
-        broadcaster -> a, b, c\n\
-        %a -> b\n\
-        %b -> c\n\
-        %c -> inv\n\
-        &inv -> a";
-
-    fn example1() -> Vec<Node> {
-        vec![
-            Node {
-                name: "broadcaster".to_string(),
-                node_type: NodeType::Broadcast,
-                outputs: vec!["a".to_string(), "b".to_string(), "c".to_string()],
-            },
-            Node {
-                name: "a".to_string(),
-                node_type: NodeType::FlipFlop {
-                    state: FlipFlopState::Off,
-                },
-                outputs: vec!["b".to_string()],
-            },
-            Node {
-                name: "b".to_string(),
-                node_type: NodeType::FlipFlop {
-                    state: FlipFlopState::Off,
-                },
-                outputs: vec!["c".to_string()],
-            },
-            Node {
-                name: "c".to_string(),
-                node_type: NodeType::FlipFlop {
-                    state: FlipFlopState::Off,
-                },
-                outputs: vec!["inv".to_string()],
-            },
-            Node {
-                name: "inv".to_string(),
-                node_type: NodeType::Conjunction {
-                    inputs: HashMap::new(),
-                },
-                outputs: vec!["a".to_string()],
-            },
-        ]
-    }
-
-    const EXAMPLE2: &str = "\
-        broadcaster -> a\n\
-        %a -> inv, con\n\
-        &inv -> b\n\
-        %b -> con\n\
-        &con -> output";
-
-    fn example2() -> Vec<Node> {
-        vec![
-            Node {
-                name: "broadcaster".to_string(),
-                node_type: NodeType::Broadcast,
-                outputs: vec!["a".to_string()],
-            },
-            Node {
-                name: "a".to_string(),
-                node_type: NodeType::FlipFlop {
-                    state: FlipFlopState::Off,
-                },
-                outputs: vec!["inv".to_string(), "con".to_string()],
-            },
-            Node {
-                name: "inv".to_string(),
-                node_type: NodeType::Conjunction {
-                    inputs: HashMap::new(),
-                },
-                outputs: vec!["b".to_string()],
-            },
-            Node {
-                name: "b".to_string(),
-                node_type: NodeType::FlipFlop {
-                    state: FlipFlopState::Off,
-                },
-                outputs: vec!["con".to_string()],
-            },
-            Node {
-                name: "con".to_string(),
-                node_type: NodeType::Conjunction {
-                    inputs: HashMap::new(),
-                },
-                outputs: vec!["output".to_string()],
-            },
-        ]
-    }
-
-    #[test]
-    fn test_parse_example1() -> Result<(), Box<dyn Error>> {
-        let result = parse(EXAMPLE1.lines().map(|s| s.to_string()))?;
-        assert_eq!(result, example1());
-        Ok(())
-    }
-
-    #[test]
-    fn test_parse_example2() -> Result<(), Box<dyn Error>> {
-        let result = parse(EXAMPLE2.lines().map(|s| s.to_string()))?;
-        assert_eq!(result, example2());
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve_example1() -> Result<(), Box<dyn Error>> {
-        let result = solve(example1(), 1)?;
-        assert_eq!(result, PulseCount { high: 4, low: 8 });
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve_example2() -> Result<(), Box<dyn Error>> {
-        let result = solve(example2(), 1000)?;
-        assert_eq!(
-            result,
-            PulseCount {
-                high: 2750,
-                low: 4250
             }
-        );
-        Ok(())
-    }
-
-    #[test]
-    fn test_solve1_example1() -> Result<(), Box<dyn Error>> {
-        let result = solve1(example1())?;
-        assert_eq!(result, 32000000);
-        Ok(())
-    }
+            let every = every.filter(|every| *every > 0).ok_or("Invalid period")?;
+            let presses = presses.ok_or("Missing presses")?;
+            let dir = options
+                .out
+                .as_ref()
+                .ok_or("The snapshots are written in the --out directory")?;
+
+            let nodes = options
+                .input()?
+                .lines()
+                .process_results(|lines| parse(lines))??;
+            let mut machine = Machine::new(nodes);
+            if let Some(path) = resume {
+                machine.restore(&read(path).map_err(|e| format!("{}: {}", path, e))?)?;
+            }
 
-    #[test]
-    fn test_solve1_example2() -> Result<(), Box<dyn Error>> {
-        let result = solve1(example2())?;
-        assert_eq!(result, 11687500);
-        Ok(())
-    }
+            create_dir_all(dir)?;
+            while machine.presses < presses {
+                machine.press()?;
+                if machine.presses % every == 0 {
+                    let path = Path::new(dir).join(format!("day-20-{}.snapshot", machine.presses));
+                    write(&path, machine.snapshot())?;
+                    eprintln!("Wrote {}", path.display());
+                }
+            }
+        }
+        Some(arg) if arg == "--diff-snapshots" => {
+            let nodes = options
+                .input()?
+                .lines()
+                .process_results(|lines| parse(lines))??;
 
-    #[test]
-    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
-        let file = File::open("input")?;
-        let reader = BufReader::new(file);
-        let nodes = reader.lines().process_results(|itr| parse(itr))??;
-        let result = solve1(nodes).unwrap();
+            let mut machines = Vec::new();
+            for path in [args.get(1), args.get(2)] {
+                let path = path.ok_or("Missing snapshot")?;
+                let mut machine = Machine::new(nodes.clone());
+                machine.restore(&read(path).map_err(|e| format!("{}: {}", path, e))?)?;
+                machines.push(machine);
+            }
 
-        assert_eq!(result, 944750144);
-        Ok(())
+            println!(
+                "presses: {} -> {}",
+                machines[0].presses, machines[1].presses
+            );
+            diff_states(&machines[0], &machines[1])?
+                .iter()
+                .for_each(|(name, state1, state2)| println!("{}: {} -> {}", name, state1, state2));
+        }
+        Some(arg) if arg == "--validate" => lib::validate::run(options.input()?, validate)?,
+        _ => usage(&options),
     }
 
-    #[test]
-    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
-        let file = File::open("input")?;
-        let reader = BufReader::new(file);
-        let nodes = reader.lines().process_results(|itr| parse(itr))??;
-        let result = solve2(nodes)?;
-
-        assert_eq!(result, 222718819437131);
-        Ok(())
-    }
+    Ok(())
 }
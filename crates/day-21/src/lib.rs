@@ -0,0 +1,857 @@
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt::{self, Display},
+};
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Coordinates {
+    x: i32,
+    y: i32,
+}
+
+pub struct Grid {
+    rocks: HashSet<Coordinates>,
+    width: usize,
+    height: usize,
+}
+
+fn valid1(grid: &Grid, c: &Coordinates) -> Result<bool, Box<dyn Error>> {
+    Ok(c.x >= 0
+        && c.y >= 0
+        && c.x < i32::try_from(grid.width)?
+        && c.y < i32::try_from(grid.height)?
+        && !grid.rocks.contains(c))
+}
+
+type ValidFn = fn(grid: &Grid, c: &Coordinates) -> Result<bool, Box<dyn Error>>;
+
+fn advance(
+    grid: &Grid,
+    current: &HashSet<Coordinates>,
+    valid: ValidFn,
+) -> Result<HashSet<Coordinates>, Box<dyn Error>> {
+    let mut next = HashSet::new();
+
+    current.iter().try_for_each(|c| {
+        vec![(0, 1), (0, -1), (1, 0), (-1, 0)]
+            .iter()
+            .try_for_each(|(dx, dy)| {
+                let new_c = Coordinates {
+                    x: i32::try_from(c.x)? + dx,
+                    y: i32::try_from(c.y)? + dy,
+                };
+
+                if valid(grid, &new_c)? {
+                    next.insert(new_c);
+                };
+                Ok::<(), Box<dyn Error>>(())
+            })
+    })?;
+
+    Ok(next)
+}
+
+fn advance_count(
+    grid: &Grid,
+    start: &Coordinates,
+    count: i32,
+    valid: ValidFn,
+) -> Result<usize, Box<dyn Error>> {
+    let mut current = HashSet::new();
+    current.insert(start.clone());
+
+    (0..count).try_for_each(|_| {
+        current = advance(grid, &current, valid)?;
+        Ok::<(), Box<dyn Error>>(())
+    })?;
+
+    Ok(current.len())
+}
+
+// minimal number of steps to reach each tile of the bounded grid, None for rocks and unreachable
+// tiles. A tile reachable in d steps is reachable in exactly n steps if d <= n and both have the
+// same parity, going back and forth on the last tile
+pub struct DistanceMap {
+    distances: Vec<Option<usize>>,
+}
+
+impl DistanceMap {
+    pub fn new(grid: &Grid, start: &Coordinates) -> Result<Self, Box<dyn Error>> {
+        let width = i32::try_from(grid.width)?;
+        let height = i32::try_from(grid.height)?;
+        let index = |c: &Coordinates| -> Result<usize, Box<dyn Error>> {
+            Ok(usize::try_from(c.y * width + c.x)?)
+        };
+
+        let mut distances = vec![None; grid.width * grid.height];
+        let mut queue = VecDeque::from([(start.clone(), 0)]);
+        *distances
+            .get_mut(index(start)?)
+            .ok_or("Start out of the grid")? = Some(0);
+
+        while let Some((c, distance)) = queue.pop_front() {
+            [(0, 1), (0, -1), (1, 0), (-1, 0)].iter().try_for_each(
+                |(dx, dy)| -> Result<(), Box<dyn Error>> {
+                    let new_c = Coordinates {
+                        x: c.x + dx,
+                        y: c.y + dy,
+                    };
+
+                    if new_c.x >= 0
+                        && new_c.y >= 0
+                        && new_c.x < width
+                        && new_c.y < height
+                        && !grid.rocks.contains(&new_c)
+                    {
+                        let new_distance =
+                            distances.get_mut(index(&new_c)?).ok_or("Out of the grid")?;
+                        if new_distance.is_none() {
+                            *new_distance = Some(distance + 1);
+                            queue.push_back((new_c, distance + 1));
+                        }
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+
+        Ok(DistanceMap { distances })
+    }
+
+    pub fn count(&self, steps: usize) -> usize {
+        self.distances
+            .iter()
+            .flatten()
+            .filter(|&&distance| distance <= steps && distance % 2 == steps % 2)
+            .count()
+    }
+
+    // one value per tile for the exports: the distance saturated to 254, 255 for rocks and
+    // unreachable tiles
+    pub fn to_rows(&self, grid: &Grid) -> Vec<Vec<u8>> {
+        self.distances
+            .chunks(grid.width.max(1))
+            .map(|row| {
+                row.iter()
+                    .map(|distance| {
+                        distance.map_or(255, |distance| {
+                            u8::try_from(distance.min(254)).unwrap_or(254)
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// a fixed size set of indices stored as bits
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        BitSet {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.words[index / 64] & 1 << (index % 64) != 0
+    }
+
+    // true if the index wasn't in the set yet
+    fn insert(&mut self, index: usize) -> bool {
+        let word = &mut self.words[index / 64];
+        let bit = 1 << (index % 64);
+        let inserted = *word & bit == 0;
+        *word |= bit;
+        inserted
+    }
+}
+
+// the number of tiles reachable in exactly 0, 1, .. `steps` steps, on the grid repeated
+// infinitely if `wrap` is set.
+//
+// the reached tiles are stored in a bit vector, one block of width * height bits per copy of the
+// grid, for all the copies the walk can reach. A tile first reached after d steps is reachable in
+// n steps if d <= n and both have the same parity, so the counts are kept per parity while the
+// frontier, the tiles reached on the last step, moves forward
+fn bitset_counts(
+    grid: &Grid,
+    start: &Coordinates,
+    steps: usize,
+    wrap: bool,
+) -> Result<Vec<usize>, Box<dyn Error>> {
+    let width = i64::try_from(grid.width)?;
+    let height = i64::try_from(grid.height)?;
+    if width == 0 || height == 0 {
+        return Err("Empty grid".into());
+    }
+
+    // the number of copies on each side of the one holding the start
+    let radius = if wrap {
+        i64::try_from(steps)? / width.min(height) + 1
+    } else {
+        0
+    };
+    let side = 2 * radius + 1;
+    let tile_count = usize::try_from(width * height)?;
+
+    let mut rocks = BitSet::new(tile_count);
+    grid.rocks
+        .iter()
+        .try_for_each(|c| -> Result<(), Box<dyn Error>> {
+            rocks.insert(usize::try_from(i64::from(c.y) * width + i64::from(c.x))?);
+            Ok(())
+        })?;
+
+    // the index of a tile in the reached bit vector, None for rocks and tiles out of the window
+    let index = |x: i64, y: i64| -> Option<usize> {
+        let (copy_x, copy_y) = (x.div_euclid(width) + radius, y.div_euclid(height) + radius);
+        let local = usize::try_from(y.rem_euclid(height) * width + x.rem_euclid(width)).ok()?;
+        let copy = usize::try_from(copy_y * side + copy_x).ok()?;
+
+        ((0..side).contains(&copy_x) && (0..side).contains(&copy_y) && !rocks.contains(local))
+            .then_some(copy * tile_count + local)
+    };
+
+    let mut reached = BitSet::new(tile_count * usize::try_from(side * side)?);
+    let start = (i64::from(start.x), i64::from(start.y));
+    reached.insert(index(start.0, start.1).ok_or("Invalid start")?);
+
+    let mut frontier = vec![start];
+    let mut by_parity = [1, 0];
+    let mut counts = vec![1];
+
+    (1..=steps).for_each(|step| {
+        frontier = frontier
+            .iter()
+            .flat_map(|(x, y)| [(x + 1, *y), (x - 1, *y), (*x, y + 1), (*x, y - 1)])
+            .filter(|(x, y)| index(*x, *y).is_some_and(|index| reached.insert(index)))
+            .collect();
+        by_parity[step % 2] += frontier.len();
+        counts.push(by_parity[step % 2]);
+    });
+
+    Ok(counts)
+}
+
+pub fn solve1(grid: &Grid, start: &Coordinates) -> Result<usize, Box<dyn Error>> {
+    advance_count(grid, start, 64, valid1)
+}
+
+// Solution found here:
+// https://github.com/derailed-dash/Advent-of-Code/blob/master/src/AoC_2023/Dazbo's_Advent_of_Code_2023.ipynb
+pub fn solve2(grid: &Grid, start: &Coordinates) -> Result<i64, Box<dyn Error>> {
+    const NO_VALUE: &str = "No value";
+
+    let xs = (0..3).map(|i| 65 + 131 * i).collect::<Vec<_>>();
+    let max_value = xs.iter().max().ok_or("No max value")?;
+    let counts = bitset_counts(grid, start, *max_value, true)?;
+
+    let get_point = |i| {
+        xs.get(i)
+            .and_then(|x| counts.get(*x))
+            .ok_or::<Box<dyn Error>>(NO_VALUE.into())
+            .and_then(|&x: &usize| i64::try_from(x).map_err(|e| e.into()))
+    };
+    let p0 = get_point(0)?;
+    let p1 = get_point(1)?;
+    let p2 = get_point(2)?;
+
+    let c = p0;
+    let b = (4 * p1 - 3 * p0 - p2) / 2;
+    let a = p1 - p0 - b;
+
+    let width = i64::try_from(grid.width)?;
+    let x = (PART2_STEPS - width / 2) / width;
+
+    Ok(a * x * x + b * x + c)
+}
+
+pub const PART2_STEPS: i64 = 26501365;
+
+// the copies of the grid in the diamond reached on the infinite grid, when the start is in the
+// middle of a square grid with free lines through it and on its borders. The sides are unit
+// vectors (x, y), towards the tip for the corners and the diagonal quadrant for the edges
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TileClass {
+    // fully covered, with the same parity as the copy of the start or the other one
+    FullEven,
+    FullOdd,
+    // the tips of the diamond
+    Corner(i64, i64),
+    // the copies on the edges of the diamond, with most of their tiles out of it or in it
+    SmallEdge(i64, i64),
+    LargeEdge(i64, i64),
+}
+
+fn side_name(dx: i64, dy: i64) -> String {
+    let vertical = match dy {
+        -1 => "north",
+        1 => "south",
+        _ => "",
+    };
+    let horizontal = match dx {
+        -1 => "west",
+        1 => "east",
+        _ => "",
+    };
+    [vertical, horizontal]
+        .iter()
+        .filter(|name| !name.is_empty())
+        .join("-")
+}
+
+impl Display for TileClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TileClass::FullEven => write!(f, "full even"),
+            TileClass::FullOdd => write!(f, "full odd"),
+            TileClass::Corner(dx, dy) => write!(f, "corner {}", side_name(*dx, *dy)),
+            TileClass::SmallEdge(dx, dy) => write!(f, "small edge {}", side_name(*dx, *dy)),
+            TileClass::LargeEdge(dx, dy) => write!(f, "large edge {}", side_name(*dx, *dy)),
+        }
+    }
+}
+
+// the class of the copy at (x, y) of the grid, the one of the start being (0, 0), when the walk
+// reaches n copies away on each axis
+fn classify(x: i64, y: i64, n: i64) -> Option<TileClass> {
+    let k = x.abs() + y.abs();
+    let side = (x.signum(), y.signum());
+
+    match k {
+        _ if k < n && k % 2 == 0 => Some(TileClass::FullEven),
+        _ if k < n => Some(TileClass::FullOdd),
+        _ if k == n && (x == 0 || y == 0) => Some(TileClass::Corner(side.0, side.1)),
+        _ if k == n => Some(TileClass::LargeEdge(side.0, side.1)),
+        _ if k == n + 1 && x != 0 && y != 0 => Some(TileClass::SmallEdge(side.0, side.1)),
+        _ => None,
+    }
+}
+
+// the number of copies of a class when the walk reaches n copies away on each axis
+fn copies(class: TileClass, n: i64) -> i64 {
+    let full = |parity| {
+        (0..n)
+            .filter(|k| k % 2 == parity)
+            .map(|k| if k == 0 { 1 } else { 4 * k })
+            .sum()
+    };
+
+    match class {
+        TileClass::FullEven => full(0),
+        TileClass::FullOdd => full(1),
+        TileClass::Corner(_, _) => 1,
+        TileClass::SmallEdge(_, _) => n,
+        TileClass::LargeEdge(_, _) => n - 1,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TileCount {
+    pub class: TileClass,
+    pub copies: i64,
+    // the tiles reached in each copy
+    pub reached: i64,
+}
+
+pub fn tiles_total(tiles: &[TileCount]) -> i64 {
+    tiles.iter().map(|tile| tile.copies * tile.reached).sum()
+}
+
+// the size of the grid and the number of copies reached on each axis
+fn diamond(grid: &Grid, start: &Coordinates, steps: i64) -> Result<(i64, i64), Box<dyn Error>> {
+    let size = i64::try_from(grid.width)?;
+    let half = size / 2;
+    if grid.width != grid.height || size % 2 == 0 {
+        return Err("The grid must be a square with an odd size".into());
+    }
+    if (i64::from(start.x), i64::from(start.y)) != (half, half) {
+        return Err("The start must be in the middle of the grid".into());
+    }
+    // a single copy away, the copy of the start may not be fully covered yet
+    if steps < half + 2 * size || (steps - half) % size != 0 {
+        return Err(format!(
+            "The steps must be {} plus a multiple of {}, at least twice",
+            half, size
+        )
+        .into());
+    }
+
+    Ok((size, (steps - half) / size))
+}
+
+// the tiles reached in exactly `steps` steps on the infinite grid, by class of copies of the grid.
+// The walk is simulated on a 5x5 block of copies, 7x7 when the number of copies reached on each
+// axis is odd so that the parities match, and every copy of a class must then reach the same
+// number of tiles
+pub fn tile_decomposition(
+    grid: &Grid,
+    start: &Coordinates,
+    steps: i64,
+) -> Result<Vec<TileCount>, Box<dyn Error>> {
+    let (size, n) = diamond(grid, start, steps)?;
+    let half = size / 2;
+    let simulated = 2 + n % 2;
+    let radius = simulated;
+    let side = 2 * radius + 1;
+    let big = side * size;
+    let simulated_steps = usize::try_from(half + simulated * size)?;
+
+    // the distances from the start on the block of copies
+    let index = |x: i64, y: i64| usize::try_from(y * big + x);
+    let mut distances: Vec<Option<usize>> = vec![None; usize::try_from(big * big)?];
+    let center = (radius * size + half, radius * size + half);
+    distances[index(center.0, center.1)?] = Some(0);
+    let mut queue = VecDeque::from([(center, 0)]);
+    while let Some(((x, y), distance)) = queue.pop_front() {
+        for (nx, ny) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+            let rock = Coordinates {
+                x: i32::try_from(nx.rem_euclid(size))?,
+                y: i32::try_from(ny.rem_euclid(size))?,
+            };
+            if nx < 0 || ny < 0 || nx >= big || ny >= big || grid.rocks.contains(&rock) {
+                continue;
+            }
+            let next = &mut distances[index(nx, ny)?];
+            if next.is_none() && distance < simulated_steps {
+                *next = Some(distance + 1);
+                queue.push_back(((nx, ny), distance + 1));
+            }
+        }
+    }
+
+    let mut reached: HashMap<TileClass, i64> = HashMap::new();
+    for (copy_x, copy_y) in (-radius..=radius).cartesian_product(-radius..=radius) {
+        let mut count = 0;
+        for (x, y) in (0..size).cartesian_product(0..size) {
+            let i = index((copy_x + radius) * size + x, (copy_y + radius) * size + y)?;
+            if distances[i].is_some_and(|distance| distance % 2 == simulated_steps % 2) {
+                count += 1;
+            }
+        }
+
+        match classify(copy_x, copy_y, simulated) {
+            Some(class) if *reached.entry(class).or_insert(count) != count => {
+                return Err(format!("The copies of {} differ", class).into());
+            }
+            Some(_) => {}
+            None if count != 0 => return Err("Copies reached out of the diamond".into()),
+            None => {}
+        }
+    }
+
+    Ok(reached
+        .into_iter()
+        .sorted()
+        .map(|(class, reached)| TileCount {
+            class,
+            copies: copies(class, n),
+            reached,
+        })
+        .filter(|tile| tile.copies != 0)
+        .collect())
+}
+
+// the same decomposition without walking on the copies. When the lines through the start and the
+// borders of the grid are free, the shortest paths to a copy go along them: all the copies of a
+// class are entered through the same tile with the same number of steps left, the middle of a
+// side for the corners and a corner of the grid for the edges. The full copies are reached on
+// every tile, with the parity of their distance to the start
+pub fn geometric_decomposition(
+    grid: &Grid,
+    start: &Coordinates,
+    steps: i64,
+) -> Result<Vec<TileCount>, Box<dyn Error>> {
+    let (size, n) = diamond(grid, start, steps)?;
+    let half = size / 2;
+
+    let blocked = grid.rocks.iter().any(|rock| {
+        [rock.x, rock.y]
+            .iter()
+            .any(|&z| [0, half, size - 1].contains(&i64::from(z)))
+    });
+    if blocked {
+        return Err("The lines through the start and the borders must be free".into());
+    }
+
+    // entered at (x, y) with the given steps left
+    let reached = |x: i64, y: i64, left: i64| -> Result<i64, Box<dyn Error>> {
+        let entry = Coordinates {
+            x: i32::try_from(x)?,
+            y: i32::try_from(y)?,
+        };
+        let count = DistanceMap::new(grid, &entry)?.count(usize::try_from(left)?);
+        Ok(i64::try_from(count)?)
+    };
+    // the side of the grid opposite to the one of the copy
+    let entry = |d: i64| half - d * half;
+
+    let sides = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+    let quadrants = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+    let mut tiles = vec![
+        TileCount {
+            class: TileClass::FullEven,
+            copies: copies(TileClass::FullEven, n),
+            reached: reached(half, half, steps)?,
+        },
+        TileCount {
+            class: TileClass::FullOdd,
+            copies: copies(TileClass::FullOdd, n),
+            reached: reached(half, half, steps + 1)?,
+        },
+    ];
+    for (dx, dy) in sides {
+        let class = TileClass::Corner(dx, dy);
+        tiles.push(TileCount {
+            class,
+            copies: copies(class, n),
+            reached: reached(entry(dx), entry(dy), size - 1)?,
+        });
+    }
+    for (dx, dy) in quadrants {
+        for (class, left) in [
+            (TileClass::SmallEdge(dx, dy), half - 1),
+            (TileClass::LargeEdge(dx, dy), size + half - 1),
+        ] {
+            tiles.push(TileCount {
+                class,
+                copies: copies(class, n),
+                reached: reached(entry(dx), entry(dy), left)?,
+            });
+        }
+    }
+
+    Ok(tiles.into_iter().sorted_by_key(|tile| tile.class).collect())
+}
+
+pub fn parse(lines: impl Iterator<Item = String>) -> Result<(Grid, Coordinates), Box<dyn Error>> {
+    let mut rocks = HashSet::new();
+    let mut start = None;
+    let mut width = 0;
+    let mut height = 0;
+
+    lines
+        .enumerate()
+        .try_for_each(|(y, line)| -> Result<(), Box<dyn Error>> {
+            if width == 0 {
+                width = line.len();
+            } else if width != line.len() {
+                Err::<_, Box<dyn Error>>("Inconsistent line length".into())?;
+            }
+            height += 1;
+
+            line.chars()
+                .enumerate()
+                .try_for_each(|(x, c)| -> Result<(), Box<dyn Error>> {
+                    let x = i32::try_from(x)?;
+                    let y = i32::try_from(y)?;
+                    match c {
+                        '#' => {
+                            rocks.insert(Coordinates { x, y });
+                        }
+                        'S' => {
+                            if let Some(_) = start {
+                                Err::<_, Box<dyn Error>>("Multiple starts found".into())?;
+                            } else {
+                                start = Some(Coordinates { x, y });
+                            }
+                        }
+                        _ => (),
+                    };
+                    Ok(())
+                })
+        })?;
+
+    Ok((
+        Grid {
+            rocks,
+            width,
+            height,
+        },
+        start.ok_or("No start found")?,
+    ))
+}
+
+#[cfg(test)]
+mod day21 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+
+    use crate::{
+        advance_count, bitset_counts, geometric_decomposition, parse, solve1, solve2,
+        tile_decomposition, tiles_total, valid1, Coordinates, DistanceMap, Grid, TileClass,
+    };
+
+    // the walk on the grid repeated infinitely, the reference for the bit vectors
+    fn valid2(grid: &Grid, c: &Coordinates) -> Result<bool, Box<dyn Error>> {
+        let c_mod = Coordinates {
+            x: i32::rem_euclid(c.x, i32::try_from(grid.width)?),
+            y: i32::rem_euclid(c.y, i32::try_from(grid.height)?),
+        };
+
+        Ok(!grid.rocks.contains(&c_mod))
+    }
+
+    const EXAMPLE: &str = "\
+        ...........\n\
+        .....###.#.\n\
+        .###.##..#.\n\
+        ..#.#...#..\n\
+        ....#.#....\n\
+        .##..S####.\n\
+        .##..#...#.\n\
+        .......##..\n\
+        .##.#.####.\n\
+        .##..##.##.\n\
+        ...........";
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+
+        assert_eq!(grid.width, 11);
+        assert_eq!(grid.height, 11);
+        assert_eq!(grid.rocks.len(), 40);
+        assert_eq!(start, Coordinates { x: 5, y: 5 });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_advance_count_valid1() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+
+        let result = advance_count(&grid, &start, 6, valid1)?;
+        assert_eq!(result, 16);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_distance_map() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        let distance_map = DistanceMap::new(&grid, &start)?;
+
+        assert_eq!(distance_map.count(0), 1);
+        assert_eq!(distance_map.count(1), 2);
+        assert_eq!(distance_map.count(6), 16);
+
+        Ok(())
+    }
+
+    // the walk stays in the bounded grid on every side, the start on the top left corner can only
+    // go right or down
+    #[test]
+    fn test_advance_count_valid1_corner() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(["S.", ".."].iter().map(|s| s.to_string()))?;
+
+        assert_eq!(advance_count(&grid, &start, 1, valid1)?, 2);
+        assert_eq!(advance_count(&grid, &start, 2, valid1)?, 2);
+        assert_eq!(advance_count(&grid, &start, 64, valid1)?, 2);
+        assert_eq!(DistanceMap::new(&grid, &start)?.count(64), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_distance_map_count_valid1() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        let distance_map = DistanceMap::new(&grid, &start)?;
+
+        (0..20).try_for_each(|steps| -> Result<(), Box<dyn Error>> {
+            let expected = advance_count(&grid, &start, steps, valid1)?;
+            assert_eq!(distance_map.count(usize::try_from(steps)?), expected);
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_distance_map_to_rows() -> Result<(), Box<dyn Error>> {
+        // the right column is walled off by the rocks
+        let (grid, start) = parse(["S.#.", "..#."].iter().map(|s| s.to_string()))?;
+        let rows = DistanceMap::new(&grid, &start)?.to_rows(&grid);
+
+        assert_eq!(rows, vec![vec![0, 1, 255, 255], vec![1, 2, 255, 255]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_advance_count_valid2() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+
+        let result = advance_count(&grid, &start, 6, valid2)?;
+        assert_eq!(result, 16);
+
+        let result = advance_count(&grid, &start, 10, valid2)?;
+        assert_eq!(result, 50);
+
+        let result = advance_count(&grid, &start, 50, valid2)?;
+        assert_eq!(result, 1594);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bitset_counts_bounded() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        let counts = bitset_counts(&grid, &start, 20, false)?;
+
+        (0..=20).try_for_each(|steps| -> Result<(), Box<dyn Error>> {
+            let expected = advance_count(&grid, &start, i32::try_from(steps)?, valid1)?;
+            assert_eq!(counts[steps], expected);
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bitset_counts_wrap() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        let counts = bitset_counts(&grid, &start, 5000, true)?;
+
+        (0..=30).try_for_each(|steps| -> Result<(), Box<dyn Error>> {
+            let expected = advance_count(&grid, &start, i32::try_from(steps)?, valid2)?;
+            assert_eq!(counts[steps], expected);
+            Ok(())
+        })?;
+
+        assert_eq!(counts[6], 16);
+        assert_eq!(counts[10], 50);
+        assert_eq!(counts[50], 1594);
+        assert_eq!(counts[100], 6536);
+        assert_eq!(counts[500], 167004);
+        assert_eq!(counts[1000], 668697);
+        assert_eq!(counts[5000], 16733044);
+
+        Ok(())
+    }
+
+    // free lines through the start and on the borders, like the input
+    const OPEN: &str = "\
+        ...........\n\
+        .##.....#..\n\
+        ..#....##..\n\
+        .#.........\n\
+        ...#....#..\n\
+        .....S.....\n\
+        .#......##.\n\
+        ..#......#.\n\
+        .##....#...\n\
+        ....#......\n\
+        ...........";
+
+    #[test]
+    fn test_tile_decomposition() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(OPEN.lines().map(|s| s.to_string()))?;
+        let counts = bitset_counts(&grid, &start, 5 + 11 * 9, true)?;
+
+        for n in 2..=9 {
+            let steps = 5 + 11 * n;
+            let tiles = tile_decomposition(&grid, &start, steps)?;
+            assert_eq!(
+                tiles_total(&tiles),
+                i64::try_from(counts[usize::try_from(steps)?])?
+            );
+
+            // the 2 full parities, 4 corners, 4 small edges and 4 large edges
+            assert_eq!(tiles.len(), 14);
+            let copies = |class| {
+                tiles
+                    .iter()
+                    .find(|tile| tile.class == class)
+                    .map(|tile| tile.copies)
+            };
+            assert_eq!(copies(TileClass::Corner(0, -1)), Some(1));
+            assert_eq!(copies(TileClass::SmallEdge(1, 1)), Some(n));
+            assert_eq!(
+                tiles.iter().map(|tile| tile.copies).sum::<i64>(),
+                // the full diamond, then the corners and the edges of each side
+                (n * n + (n - 1) * (n - 1)) + 4 + 4 * n + 4 * (n - 1)
+            );
+        }
+
+        // the walls of the example keep the straight lines from the start from being free, the copies
+        // of a class aren't reached the same way
+        let (grid, start) = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        assert!(tile_decomposition(&grid, &start, 5 + 11 * 2).is_err());
+
+        let (grid, start) = parse(OPEN.lines().map(|s| s.to_string()))?;
+        assert!(tile_decomposition(&grid, &start, 5).is_err());
+        assert!(tile_decomposition(&grid, &start, 5 + 11).is_err());
+        assert!(tile_decomposition(&grid, &start, 5 + 11 * 2 + 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_geometric_decomposition() -> Result<(), Box<dyn Error>> {
+        let (grid, start) = parse(OPEN.lines().map(|s| s.to_string()))?;
+        let counts = bitset_counts(&grid, &start, 5 + 11 * 9, true)?;
+
+        for n in 2..=9 {
+            let steps = 5 + 11 * n;
+            let tiles = geometric_decomposition(&grid, &start, steps)?;
+            assert_eq!(tiles, tile_decomposition(&grid, &start, steps)?);
+            assert_eq!(
+                tiles_total(&tiles),
+                i64::try_from(counts[usize::try_from(steps)?])?
+            );
+        }
+
+        // the example has rocks on the line through the start
+        let (grid, start) = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        assert!(geometric_decomposition(&grid, &start, 5 + 11 * 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tile_decomposition_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let (grid, start) = reader.lines().process_results(|itr| parse(itr))??;
+
+        let tiles = tile_decomposition(&grid, &start, 26501365)?;
+        assert_eq!(tiles_total(&tiles), solve2(&grid, &start)?);
+        assert_eq!(tiles, geometric_decomposition(&grid, &start, 26501365)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let (grid, start) = reader.lines().process_results(|itr| parse(itr))??;
+
+        let result = solve1(&grid, &start)?;
+        assert_eq!(result, 3758);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let (grid, start) = reader.lines().process_results(|itr| parse(itr))??;
+
+        let result = solve2(&grid, &start)?;
+        assert_eq!(result, 621494544278648);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,787 @@
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    str::FromStr,
+};
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Brick {
+    pub from: Coordinate,
+    pub to: Coordinate,
+}
+
+impl FromStr for Brick {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (p1_str, p2_str) = s.split_once("~").ok_or("Invalid line")?;
+
+        let p1 = p1_str.parse::<Coordinate>()?;
+        let p2 = p2_str.parse::<Coordinate>()?;
+
+        Ok(Brick { from: p1, to: p2 })
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Coordinate {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl FromStr for Coordinate {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y, z) = s
+            .split_once(",")
+            .and_then(|(x, yz)| yz.split_once(",").map(|(y, z)| (x, y, z)))
+            .ok_or("Invalid coordinate")?;
+
+        Ok(Coordinate {
+            x: x.parse()?,
+            y: y.parse()?,
+            z: z.parse()?,
+        })
+    }
+}
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Vec<Brick>, Box<dyn Error>> {
+    let bricks = itr
+        .map(|line| line.parse())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    normalize(&bricks)
+}
+
+// translate the bricks so that the lowest one lies on the ground at z = 1 and x and y start at 0,
+// which is where fall expects them
+pub fn normalize(bricks: &[Brick]) -> Result<Vec<Brick>, Box<dyn Error>> {
+    let min = |coordinate: fn(&Coordinate) -> i64| {
+        bricks
+            .iter()
+            .flat_map(|brick| [coordinate(&brick.from), coordinate(&brick.to)])
+            .min()
+            .unwrap_or(0)
+    };
+    let (min_x, min_y, min_z) = (min(|c| c.x), min(|c| c.y), min(|c| c.z));
+
+    let translate = |c: &Coordinate| -> Result<Coordinate, Box<dyn Error>> {
+        Ok(Coordinate {
+            x: c.x.checked_sub(min_x).ok_or("Coordinates too far apart")?,
+            y: c.y.checked_sub(min_y).ok_or("Coordinates too far apart")?,
+            z: c.z
+                .checked_sub(min_z)
+                .and_then(|z| z.checked_add(1))
+                .ok_or("Coordinates too far apart")?,
+        })
+    };
+
+    bricks
+        .iter()
+        .map(|brick| {
+            Ok(Brick {
+                from: translate(&brick.from)?,
+                to: translate(&brick.to)?,
+            })
+        })
+        .collect()
+}
+
+pub fn fall(bricks: &Vec<Brick>) -> Vec<Brick> {
+    // sort the bricks by z ascending
+    let mut sorted_bricks = bricks.clone();
+    sorted_bricks.sort_by_key(|b| bottom(b));
+
+    let result: Vec<Brick> =
+        sorted_bricks
+            .iter()
+            .enumerate()
+            .fold(vec![], |mut acc, (i, current_brick)| {
+                // get the highest fallen bricks in (0..=i).rev()
+                let highest_intersected_brick = acc
+                    // in fallen bricks
+                    .get(0..i)
+                    .unwrap_or(&[])
+                    .iter()
+                    // that intersect with current brick
+                    .filter(|other| intersect_xy(current_brick, *other))
+                    // in reverse top z order
+                    .sorted_by_key(|b| top(b))
+                    .rev()
+                    .next();
+
+                let new_bottom = highest_intersected_brick.map_or(1, |b| top(b) + 1);
+                let moved_brick = move_bottom_to(current_brick, new_bottom);
+
+                acc.push(moved_brick);
+                acc
+            });
+
+    result
+}
+
+pub fn solve1(bricks: &Vec<Brick>) -> Result<i32, Box<dyn Error>> {
+    let supporters = bricks.iter().fold(HashMap::new(), |mut acc, b| {
+        let below_b = bricks
+            .iter()
+            .filter(|other| *other != b && top(other) + 1 == bottom(b) && intersect_xy(b, other))
+            .collect::<Vec<_>>();
+
+        acc.insert(b, below_b);
+        acc
+    });
+
+    let unsafe_to_delete = bricks
+        .iter()
+        .filter_map(|b| {
+            supporters
+                .get(b)
+                .and_then(|s| (s.len() == 1).then_some(s.get(0)).flatten())
+        })
+        .collect::<HashSet<_>>()
+        .len();
+
+    Ok(i32::try_from(bricks.len())? - i32::try_from(unsafe_to_delete)?)
+}
+
+pub fn solve2(bricks: &Vec<Brick>) -> Result<i32, Box<dyn Error>> {
+    let tower = Tower::new(bricks.clone());
+
+    (0..bricks.len())
+        .map(|i| Ok(i32::try_from(tower.remove(i).fallen.len())?))
+        .sum()
+}
+
+// the fallen bricks with, for each of them, the bricks right below and right above it. Removing a
+// brick only walks the bricks above it
+pub struct Tower {
+    bricks: Vec<Brick>,
+    supporters: Vec<Vec<usize>>,
+    supporting: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct FallReport {
+    removed: usize,
+    // in the order they start falling
+    fallen: Vec<usize>,
+}
+
+impl Tower {
+    pub fn new(bricks: Vec<Brick>) -> Self {
+        let supporters = supporter_indices(&bricks);
+        let mut supporting = vec![vec![]; bricks.len()];
+        for (i, below) in supporters.iter().enumerate() {
+            below.iter().for_each(|j| supporting[*j].push(i));
+        }
+
+        Tower {
+            bricks,
+            supporters,
+            supporting,
+        }
+    }
+
+    // the index of the brick occupying the coordinate
+    fn brick_at(&self, coordinate: &Coordinate) -> Option<usize> {
+        self.bricks.iter().position(|brick| {
+            (left(brick)..=right(brick)).contains(&coordinate.x)
+                && (back(brick)..=front(brick)).contains(&coordinate.y)
+                && (bottom(brick)..=top(brick)).contains(&coordinate.z)
+        })
+    }
+
+    // the bricks falling when the brick at index is disintegrated. A brick falls once all its
+    // supporters have, so only the count of its remaining supporters is kept, for the bricks
+    // reached so far
+    fn remove(&self, index: usize) -> FallReport {
+        let mut remaining: HashMap<usize, usize> = HashMap::new();
+        let mut fallen = vec![];
+        let mut queue = VecDeque::from([index]);
+
+        while let Some(brick) = queue.pop_front() {
+            for above in self.supporting.get(brick).into_iter().flatten() {
+                let count = remaining
+                    .entry(*above)
+                    .or_insert(self.supporters[*above].len());
+                *count -= 1;
+                if *count == 0 {
+                    fallen.push(*above);
+                    queue.push_back(*above);
+                }
+            }
+        }
+
+        FallReport {
+            removed: index,
+            fallen,
+        }
+    }
+}
+
+pub fn show(brick: &Brick) -> String {
+    format!(
+        "{},{},{}~{},{},{}",
+        brick.from.x, brick.from.y, brick.from.z, brick.to.x, brick.to.y, brick.to.z
+    )
+}
+
+// prints the bricks falling when the brick at the coordinate is disintegrated
+pub fn query(tower: &Tower, coordinate: &str) -> Result<(), Box<dyn Error>> {
+    let coordinate = coordinate.trim().parse::<Coordinate>()?;
+    let index = tower
+        .brick_at(&coordinate)
+        .ok_or(format!("No brick at {}", coordinate_str(&coordinate)))?;
+    let report = tower.remove(index);
+
+    println!(
+        "{} makes {} bricks fall",
+        show(&tower.bricks[report.removed]),
+        report.fallen.len()
+    );
+    report
+        .fallen
+        .iter()
+        .for_each(|i| println!("{}", show(&tower.bricks[*i])));
+
+    Ok(())
+}
+
+fn coordinate_str(coordinate: &Coordinate) -> String {
+    format!("{},{},{}", coordinate.x, coordinate.y, coordinate.z)
+}
+
+// above this number of bricks to choose from, the minimum set of bricks to keep is approximated
+pub const EXACT_LIMIT: usize = 20;
+
+pub struct SafeSet<'a> {
+    pub bricks: Vec<&'a Brick>,
+    pub exact: bool,
+}
+
+// the indices of the bricks directly below each brick
+fn supporter_indices(bricks: &[Brick]) -> Vec<Vec<usize>> {
+    bricks
+        .iter()
+        .map(|b| {
+            bricks
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| {
+                    *other != b && top(other) + 1 == bottom(b) && intersect_xy(b, other)
+                })
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect()
+}
+
+fn hits_all(kept: &HashSet<usize>, supports: &[Vec<usize>]) -> bool {
+    supports
+        .iter()
+        .all(|support| support.iter().any(|i| kept.contains(i)))
+}
+
+// a set of bricks can be disintegrated at once if no brick, disintegrated or not, loses all its
+// supporters. So the bricks to keep must hit every set of supporters, and the largest safe set is
+// the complement of the smallest such hitting set.
+pub fn max_safe_set(bricks: &[Brick], exact_limit: usize) -> SafeSet<'_> {
+    let supports = supporter_indices(bricks)
+        .into_iter()
+        .filter(|support| !support.is_empty())
+        .unique()
+        .collect::<Vec<_>>();
+
+    // the sole supporter of a brick must always be kept
+    let mut kept = supports
+        .iter()
+        .filter(|support| support.len() == 1)
+        .flatten()
+        .copied()
+        .collect::<HashSet<_>>();
+
+    let remaining = supports
+        .into_iter()
+        .filter(|support| !support.iter().any(|i| kept.contains(i)))
+        .collect::<Vec<_>>();
+    let candidates = remaining.iter().flatten().copied().unique().collect_vec();
+
+    let exact = candidates.len() <= exact_limit;
+    if exact {
+        // try all the subsets of the candidates, the smallest first
+        let best = (0..=candidates.len())
+            .flat_map(|size| candidates.iter().copied().combinations(size))
+            .find(|subset| hits_all(&subset.iter().copied().collect(), &remaining))
+            .unwrap_or_default();
+        kept.extend(best);
+    } else {
+        // keep the brick supporting the most bricks until they are all supported
+        let mut remaining = remaining;
+        while let Some(best) = remaining
+            .iter()
+            .flatten()
+            .copied()
+            .counts()
+            .into_iter()
+            .max_by_key(|(i, count)| (*count, usize::MAX - i))
+            .map(|(i, _)| i)
+        {
+            kept.insert(best);
+            remaining.retain(|support| !support.contains(&best));
+        }
+    }
+
+    SafeSet {
+        bricks: bricks
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !kept.contains(i))
+            .map(|(_, brick)| brick)
+            .collect(),
+        exact,
+    }
+}
+
+fn intersect_xy(brick1: &Brick, brick2: &Brick) -> bool {
+    !disjoint_xy(brick1, brick2)
+}
+
+fn disjoint_xy(brick1: &Brick, brick2: &Brick) -> bool {
+    left(brick1) > right(brick2)
+        || left(brick2) > right(brick1)
+        || back(brick1) > front(brick2)
+        || back(brick2) > front(brick1)
+}
+
+fn top(brick: &Brick) -> i64 {
+    brick.from.z.max(brick.to.z)
+}
+
+fn bottom(brick: &Brick) -> i64 {
+    brick.from.z.min(brick.to.z)
+}
+
+fn left(brick: &Brick) -> i64 {
+    brick.from.x.min(brick.to.x)
+}
+
+fn right(brick: &Brick) -> i64 {
+    brick.from.x.max(brick.to.x)
+}
+
+fn front(brick: &Brick) -> i64 {
+    brick.from.y.max(brick.to.y)
+}
+
+fn back(brick: &Brick) -> i64 {
+    brick.from.y.min(brick.to.y)
+}
+
+fn move_bottom_to(brick: &Brick, z: i64) -> Brick {
+    let offset = bottom(brick) - z;
+    Brick {
+        from: Coordinate {
+            x: brick.from.x,
+            y: brick.from.y,
+            z: brick.from.z - offset,
+        },
+        to: Coordinate {
+            x: brick.to.x,
+            y: brick.to.y,
+            z: brick.to.z - offset,
+        },
+    }
+}
+
+#[cfg(test)]
+mod day22 {
+    use std::{
+        collections::{HashMap, HashSet},
+        error::Error,
+    };
+
+    use itertools::Itertools;
+    use lib::aoc_tests;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::normalize;
+
+    use crate::{
+        bottom, fall, hits_all, intersect_xy, max_safe_set, move_bottom_to, parse, solve1, solve2,
+        supporter_indices, Brick, Coordinate, FallReport, Tower,
+    };
+
+    const EXAMPLE: &str = "\
+        1,0,1~1,2,1\n\
+        0,0,2~2,0,2\n\
+        0,2,3~2,2,3\n\
+        0,0,4~0,2,4\n\
+        2,0,5~2,2,5\n\
+        0,1,6~2,1,6\n\
+        1,1,8~1,1,9";
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        let bricks = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+
+        assert_eq!(bricks.len(), 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fall() -> Result<(), Box<dyn Error>> {
+        let bricks = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        let fallen_bricks = fall(&bricks);
+
+        assert_eq!(fallen_bricks.len(), 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_intersect() -> Result<(), Box<dyn Error>> {
+        let bricks = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        let brick_a = &bricks.get(0).ok_or("No brick")?;
+        let brick_b = &bricks.get(1).ok_or("No brick")?;
+        // let brick_c = &bricks.get(2).ok_or("No brick")?;
+        let brick_d = &bricks.get(3).ok_or("No brick")?;
+        let brick_e = &bricks.get(4).ok_or("No brick")?;
+        let brick_f = &bricks.get(5).ok_or("No brick")?;
+        // let brick_g = &bricks.get(6).ok_or("No brick")?;
+
+        assert!(intersect_xy(brick_a, brick_b));
+        assert!(intersect_xy(brick_b, brick_a));
+        assert!(intersect_xy(brick_d, brick_f));
+        assert!(intersect_xy(brick_f, brick_d));
+        assert!(intersect_xy(brick_e, brick_f));
+        assert!(intersect_xy(brick_f, brick_e));
+
+        Ok(())
+    }
+
+    aoc_tests! {
+        test_solve1_example: example(EXAMPLE), parse, |bricks| solve1(&fall(&bricks)) => 5;
+        test_solve2_example: example(EXAMPLE), parse, |bricks| solve2(&fall(&bricks)) => 7;
+        test_solve1_input: file("input"), parse, |bricks| solve1(&fall(&bricks)) => 432;
+        test_solve2_input: file("input"), parse, |bricks| solve2(&fall(&bricks)) => 63166;
+    }
+
+    #[test]
+    fn test_tower_remove() -> Result<(), Box<dyn Error>> {
+        let bricks = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        let tower = Tower::new(fall(&bricks));
+
+        // A takes everything down, F only G, and nothing rests on G alone
+        let report = tower.remove(0);
+        assert_eq!(report.removed, 0);
+        assert_eq!(
+            report.fallen.iter().sorted().collect_vec(),
+            vec![&1, &2, &3, &4, &5, &6]
+        );
+        assert_eq!(
+            tower.remove(5),
+            FallReport {
+                removed: 5,
+                fallen: vec![6]
+            }
+        );
+        for i in [1, 2, 3, 4, 6] {
+            assert!(tower.remove(i).fallen.is_empty());
+        }
+
+        // the coordinates are the ones of the fallen bricks, C is now next to B and E next to D
+        assert_eq!(tower.brick_at(&Coordinate { x: 1, y: 1, z: 6 }), Some(6));
+        assert_eq!(tower.brick_at(&Coordinate { x: 0, y: 2, z: 2 }), Some(2));
+        assert_eq!(tower.brick_at(&Coordinate { x: 2, y: 2, z: 3 }), Some(4));
+        assert_eq!(tower.brick_at(&Coordinate { x: 1, y: 1, z: 8 }), None);
+
+        Ok(())
+    }
+
+    // the example moved by the offset, parsed the same way as the input
+    fn translated(dx: i64, dy: i64, dz: i64) -> Result<Vec<Brick>, Box<dyn Error>> {
+        let lines = parse(EXAMPLE.lines().map(|s| s.to_string()))?
+            .iter()
+            .map(|brick| {
+                format!(
+                    "{},{},{}~{},{},{}",
+                    brick.from.x + dx,
+                    brick.from.y + dy,
+                    brick.from.z + dz,
+                    brick.to.x + dx,
+                    brick.to.y + dy,
+                    brick.to.z + dz
+                )
+            })
+            .collect::<Vec<_>>();
+
+        parse(lines.into_iter())
+    }
+
+    #[test]
+    fn test_normalize() -> Result<(), Box<dyn Error>> {
+        let bricks = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        assert_eq!(normalize(&bricks)?, bricks);
+
+        for (dx, dy, dz) in [
+            (-10, -20, -30),
+            (-1_000_000_000_000, 5, 0),
+            (3, -7, 1_000_000_000_000_000),
+            (i64::MIN / 2, i64::MAX / 2, i64::MAX - 10),
+        ] {
+            assert_eq!(translated(dx, dy, dz)?, bricks);
+
+            let fallen_bricks = fall(&translated(dx, dy, dz)?);
+            assert_eq!(solve1(&fallen_bricks)?, 5);
+            assert_eq!(solve2(&fallen_bricks)?, 7);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_overflow() {
+        let bricks = parse(
+            [
+                "0,0,1~0,0,1",
+                "9223372036854775807,0,1~9223372036854775807,0,1",
+            ]
+            .map(String::from)
+            .into_iter(),
+        );
+        assert!(bricks.is_ok());
+
+        let bricks = parse(
+            [
+                "-1,0,1~-1,0,1",
+                "9223372036854775807,0,1~9223372036854775807,0,1",
+            ]
+            .map(String::from)
+            .into_iter(),
+        );
+        assert!(bricks.is_err());
+
+        let bricks = parse(
+            [
+                "0,0,-9223372036854775808~0,0,-9223372036854775808",
+                "0,0,0~0,0,0",
+            ]
+            .map(String::from)
+            .into_iter(),
+        );
+        assert!(bricks.is_err());
+    }
+
+    #[test]
+    fn test_max_safe_set_example() -> Result<(), Box<dyn Error>> {
+        let bricks = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        let fallen_bricks = fall(&bricks);
+        let supports = supporter_indices(&fallen_bricks)
+            .into_iter()
+            .filter(|support| !support.is_empty())
+            .collect::<Vec<_>>();
+
+        // exact and greedy
+        for exact_limit in [20, 0] {
+            let safe_set = max_safe_set(&fallen_bricks, exact_limit);
+            assert_eq!(safe_set.exact, exact_limit != 0);
+            // A and F must stay, as well as one of B and C and one of D and E
+            assert_eq!(safe_set.bricks.len(), 3);
+
+            let kept = fallen_bricks
+                .iter()
+                .enumerate()
+                .filter(|(_, brick)| !safe_set.bricks.contains(brick))
+                .map(|(i, _)| i)
+                .collect();
+            assert!(hits_all(&kept, &supports));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_safe_set_ground() -> Result<(), Box<dyn Error>> {
+        // nothing rests on these bricks, they can all go
+        let bricks = parse(
+            ["0,0,1~0,0,1", "2,0,1~2,0,1", "4,0,1~4,0,3"]
+                .map(String::from)
+                .into_iter(),
+        )?;
+        let fallen_bricks = fall(&bricks);
+        let safe_set = max_safe_set(&fallen_bricks, 20);
+        assert_eq!(safe_set.bricks.len(), 3);
+        Ok(())
+    }
+
+    type Voxel = (i64, i64, i64);
+
+    // the cells of a brick, whatever the order of its ends
+    fn voxels(brick: &Brick) -> Vec<Voxel> {
+        let range = |a: i64, b: i64| a.min(b)..=a.max(b);
+
+        range(brick.from.x, brick.to.x)
+            .cartesian_product(range(brick.from.y, brick.to.y))
+            .cartesian_product(range(brick.from.z, brick.to.z))
+            .map(|((x, y), z)| (x, y, z))
+            .collect()
+    }
+
+    // a slow but simple settle to check fall against: the bricks fill an occupancy grid, and any
+    // brick with only free cells below it goes down one cell, until none can. The supporters of a
+    // brick are then the ones right below any of its cells
+    fn settle_voxels(bricks: &[Brick]) -> (Vec<Brick>, Vec<HashSet<usize>>) {
+        let mut bricks = bricks.to_vec();
+        let mut grid: HashMap<Voxel, usize> = bricks
+            .iter()
+            .enumerate()
+            .flat_map(|(i, brick)| voxels(brick).into_iter().map(move |voxel| (voxel, i)))
+            .collect();
+
+        let mut moved = true;
+        while moved {
+            moved = false;
+            for (i, brick) in bricks.iter_mut().enumerate() {
+                let cells = voxels(brick);
+                let free = cells
+                    .iter()
+                    .all(|&(x, y, z)| z > 1 && grid.get(&(x, y, z - 1)).is_none_or(|j| *j == i));
+                if free {
+                    cells.iter().for_each(|voxel| {
+                        grid.remove(voxel);
+                    });
+                    cells.iter().for_each(|&(x, y, z)| {
+                        grid.insert((x, y, z - 1), i);
+                    });
+                    *brick = move_bottom_to(brick, bottom(brick) - 1);
+                    moved = true;
+                }
+            }
+        }
+
+        let supporters = bricks
+            .iter()
+            .enumerate()
+            .map(|(i, brick)| {
+                voxels(brick)
+                    .iter()
+                    .filter_map(|&(x, y, z)| grid.get(&(x, y, z - 1)).copied())
+                    .filter(|j| *j != i)
+                    .collect()
+            })
+            .collect();
+
+        (bricks, supporters)
+    }
+
+    fn check_against_voxels(bricks: &[Brick]) -> Result<(), Box<dyn Error>> {
+        let (settled, supporters) = settle_voxels(bricks);
+        let fallen = fall(&bricks.to_vec());
+
+        // fall sorts the bricks, they are matched on where they end up
+        let indices = settled
+            .iter()
+            .enumerate()
+            .map(|(i, brick)| (brick, i))
+            .collect::<HashMap<_, _>>();
+        let order = fallen
+            .iter()
+            .map(|brick| indices.get(brick).copied())
+            .collect::<Option<Vec<_>>>()
+            .ok_or("fall and the voxels disagree on a position")?;
+        assert_eq!(order.iter().unique().count(), bricks.len());
+
+        for (i, below) in supporter_indices(&fallen).iter().enumerate() {
+            let below = below.iter().map(|j| order[*j]).collect::<HashSet<_>>();
+            assert_eq!(below, supporters[order[i]]);
+        }
+
+        // a brick is safe to disintegrate unless it is the only supporter of another one
+        let sole_supporters = supporters
+            .iter()
+            .filter(|below| below.len() == 1)
+            .flatten()
+            .unique()
+            .count();
+        assert_eq!(
+            solve1(&fallen)?,
+            i32::try_from(bricks.len() - sole_supporters)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fall_voxels_example() -> Result<(), Box<dyn Error>> {
+        check_against_voxels(&parse(EXAMPLE.lines().map(|s| s.to_string()))?)
+    }
+
+    // bricks of up to three cubes in any direction, with their ends in any order, crowded in a
+    // narrow column so that they pile up
+    fn generate_bricks(count: usize, rng: &mut StdRng) -> Vec<Brick> {
+        let mut bricks = Vec::new();
+        let mut taken = HashSet::new();
+
+        while bricks.len() < count {
+            let (x, y, z) = (
+                rng.gen_range(0..4),
+                rng.gen_range(0..4),
+                rng.gen_range(1..40),
+            );
+            let length = rng.gen_range(0..3);
+            let (to_x, to_y, to_z) = match rng.gen_range(0..3) {
+                0 => (x + length, y, z),
+                1 => (x, y + length, z),
+                _ => (x, y, z + length),
+            };
+            let (from, to) = (
+                Coordinate { x, y, z },
+                Coordinate {
+                    x: to_x,
+                    y: to_y,
+                    z: to_z,
+                },
+            );
+            let brick = if rng.gen_bool(0.5) {
+                Brick { from, to }
+            } else {
+                Brick { from: to, to: from }
+            };
+
+            let cells = voxels(&brick);
+            if cells.iter().all(|voxel| !taken.contains(voxel)) {
+                taken.extend(cells);
+                bricks.push(brick);
+            }
+        }
+
+        bricks
+    }
+
+    #[test]
+    fn test_fall_voxels_generated() -> Result<(), Box<dyn Error>> {
+        let mut rng = StdRng::seed_from_u64(22);
+        for _ in 0..100 {
+            let count = rng.gen_range(1..25);
+            check_against_voxels(&generate_bricks(count, &mut rng))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        for s in [
+            "1,0,1",
+            "1,0,1~1,2",
+            "1,0,1~1,2,x",
+            "1,0~1,2,3",
+            "-9223372036854775808,0,1~9223372036854775807,0,1",
+            "0,0,-9223372036854775808~0,0,9223372036854775807",
+        ] {
+            assert!(parse(s.lines().map(|s| s.to_string())).is_err(), "{:?}", s);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use rand::{seq::SliceRandom, Rng};
+use std::{collections::VecDeque, error::Error};
+
+// a cell of the maze, the tile at (2 * x + 1, 2 * y + 1) of the map
+type Cell = (usize, usize);
+
+// the walls between a cell and its neighbors on the right and below
+fn walls((x, y): Cell, columns: usize, rows: usize) -> impl Iterator<Item = (Cell, Cell)> {
+    [(x + 1, y), (x, y + 1)]
+        .into_iter()
+        .filter(move |(nx, ny)| *nx < columns && *ny < rows)
+        .map(move |next| ((x, y), next))
+}
+
+fn neighbors((x, y): Cell, columns: usize, rows: usize) -> Vec<Cell> {
+    [
+        x.checked_add(1).map(|x| (x, y)),
+        x.checked_sub(1).map(|x| (x, y)),
+        y.checked_add(1).map(|y| (x, y)),
+        y.checked_sub(1).map(|y| (x, y)),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|(x, y)| *x < columns && *y < rows)
+    .collect()
+}
+
+// the tile between two adjacent cells
+fn opening((x1, y1): Cell, (x2, y2): Cell) -> (usize, usize) {
+    (x1 + x2 + 1, y1 + y2 + 1)
+}
+
+// a random map in the puzzle format. A perfect maze is carved first with a randomized depth first
+// search, so there is a single path between the start and the end. Then each remaining wall
+// between two cells is opened with the given density, which creates loops and thus junctions.
+// Slopes are put next to the junctions, pointing away from the start, so that the shortest path
+// is always walkable in part 1.
+pub fn generate_map(
+    width: usize,
+    height: usize,
+    density: f64,
+    rng: &mut impl Rng,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    if width < 3 || height < 3 || width.is_multiple_of(2) || height.is_multiple_of(2) {
+        return Err(format!(
+            "invalid size: {}x{}, must be odd and at least 3",
+            width, height
+        )
+        .into());
+    }
+    if !(0.0..=1.0).contains(&density) {
+        return Err(format!("invalid density: {}", density).into());
+    }
+
+    let (columns, rows) = (width / 2, height / 2);
+    let mut tiles = vec![vec!['#'; width]; height];
+    for (x, y) in (0..columns).flat_map(|x| (0..rows).map(move |y| (x, y))) {
+        tiles[2 * y + 1][2 * x + 1] = '.';
+    }
+    tiles[0][1] = '.';
+    tiles[height - 1][width - 2] = '.';
+
+    // the perfect maze
+    let mut visited = vec![vec![false; columns]; rows];
+    visited[0][0] = true;
+    let mut stack = vec![(0, 0)];
+    while let Some(&cell) = stack.last() {
+        let next = neighbors(cell, columns, rows)
+            .into_iter()
+            .filter(|(x, y)| !visited[*y][*x])
+            .collect::<Vec<_>>()
+            .choose(rng)
+            .copied();
+
+        match next {
+            Some((x, y)) => {
+                visited[y][x] = true;
+                let (ox, oy) = opening(cell, (x, y));
+                tiles[oy][ox] = '.';
+                stack.push((x, y));
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    // the loops
+    for (from, to) in (0..columns)
+        .flat_map(|x| (0..rows).map(move |y| (x, y)))
+        .flat_map(|cell| walls(cell, columns, rows))
+    {
+        let (ox, oy) = opening(from, to);
+        if tiles[oy][ox] == '#' && rng.gen_bool(density) {
+            tiles[oy][ox] = '.';
+        }
+    }
+
+    let is_open = |tiles: &[Vec<char>], from: Cell, to: Cell| {
+        let (ox, oy) = opening(from, to);
+        tiles[oy][ox] != '#'
+    };
+
+    // the distances from the start, adjacent cells are always one step apart on this grid
+    let mut distances = vec![vec![usize::MAX; columns]; rows];
+    distances[0][0] = 0;
+    let mut queue = VecDeque::from([(0, 0)]);
+    while let Some((x, y)) = queue.pop_front() {
+        for (nx, ny) in neighbors((x, y), columns, rows) {
+            if distances[ny][nx] == usize::MAX && is_open(&tiles, (x, y), (nx, ny)) {
+                distances[ny][nx] = distances[y][x] + 1;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    // the slopes, the border openings count as exits of the start and end cells
+    let degree = |tiles: &[Vec<char>], cell: Cell| {
+        let border = usize::from(cell == (0, 0)) + usize::from(cell == (columns - 1, rows - 1));
+        neighbors(cell, columns, rows)
+            .into_iter()
+            .filter(|next| is_open(tiles, cell, *next))
+            .count()
+            + border
+    };
+    for (from, to) in (0..columns)
+        .flat_map(|x| (0..rows).map(move |y| (x, y)))
+        .flat_map(|cell| walls(cell, columns, rows))
+    {
+        if !is_open(&tiles, from, to) || (degree(&tiles, from) < 3 && degree(&tiles, to) < 3) {
+            continue;
+        }
+
+        let away = distances[to.1][to.0] > distances[from.1][from.0];
+        let slope = match (from.0 < to.0, away) {
+            (true, true) => '>',
+            (true, false) => '<',
+            (false, true) => 'v',
+            (false, false) => '^',
+        };
+        let (ox, oy) = opening(from, to);
+        tiles[oy][ox] = slope;
+    }
+
+    Ok(tiles
+        .into_iter()
+        .map(|row| row.into_iter().collect())
+        .collect())
+}
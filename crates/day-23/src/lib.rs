@@ -0,0 +1,893 @@
+use itertools::Itertools;
+use lib::{
+    par::map_indexed,
+    validate::{check_grid, Problem},
+};
+use rand::{rngs::StdRng, seq::SliceRandom};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt::{self, Display},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+pub mod genfuzz;
+
+pub fn validate(lines: &[String]) -> Vec<Problem> {
+    check_grid(lines, ".#<>^v")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tile {
+    Path,
+    Forest,
+    SlopeNorth,
+    SlopeSouth,
+    SlopeEast,
+    SlopeWest,
+}
+
+impl TryFrom<char> for Tile {
+    type Error = Box<dyn Error>;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Tile::Path),
+            '#' => Ok(Tile::Forest),
+            '>' => Ok(Tile::SlopeEast),
+            '<' => Ok(Tile::SlopeWest),
+            '^' => Ok(Tile::SlopeNorth),
+            'v' => Ok(Tile::SlopeSouth),
+            _ => Err(format!("Invalid tile: {}", c).into()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Map {
+    tiles: Vec<Vec<Tile>>,
+    width: usize,
+    height: usize,
+}
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Map, Box<dyn Error>> {
+    let mut height = 0;
+    let mut width = 0;
+
+    let tiles = itr
+        .map(|line| {
+            height += 1;
+
+            if width == 0 {
+                width = line.len();
+            } else if width != line.len() {
+                Err::<_, Box<dyn Error>>(format!("Invalid line length: {}", line.len()).into())?;
+            }
+
+            line.chars()
+                .map(|c| Tile::try_from(c))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Map {
+        tiles,
+        width,
+        height,
+    })
+}
+
+pub fn remove_slopes(str: &str) -> String {
+    str.chars()
+        .map(|c| match c {
+            '>' => '.',
+            '<' => '.',
+            '^' => '.',
+            'v' => '.',
+            _ => c,
+        })
+        .collect::<String>()
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct Position {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Step {
+    current: Position,
+    visited: HashSet<Position>,
+}
+
+fn top(Position { x, y }: &Position) -> Position {
+    Position { x: *x, y: y - 1 }
+}
+
+fn bottom(Position { x, y }: &Position) -> Position {
+    Position { x: *x, y: y + 1 }
+}
+
+fn left(Position { x, y }: &Position) -> Position {
+    Position { x: x - 1, y: *y }
+}
+
+fn right(Position { x, y }: &Position) -> Position {
+    Position { x: x + 1, y: *y }
+}
+
+fn get_adjacent_positions(map: &Map, from: &Position) -> Result<Vec<Position>, Box<dyn Error>> {
+    let from_tile = map_get(map, &from).ok_or("Invalid from position")?;
+
+    let next_possible_positions = match from_tile {
+        Tile::SlopeNorth => vec![top(&from)],
+        Tile::SlopeSouth => vec![bottom(&from)],
+        Tile::SlopeEast => vec![right(&from)],
+        Tile::SlopeWest => vec![left(&from)],
+        _ => vec![top(&from), bottom(&from), left(&from), right(&from)],
+    };
+
+    Ok(next_possible_positions
+        .into_iter()
+        .filter(|next| on_map_and_not_forest(map, next))
+        .collect())
+}
+
+fn map_get(map: &Map, Position { x, y }: &Position) -> Option<Tile> {
+    (*x >= 0 && *y >= 0)
+        .then_some(usize::try_from(*x).ok().zip(usize::try_from(*y).ok()))
+        .flatten()
+        .map(|(x, y)| map.tiles.get(y).and_then(|row| row.get(x)).copied())
+        .flatten()
+}
+
+fn on_map_and_not_forest(map: &Map, position: &Position) -> bool {
+    let tile = map_get(map, position);
+
+    tile.is_some() && tile != Some(Tile::Forest)
+}
+
+type Graph = HashMap<Position, Vec<(Position, usize)>>;
+
+// create a compressed graph whose vertices are the junctions, the start and the end
+fn compress(map: &Map) -> Result<(Graph, Position, Position), Box<dyn Error>> {
+    // find all vertices
+    let start = Position { x: 1, y: 0 };
+    let end = Position {
+        x: i32::try_from(map.width)? - 2,
+        y: i32::try_from(map.height)? - 1,
+    };
+    let vertices = (0..map.width)
+        .map(|x| {
+            let start = &start;
+            let end = &end;
+            (0..map.height).filter_map(move |y| -> Option<Result<Position, Box<dyn Error>>> {
+                let position = i32::try_from(x)
+                    .and_then(|x| i32::try_from(y).and_then(|y| Ok(Position { x, y })));
+                match position {
+                    Ok(position) => {
+                        if position == *start || position == *end {
+                            Some(Ok(position))
+                        } else {
+                            match is_junction(map, &position) {
+                                Ok(true) => Some(Ok(position)),
+                                Ok(false) => None,
+                                Err(e) => Some(Err(e)),
+                            }
+                        }
+                    }
+                    Err(e) => Some(Err(e.into())),
+                }
+            })
+        })
+        .flatten()
+        .collect::<Result<HashSet<_>, _>>()?;
+
+    // build the graph
+    let mut graph = Graph::new();
+    vertices
+        .iter()
+        .try_for_each(|vertex| -> Result<_, Box<dyn Error>> {
+            let mut stack: Vec<(Position, usize)> = vec![(vertex.clone(), 0)];
+            let mut visited: HashSet<Position> = HashSet::new();
+
+            while let Some((current, distance)) = stack.pop() {
+                if current != *vertex && vertices.contains(&current) {
+                    // add the edge, the other vertices are not marked as visited so that the
+                    // corridors leading to the same one make edges of their own
+                    graph
+                        .entry(vertex.clone())
+                        .or_default()
+                        .push((current.clone(), distance));
+
+                    continue;
+                }
+                visited.insert(current.clone());
+
+                let next_positions = get_adjacent_positions(map, &current)?;
+
+                next_positions
+                    .into_iter()
+                    .filter(|next| !visited.contains(next))
+                    .for_each(|next| stack.push((next, distance + 1)));
+            }
+            Ok(())
+        })?;
+
+    Ok((graph, start, end))
+}
+
+pub fn solve2(map: &Map) -> Result<usize, Box<dyn Error>> {
+    let (graph, start, end) = compress(map)?;
+
+    // DFS the graph to find the longest path from start to end
+    let mut queue: VecDeque<(&Position, HashSet<&Position>, usize)> = VecDeque::new();
+    queue.push_front((&start, HashSet::from([&start]), 0));
+    let mut paths = Vec::new();
+
+    while let Some((current, visited, distance)) = queue.pop_front() {
+        if *current == end {
+            paths.push(distance);
+            continue;
+        }
+
+        let next_positions = graph.get(&current).ok_or("Invalid current position")?;
+
+        next_positions
+            .into_iter()
+            .filter(|(next, _)| !visited.contains(next))
+            .for_each(|(next_position, next_distance)| {
+                let mut new_visited = visited.clone();
+                new_visited.insert(current);
+                queue.push_back((next_position, new_visited, distance + next_distance))
+            });
+    }
+
+    paths.into_iter().max().ok_or("No path found".into())
+}
+
+// the compressed graph with its vertices numbered, the searches keep their visited vertices in a
+// vector
+struct IndexedGraph {
+    edges: Vec<Vec<(usize, usize)>>,
+    start: usize,
+    end: usize,
+}
+
+fn index_graph(
+    graph: &Graph,
+    start: &Position,
+    end: &Position,
+) -> Result<IndexedGraph, Box<dyn Error>> {
+    let vertices = graph
+        .keys()
+        .chain([start, end])
+        .sorted_by_key(|position| (position.y, position.x))
+        .dedup()
+        .collect::<Vec<_>>();
+    let index = |position: &Position| {
+        vertices
+            .binary_search_by_key(&(position.y, position.x), |vertex| (vertex.y, vertex.x))
+            .map_err(|_| "Invalid position")
+    };
+
+    let edges = vertices
+        .iter()
+        .map(|vertex| {
+            graph
+                .get(vertex)
+                .map_or(&[][..], |edges| edges.as_slice())
+                .iter()
+                .map(|(next, distance)| Ok((index(next)?, *distance)))
+                .collect::<Result<Vec<_>, Box<dyn Error>>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(IndexedGraph {
+        edges,
+        start: index(start)?,
+        end: index(end)?,
+    })
+}
+
+// a partial path of the search, `remaining` bounds the length it can still gain: each vertex is
+// entered at most once, at best through its longest edge, so the longest edges entering the
+// vertices not visited yet add up to at least what is left
+#[derive(Clone)]
+struct Branch {
+    current: usize,
+    visited: Vec<bool>,
+    distance: usize,
+    remaining: usize,
+}
+
+impl Branch {
+    fn next(&self, next: usize, distance: usize, longest_in: &[usize]) -> Branch {
+        let mut visited = self.visited.clone();
+        visited[next] = true;
+        Branch {
+            current: next,
+            visited,
+            distance: self.distance + distance,
+            remaining: self.remaining - longest_in[next],
+        }
+    }
+}
+
+// the depth first search of the longest path, the branches which can't beat the best path found
+// so far, by any of the threads, are cut
+fn search(graph: &IndexedGraph, longest_in: &[usize], branch: &mut Branch, best: &AtomicUsize) {
+    if branch.current == graph.end {
+        best.fetch_max(branch.distance, Ordering::Relaxed);
+        return;
+    }
+    if branch.distance + branch.remaining <= best.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let current = branch.current;
+    for &(next, distance) in &graph.edges[current] {
+        if branch.visited[next] {
+            continue;
+        }
+        branch.visited[next] = true;
+        branch.current = next;
+        branch.distance += distance;
+        branch.remaining -= longest_in[next];
+
+        search(graph, longest_in, branch, best);
+
+        branch.remaining += longest_in[next];
+        branch.distance -= distance;
+        branch.current = current;
+        branch.visited[next] = false;
+    }
+}
+
+// the number of branches the search is split in before they are shared between the threads
+const PARALLEL_BRANCHES: usize = 64;
+
+pub fn solve2_pruned(map: &Map) -> Result<usize, Box<dyn Error>> {
+    let (graph, start, end) = compress(map)?;
+    let mut graph = index_graph(&graph, &start, &end)?;
+
+    // when the end can only be reached from a single vertex, a path reaching this vertex must go
+    // to the end right away, or it could never get there
+    let penultimates = graph
+        .edges
+        .iter()
+        .positions(|edges| edges.iter().any(|(next, _)| *next == graph.end))
+        .collect::<Vec<_>>();
+    if let [penultimate] = penultimates[..] {
+        let end = graph.end;
+        graph.edges[penultimate].retain(|(next, _)| *next == end);
+    }
+
+    let mut longest_in = vec![0; graph.edges.len()];
+    for &(next, distance) in graph.edges.iter().flatten() {
+        longest_in[next] = longest_in[next].max(distance);
+    }
+
+    let mut visited = vec![false; graph.edges.len()];
+    visited[graph.start] = true;
+    let root = Branch {
+        current: graph.start,
+        visited,
+        distance: 0,
+        remaining: longest_in.iter().sum::<usize>() - longest_in[graph.start],
+    };
+
+    // the first levels breadth first, the paths reaching the end already are complete
+    let best = AtomicUsize::new(0);
+    let mut branches = vec![root];
+    while !branches.is_empty() && branches.len() < PARALLEL_BRANCHES {
+        branches = branches
+            .iter()
+            .flat_map(|branch| {
+                graph.edges[branch.current]
+                    .iter()
+                    .filter(|(next, _)| !branch.visited[*next])
+                    .map(|&(next, distance)| branch.next(next, distance, &longest_in))
+            })
+            .filter(|branch| {
+                let complete = branch.current == graph.end;
+                if complete {
+                    best.fetch_max(branch.distance, Ordering::Relaxed);
+                }
+                !complete
+            })
+            .collect();
+    }
+
+    map_indexed(&branches, |branch| {
+        search(&graph, &longest_in, &mut branch.clone(), &best)
+    });
+
+    // the start and the end are different, the paths between them aren't empty
+    match best.into_inner() {
+        0 => Err("No path found".into()),
+        result => Ok(result),
+    }
+}
+
+// randomized walks on the compressed graph, restarted from the start each time the walk reaches
+// the end or gets stuck. The result is the longest path found, it is a lower bound of the answer.
+// None when every walk got stuck before the end
+pub fn solve_heuristic(
+    map: &Map,
+    restarts: usize,
+    rng: &mut StdRng,
+) -> Result<Option<usize>, Box<dyn Error>> {
+    let (graph, start, end) = compress(map)?;
+
+    let mut best = None;
+
+    for _ in 0..restarts {
+        let mut current = &start;
+        let mut visited = HashSet::from([&start]);
+        let mut distance = 0;
+
+        while *current != end {
+            // a vertex without edges, like a start leading to a dead end, is not in the graph
+            let next_positions = graph
+                .get(current)
+                .into_iter()
+                .flatten()
+                .filter(|(next, _)| !visited.contains(next))
+                .collect::<Vec<_>>();
+
+            match next_positions.choose(rng) {
+                Some((next_position, next_distance)) => {
+                    visited.insert(next_position);
+                    distance += next_distance;
+                    current = next_position;
+                }
+                None => break,
+            }
+        }
+
+        if *current == end {
+            best = best.max(Some(distance));
+        }
+    }
+
+    Ok(best)
+}
+
+// forest tiles may have paths all around them in generated maps, they are not junctions though
+fn is_junction(map: &Map, position: &Position) -> Result<bool, Box<dyn Error>> {
+    Ok(on_map_and_not_forest(map, position) && get_adjacent_positions(map, position)?.len() > 2)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Strategy {
+    // longest path in topological order, when the slopes orient the graph without cycles
+    Dag,
+    // exhaustive search of the paths on the map
+    Dfs,
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Strategy::Dag => write!(f, "dag"),
+            Strategy::Dfs => write!(f, "dfs"),
+        }
+    }
+}
+
+// the vertices ordered so that all the edges go forward, None if the graph has a cycle. The
+// vertices without incoming edges are taken first, removing their edges, as long as there are some
+// see https://en.wikipedia.org/wiki/Topological_sorting#Kahn's_algorithm
+fn topological_order(graph: &Graph) -> Option<Vec<&Position>> {
+    let mut in_degrees = graph
+        .keys()
+        .map(|vertex| (vertex, 0))
+        .collect::<HashMap<_, _>>();
+    graph
+        .values()
+        .flatten()
+        .for_each(|(next, _)| *in_degrees.entry(next).or_default() += 1);
+
+    let mut sources = in_degrees
+        .iter()
+        .filter(|(_, degree)| **degree == 0)
+        .map(|(vertex, _)| *vertex)
+        .collect::<Vec<_>>();
+    let mut order = Vec::with_capacity(in_degrees.len());
+
+    while let Some(vertex) = sources.pop() {
+        order.push(vertex);
+        for (next, _) in graph.get(vertex).into_iter().flatten() {
+            let degree = in_degrees.get_mut(next)?;
+            *degree -= 1;
+            if *degree == 0 {
+                sources.push(next);
+            }
+        }
+    }
+
+    (order.len() == in_degrees.len()).then_some(order)
+}
+
+// the longest distance from the start to each vertex, relaxing the edges in topological order so
+// that a vertex is final when its edges are followed. None if the end can't be reached
+fn longest_path_dag(
+    graph: &Graph,
+    order: &[&Position],
+    start: &Position,
+    end: &Position,
+) -> Option<usize> {
+    let mut distances = HashMap::from([(start, 0)]);
+
+    for vertex in order {
+        let Some(&distance) = distances.get(vertex) else {
+            continue;
+        };
+        for (next, length) in graph.get(*vertex).into_iter().flatten() {
+            let longest = distances.entry(next).or_default();
+            *longest = (*longest).max(distance + length);
+        }
+    }
+
+    distances.get(end).copied()
+}
+
+// the slopes of the inputs make the compressed graph acyclic, its longest path is then found in
+// linear time. The exhaustive search is kept for the maps whose slopes leave cycles
+pub fn solve1_with_strategy(map: &Map) -> Result<(usize, Strategy), Box<dyn Error>> {
+    let (graph, start, end) = compress(map)?;
+
+    match topological_order(&graph) {
+        Some(order) => Ok((
+            longest_path_dag(&graph, &order, &start, &end).ok_or("No path found")?,
+            Strategy::Dag,
+        )),
+        None => Ok((solve1_dfs(map)?, Strategy::Dfs)),
+    }
+}
+
+pub fn solve1(map: &Map) -> Result<usize, Box<dyn Error>> {
+    solve1_with_strategy(map).map(|(result, _)| result)
+}
+
+fn solve1_dfs(map: &Map) -> Result<usize, Box<dyn Error>> {
+    let start = Position { x: 1, y: 0 };
+    let end = Position {
+        x: i32::try_from(map.width)? - 2,
+        y: i32::try_from(map.height)? - 1,
+    };
+
+    let mut stack: Vec<Step> = Vec::new();
+    stack.push(Step {
+        current: start,
+        visited: HashSet::new(),
+    });
+    let mut paths: Vec<usize> = Vec::new();
+
+    while let Some(Step { current, visited }) = stack.pop() {
+        if current == end {
+            paths.push(visited.len());
+            continue;
+        }
+
+        let next_positions = get_adjacent_positions(map, &current)?;
+
+        next_positions
+            .into_iter()
+            .filter(|next| !visited.contains(next))
+            .for_each(|next| {
+                let mut visited = visited.clone();
+                visited.insert(current.clone());
+
+                stack.push(Step {
+                    current: next,
+                    visited,
+                });
+            });
+    }
+
+    paths.into_iter().max().ok_or("No path found".into())
+}
+
+#[cfg(test)]
+mod day23 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        compress, genfuzz, parse, remove_slopes, solve1, solve1_dfs, solve1_with_strategy, solve2,
+        solve2_pruned, solve_heuristic, topological_order, validate, Position, Strategy,
+    };
+
+    const EXAMPLE: &str = "\
+        #.#####################\n\
+        #.......#########...###\n\
+        #######.#########.#.###\n\
+        ###.....#.>.>.###.#.###\n\
+        ###v#####.#v#.###.#.###\n\
+        ###.>...#.#.#.....#...#\n\
+        ###v###.#.#.#########.#\n\
+        ###...#.#.#.......#...#\n\
+        #####.#.#.#######.#.###\n\
+        #.....#.#.#.......#...#\n\
+        #.#####.#.#.#########v#\n\
+        #.#...#...#...###...>.#\n\
+        #.#.#v#######v###.###v#\n\
+        #...#.>.#...>.>.#.###.#\n\
+        #####v#.#.###v#.#.###.#\n\
+        #.....#...#...#.#.#...#\n\
+        #.#########.###.#.#.###\n\
+        #...###...#...#...#.###\n\
+        ###.###.#.###v#####v###\n\
+        #...#...#.#.>.>.#.>.###\n\
+        #.###.###.#.###.#.#v###\n\
+        #.....###...###...#...#\n\
+        #####################.#";
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        let map = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+
+        assert_eq!(map.width, 23);
+        assert_eq!(map.height, 23);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1() -> Result<(), Box<dyn Error>> {
+        let map = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+
+        assert_eq!(solve1(&map)?, 94);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_noslopes() -> Result<(), Box<dyn Error>> {
+        let map = parse(EXAMPLE.lines().map(remove_slopes))?;
+
+        assert_eq!(solve1(&map)?, 154);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_strategy() -> Result<(), Box<dyn Error>> {
+        // the slopes of the example orient all the edges between the junctions
+        let map = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        let (graph, _, _) = compress(&map)?;
+        assert_eq!(topological_order(&graph).map(|order| order.len()), Some(9));
+        assert_eq!(solve1_with_strategy(&map)?, (94, Strategy::Dag));
+        assert_eq!(solve1_dfs(&map)?, 94);
+
+        // without them the corridors go both ways
+        let map = parse(EXAMPLE.lines().map(remove_slopes))?;
+        let (graph, _, _) = compress(&map)?;
+        assert_eq!(topological_order(&graph), None);
+        assert_eq!(solve1_with_strategy(&map)?, (154, Strategy::Dfs));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_parallel_corridors() -> Result<(), Box<dyn Error>> {
+        // two corridors lead from the junction below the start to the one above the end, each
+        // makes an edge of its own so that the longest one is still there for part 2
+        let map = parse(
+            [
+                "#.#######",
+                "#.......#",
+                "#.#####.#",
+                "#.#...#.#",
+                "#.#.#.#.#",
+                "#...#...#",
+                "#######.#",
+            ]
+            .iter()
+            .map(|line| line.to_string()),
+        )?;
+        let (graph, _, _) = compress(&map)?;
+
+        let mut lengths = graph
+            .get(&Position { x: 1, y: 1 })
+            .ok_or("missing junction")?
+            .iter()
+            .filter(|(next, _)| *next == Position { x: 7, y: 5 })
+            .map(|(_, length)| *length)
+            .collect::<Vec<_>>();
+        lengths.sort();
+        assert_eq!(lengths, vec![10, 14]);
+        assert_eq!(solve2(&map)?, 16);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2() -> Result<(), Box<dyn Error>> {
+        let map = parse(EXAMPLE.lines().map(remove_slopes))?;
+
+        assert_eq!(solve2(&map)?, 154);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_pruned() -> Result<(), Box<dyn Error>> {
+        let map = parse(EXAMPLE.lines().map(remove_slopes))?;
+        assert_eq!(solve2_pruned(&map)?, 154);
+
+        // the bound never cuts the longest path
+        let mut rng = StdRng::seed_from_u64(23);
+        for _ in 0..5 {
+            let lines = genfuzz::generate_map(15, 15, 0.15, &mut rng)?;
+            let map = parse(lines.iter().map(|line| remove_slopes(line)))?;
+            assert_eq!(solve2_pruned(&map)?, solve2(&map)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_heuristic() -> Result<(), Box<dyn Error>> {
+        let map = parse(EXAMPLE.lines().map(remove_slopes))?;
+        let mut rng = StdRng::seed_from_u64(23);
+
+        // a single walk is a lower bound of the longest path
+        assert!(solve_heuristic(&map, 1, &mut rng)?.is_some_and(|length| length <= 154));
+        // the example is small enough to find the longest path with a few restarts
+        assert_eq!(solve_heuristic(&map, 1000, &mut rng)?, Some(154));
+        // no walk at all
+        assert_eq!(solve_heuristic(&map, 0, &mut rng)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_heuristic_stuck() -> Result<(), Box<dyn Error>> {
+        // the corridor from the start is a dead end, every walk gets stuck
+        let map = parse(["#.###", "#.#.#", "#.#.#"].into_iter().map(String::from))?;
+        let mut rng = StdRng::seed_from_u64(23);
+
+        assert_eq!(solve_heuristic(&map, 10, &mut rng)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_valid() -> Result<(), Box<dyn Error>> {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for (width, height, density) in [(3, 3, 0.0), (5, 21, 0.5), (41, 41, 0.1), (31, 11, 1.0)] {
+            let lines = genfuzz::generate_map(width, height, density, &mut rng)?;
+            assert!(validate(&lines).is_empty());
+
+            let map = parse(lines.into_iter())?;
+            assert_eq!((map.width, map.height), (width, height));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_reproducible() -> Result<(), Box<dyn Error>> {
+        let generate = |seed| genfuzz::generate_map(21, 21, 0.2, &mut StdRng::seed_from_u64(seed));
+
+        assert_eq!(generate(7)?, generate(7)?);
+        assert_ne!(generate(7)?, generate(8)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_perfect_maze() -> Result<(), Box<dyn Error>> {
+        let mut rng = StdRng::seed_from_u64(1);
+        let lines = genfuzz::generate_map(41, 41, 0.0, &mut rng)?;
+
+        // the compressed graph is a tree, each edge being found from both of its ends
+        let map = parse(lines.iter().map(|line| remove_slopes(line)))?;
+        let (graph, _, _) = compress(&map)?;
+        let edges = graph.values().map(|next| next.len()).sum::<usize>();
+        assert_eq!(edges, 2 * (graph.len() - 1));
+
+        let longest = solve2(&map)?;
+        assert_eq!(solve1(&parse(lines.into_iter())?)?, longest);
+        assert_eq!(solve_heuristic(&map, 1000, &mut rng)?, Some(longest));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_junctions() -> Result<(), Box<dyn Error>> {
+        let mut rng = StdRng::seed_from_u64(2);
+        let count = |density, rng: &mut StdRng| -> Result<usize, Box<dyn Error>> {
+            let lines = genfuzz::generate_map(21, 21, density, rng)?;
+            let (graph, _, _) = compress(&parse(lines.iter().map(|line| remove_slopes(line)))?)?;
+            Ok(graph.len())
+        };
+
+        let sparse = count(0.05, &mut rng)?;
+        let dense = count(0.3, &mut rng)?;
+        assert!(2 < sparse && sparse < dense);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_solvable() -> Result<(), Box<dyn Error>> {
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for _ in 0..5 {
+            let lines = genfuzz::generate_map(15, 15, 0.15, &mut rng)?;
+            let longest = solve2(&parse(lines.iter().map(|line| remove_slopes(line)))?)?;
+
+            // the slopes only remove paths, both strategies agree whichever is taken
+            let map = parse(lines.into_iter())?;
+            let result = solve1(&map)?;
+            assert!(result <= longest);
+            assert_eq!(solve1_dfs(&map)?, result);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_invalid() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert!(genfuzz::generate_map(1, 5, 0.5, &mut rng).is_err());
+        assert!(genfuzz::generate_map(4, 5, 0.5, &mut rng).is_err());
+        assert!(genfuzz::generate_map(5, 6, 0.5, &mut rng).is_err());
+        assert!(genfuzz::generate_map(5, 5, -0.1, &mut rng).is_err());
+        assert!(genfuzz::generate_map(5, 5, 1.5, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let map = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve1(&map)?;
+
+        assert_eq!(result, 1966);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_pruned_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let map = reader
+            .lines()
+            .process_results(|itr| parse(itr.map(|line| remove_slopes(&line))))??;
+        let result = solve2_pruned(&map)?;
+
+        assert_eq!(result, 6286);
+
+        Ok(())
+    }
+
+    // too slow for running in tests
+    // #[test]
+    // fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+    //     let file = File::open("input")?;
+    //     let reader = BufReader::new(file);
+    //     let map = reader
+    //         .lines()
+    //         .process_results(|itr| parse(itr.map(|line| remove_slopes(&line))))??;
+    //     let result = solve2(&map)?;
+
+    //     assert_eq!(result, 6286);
+
+    //     Ok(())
+    // }
+}
@@ -0,0 +1,856 @@
+use itertools::Itertools;
+use lib::{
+    export::Field,
+    linalg::{solve, Line3, Vector3},
+    render::Svg,
+    table::table,
+    validate::Problem,
+};
+use std::{error::Error, str::FromStr};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coordinates {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl FromStr for Coordinates {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let coords = s
+            .split(',')
+            .map(|s| s.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let [x, y, z] = coords[..] else {
+            return Err("expected 3 coordinates".into());
+        };
+        // "inf" and "NaN" parse as floats but can't be solved for
+        if !(x.is_finite() && y.is_finite() && z.is_finite()) {
+            return Err("non finite coordinate".into());
+        }
+
+        Ok(Self { x, y, z })
+    }
+}
+
+pub type Position = Coordinates;
+pub type Velocity = Coordinates;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hailstone {
+    pub position: Position,
+    pub velocity: Velocity,
+}
+
+impl FromStr for Hailstone {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (position_str, velocity_str) = s.split_once('@').ok_or("missing @")?;
+
+        let position = position_str.trim().parse::<Position>()?;
+        let velocity = velocity_str.trim().parse::<Velocity>()?;
+
+        Ok(Self { position, velocity })
+    }
+}
+
+impl Coordinates {
+    pub fn vector(&self) -> Vector3 {
+        Vector3::new(self.x, self.y, self.z)
+    }
+}
+
+impl Hailstone {
+    pub fn line(&self) -> Line3 {
+        Line3::new(self.position.vector(), self.velocity.vector())
+    }
+
+    pub fn position_at(&self, t: f64) -> Position {
+        Position {
+            x: self.position.x + self.velocity.x * t,
+            y: self.position.y + self.velocity.y * t,
+            z: self.position.z + self.velocity.z * t,
+        }
+    }
+}
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Vec<Hailstone>, Box<dyn Error>> {
+    itr.map(|line| line.parse()).collect()
+}
+
+// the hailstones that make the solvers degenerate: the duplicates, the ones that don't move and the
+// ones at the same position as another one at t = 0
+pub fn check_hailstones(hailstones: &[Hailstone]) -> Vec<Problem> {
+    let zero = Velocity {
+        x: 0.,
+        y: 0.,
+        z: 0.,
+    };
+
+    hailstones
+        .iter()
+        .enumerate()
+        .filter_map(|(i, hailstone)| {
+            let previous = hailstones[..i]
+                .iter()
+                .position(|other| other.position == hailstone.position);
+
+            let message = match previous {
+                Some(j) if hailstones[j].velocity == hailstone.velocity => {
+                    format!("duplicate of line {}", j + 1)
+                }
+                Some(j) => format!("same position as line {} at t=0", j + 1),
+                None if hailstone.velocity == zero => "zero velocity".to_string(),
+                None => return None,
+            };
+            Some(Problem::new(i + 1, None, message))
+        })
+        .collect()
+}
+
+// the hailstones without the ones reported by check_hailstones
+pub fn sanitize_hailstones(hailstones: Vec<Hailstone>) -> (Vec<Hailstone>, Vec<Problem>) {
+    let problems = check_hailstones(&hailstones);
+    let hailstones = hailstones
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !problems.iter().any(|problem| problem.line == i + 1))
+        .map(|(_, hailstone)| hailstone)
+        .collect();
+
+    (hailstones, problems)
+}
+
+fn intersect_2d(h1: &Hailstone, h2: &Hailstone, past: bool) -> Option<Position> {
+    // unless asked otherwise, the hailstones only move forward in time
+    h1.line()
+        .xy()
+        .intersection(&h2.line().xy())
+        .filter(|(_, t1, t2)| past || (*t1 >= 0. && *t2 >= 0.))
+        .map(|(p, _, _)| Position {
+            x: p[0],
+            y: p[1],
+            z: 0.,
+        })
+}
+
+pub struct Solve1Options {
+    // the test area is the square [min, max] on both x and y
+    min: f64,
+    max: f64,
+    // also count the paths that crossed before time 0
+    past: bool,
+}
+
+impl Default for Solve1Options {
+    fn default() -> Self {
+        Self {
+            min: 200_000_000_000_000.,
+            max: 400_000_000_000_000.,
+            past: false,
+        }
+    }
+}
+
+pub fn parse_solve1_options(args: &[String]) -> Result<Solve1Options, Box<dyn Error>> {
+    let mut options = Solve1Options::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--min" => options.min = args.next().ok_or("missing min")?.parse()?,
+            "--max" => options.max = args.next().ok_or("missing max")?.parse()?,
+            "--past" => options.past = true,
+            _ => return Err(format!("unknown option: {}", arg).into()),
+        }
+    }
+
+    if options.min > options.max {
+        return Err("min is greater than max".into());
+    }
+
+    Ok(options)
+}
+
+fn in_2d_range(p: &Position, min: f64, max: f64) -> bool {
+    p.x >= min && p.x <= max && p.y >= min && p.y <= max
+}
+
+pub fn solve1(hailstones: &[Hailstone], options: &Solve1Options) -> usize {
+    hailstones
+        .iter()
+        .combinations(2)
+        .filter_map(|two_hailstones| {
+            two_hailstones
+                .get(0)
+                .zip(two_hailstones.get(1))
+                .and_then(|(h1, h2)| intersect_2d(h1, h2, options.past))
+        })
+        .filter(|p| in_2d_range(p, options.min, options.max))
+        .count()
+}
+
+// the part of the xy trajectory of a hailstone in the test area, None if it doesn't go through it.
+// The times at which the hailstone is in the area on each axis are intersected
+fn clip_2d(hailstone: &Hailstone, options: &Solve1Options) -> Option<(Position, Position)> {
+    let (mut start, mut end) = if options.past {
+        (f64::NEG_INFINITY, f64::INFINITY)
+    } else {
+        (0., f64::INFINITY)
+    };
+
+    let axes = [
+        (hailstone.position.x, hailstone.velocity.x),
+        (hailstone.position.y, hailstone.velocity.y),
+    ];
+    for (position, velocity) in axes {
+        if velocity == 0. {
+            if position < options.min || position > options.max {
+                return None;
+            }
+        } else {
+            let t1 = (options.min - position) / velocity;
+            let t2 = (options.max - position) / velocity;
+            start = start.max(t1.min(t2));
+            end = end.min(t1.max(t2));
+        }
+    }
+
+    // a time is only left infinite when the hailstone doesn't move in the plane
+    let at = |t: f64| hailstone.position_at(if t.is_finite() { t } else { 0. });
+    (start <= end).then(|| (at(start), at(end)))
+}
+
+// the test area with the trajectories of the hailstones in it and their intersections, the whole
+// line of the rock is drawn as it goes through all the hailstones
+pub fn to_svg(
+    hailstones: &[Hailstone],
+    rock: Option<&Hailstone>,
+    options: &Solve1Options,
+) -> Result<String, Box<dyn Error>> {
+    let (min, max) = (options.min, options.max);
+    let mut svg = Svg::new((min, min), (max, max), 800.)?;
+    svg.rect((min, min), (max, max), "black", 1.);
+
+    for (from, to) in hailstones
+        .iter()
+        .filter_map(|hailstone| clip_2d(hailstone, options))
+    {
+        svg.line((from.x, from.y), (to.x, to.y), "steelblue", 1.);
+    }
+
+    let rock_options = Solve1Options {
+        past: true,
+        ..*options
+    };
+    if let Some((from, to)) = rock.and_then(|rock| clip_2d(rock, &rock_options)) {
+        svg.line((from.x, from.y), (to.x, to.y), "orange", 2.);
+    }
+
+    hailstones
+        .iter()
+        .tuple_combinations()
+        .filter_map(|(h1, h2)| intersect_2d(h1, h2, options.past))
+        .filter(|p| in_2d_range(p, min, max))
+        .for_each(|p| svg.circle((p.x, p.y), 3., "red"));
+
+    Ok(svg.render())
+}
+
+// the times at which the lines followed by the hailstones are the closest and the distance then,
+// the times can be negative as the whole lines are considered
+fn closest_approach(h1: &Hailstone, h2: &Hailstone) -> (f64, f64, f64) {
+    h1.line().closest_approach(&h2.line())
+}
+
+// the k pairs of hailstones with the closest trajectories, identified by their index in the input
+pub fn closest_pairs(hailstones: &[Hailstone], k: usize) -> Vec<(usize, usize, (f64, f64, f64))> {
+    hailstones
+        .iter()
+        .enumerate()
+        .tuple_combinations()
+        .map(|((i, h1), (j, h2))| (i, j, closest_approach(h1, h2)))
+        .sorted_by(|(_, _, (_, _, d1)), (_, _, (_, _, d2))| d1.total_cmp(d2))
+        .take(k)
+        .collect()
+}
+
+pub fn closest_report(pairs: &[(usize, usize, (f64, f64, f64))]) -> String {
+    let rows = pairs
+        .iter()
+        .map(|(i, j, (t1, t2, distance))| {
+            // adding zero turns -0 into 0
+            let (t1, t2) = (t1 + 0., t2 + 0.);
+            vec![
+                i.to_string(),
+                j.to_string(),
+                format!("{:.3}", t1),
+                format!("{:.3}", t2),
+                format!("{:.3}", distance),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    table(&["h1", "h2", "t1", "t2", "distance"], &rows)
+}
+
+// considering the rock starting from p and moving with velocity v, it move with
+// p' = p + v * t
+// and a hailstone1 at p1 moving with velocity v1:
+// p1' = p1 + v1 * t
+// they intersect if:
+// p' = p1'
+// p + v * t = p1 + v1 * t
+// t = (p1 - p) / (v - v1)
+//
+// this must be valid for x, y and z:
+// t = (p1.x - p.x) / (v.x - v1.x) = (p1.y - p.y) / (v.y - v1.y) = (p1.z - p.z) / (v.z - v1.z)
+//
+// now let's write it as:
+// 1)  (p1.x - p.x) / (v.x - v1.x) = (p1.y - p.y) / (v.y - v1.y)
+// 2)  (p1.x - p.x) / (v.x - v1.x) = (p1.z - p.z) / (v.z - v1.z)
+// 3)  (p1.y - p.y) / (v.y - v1.y) = (p1.z - p.z) / (v.z - v1.z)
+//
+// let's expand the first one 1):
+// (p1.x - p.x) * (v.y - v1.y) = (p1.y - p.y) * (v.x - v1.x)
+// (p1.x - p.x) * (v.y - v1.y) - (p1.y - p.y) * (v.x - v1.x) = 0
+// p1.x * v.y - p1.x * v1.y - p.x * v.y + p.x * v1.y - p1.y * v.x + p1.y * v1.x + p.y * v.x - p.y * v1.x = 0
+//        ---                 ---------   ---                 ---                 ---------   ---
+// p.x * v.y and p.y * v.x are non-linear terms
+//
+// now let's consider another point p2 with a velocity v2:
+// p2.x * v.y - p2.x * v2.y - p.x * v.y + p.x * v2.y - p2.y * v.x + p2.y * v2.x - p.y * v.x - p.y * v2.x = 0
+//
+// if we take the difference between the two equations we get:
+//   p1.x * v.y - p1.x * v1.y + p.x * v1.y - p1.y * v.x + p1.y * v1.x - p.y * v1.x
+// - p2.x * v.y + p2.x * v2.y - p.x * v2.y + p2.y * v.x - p2.y * v2.x + p.y * v2.x = 0
+// p.x * (v1.y - v2.y) + p.y * (v2.x - v1.x) + v.x * (p2.y - p1.y) + v.y * (p1.x - p2.x) - p1.x * v1.y + p2.x * v2.y + p1.y * v1.x - p2.y * v2.x = 0
+//
+// Now the second one 2):
+// p.x * (v1.z - v2.z) + p.z * (v2.x - v1.x) + v.x * (p2.z - p1.z) + v.z * (p1.x - p2.x) - p1.x * v1.z + p2.x * v2.z + p1.z * v1.x - p2.z * v2.x = 0
+//
+// And the third 3):
+// p.y * (v1.z - v2.z) + p.z * (v2.y - v1.y) + v.y * (p2.z - p1.z) + v.z * (p1.y - p2.y) - p1.y * v1.z + p2.y * v2.z + p1.z * v1.y - p2.z * v2.y = 0
+//
+// Now we have three equations with 6 unknowns. We need another hailstone to get the final system
+// p.x * (v1.y - v2.y) + p.y * (v2.x - v1.x)                       + v.x * (p2.y - p1.y) + v.y * (p1.x - p2.x)                       - p1.x * v1.y + p2.x * v2.y + p1.y * v1.x - p2.y * v2.x = 0
+// p.x * (v1.z - v2.z)                       + p.z * (v2.x - v1.x) + v.x * (p2.z - p1.z)                       + v.z * (p1.x - p2.x) - p1.x * v1.z + p2.x * v2.z + p1.z * v1.x - p2.z * v2.x = 0
+//                       p.y * (v1.z - v2.z) + p.z * (v2.y - v1.y)                       + v.y * (p2.z - p1.z) + v.z * (p1.y - p2.y) - p1.y * v1.z + p2.y * v2.z + p1.z * v1.y - p2.z * v2.y = 0
+//
+//  (dy'-dy) X + (dx-dx') Y              + (y-y') DX + (x'-x) DY             =  x' dy' - y' dx' - x dy + y dx
+//  (dz'-dz) X              + (dx-dx') Z + (z-z') DX             + (x'-x) DZ =  x' dz' - z' dx' - x dz + z dx
+//               (dz-dz') Y + (dy'-dy) Z             + (z'-z) DY + (y-y') DZ = -y' dz' + z' dy' + y dz - z dy
+pub fn solve_rock(hailstones: &[Hailstone]) -> Result<Hailstone, Box<dyn Error>> {
+    if hailstones.len() < 3 {
+        return Err("the rock needs at least 3 hailstones".into());
+    }
+
+    // the hailstones moving in parallel don't give independent equations, the first triple whose
+    // velocities are pairwise non parallel is used
+    let (h1, h2, h3) = hailstones
+        .iter()
+        .tuple_combinations()
+        .find(|(h1, h2, h3): &(&Hailstone, &Hailstone, &Hailstone)| {
+            let (l1, l2, l3) = (h1.line(), h2.line(), h3.line());
+            !l1.is_parallel(&l2) && !l1.is_parallel(&l3) && !l2.is_parallel(&l3)
+        })
+        .ok_or("no three hailstones with pairwise non parallel velocities")?;
+
+    let p1 = &h1.position;
+    let p2 = &h2.position;
+    let p3 = &h3.position;
+
+    let v1 = &h1.velocity;
+    let v2 = &h2.velocity;
+    let v3 = &h3.velocity;
+
+    let coefficients = [
+        [v1.y - v2.y, v2.x - v1.x, 0., p2.y - p1.y, p1.x - p2.x, 0.],
+        [v1.z - v2.z, 0., v2.x - v1.x, p2.z - p1.z, 0., p1.x - p2.x],
+        [0., v1.z - v2.z, v2.y - v1.y, 0., p2.z - p1.z, p1.y - p2.y],
+        [v1.y - v3.y, v3.x - v1.x, 0., p3.y - p1.y, p1.x - p3.x, 0.],
+        [v1.z - v3.z, 0., v3.x - v1.x, p3.z - p1.z, 0., p1.x - p3.x],
+        [0., v1.z - v3.z, v3.y - v1.y, 0., p3.z - p1.z, p1.y - p3.y],
+    ];
+    let constant = [
+        p1.x * v1.y - p2.x * v2.y - p1.y * v1.x + p2.y * v2.x,
+        p1.x * v1.z - p2.x * v2.z - p1.z * v1.x + p2.z * v2.x,
+        p1.y * v1.z - p2.y * v2.z - p1.z * v1.y + p2.z * v2.y,
+        p1.x * v1.y - p3.x * v3.y - p1.y * v1.x + p3.y * v3.x,
+        p1.x * v1.z - p3.x * v3.z - p1.z * v1.x + p3.z * v3.x,
+        p1.y * v1.z - p3.y * v3.z - p1.z * v1.y + p3.z * v3.y,
+    ];
+
+    // In theory, we should check that there is a solution to the system and if not, take other
+    // hailstones. As for this input, the first suitable three hailstones yield the result.
+    let result = solve(coefficients, constant).ok_or("no solution")?;
+
+    Ok(Hailstone {
+        position: Position {
+            x: result[0],
+            y: result[1],
+            z: result[2],
+        },
+        velocity: Velocity {
+            x: result[3],
+            y: result[4],
+            z: result[5],
+        },
+    })
+}
+
+pub fn solve2(hailstones: &[Hailstone]) -> Result<usize, Box<dyn Error>> {
+    let p = solve_rock(hailstones)?.position;
+
+    // As is safe to use in this case. It's the only way to cast a float to an integer.
+    Ok(p.x.round() as usize + p.y.round() as usize + p.z.round() as usize)
+}
+
+// the positions of the objects at a given time, the hailstones being identified by their index
+// in the input
+pub struct Frame {
+    time: f64,
+    positions: Vec<(String, Position)>,
+}
+
+// samples the trajectories every dt starting at time 0
+pub fn frames(
+    hailstones: &[Hailstone],
+    rock: Option<&Hailstone>,
+    dt: f64,
+    count: usize,
+) -> Vec<Frame> {
+    (0..count)
+        .map(|i| {
+            let time = i as f64 * dt;
+            let positions = hailstones
+                .iter()
+                .enumerate()
+                .map(|(id, hailstone)| (id.to_string(), hailstone.position_at(time)))
+                .chain(rock.map(|rock| ("rock".to_string(), rock.position_at(time))))
+                .collect();
+
+            Frame { time, positions }
+        })
+        .collect()
+}
+
+pub const FRAME_FIELDS: [&str; 6] = ["frame", "time", "id", "x", "y", "z"];
+
+// one record per position of each frame, to be rendered with the shared writers
+pub fn frame_records(frames: &[Frame]) -> Result<Vec<Vec<Field>>, Box<dyn Error>> {
+    let mut records = Vec::new();
+
+    for (i, frame) in frames.iter().enumerate() {
+        let i = i64::try_from(i)?;
+        records.extend(frame.positions.iter().map(|(id, p)| {
+            vec![
+                Field::Number(i),
+                Field::Float(frame.time),
+                Field::Text(id.clone()),
+                Field::Float(p.x),
+                Field::Float(p.y),
+                Field::Float(p.z),
+            ]
+        }));
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod day24 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+        iter::once,
+    };
+
+    use itertools::Itertools;
+
+    use crate::{
+        check_hailstones, clip_2d, closest_approach, closest_pairs, closest_report, frame_records,
+        frames, parse, parse_solve1_options, sanitize_hailstones, solve1, solve2, solve_rock,
+        to_svg, Hailstone, Position, Solve1Options, Velocity, FRAME_FIELDS,
+    };
+    use lib::export::Format;
+
+    const EXAMPLE: &str = "\
+        19, 13, 30 @ -2,  1, -2\n\
+        18, 19, 22 @ -1, -1, -2\n\
+        20, 25, 34 @ -2, -2, -4\n\
+        12, 31, 28 @ -1, -2, -1\n\
+        20, 19, 15 @  1, -5, -3";
+
+    fn example() -> Vec<Hailstone> {
+        vec![
+            Hailstone {
+                position: Position {
+                    x: 19.,
+                    y: 13.,
+                    z: 30.,
+                },
+                velocity: Velocity {
+                    x: -2.,
+                    y: 1.,
+                    z: -2.,
+                },
+            },
+            Hailstone {
+                position: Position {
+                    x: 18.,
+                    y: 19.,
+                    z: 22.,
+                },
+                velocity: Velocity {
+                    x: -1.,
+                    y: -1.,
+                    z: -2.,
+                },
+            },
+            Hailstone {
+                position: Position {
+                    x: 20.,
+                    y: 25.,
+                    z: 34.,
+                },
+                velocity: Velocity {
+                    x: -2.,
+                    y: -2.,
+                    z: -4.,
+                },
+            },
+            Hailstone {
+                position: Position {
+                    x: 12.,
+                    y: 31.,
+                    z: 28.,
+                },
+                velocity: Velocity {
+                    x: -1.,
+                    y: -2.,
+                    z: -1.,
+                },
+            },
+            Hailstone {
+                position: Position {
+                    x: 20.,
+                    y: 19.,
+                    z: 15.,
+                },
+                velocity: Velocity {
+                    x: 1.,
+                    y: -5.,
+                    z: -3.,
+                },
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        let hailstones = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+        assert_eq!(hailstones, example());
+        Ok(())
+    }
+
+    fn example_window(past: bool) -> Solve1Options {
+        Solve1Options {
+            min: 7.,
+            max: 27.,
+            past,
+        }
+    }
+
+    #[test]
+    fn test_solve1() {
+        assert_eq!(solve1(&example(), &example_window(false)), 2);
+        assert_eq!(solve1(&example(), &example_window(true)), 5);
+    }
+
+    #[test]
+    fn test_solve1_zero_width() -> Result<(), Box<dyn Error>> {
+        let window = |at: f64| Solve1Options {
+            min: at,
+            max: at,
+            past: false,
+        };
+        assert_eq!(solve1(&example(), &window(7.)), 0);
+
+        // these two cross at (10, 10) which is the whole window
+        let crossing = parse(
+            ["0, 0, 0 @ 1, 1, 0", "20, 0, 0 @ -1, 1, 0"]
+                .map(String::from)
+                .into_iter(),
+        )?;
+        assert_eq!(solve1(&crossing, &window(10.)), 1);
+        assert_eq!(solve1(&crossing, &window(10.5)), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_options() -> Result<(), Box<dyn Error>> {
+        let args = ["--max", "27", "--past", "--min", "7"].map(String::from);
+        let options = parse_solve1_options(&args)?;
+        assert_eq!((options.min, options.max, options.past), (7., 27., true));
+
+        assert!(parse_solve1_options(&["--min".to_string()]).is_err());
+        assert!(parse_solve1_options(&["--min", "3", "--max", "2"].map(String::from)).is_err());
+        Ok(())
+    }
+
+    fn hailstone(s: &str) -> Result<Hailstone, Box<dyn Error>> {
+        s.parse()
+    }
+
+    #[test]
+    fn test_closest_approach() -> Result<(), Box<dyn Error>> {
+        // skew lines, one above the other
+        let (t1, t2, distance) = closest_approach(
+            &hailstone("0, 0, 0 @ 1, 0, 0")?,
+            &hailstone("0, 5, 3 @ 0, 1, 0")?,
+        );
+        assert_eq!((t1, t2, distance), (0., -5., 3.));
+
+        // parallel lines
+        let (t1, t2, distance) = closest_approach(
+            &hailstone("0, 0, 0 @ 1, 0, 0")?,
+            &hailstone("2, 4, 0 @ 2, 0, 0")?,
+        );
+        assert_eq!((t1, t2, distance), (0., -1., 4.));
+
+        // the rock hits the first hailstone at t = 5
+        let (t1, t2, distance) = closest_approach(&solve_rock(&example())?, &example()[0]);
+        assert!((t1 - 5.).abs() < 1e-6);
+        assert!((t2 - 5.).abs() < 1e-6);
+        assert!(distance < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_closest_pairs() -> Result<(), Box<dyn Error>> {
+        let hailstones = vec![
+            hailstone("0, 0, 0 @ 1, 0, 0")?,
+            hailstone("0, 5, 3 @ 0, 1, 0")?,
+            hailstone("2, 4, 2 @ 2, 0, 0")?,
+        ];
+
+        let pairs = closest_pairs(&hailstones, 2);
+        let ids = pairs.iter().map(|(i, j, _)| (*i, *j)).collect::<Vec<_>>();
+        assert_eq!(ids, vec![(1, 2), (0, 1)]);
+
+        assert_eq!(
+            closest_report(&pairs[1..]),
+            "\
+            h1  h2  t1     t2      distance\n\
+            --  --  -----  ------  --------\n\
+            \x200   1  0.000  -5.000     3.000\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2() -> Result<(), Box<dyn Error>> {
+        assert_eq!(solve2(&example())?, 47);
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_hailstones() -> Result<(), Box<dyn Error>> {
+        let hailstones = [
+            "19, 13, 30 @ -2, 1, -2",
+            "18, 19, 22 @ -1, -1, -2",
+            "19, 13, 30 @ -2, 1, -2",
+            "20, 25, 34 @ 0, 0, 0",
+            "18, 19, 22 @ 1, 1, 1",
+        ]
+        .into_iter()
+        .map(hailstone)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let problems = check_hailstones(&hailstones)
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            vec![
+                "line 3: duplicate of line 1",
+                "line 4: zero velocity",
+                "line 5: same position as line 2 at t=0",
+            ]
+        );
+        assert!(check_hailstones(&example()).is_empty());
+
+        let (sanitized, problems) = sanitize_hailstones(hailstones.clone());
+        assert_eq!(sanitized, hailstones[..2]);
+        assert_eq!(problems.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_rock_parallel() -> Result<(), Box<dyn Error>> {
+        // the second and third hailstones of the example move in parallel, they are not used
+        // together
+        let rock = solve_rock(&example())?;
+        assert_eq!(
+            solve_rock(&[
+                example()[0].clone(),
+                example()[1].clone(),
+                example()[3].clone()
+            ])?,
+            rock
+        );
+
+        let parallel = [
+            "0, 0, 0 @ 1, 2, 3",
+            "1, 0, 0 @ 2, 4, 6",
+            "0, 1, 0 @ -1, -2, -3",
+            "0, 0, 1 @ 1, 1, 1",
+        ]
+        .into_iter()
+        .map(hailstone)
+        .collect::<Result<Vec<_>, _>>()?;
+        let error = solve_rock(&parallel).err().ok_or("expected an error")?;
+        assert_eq!(
+            error.to_string(),
+            "no three hailstones with pairwise non parallel velocities"
+        );
+
+        assert!(solve_rock(&example()[..2]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_clip_2d() -> Result<(), Box<dyn Error>> {
+        let window = example_window(false);
+        let clipped = |hailstone: &Hailstone, options: &Solve1Options| {
+            clip_2d(hailstone, options).map(|(from, to)| ((from.x, from.y), (to.x, to.y)))
+        };
+
+        // from its start to the edge it leaves the area through
+        assert_eq!(
+            clipped(&example()[0], &window),
+            Some(((19., 13.), (7., 19.)))
+        );
+        // this one starts above the area
+        assert_eq!(
+            clipped(&example()[3], &window),
+            Some(((10., 27.), (7., 21.)))
+        );
+        // the first one was in the area before its start
+        assert_eq!(
+            clipped(&example()[0], &example_window(true)),
+            Some(((27., 9.), (7., 19.)))
+        );
+
+        let still = parse(once("10, 10, 0 @ 0, 0, 1".to_string()))?;
+        assert_eq!(
+            clipped(&still[0], &example_window(true)),
+            Some(((10., 10.), (10., 10.)))
+        );
+        let away = parse(once("30, 10, 0 @ 1, 0, 0".to_string()))?;
+        assert_eq!(clipped(&away[0], &window), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_svg() -> Result<(), Box<dyn Error>> {
+        let hailstones = example();
+        let rock = solve_rock(&hailstones)?;
+        let window = example_window(false);
+
+        let svg = to_svg(&hailstones, Some(&rock), &window)?;
+        assert!(svg.starts_with("<svg "));
+        // the 5 trajectories and the rock, one point per intersection counted in part 1
+        assert_eq!(svg.matches("<line ").count(), 6);
+        assert_eq!(svg.matches("stroke=\"orange\"").count(), 1);
+        assert_eq!(
+            svg.matches("<circle ").count(),
+            solve1(&hailstones, &window)
+        );
+
+        let svg = to_svg(&hailstones, None, &example_window(true))?;
+        assert_eq!(svg.matches("<line ").count(), 5);
+        assert_eq!(svg.matches("<circle ").count(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_frames() -> Result<(), Box<dyn Error>> {
+        let hailstones = example();
+        let rock = solve_rock(&hailstones)?;
+        let frames = frames(&hailstones, Some(&rock), 0.5, 12);
+
+        assert_eq!(frames.len(), 12);
+        assert!(frames.iter().all(|frame| frame.positions.len() == 6));
+
+        // the rock hits the first hailstone at t = 5
+        let frame = &frames[10];
+        assert_eq!(frame.time, 5.);
+        let (_, hailstone) = &frame.positions[0];
+        let (id, rock) = &frame.positions[5];
+        assert_eq!(id, "rock");
+        assert!((hailstone.x - rock.x).abs() < 1e-6);
+        assert!((hailstone.y - rock.y).abs() < 1e-6);
+        assert!((hailstone.z - rock.z).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_export() -> Result<(), Box<dyn Error>> {
+        let frames = frames(&example()[..1], None, 0.5, 2);
+        let records = frame_records(&frames)?;
+        assert_eq!(
+            Format::Csv.render_records(&FRAME_FIELDS, &records)?,
+            "frame,time,id,x,y,z\n0,0,0,19,13,30\n1,0.5,0,18,13.5,29\n"
+        );
+        assert_eq!(
+            Format::Json.render_records(&FRAME_FIELDS, &records)?,
+            "[{\"frame\":0,\"time\":0,\"id\":\"0\",\"x\":19,\"y\":13,\"z\":30},\
+            {\"frame\":1,\"time\":0.5,\"id\":\"0\",\"x\":18,\"y\":13.5,\"z\":29}]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve1_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let hailstones = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve1(&hailstones, &Solve1Options::default());
+
+        assert_eq!(result, 24627);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve2_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let hailstones = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve2(&hailstones)?;
+
+        assert_eq!(result, 527310134398221);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        for s in [
+            "1, 2, 3",
+            "1, 2, 3 @ 1, 2",
+            "1, 2, 3 @ 1, 2, 3, 4",
+            "1, 2, 3 @ 1, , 3",
+            "inf, 0, 0 @ 1, 1, 1",
+            "0, 0, 0 @ NaN, 1, 1",
+        ] {
+            assert!(parse(s.lines().map(|s| s.to_string())).is_err(), "{:?}", s);
+        }
+    }
+}
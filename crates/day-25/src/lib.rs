@@ -0,0 +1,367 @@
+use itertools::Itertools;
+use rand::prelude::IteratorRandom;
+use rand::thread_rng;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::error::Error;
+
+pub type Graph = HashMap<String, HashSet<String>>;
+
+pub fn parse(itr: impl Iterator<Item = String>) -> Result<Graph, Box<dyn Error>> {
+    Ok(itr
+        .map(|line| -> Result<_, Box<dyn Error>> {
+            let (key, values) = line.split_once(": ").ok_or("Invalid input")?;
+            Ok(values
+                .split(' ')
+                .map(|v| (key.to_string(), v.to_string()))
+                .collect::<Vec<(String, String)>>())
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .fold(HashMap::new(), |mut acc, (k, v)| {
+            acc.entry(k.clone())
+                .or_insert_with(HashSet::new)
+                .insert(v.clone());
+            acc.entry(v).or_insert_with(HashSet::new).insert(k);
+            acc
+        }))
+}
+
+fn most_common_edges(graph: &Graph, count: i32) -> Result<Vec<(&String, &String)>, Box<dyn Error>> {
+    let mut edges: HashMap<(&String, &String), usize> = HashMap::new();
+
+    (0..count).try_for_each(|_| -> Result<(), Box<dyn Error>> {
+        // get two random keys
+        let key1 = graph
+            .keys()
+            .choose(&mut thread_rng())
+            .ok_or("Invalid key")?;
+        let key2 = graph
+            .keys()
+            .choose(&mut thread_rng())
+            .ok_or("Invalid key")?;
+
+        // now find a path from key1 to key2
+        let mut visited: HashSet<&String> = HashSet::new();
+        let mut queue: VecDeque<Vec<&String>> = VecDeque::new();
+        queue.push_front(vec![key1]);
+
+        while let Some(current_path) = queue.pop_back() {
+            let key = current_path.last().ok_or("Invalid path")?;
+
+            if *key == key2 {
+                current_path
+                    .into_iter()
+                    .tuple_windows()
+                    .for_each(|(k1, k2)| {
+                        let (k1, k2) = if k1 < k2 { (k1, k2) } else { (k2, k1) };
+                        edges.entry((k1, k2)).and_modify(|e| *e += 1).or_insert(1);
+                    });
+                break;
+            }
+
+            if visited.contains(key) {
+                continue;
+            }
+
+            visited.insert(key);
+
+            graph.get(*key).ok_or("Invalid key")?.iter().for_each(|k| {
+                let mut new_path = current_path.clone();
+                new_path.push(k);
+                queue.push_front(new_path);
+            });
+        }
+        Ok(())
+    })?;
+
+    // take the three most common edges
+    Ok(edges
+        .into_iter()
+        .sorted_by_key(|(_, v)| *v)
+        .rev()
+        .take(3)
+        .map(|(e, _)| e)
+        .sorted()
+        .collect::<Vec<_>>())
+}
+
+fn remove_edges(graph: &Graph, edges: &Vec<(&String, &String)>) -> Graph {
+    graph
+        .into_iter()
+        .map(|(k, v)| v.iter().map(move |v| (k, v)))
+        .flatten()
+        .filter(|(k, v)| !edges.contains(&(k, v)) && !edges.contains(&(v, k)))
+        .fold(HashMap::new(), |mut acc, (k, v)| {
+            acc.entry(k.clone())
+                .or_insert_with(HashSet::new)
+                .insert(v.clone());
+            acc.entry(v.clone())
+                .or_insert_with(HashSet::new)
+                .insert(k.clone());
+            acc
+        })
+}
+
+pub fn solve(graph: &Graph) -> Result<usize, Box<dyn Error>> {
+    let most_common = most_common_edges(graph, 300)?;
+
+    // now remove those three edges from a copy of the graph
+    let graph = remove_edges(graph, &most_common);
+
+    // now find the connected components
+    let node = graph.keys().next().ok_or("Invalid graph")?;
+
+    let visited = bfs_visit(&graph, &node, HashSet::new())?;
+    let count1 = visited.len();
+
+    // another non visited node
+    let node = graph
+        .keys()
+        .find(|k| !visited.contains(*k))
+        .ok_or("Invalid graph")?;
+
+    let visited = bfs_visit(&graph, &node, HashSet::new())?;
+    let count2 = visited.len();
+
+    assert_eq!(count1 + count2, graph.len());
+
+    Ok(count1 * count2)
+}
+
+// the vertices are numbered in the order of their names, each one lists its neighbors
+struct AdjacencyList {
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl From<&Graph> for AdjacencyList {
+    fn from(graph: &Graph) -> Self {
+        let names = graph.keys().sorted().collect::<Vec<_>>();
+        let indices = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (*name, index))
+            .collect::<HashMap<_, _>>();
+
+        let neighbors = names
+            .iter()
+            .map(|name| {
+                graph[*name]
+                    .iter()
+                    .filter_map(|neighbor| indices.get(neighbor).copied())
+                    .sorted()
+                    .collect()
+            })
+            .collect();
+
+        AdjacencyList { neighbors }
+    }
+}
+
+// Stoer-Wagner: each phase orders the vertices by maximum adjacency, the cut between the last one
+// and all the others is a candidate, then the last two are merged. Returns the size of the
+// minimum cut and the number of vertices on each side of it, the smaller side first.
+fn min_cut(graph: &AdjacencyList) -> Result<(usize, (usize, usize)), Box<dyn Error>> {
+    let count = graph.neighbors.len();
+    if count < 2 {
+        return Err("At least two vertices are needed".into());
+    }
+
+    // the number of edges between the merged vertices
+    let mut weights = graph
+        .neighbors
+        .iter()
+        .enumerate()
+        .map(|(vertex, neighbors)| {
+            neighbors
+                .iter()
+                .filter(|neighbor| **neighbor != vertex)
+                .fold(HashMap::new(), |mut acc, neighbor| {
+                    *acc.entry(*neighbor).or_insert(0) += 1;
+                    acc
+                })
+        })
+        .collect::<Vec<HashMap<usize, usize>>>();
+    let mut sizes = vec![1; count];
+    let mut active = (0..count).collect::<Vec<_>>();
+    let mut best: Option<(usize, usize)> = None;
+
+    while active.len() > 1 {
+        let mut keys = vec![0; count];
+        let mut added = vec![false; count];
+        let mut heap = active
+            .iter()
+            .map(|vertex| (0, *vertex))
+            .collect::<BinaryHeap<_>>();
+        let (mut previous, mut last) = (None, None);
+
+        while let Some((key, vertex)) = heap.pop() {
+            if added[vertex] || key != keys[vertex] {
+                continue;
+            }
+            added[vertex] = true;
+            (previous, last) = (last, Some(vertex));
+
+            for (neighbor, weight) in &weights[vertex] {
+                if !added[*neighbor] {
+                    keys[*neighbor] += weight;
+                    heap.push((keys[*neighbor], *neighbor));
+                }
+            }
+        }
+
+        let (s, t) = previous.zip(last).ok_or("Invalid phase")?;
+        if best.is_none_or(|(cut, _)| keys[t] < cut) {
+            best = Some((keys[t], sizes[t]));
+        }
+
+        // merge t into s
+        for (neighbor, weight) in std::mem::take(&mut weights[t]) {
+            weights[neighbor].remove(&t);
+            if neighbor != s {
+                *weights[s].entry(neighbor).or_insert(0) += weight;
+                *weights[neighbor].entry(s).or_insert(0) += weight;
+            }
+        }
+        sizes[s] += sizes[t];
+        active.retain(|vertex| *vertex != t);
+    }
+
+    let (cut, size) = best.ok_or("No cut found")?;
+    Ok((cut, (size.min(count - size), size.max(count - size))))
+}
+
+// deterministic version of solve, the three wires to cut are the minimum cut
+pub fn solve_min_cut(graph: &Graph) -> Result<usize, Box<dyn Error>> {
+    let (cut, (size1, size2)) = min_cut(&AdjacencyList::from(graph))?;
+    if cut != 3 {
+        return Err(format!("Expected a cut of 3 wires, got {}", cut).into());
+    }
+
+    Ok(size1 * size2)
+}
+
+fn bfs_visit<'a>(
+    graph: &'a HashMap<String, HashSet<String>>,
+    node: &'a String,
+    mut visited: HashSet<&'a String>,
+) -> Result<HashSet<&'a String>, Box<dyn Error>> {
+    let mut queue: VecDeque<&String> = VecDeque::new();
+    queue.push_front(&node);
+
+    while let Some(node) = queue.pop_back() {
+        if visited.contains(&node) {
+            continue;
+        }
+
+        visited.insert(&node);
+
+        graph.get(node).ok_or("Invalid node")?.iter().for_each(|n| {
+            queue.push_front(n);
+        });
+    }
+
+    Ok(visited)
+}
+
+#[cfg(test)]
+mod day25 {
+    use std::{
+        error::Error,
+        fs::File,
+        io::{BufRead, BufReader},
+    };
+
+    use itertools::Itertools;
+
+    use crate::{min_cut, parse, solve, solve_min_cut, AdjacencyList};
+
+    const EXAMPLE: &str = "\
+        jqt: rhn xhk nvd\n\
+        rsh: frs pzl lsr\n\
+        xhk: hfx\n\
+        cmg: qnr nvd lhk bvb\n\
+        rhn: xhk bvb hfx\n\
+        bvb: xhk hfx\n\
+        pzl: lsr hfx nvd\n\
+        qnr: nvd\n\
+        ntq: jqt hfx bvb xhk\n\
+        nvd: lhk\n\
+        lsr: lhk\n\
+        rzs: qnr cmg lsr rsh\n\
+        frs: qnr lhk lsr";
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+
+        assert_eq!(graph.len(), 15);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+
+        assert_eq!(solve(&graph)?, 54);
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_cut_example() -> Result<(), Box<dyn Error>> {
+        let graph = parse(EXAMPLE.lines().map(|s| s.to_string()))?;
+
+        assert_eq!(min_cut(&AdjacencyList::from(&graph))?, (3, (6, 9)));
+        assert_eq!(solve_min_cut(&graph)?, 54);
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_cut_small() -> Result<(), Box<dyn Error>> {
+        let min_cut_of = |lines: &[&str]| -> Result<_, Box<dyn Error>> {
+            let graph = parse(lines.iter().map(|s| s.to_string()))?;
+            min_cut(&AdjacencyList::from(&graph))
+        };
+
+        // two triangles joined by a single edge
+        assert_eq!(
+            min_cut_of(&["a: b c", "b: c", "c: d", "d: e f", "e: f"])?,
+            (1, (3, 3))
+        );
+        // a cycle, any vertex can be cut out with two edges
+        assert_eq!(min_cut_of(&["a: b", "b: c", "c: d", "d: e", "e: a"])?.0, 2);
+        // already disconnected
+        assert_eq!(min_cut_of(&["a: b c", "b: c", "d: e"])?, (0, (2, 3)));
+        // a single edge
+        assert_eq!(min_cut_of(&["a: b"])?, (1, (1, 1)));
+
+        assert!(min_cut(&AdjacencyList { neighbors: vec![] }).is_err());
+        assert!(min_cut(&AdjacencyList {
+            neighbors: vec![vec![]]
+        })
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_min_cut_invalid() -> Result<(), Box<dyn Error>> {
+        let graph = parse(["a: b c", "b: c"].iter().map(|s| s.to_string()))?;
+
+        assert!(solve_min_cut(&graph).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_input() -> Result<(), Box<dyn Error>> {
+        let file = File::open("input")?;
+        let reader = BufReader::new(file);
+        let graph = reader.lines().process_results(|itr| parse(itr))??;
+        let result = solve(&graph)?;
+
+        assert_eq!(result, 527790);
+        Ok(())
+    }
+}
@@ -0,0 +1,322 @@
+// checks run on the sources of the workspace rather than on the behavior of the days. The solvers
+// report their errors with Result, unwrap and expect are only allowed in the tests or on a line
+// justified with an allow-unwrap comment, on the line itself or on the one before
+
+use std::{
+    error::Error,
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+
+pub const ALLOW_MARKER: &str = "allow-unwrap";
+
+const FORBIDDEN: [&str; 2] = [".unwrap()", ".expect("];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub path: PathBuf,
+    pub line: usize,
+    pub text: String,
+}
+
+// the lines, numbered from 1, calling unwrap or expect outside of the items under #[cfg(test)]
+pub fn find_unwraps(source: &str) -> Vec<(usize, String)> {
+    let mut violations = Vec::new();
+    let lines = source.lines().collect::<Vec<_>>();
+
+    // the braces of the test item being skipped, None when not in a test item
+    let mut test_depth: Option<i64> = None;
+    let mut entered = false;
+    let mut literal = Literal::Code;
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(depth) = test_depth {
+            let depth = depth + brace_balance(line, &mut literal);
+            entered |= depth > 0;
+            // the item ends with its last brace, or with a semicolon when it has none
+            test_depth = if (entered && depth <= 0) || (!entered && trimmed.ends_with(';')) {
+                None
+            } else {
+                Some(depth)
+            };
+            continue;
+        }
+
+        if trimmed.starts_with("#[cfg(test)]") {
+            test_depth = Some(0);
+            entered = false;
+            literal = Literal::Code;
+            continue;
+        }
+
+        let code = trimmed.split("//").next().unwrap_or_default();
+        if !FORBIDDEN.iter().any(|pattern| code.contains(pattern)) {
+            continue;
+        }
+
+        let allowed = line.contains(ALLOW_MARKER)
+            || i.checked_sub(1)
+                .and_then(|previous| lines.get(previous))
+                .is_some_and(|previous| previous.contains(ALLOW_MARKER));
+        if !allowed {
+            violations.push((i + 1, trimmed.to_string()));
+        }
+    }
+
+    violations
+}
+
+// what the end of a line is in, the strings and the block comments can go on over the next lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Literal {
+    Code,
+    String,
+    // the number of hashes closing it
+    RawString(usize),
+    // the depth of the nested comments
+    Comment(usize),
+}
+
+// the braces opened minus the ones closed on a line, leaving out those of the strings, characters
+// and comments
+fn brace_balance(line: &str, literal: &mut Literal) -> i64 {
+    let chars = line.chars().collect::<Vec<_>>();
+    let at = |i: usize, s: &str| {
+        s.chars()
+            .enumerate()
+            .all(|(j, c)| chars.get(i + j) == Some(&c))
+    };
+    let mut balance = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match *literal {
+            Literal::String => match chars[i] {
+                '\\' => i += 1,
+                '"' => *literal = Literal::Code,
+                _ => (),
+            },
+            Literal::RawString(hashes) => {
+                if at(i, &format!("\"{}", "#".repeat(hashes))) {
+                    *literal = Literal::Code;
+                    i += hashes;
+                }
+            }
+            Literal::Comment(depth) => {
+                if at(i, "*/") {
+                    *literal = if depth > 1 {
+                        Literal::Comment(depth - 1)
+                    } else {
+                        Literal::Code
+                    };
+                    i += 1;
+                } else if at(i, "/*") {
+                    *literal = Literal::Comment(depth + 1);
+                    i += 1;
+                }
+            }
+            Literal::Code => match chars[i] {
+                '/' if at(i, "//") => break,
+                '/' if at(i, "/*") => {
+                    *literal = Literal::Comment(1);
+                    i += 1;
+                }
+                '"' => *literal = Literal::String,
+                // a raw string, not the end of an identifier
+                'r' if !i.checked_sub(1).is_some_and(|previous| {
+                    chars[previous].is_alphanumeric() || chars[previous] == '_'
+                }) =>
+                {
+                    let hashes = chars[i + 1..].iter().take_while(|c| **c == '#').count();
+                    if chars.get(i + 1 + hashes) == Some(&'"') {
+                        *literal = Literal::RawString(hashes);
+                        i += 1 + hashes;
+                    }
+                }
+                // a character, its quote being 2 characters away unless escaped, or a lifetime
+                '\'' if chars.get(i + 1) == Some(&'\\') => {
+                    i += 3;
+                    while i < chars.len() && chars[i] != '\'' {
+                        i += 1;
+                    }
+                }
+                '\'' if chars.get(i + 2) == Some(&'\'') => i += 2,
+                '{' => balance += 1,
+                '}' => balance -= 1,
+                _ => (),
+            },
+        }
+        i += 1;
+    }
+
+    balance
+}
+
+// the rust files under dir, sorted so that the reports are stable
+pub fn rust_files(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = Vec::new();
+
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(rust_files(&path)?);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+// the violations in the sources of the crates of the workspace. This crate is left out, it
+// mentions the forbidden calls to look for them
+pub fn scan(workspace: &Path) -> Result<Vec<Violation>, Box<dyn Error>> {
+    let mut crates = read_dir(workspace.join("crates"))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    crates.sort();
+
+    let mut violations = Vec::new();
+    for crate_dir in crates {
+        let src = crate_dir.join("src");
+        if crate_dir.ends_with("integration-tests") || !src.is_dir() {
+            continue;
+        }
+
+        for path in rust_files(&src)? {
+            let source = read_to_string(&path)?;
+            violations.extend(
+                find_unwraps(&source)
+                    .into_iter()
+                    .map(|(line, text)| Violation {
+                        path: path.clone(),
+                        line,
+                        text,
+                    }),
+            );
+        }
+    }
+
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{brace_balance, find_unwraps, scan, Literal};
+    use std::{error::Error, path::Path};
+
+    const SOURCE: &str = r#"
+fn solve(values: &[i32]) -> i32 {
+    let first = values.first().unwrap();
+    let last = values.last().copied().unwrap_or(0);
+    // values.first().unwrap() in a comment
+    let max = values.iter().max().expect("not empty"); // allow-unwrap: checked by the caller
+    // allow-unwrap: the slice is never empty
+    let min = values.iter().min().unwrap();
+    first + last + max + min + parse().expect("a number")
+}
+
+#[cfg(test)]
+use std::fs::File;
+
+fn other() -> i32 {
+    "1".parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    fn helper() {
+        if true {
+            Some(1).unwrap();
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_find_unwraps() {
+        let lines = find_unwraps(SOURCE)
+            .into_iter()
+            .map(|(line, _)| line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![3, 9, 16]);
+    }
+
+    #[test]
+    fn test_find_unwraps_clean() {
+        assert!(find_unwraps("fn f() -> Option<i32> {\n    Some(1)\n}\n").is_empty());
+        assert!(find_unwraps(
+            "#[cfg(test)]\nmod tests {\n    fn f() { None::<i32>.unwrap(); }\n}\n"
+        )
+        .is_empty());
+    }
+
+    // the braces in the literals and comments of the test items don't end them
+    const LITERALS: &str = r##"
+#[cfg(test)]
+mod tests {
+    fn braces() -> (&'static str, char, char) {
+        ("}", '}', '\'')
+    }
+
+    fn raw() -> &'static str {
+        r#"{{"#
+    }
+
+    /* } */
+    fn inside() {
+        Some(1).unwrap();
+    }
+}
+
+fn solve() -> i32 {
+    "{".parse().unwrap()
+}
+"##;
+
+    #[test]
+    fn test_find_unwraps_literals() {
+        let lines = find_unwraps(LITERALS)
+            .into_iter()
+            .map(|(line, _)| line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![19]);
+    }
+
+    #[test]
+    fn test_brace_balance() {
+        let mut literal = Literal::Code;
+        assert_eq!(brace_balance("fn f<'a>(x: &'a str) {", &mut literal), 1);
+        assert_eq!(brace_balance("let s = \"{\\\"{\"; }", &mut literal), -1);
+        assert_eq!(brace_balance("let c = '\\u{7b}'; {", &mut literal), 1);
+        assert_eq!(brace_balance("('\\'', '{')", &mut literal), 0);
+
+        // a string and a comment going on over the next line
+        assert_eq!(brace_balance("let s = \"{", &mut literal), 0);
+        assert_eq!(literal, Literal::String);
+        assert_eq!(brace_balance("}\"; /* { /* */", &mut literal), 0);
+        assert_eq!(literal, Literal::Comment(1));
+        assert_eq!(brace_balance("} */ }", &mut literal), -1);
+        assert_eq!(literal, Literal::Code);
+    }
+
+    #[test]
+    fn test_workspace() -> Result<(), Box<dyn Error>> {
+        let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let violations = scan(&workspace)?;
+
+        violations.iter().for_each(|violation| {
+            println!(
+                "{}:{}: {}",
+                violation.path.display(),
+                violation.line,
+                violation.text
+            )
+        });
+        assert!(violations.is_empty());
+        Ok(())
+    }
+}
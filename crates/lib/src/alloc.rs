@@ -0,0 +1,76 @@
+// an allocator counting the allocations of each thread, for the tests keeping an eye on the
+// allocations of the parsers. A test binary uses it with:
+//
+//     #[global_allocator]
+//     static ALLOCATOR: CountingAllocator = CountingAllocator;
+//
+// the counts are per thread as the tests run in parallel
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count() {
+    // the count is lost once the thread is being destroyed
+    let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+}
+
+pub struct CountingAllocator;
+
+// the memory is handled by the system allocator, a reallocation counts as an allocation
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+// the result of f with the number of allocations it made on this thread, always 0 when the
+// counting allocator isn't the global one
+pub fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+
+    (result, after - before)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alloc::{count_allocations, CountingAllocator};
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_count_allocations() {
+        let (_, count) = count_allocations(|| 1 + 1);
+        assert_eq!(count, 0);
+
+        let (_, count) = count_allocations(|| {
+            let mut values = vec![1];
+            values.reserve(10);
+            values
+        });
+        assert_eq!(count, 2);
+    }
+}
@@ -0,0 +1,266 @@
+// the command line options shared by the days: the part to run, where to read the input from,
+// where to write the exports and whether to time the run. The other arguments are left to the
+// days, `--part N` being passed to them as `-N` so that the modes keep their short form
+
+use crate::{
+    export::{write_artifact, Format},
+    input,
+};
+use std::{env::args, error::Error, io::BufRead, path::Path, str::FromStr, time::Instant};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    pub prog_name: String,
+    pub args: Vec<String>,
+    pub input: Option<String>,
+    pub out: Option<String>,
+    pub time: bool,
+    pub help: bool,
+}
+
+impl Options {
+    pub fn parse() -> Result<Self, Box<dyn Error>> {
+        let mut args = args();
+        let prog_name = args.next().ok_or("Cant get the program name")?;
+
+        Self::from_args(prog_name, args)
+    }
+
+    pub fn from_args(
+        prog_name: String,
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut options = Options {
+            prog_name,
+            ..Default::default()
+        };
+        let mut part = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" if part.is_some() => return Err("Multiple parts".into()),
+                "--part" => part = Some(format!("-{}", args.next().ok_or("Missing part")?)),
+                "--input" => options.input = Some(args.next().ok_or("Missing input file")?),
+                "--out" => options.out = Some(args.next().ok_or("Missing output directory")?),
+                "--time" => options.time = true,
+                "-h" | "--help" => options.help = true,
+                // the input can also be given before the mode, which always starts with a dash
+                _ if options.args.is_empty()
+                    && options.input.is_none()
+                    && (arg == "-" || !arg.starts_with('-')) =>
+                {
+                    options.input = Some(arg)
+                }
+                _ => options.args.push(arg),
+            }
+        }
+
+        // the part is the mode of the day, it comes first
+        if let Some(part) = part {
+            options.args.insert(0, part);
+        }
+
+        Ok(options)
+    }
+
+    // the mode of the day, None when the help is requested
+    pub fn mode(&self) -> Option<&String> {
+        if self.help {
+            None
+        } else {
+            self.args.first()
+        }
+    }
+
+    // the file given with --input or before the mode, stdin otherwise
+    pub fn input(&self) -> Result<Box<dyn BufRead>, Box<dyn Error>> {
+        input::open(self.input.as_deref().unwrap_or("-"))
+    }
+
+    // prints the export on stdout, or writes it in the --out directory as an artifact named after
+    // the day and the export, e.g. day-21-distances.pgm
+    pub fn export(&self, name: &str, format: Format, content: &str) -> Result<(), Box<dyn Error>> {
+        match &self.out {
+            Some(dir) => {
+                let day = Path::new(&self.prog_name)
+                    .file_name()
+                    .ok_or("Cant get the program name")?
+                    .to_string_lossy();
+                let name = format!("{}-{}", day, name);
+                let path = write_artifact(Path::new(dir), &name, format, content)?;
+                eprintln!("Wrote {}", path.display());
+            }
+            None => print!("{}", content),
+        }
+        Ok(())
+    }
+
+    // the modes are specific to each day, the options are common to all of them
+    pub fn usage(&self, modes: &str) -> String {
+        format!(
+            "Usage: {} [FILE] [--input FILE] [--out DIR] [--time] {}\n\
+            \n\
+            Options:\n  \
+            --part N      run the mode -N\n  \
+            --input FILE  read the input from FILE instead of stdin, - for stdin, gzipped or not\n  \
+            FILE          the same as --input FILE, before the mode\n  \
+            --out DIR     write the exports in DIR instead of stdout\n  \
+            --time        print the time taken on stderr\n  \
+            -h, --help    print this help",
+            self.prog_name, modes
+        )
+    }
+
+    // prints the time elapsed until it is dropped, when --time is given
+    pub fn timer(&self) -> Timer {
+        Timer {
+            start: self.time.then(Instant::now),
+        }
+    }
+}
+
+pub struct Timer {
+    start: Option<Instant>,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            eprintln!("Time: {:?}", start.elapsed());
+        }
+    }
+}
+
+// the implementation chosen with --impl by the days that have a bitboard one, which they only build
+// with their simd feature
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Implementation {
+    #[default]
+    Scalar,
+    Bitboard,
+}
+
+impl FromStr for Implementation {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scalar" => Ok(Implementation::Scalar),
+            "bitboard" => Ok(Implementation::Bitboard),
+            _ => Err(format!("unknown implementation: {}", s).into()),
+        }
+    }
+}
+
+impl Implementation {
+    // the implementation if the day is built with it, simd telling whether the day has its feature
+    pub fn available(self, simd: bool) -> Result<Self, Box<dyn Error>> {
+        match self {
+            Implementation::Bitboard if !simd => {
+                Err("the bitboard implementation needs the simd feature".into())
+            }
+            _ => Ok(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Implementation, Options};
+    use std::error::Error;
+
+    fn from_args(args: &[&str]) -> Result<Options, Box<dyn Error>> {
+        Options::from_args("day".into(), args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_short_form() -> Result<(), Box<dyn Error>> {
+        let options = from_args(&["-2", "--steps", "5"])?;
+        assert_eq!(options.args, vec!["-2", "--steps", "5"]);
+        assert_eq!(options.mode().map(|mode| mode.as_str()), Some("-2"));
+        assert_eq!(options.input, None);
+        assert!(!options.time);
+        Ok(())
+    }
+
+    #[test]
+    fn test_long_form() -> Result<(), Box<dyn Error>> {
+        let options = from_args(&[
+            "--input", "input", "--cost", "square", "--part", "1", "--time",
+        ])?;
+        assert_eq!(options.args, vec!["-1", "--cost", "square"]);
+        assert_eq!(options.input.as_deref(), Some("input"));
+        assert!(options.time);
+        Ok(())
+    }
+
+    #[test]
+    fn test_help() -> Result<(), Box<dyn Error>> {
+        for args in [&["-h"][..], &["--part", "1", "--help"]] {
+            let options = from_args(args)?;
+            assert!(options.help);
+            assert_eq!(options.mode(), None);
+        }
+
+        let usage = from_args(&[])?.usage("[-1|-2]");
+        assert!(
+            usage.starts_with("Usage: day [FILE] [--input FILE] [--out DIR] [--time] [-1|-2]\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_positional_input() -> Result<(), Box<dyn Error>> {
+        let options = from_args(&["--time", "input.gz", "-2", "file"])?;
+        assert_eq!(options.input.as_deref(), Some("input.gz"));
+        assert_eq!(options.args, vec!["-2", "file"]);
+
+        let options = from_args(&["-", "-1"])?;
+        assert_eq!(options.input.as_deref(), Some("-"));
+        assert_eq!(options.args, vec!["-1"]);
+
+        // after --input, the argument is left to the day
+        let options = from_args(&["--input", "input", "file"])?;
+        assert_eq!(options.args, vec!["file"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(from_args(&["--part"]).is_err());
+        assert!(from_args(&["--input"]).is_err());
+        assert!(from_args(&["--out"]).is_err());
+        assert!(from_args(&["--part", "1", "--part", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_implementation() -> Result<(), Box<dyn Error>> {
+        assert_eq!("scalar".parse::<Implementation>()?, Implementation::Scalar);
+        assert_eq!(
+            "bitboard".parse::<Implementation>()?,
+            Implementation::Bitboard
+        );
+        assert!("gpu".parse::<Implementation>().is_err());
+
+        assert_eq!(
+            Implementation::Bitboard.available(true)?,
+            Implementation::Bitboard
+        );
+        assert!(Implementation::Bitboard.available(false).is_err());
+        assert_eq!(
+            Implementation::Scalar.available(false)?,
+            Implementation::Scalar
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let options = Options {
+            input: Some("does/not/exist".into()),
+            ..Default::default()
+        };
+        assert!(options.input().is_err());
+    }
+}
@@ -0,0 +1,338 @@
+// writers for the grids and the records exported by the days, to be analysed or visualized with
+// external tools. They are printed on stdout or written as artifact files named after the day and
+// the export, see Options::export
+
+use std::{
+    error::Error,
+    fs::{create_dir_all, rename, write},
+    iter::once,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+fn width(rows: &[Vec<u8>]) -> Result<usize, Box<dyn Error>> {
+    let width = rows.first().map_or(0, |row| row.len());
+    if rows.iter().any(|row| row.len() != width) {
+        return Err("Inconsistent row length".into());
+    }
+    Ok(width)
+}
+
+fn join_rows(rows: &[Vec<u8>], separator: &str) -> String {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join(separator)
+                + "\n"
+        })
+        .collect()
+}
+
+// a plain graymap, see https://netpbm.sourceforge.net/doc/pgm.html
+pub fn pgm(rows: &[Vec<u8>]) -> Result<String, Box<dyn Error>> {
+    let width = width(rows)?;
+
+    Ok(format!("P2\n{} {}\n255\n", width, rows.len()) + &join_rows(rows, " "))
+}
+
+// one line per row, the values separated by commas
+pub fn csv(rows: &[Vec<u8>]) -> Result<String, Box<dyn Error>> {
+    width(rows)?;
+
+    Ok(join_rows(rows, ","))
+}
+
+// an array of rows, each row being an array of values
+pub fn json(rows: &[Vec<u8>]) -> Result<String, Box<dyn Error>> {
+    width(rows)?;
+
+    let rows = rows
+        .iter()
+        .map(|row| {
+            let values = row
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>();
+            format!("[{}]", values.join(","))
+        })
+        .collect::<Vec<_>>();
+    Ok(format!("[{}]\n", rows.join(",")))
+}
+
+// a value of a record, the numbers and the booleans are not quoted in json. A missing value is
+// empty in csv and null in json, like the floats which are not finite
+#[derive(Clone, Debug, PartialEq)]
+pub enum Field {
+    Number(i64),
+    Float(f64),
+    Bool(bool),
+    Text(String),
+    Null,
+}
+
+fn check_records(header: &[&str], records: &[Vec<Field>]) -> Result<(), Box<dyn Error>> {
+    if records.iter().any(|record| record.len() != header.len()) {
+        return Err("Inconsistent record length".into());
+    }
+    Ok(())
+}
+
+// a header line with the names of the fields then one line per record, the texts are quoted when
+// they contain a separator or a quote
+pub fn csv_records(header: &[&str], records: &[Vec<Field>]) -> Result<String, Box<dyn Error>> {
+    check_records(header, records)?;
+
+    let fields = records.iter().map(|record| {
+        record
+            .iter()
+            .map(|field| match field {
+                Field::Number(n) => n.to_string(),
+                Field::Float(f) if f.is_finite() => f.to_string(),
+                Field::Float(_) | Field::Null => String::new(),
+                Field::Bool(b) => b.to_string(),
+                Field::Text(s) if s.contains([',', '"', '\n']) => {
+                    format!("\"{}\"", s.replace('"', "\"\""))
+                }
+                Field::Text(s) => s.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+
+    Ok(once(header.join(","))
+        .chain(fields)
+        .map(|line| line + "\n")
+        .collect())
+}
+
+fn json_string(s: &str) -> String {
+    let escaped = s
+        .chars()
+        .map(|c| match c {
+            '"' => "\\\"".to_string(),
+            '\\' => "\\\\".to_string(),
+            '\n' => "\\n".to_string(),
+            c if c.is_control() => format!("\\u{:04x}", u32::from(c)),
+            c => c.to_string(),
+        })
+        .collect::<String>();
+    format!("\"{}\"", escaped)
+}
+
+// an array of objects, one per record, keyed by the names of the fields
+pub fn json_records(header: &[&str], records: &[Vec<Field>]) -> Result<String, Box<dyn Error>> {
+    check_records(header, records)?;
+
+    let objects = records
+        .iter()
+        .map(|record| {
+            let fields = header
+                .iter()
+                .zip(record)
+                .map(|(name, field)| {
+                    let value = match field {
+                        Field::Number(n) => n.to_string(),
+                        Field::Float(f) if f.is_finite() => f.to_string(),
+                        Field::Bool(b) => b.to_string(),
+                        Field::Text(s) => json_string(s),
+                        Field::Float(_) | Field::Null => "null".to_string(),
+                    };
+                    format!("{}:{}", json_string(name), value)
+                })
+                .collect::<Vec<_>>();
+            format!("{{{}}}", fields.join(","))
+        })
+        .collect::<Vec<_>>();
+    Ok(format!("[{}]\n", objects.join(",")))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Pgm,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "pgm" => Ok(Format::Pgm),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Invalid export format: {}", s).into()),
+        }
+    }
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Pgm => "pgm",
+            Format::Json => "json",
+        }
+    }
+
+    pub fn render(&self, rows: &[Vec<u8>]) -> Result<String, Box<dyn Error>> {
+        match self {
+            Format::Csv => csv(rows),
+            Format::Pgm => pgm(rows),
+            Format::Json => json(rows),
+        }
+    }
+
+    pub fn render_records(
+        &self,
+        header: &[&str],
+        records: &[Vec<Field>],
+    ) -> Result<String, Box<dyn Error>> {
+        match self {
+            Format::Csv => csv_records(header, records),
+            Format::Pgm => Err("Records can't be exported as pgm".into()),
+            Format::Json => json_records(header, records),
+        }
+    }
+}
+
+// writes the artifact `name` in `dir`, which is created if needed. An existing artifact is
+// replaced, the content being written to a temporary file first so that it is never left half
+// written
+pub fn write_artifact(
+    dir: &Path,
+    name: &str,
+    format: Format,
+    content: &str,
+) -> Result<PathBuf, Box<dyn Error>> {
+    create_dir_all(dir)?;
+
+    let path = dir.join(format!("{}.{}", name, format.extension()));
+    let temporary = dir.join(format!(".{}.{}.tmp", name, format.extension()));
+    write(&temporary, content)?;
+    rename(&temporary, &path)?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{csv, csv_records, json, json_records, pgm, write_artifact, Field, Format};
+    use std::{
+        env::temp_dir,
+        error::Error,
+        fs::{read_to_string, remove_dir_all},
+        process,
+    };
+
+    fn rows() -> Vec<Vec<u8>> {
+        vec![vec![0, 1, 255], vec![2, 3, 4]]
+    }
+
+    #[test]
+    fn test_pgm() -> Result<(), Box<dyn Error>> {
+        assert_eq!(pgm(&rows())?, "P2\n3 2\n255\n0 1 255\n2 3 4\n");
+        assert_eq!(pgm(&[])?, "P2\n0 0\n255\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv() -> Result<(), Box<dyn Error>> {
+        assert_eq!(csv(&rows())?, "0,1,255\n2,3,4\n");
+        assert_eq!(csv(&[])?, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_json() -> Result<(), Box<dyn Error>> {
+        assert_eq!(json(&rows())?, "[[0,1,255],[2,3,4]]\n");
+        assert_eq!(json(&[])?, "[]\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_inconsistent_rows() {
+        let rows = vec![vec![0, 1], vec![2]];
+        assert!(pgm(&rows).is_err());
+        assert!(csv(&rows).is_err());
+        assert!(json(&rows).is_err());
+    }
+
+    fn records() -> Vec<Vec<Field>> {
+        vec![
+            vec![Field::Number(-1), Field::Text("a".to_string())],
+            vec![Field::Number(2), Field::Text("b,\"c\"".to_string())],
+        ]
+    }
+
+    #[test]
+    fn test_bool_float_null_records() -> Result<(), Box<dyn Error>> {
+        let records = vec![
+            vec![Field::Bool(true), Field::Null, Field::Float(0.5)],
+            vec![Field::Bool(false), Field::Number(3), Field::Float(f64::NAN)],
+        ];
+        assert_eq!(
+            csv_records(&["b", "n", "f"], &records)?,
+            "b,n,f\ntrue,,0.5\nfalse,3,\n"
+        );
+        assert_eq!(
+            json_records(&["b", "n", "f"], &records)?,
+            "[{\"b\":true,\"n\":null,\"f\":0.5},{\"b\":false,\"n\":3,\"f\":null}]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_records() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            csv_records(&["n", "s"], &records())?,
+            "n,s\n-1,a\n2,\"b,\"\"c\"\"\"\n"
+        );
+        assert_eq!(csv_records(&["n"], &[])?, "n\n");
+        assert!(csv_records(&["n"], &records()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_records() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            json_records(&["n", "s"], &records())?,
+            "[{\"n\":-1,\"s\":\"a\"},{\"n\":2,\"s\":\"b,\\\"c\\\"\"}]\n"
+        );
+        assert_eq!(json_records(&["n"], &[])?, "[]\n");
+        assert!(json_records(&["n", "s", "t"], &records()).is_err());
+        assert!(Format::Pgm.render_records(&["n", "s"], &records()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_format() -> Result<(), Box<dyn Error>> {
+        for format in [Format::Csv, Format::Pgm, Format::Json] {
+            assert_eq!(format.extension().parse::<Format>()?, format);
+        }
+        assert_eq!(Format::Pgm.render(&rows())?, pgm(&rows())?);
+        assert!("png".parse::<Format>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_artifact() -> Result<(), Box<dyn Error>> {
+        let dir = temp_dir()
+            .join(format!("export-{}", process::id()))
+            .join("out");
+
+        let path = write_artifact(&dir, "day-00-grid", Format::Csv, "1,2\n")?;
+        assert_eq!(path, dir.join("day-00-grid.csv"));
+        assert_eq!(read_to_string(&path)?, "1,2\n");
+
+        // overwritten
+        write_artifact(&dir, "day-00-grid", Format::Csv, "3,4\n")?;
+        assert_eq!(read_to_string(&path)?, "3,4\n");
+        assert_eq!(dir.read_dir()?.count(), 1);
+
+        remove_dir_all(dir.parent().ok_or("no parent")?)?;
+        Ok(())
+    }
+}
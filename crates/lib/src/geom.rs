@@ -0,0 +1,150 @@
+// 2D geometry shared by the days working on polygons, grids and trajectories
+
+pub fn manhattan(a: &(i64, i64), b: &(i64, i64)) -> i64 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
+
+// the consecutive pairs of vertices of a closed polygon
+fn edges(points: &[(i64, i64)]) -> impl Iterator<Item = (&(i64, i64), &(i64, i64))> {
+    points.iter().zip(points.iter().cycle().skip(1))
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// compute the area of a polygon using the shoelace formula
+// see https://en.wikipedia.org/wiki/Shoelace_formula
+pub fn polygon_area(points: &[(i64, i64)]) -> i64 {
+    edges(points)
+        .map(|(p1, p2)| p1.0 * p2.1 - p2.0 * p1.1)
+        .sum::<i64>()
+        .abs()
+        / 2
+}
+
+// the number of lattice points on the edges of a polygon whose vertices are lattice points
+pub fn boundary_point_count(points: &[(i64, i64)]) -> i64 {
+    edges(points)
+        .map(|(p1, p2)| gcd(p2.0 - p1.0, p2.1 - p1.1))
+        .sum()
+}
+
+// according to the pick theorem: https://en.wikipedia.org/wiki/Pick%27s_theorem
+//
+// A = i + b/2 - 1
+//
+// where:
+// - A is the area of the polygon
+// - i is the number of points inside the polygon
+// - b is the number of points on the boundary of the polygon
+//
+// so:
+// i = A - b/2 + 1
+pub fn interior_point_count(points: &[(i64, i64)]) -> i64 {
+    polygon_area(points) - boundary_point_count(points) / 2 + 1
+}
+
+// the number of lattice points inside or on the boundary of a polygon: i + b = A + b/2 + 1
+pub fn lattice_point_count(points: &[(i64, i64)]) -> i64 {
+    polygon_area(points) + boundary_point_count(points) / 2 + 1
+}
+
+// the intersection of the lines p1 + u * v1 and p2 + t * v2 with the parameters u and t of the
+// point on each line, None if the lines are parallel
+// see https://stackoverflow.com/a/2932601/12819315
+pub fn line_intersection(
+    (p1, v1): ((f64, f64), (f64, f64)),
+    (p2, v2): ((f64, f64), (f64, f64)),
+) -> Option<((f64, f64), f64, f64)> {
+    let dx = p2.0 - p1.0;
+    let dy = p2.1 - p1.1;
+    let det = v2.0 * v1.1 - v2.1 * v1.0;
+
+    (det != 0.).then(|| {
+        let u = (dy * v2.0 - dx * v2.1) / det;
+        let t = (dy * v1.0 - dx * v1.1) / det;
+
+        ((p1.0 + u * v1.0, p1.1 + u * v1.1), u, t)
+    })
+}
+
+// the intersection of the segments [a1, b1] and [a2, b2], None if they don't cross or are
+// parallel
+pub fn segment_intersection(
+    (a1, b1): ((f64, f64), (f64, f64)),
+    (a2, b2): ((f64, f64), (f64, f64)),
+) -> Option<(f64, f64)> {
+    let v1 = (b1.0 - a1.0, b1.1 - a1.1);
+    let v2 = (b2.0 - a2.0, b2.1 - a2.1);
+
+    line_intersection((a1, v1), (a2, v2))
+        .filter(|(_, u, t)| (0. ..=1.).contains(u) && (0. ..=1.).contains(t))
+        .map(|(p, _, _)| p)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geom::{
+        boundary_point_count, interior_point_count, lattice_point_count, line_intersection,
+        manhattan, polygon_area, segment_intersection,
+    };
+
+    // a 4x3 rectangle
+    const RECTANGLE: [(i64, i64); 4] = [(0, 0), (4, 0), (4, 3), (0, 3)];
+    // a right triangle with a diagonal edge
+    const TRIANGLE: [(i64, i64); 3] = [(0, 0), (4, 0), (0, 4)];
+
+    #[test]
+    fn test_manhattan() {
+        assert_eq!(manhattan(&(1, 6), &(5, 11)), 9);
+        assert_eq!(manhattan(&(5, 11), &(1, 6)), 9);
+    }
+
+    #[test]
+    fn test_polygon_area() {
+        assert_eq!(polygon_area(&RECTANGLE), 12);
+        assert_eq!(polygon_area(&TRIANGLE), 8);
+        // the orientation doesn't matter
+        let mut reversed = RECTANGLE;
+        reversed.reverse();
+        assert_eq!(polygon_area(&reversed), 12);
+        assert_eq!(polygon_area(&[]), 0);
+    }
+
+    #[test]
+    fn test_point_counts() {
+        assert_eq!(boundary_point_count(&RECTANGLE), 14);
+        assert_eq!(interior_point_count(&RECTANGLE), 6);
+        assert_eq!(lattice_point_count(&RECTANGLE), 20);
+
+        assert_eq!(boundary_point_count(&TRIANGLE), 12);
+        assert_eq!(interior_point_count(&TRIANGLE), 3);
+        assert_eq!(lattice_point_count(&TRIANGLE), 15);
+    }
+
+    #[test]
+    fn test_line_intersection() {
+        let (p, u, t) =
+            line_intersection(((0., 0.), (1., 1.)), ((4., 0.), (-1., 1.))).unwrap_or_default();
+        assert_eq!((p, u, t), ((2., 2.), 2., 2.));
+
+        assert!(line_intersection(((0., 0.), (1., 1.)), ((1., 0.), (2., 2.))).is_none());
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        assert_eq!(
+            segment_intersection(((0., 0.), (4., 4.)), ((4., 0.), (0., 4.))),
+            Some((2., 2.))
+        );
+        assert_eq!(
+            segment_intersection(((0., 0.), (1., 1.)), ((4., 0.), (0., 4.))),
+            None
+        );
+    }
+}
@@ -0,0 +1,195 @@
+// the symmetries of the rectangular grids: the quarter turns and the mirrors. The points and the
+// directions are mapped along with the tiles, so that a walk in a grid is the same walk in its
+// image. The y axis goes down as in vec2
+
+use crate::vec2::{Dir4, Vec2};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    // clockwise
+    QuarterTurn,
+    HalfTurn,
+    ThreeQuarterTurn,
+    // the columns in reverse order
+    MirrorX,
+    // the rows in reverse order
+    MirrorY,
+    // along the diagonal from the top left corner
+    Transpose,
+    // along the diagonal from the top right corner
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::QuarterTurn,
+        Symmetry::HalfTurn,
+        Symmetry::ThreeQuarterTurn,
+        Symmetry::MirrorX,
+        Symmetry::MirrorY,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    pub fn inverse(self) -> Self {
+        match self {
+            Symmetry::QuarterTurn => Symmetry::ThreeQuarterTurn,
+            Symmetry::ThreeQuarterTurn => Symmetry::QuarterTurn,
+            _ => self,
+        }
+    }
+
+    // the width and the height swap with the quarter turns and the transpositions
+    pub fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Symmetry::QuarterTurn
+                | Symmetry::ThreeQuarterTurn
+                | Symmetry::Transpose
+                | Symmetry::AntiTranspose
+        )
+    }
+
+    pub fn size(self, width: i64, height: i64) -> (i64, i64) {
+        if self.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    // the image of a move, without the translation that keeps the points in the grid
+    pub fn vector(self, v: Vec2) -> Vec2 {
+        match self {
+            Symmetry::Identity => v,
+            Symmetry::QuarterTurn => v.rotate_cw(),
+            Symmetry::HalfTurn => -v,
+            Symmetry::ThreeQuarterTurn => v.rotate_ccw(),
+            Symmetry::MirrorX => Vec2::new(-v.x, v.y),
+            Symmetry::MirrorY => Vec2::new(v.x, -v.y),
+            Symmetry::Transpose => Vec2::new(v.y, v.x),
+            Symmetry::AntiTranspose => Vec2::new(-v.y, -v.x),
+        }
+    }
+
+    // the image of a point of a grid of the given size, the points out of the grid included
+    pub fn point(self, point: Vec2, width: i64, height: i64) -> Vec2 {
+        let (x, y) = (point.x, point.y);
+        let (last_x, last_y) = (width - 1, height - 1);
+
+        match self {
+            Symmetry::Identity => point,
+            Symmetry::QuarterTurn => Vec2::new(last_y - y, x),
+            Symmetry::HalfTurn => Vec2::new(last_x - x, last_y - y),
+            Symmetry::ThreeQuarterTurn => Vec2::new(y, last_x - x),
+            Symmetry::MirrorX => Vec2::new(last_x - x, y),
+            Symmetry::MirrorY => Vec2::new(x, last_y - y),
+            Symmetry::Transpose => Vec2::new(y, x),
+            Symmetry::AntiTranspose => Vec2::new(last_y - y, last_x - x),
+        }
+    }
+
+    pub fn direction(self, direction: Dir4) -> Dir4 {
+        let offset = self.vector(direction.offset());
+
+        Dir4::ALL
+            .into_iter()
+            .find(|image| image.offset() == offset)
+            .unwrap_or(direction)
+    }
+
+    // the image of the rows of a grid, all as long as the first one
+    pub fn apply<T: Clone>(self, rows: &[Vec<T>]) -> Vec<Vec<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let (image_width, image_height) = if self.swaps_axes() {
+            (rows.len(), width)
+        } else {
+            (width, rows.len())
+        };
+
+        // each tile of the image comes from the point the inverse maps it to
+        let inverse = self.inverse();
+        (0..image_height)
+            .map(|y| {
+                (0..image_width)
+                    .map(|x| {
+                        let image = Vec2::new(x as i64, y as i64);
+                        let source = inverse.point(image, image_width as i64, image_height as i64);
+                        rows[source.y as usize][source.x as usize].clone()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        grid::Symmetry,
+        vec2::{Dir4, Vec2},
+    };
+
+    fn rows(lines: &[&str]) -> Vec<Vec<char>> {
+        lines.iter().map(|line| line.chars().collect()).collect()
+    }
+
+    #[test]
+    fn test_apply() {
+        let grid = rows(&["abc", "def"]);
+
+        assert_eq!(Symmetry::Identity.apply(&grid), grid);
+        assert_eq!(
+            Symmetry::QuarterTurn.apply(&grid),
+            rows(&["da", "eb", "fc"])
+        );
+        assert_eq!(Symmetry::HalfTurn.apply(&grid), rows(&["fed", "cba"]));
+        assert_eq!(
+            Symmetry::ThreeQuarterTurn.apply(&grid),
+            rows(&["cf", "be", "ad"])
+        );
+        assert_eq!(Symmetry::MirrorX.apply(&grid), rows(&["cba", "fed"]));
+        assert_eq!(Symmetry::MirrorY.apply(&grid), rows(&["def", "abc"]));
+        assert_eq!(Symmetry::Transpose.apply(&grid), rows(&["ad", "be", "cf"]));
+        assert_eq!(
+            Symmetry::AntiTranspose.apply(&grid),
+            rows(&["fc", "eb", "da"])
+        );
+        assert!(Symmetry::QuarterTurn.apply::<char>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_points_and_directions() {
+        let grid = rows(&["abcd", "efgh", "ijkl"]);
+        let (width, height) = (4, 3);
+
+        for symmetry in Symmetry::ALL {
+            let image = symmetry.apply(&grid);
+            assert_eq!(symmetry.inverse().apply(&image), grid);
+
+            let (image_width, image_height) = symmetry.size(width, height);
+            assert_eq!(
+                (image[0].len(), image.len()),
+                (image_width as usize, image_height as usize)
+            );
+
+            for (y, row) in grid.iter().enumerate() {
+                for (x, tile) in row.iter().enumerate() {
+                    let point = Vec2::new(x as i64, y as i64);
+                    let mapped = symmetry.point(point, width, height);
+                    assert_eq!(image[mapped.y as usize][mapped.x as usize], *tile);
+
+                    // a move in the grid is the move in the direction of the image
+                    for direction in Dir4::ALL {
+                        assert_eq!(
+                            symmetry.point(point + direction.offset(), width, height),
+                            mapped + symmetry.direction(direction).offset()
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// the reading of the inputs of the days, from a file or from stdin. The inputs compressed with gzip
+// are recognized by their first bytes and decompressed on the fly, whatever their name
+
+use flate2::bufread::MultiGzDecoder;
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, stdin, BufRead, BufReader, Lines},
+};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+// the source is a path, or - for stdin
+pub fn open(source: &str) -> Result<Box<dyn BufRead>, Box<dyn Error>> {
+    let reader = if source == "-" {
+        decompress(stdin().lock())
+    } else {
+        File::open(source).and_then(|file| decompress(BufReader::new(file)))
+    };
+
+    reader.map_err(|e| format!("{}: {}", source, e).into())
+}
+
+pub fn read(source: &str) -> Result<Lines<Box<dyn BufRead>>, Box<dyn Error>> {
+    Ok(open(source)?.lines())
+}
+
+// the reader as it is unless it starts with the magic bytes of gzip
+pub fn decompress(mut reader: impl BufRead + 'static) -> io::Result<Box<dyn BufRead>> {
+    if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::{decompress, read};
+    use flate2::{write::GzEncoder, Compression};
+    use std::{
+        env::temp_dir,
+        error::Error,
+        fs::{remove_file, write},
+        io::{BufRead, Cursor, Write},
+        process,
+    };
+
+    fn gzip(content: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content.as_bytes())?;
+        Ok(encoder.finish()?)
+    }
+
+    fn lines(reader: Box<dyn BufRead>) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(reader.lines().collect::<Result<_, _>>()?)
+    }
+
+    #[test]
+    fn test_decompress() -> Result<(), Box<dyn Error>> {
+        let plain = decompress(Cursor::new("1 2\n3 4\n"))?;
+        assert_eq!(lines(plain)?, vec!["1 2", "3 4"]);
+
+        let compressed = decompress(Cursor::new(gzip("1 2\n3 4\n")?))?;
+        assert_eq!(lines(compressed)?, vec!["1 2", "3 4"]);
+
+        assert!(lines(decompress(Cursor::new(""))?)?.is_empty());
+
+        // the magic bytes followed by garbage
+        let broken = decompress(Cursor::new(vec![0x1f, 0x8b, 0, 1, 2]))?;
+        assert!(lines(broken).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read() -> Result<(), Box<dyn Error>> {
+        let path = temp_dir().join(format!("input-{}.gz", process::id()));
+        write(&path, gzip("a\nb")?)?;
+        let source = path.to_string_lossy();
+
+        assert_eq!(
+            read(&source)?.collect::<Result<Vec<_>, _>>()?,
+            vec!["a", "b"]
+        );
+
+        remove_file(&path)?;
+        let error = read(&source).err().map(|e| e.to_string());
+        assert!(error.is_some_and(|e| e.starts_with(source.as_ref())));
+        Ok(())
+    }
+}
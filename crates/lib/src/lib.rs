@@ -1,5 +1,21 @@
 use std::{env::args, error::Error};
 
+pub mod alloc;
+pub mod cli;
+pub mod export;
+pub mod geom;
+pub mod grid;
+pub mod input;
+pub mod linalg;
+pub mod par;
+pub mod parse;
+pub mod render;
+pub mod scan;
+pub mod table;
+pub mod testing;
+pub mod validate;
+pub mod vec2;
+
 pub const INVALID_INPUT: &str = "Invalid input";
 
 pub fn get_args() -> Result<(String, Vec<String>), Box<dyn Error>> {
@@ -0,0 +1,246 @@
+// the linear algebra of the trajectories: vectors of floats of a fixed size, the lines a point
+// follows when moving at a constant velocity and, with the nalgebra feature, the solving of square
+// linear systems
+
+use crate::geom::line_intersection;
+use std::ops::{Add, Index, Mul, Neg, Sub};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vector<const N: usize>(pub [f64; N]);
+
+pub type Vector2 = Vector<2>;
+pub type Vector3 = Vector<3>;
+
+impl<const N: usize> Vector<N> {
+    pub const ZERO: Self = Vector([0.; N]);
+
+    pub fn dot(&self, other: &Self) -> f64 {
+        self.0.iter().zip(other.0).map(|(a, b)| a * b).sum()
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+}
+
+impl Vector2 {
+    pub fn new(x: f64, y: f64) -> Self {
+        Vector([x, y])
+    }
+}
+
+impl Vector3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Vector([x, y, z])
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        let [x1, y1, z1] = self.0;
+        let [x2, y2, z2] = other.0;
+        Vector([y1 * z2 - z1 * y2, z1 * x2 - x1 * z2, x1 * y2 - y1 * x2])
+    }
+
+    // the part in the xy plane
+    pub fn xy(&self) -> Vector2 {
+        Vector([self.0[0], self.0[1]])
+    }
+}
+
+impl<const N: usize> Index<usize> for Vector<N> {
+    type Output = f64;
+
+    fn index(&self, i: usize) -> &f64 {
+        &self.0[i]
+    }
+}
+
+impl<const N: usize> Add for Vector<N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Vector(std::array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+}
+
+impl<const N: usize> Sub for Vector<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Vector(std::array::from_fn(|i| self.0[i] - other.0[i]))
+    }
+}
+
+impl<const N: usize> Neg for Vector<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Vector(self.0.map(|a| -a))
+    }
+}
+
+impl<const N: usize> Mul<f64> for Vector<N> {
+    type Output = Self;
+
+    fn mul(self, factor: f64) -> Self {
+        Vector(self.0.map(|a| a * factor))
+    }
+}
+
+// the points p + t * v for all the times t
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Line<const N: usize> {
+    pub point: Vector<N>,
+    pub direction: Vector<N>,
+}
+
+pub type Line2 = Line<2>;
+pub type Line3 = Line<3>;
+
+impl<const N: usize> Line<N> {
+    pub fn new(point: Vector<N>, direction: Vector<N>) -> Self {
+        Line { point, direction }
+    }
+
+    pub fn at(&self, t: f64) -> Vector<N> {
+        self.point + self.direction * t
+    }
+
+    // the times t1 and t2 at which the two lines are the closest, along with the distance between
+    // the two points. With w = p1 + v1 x t1 - p2 - v2 x t2, the closest points are reached when w
+    // is orthogonal to both directions:
+    // w . v1 = 0
+    // w . v2 = 0
+    // which gives a 2x2 linear system in t1 and t2. It is singular when the lines are parallel,
+    // in which case any t1 works, t1 = 0 is taken.
+    pub fn closest_approach(&self, other: &Self) -> (f64, f64, f64) {
+        let v1 = self.direction;
+        let v2 = other.direction;
+        let w0 = self.point - other.point;
+
+        let a = v1.dot(&v1);
+        let b = v1.dot(&v2);
+        let c = v2.dot(&v2);
+        let d = v1.dot(&w0);
+        let e = v2.dot(&w0);
+
+        let determinant = a * c - b * b;
+        let (t1, t2) = if determinant.abs() <= f64::EPSILON * a * c {
+            (0., if c == 0. { 0. } else { e / c })
+        } else {
+            ((b * e - c * d) / determinant, (a * e - b * d) / determinant)
+        };
+
+        let distance = (w0 + v1 * t1 - v2 * t2).norm();
+        (t1, t2, distance)
+    }
+}
+
+impl Line2 {
+    // the point where the two lines cross with the time on each line, None if they are parallel
+    pub fn intersection(&self, other: &Self) -> Option<(Vector2, f64, f64)> {
+        let pair = |v: Vector2| (v[0], v[1]);
+
+        line_intersection(
+            (pair(self.point), pair(self.direction)),
+            (pair(other.point), pair(other.direction)),
+        )
+        .map(|((x, y), t1, t2)| (Vector2::new(x, y), t1, t2))
+    }
+}
+
+impl Line3 {
+    pub fn is_parallel(&self, other: &Self) -> bool {
+        self.direction.cross(&other.direction) == Vector3::ZERO
+    }
+
+    // the projection on the xy plane
+    pub fn xy(&self) -> Line2 {
+        Line::new(self.point.xy(), self.direction.xy())
+    }
+}
+
+// the x such that a x = b, None if the matrix is singular. The matrix is given by rows
+#[cfg(feature = "nalgebra")]
+pub fn solve<const N: usize>(a: [[f64; N]; N], b: [f64; N]) -> Option<Vector<N>> {
+    use nalgebra::{DMatrix, DVector};
+
+    let a = DMatrix::from_fn(N, N, |i, j| a[i][j]);
+    let b = DVector::from_fn(N, |i, _| b[i]);
+
+    a.lu()
+        .solve(&b)
+        .map(|x| Vector(std::array::from_fn(|i| x[i])))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linalg::{Line2, Line3, Vector2, Vector3};
+
+    #[test]
+    fn test_vectors() {
+        let v = Vector3::new(1., 2., 2.);
+        assert_eq!(v.norm(), 3.);
+        assert_eq!(v.dot(&Vector3::new(2., 0., -1.)), 0.);
+        assert_eq!(
+            Vector3::new(1., 0., 0.).cross(&Vector3::new(0., 1., 0.)),
+            Vector3::new(0., 0., 1.)
+        );
+        assert_eq!(v - v * 2. + -v, Vector3::new(-2., -4., -4.));
+        assert_eq!(v.xy(), Vector2::new(1., 2.));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let line = |x, y, dx, dy| Line2::new(Vector2::new(x, y), Vector2::new(dx, dy));
+
+        assert_eq!(
+            line(0., 0., 1., 1.).intersection(&line(4., 0., -1., 1.)),
+            Some((Vector2::new(2., 2.), 2., 2.))
+        );
+        // the times can be negative
+        assert_eq!(
+            line(0., 0., -1., -1.).intersection(&line(4., 0., 1., -1.)),
+            Some((Vector2::new(2., 2.), -2., -2.))
+        );
+        assert_eq!(
+            line(0., 0., 1., 1.).intersection(&line(1., 0., 2., 2.)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_closest_approach() {
+        let line = |p: [f64; 3], v: [f64; 3]| {
+            Line3::new(
+                Vector3::new(p[0], p[1], p[2]),
+                Vector3::new(v[0], v[1], v[2]),
+            )
+        };
+
+        // skew lines, one above the other
+        let (l1, l2) = (
+            line([0., 0., 0.], [1., 0., 0.]),
+            line([0., 5., 3.], [0., 1., 0.]),
+        );
+        assert_eq!(l1.closest_approach(&l2), (0., -5., 3.));
+        assert!(!l1.is_parallel(&l2));
+
+        // parallel lines
+        let l3 = line([2., 4., 0.], [2., 0., 0.]);
+        assert_eq!(l1.closest_approach(&l3), (0., -1., 4.));
+        assert!(l1.is_parallel(&l3));
+        assert_eq!(l1.at(2.), Vector3::new(2., 0., 0.));
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_solve() {
+        use crate::linalg::{solve, Vector};
+
+        assert_eq!(
+            solve([[2., 1.], [1., 3.]], [4., 7.]),
+            Some(Vector([1., 2.]))
+        );
+        assert_eq!(solve([[1., 2.], [2., 4.]], [1., 2.]), None);
+    }
+}
@@ -0,0 +1,81 @@
+// parallel maps whose results come back in the order of their inputs, so that the output of the
+// days stays deterministic whatever the scheduling
+
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use std::time::{Duration, Instant};
+
+// the result of a map on the item at `index` and the time it took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timed<T> {
+    pub index: usize,
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+pub fn map_indexed<T, U, F>(items: &[T], f: F) -> Vec<Timed<U>>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync,
+{
+    // collecting an indexed parallel iterator puts each result at the position of its item
+    items
+        .par_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let start = Instant::now();
+            let value = f(item);
+            Timed {
+                index,
+                value,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::par::map_indexed;
+    use std::{thread::sleep, time::Duration};
+
+    #[test]
+    fn test_map_indexed_order() {
+        let items = (0..1000).collect::<Vec<u64>>();
+        let results = map_indexed(&items, |item| item * item);
+
+        results
+            .iter()
+            .enumerate()
+            .for_each(|(i, timed)| assert_eq!(timed.index, i));
+        assert_eq!(
+            results
+                .into_iter()
+                .map(|timed| timed.value)
+                .collect::<Vec<_>>(),
+            items.iter().map(|item| item * item).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_map_indexed_timing() {
+        // the slow items finish last but stay in place
+        let items = [20, 0, 10];
+        let results = map_indexed(&items, |millis| {
+            sleep(Duration::from_millis(*millis));
+            *millis
+        });
+
+        assert_eq!(
+            results.iter().map(|timed| timed.value).collect::<Vec<_>>(),
+            vec![20, 0, 10]
+        );
+        assert!(results[0].elapsed >= Duration::from_millis(20));
+        assert!(results[2].elapsed >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_map_indexed_empty() {
+        assert!(map_indexed(&[] as &[u8], |item| *item).is_empty());
+    }
+}
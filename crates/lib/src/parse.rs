@@ -0,0 +1,254 @@
+// parsing of the fields of a line without copying them. A cursor is a slice of the line that
+// remembers where it starts in it, so the fields it is split into are slices of the line as well
+// and the errors can tell the column where the line stops making sense
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    iter::from_fn,
+    str::FromStr,
+};
+
+// columns start at 1
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cursor<'a> {
+    line: &'a str,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(line: &'a str) -> Self {
+        Cursor {
+            line,
+            start: 0,
+            end: line.len(),
+        }
+    }
+
+    // what is left of the line
+    pub fn rest(&self) -> &'a str {
+        &self.line[self.start..self.end]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn column(&self) -> usize {
+        self.line[..self.start].chars().count() + 1
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            column: self.column(),
+            message: message.into(),
+        }
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Self {
+        Cursor {
+            line: self.line,
+            start: self.start + start,
+            end: self.start + end,
+        }
+    }
+
+    // skips the tag, which must start what is left
+    pub fn tag(&mut self, tag: &str) -> Result<(), ParseError> {
+        if !self.rest().starts_with(tag) {
+            return Err(self.error(format!("expected '{}'", tag)));
+        }
+        *self = self.slice(tag.len(), self.end - self.start);
+        Ok(())
+    }
+
+    // the next char, the cursor moving after it
+    pub fn char(&mut self) -> Result<char, ParseError> {
+        let c = self
+            .rest()
+            .chars()
+            .next()
+            .ok_or_else(|| self.error("unexpected end of line"))?;
+        *self = self.slice(c.len_utf8(), self.end - self.start);
+        Ok(c)
+    }
+
+    pub fn trim(&self) -> Self {
+        let rest = self.rest();
+        let start = rest.len() - rest.trim_start().len();
+        let end = rest.trim_end().len();
+        self.slice(start, end.max(start))
+    }
+
+    pub fn trim_start(&self) -> Self {
+        let rest = self.rest();
+        self.slice(rest.len() - rest.trim_start().len(), rest.len())
+    }
+
+    // the field before the first delimiter, the cursor moving after it
+    pub fn until(&mut self, delimiter: &str) -> Result<Self, ParseError> {
+        let (field, rest) = self.split_once(delimiter)?;
+        *self = rest;
+        Ok(field)
+    }
+
+    // the fields before and after the first delimiter, without building an error when it is
+    // missing so that splitting doesn't allocate
+    fn find(&self, delimiter: &str) -> Option<(Self, Self)> {
+        let rest = self.rest();
+        let i = rest.find(delimiter)?;
+
+        Some((
+            self.slice(0, i),
+            self.slice(i + delimiter.len(), rest.len()),
+        ))
+    }
+
+    pub fn split_once(&self, delimiter: &str) -> Result<(Self, Self), ParseError> {
+        self.find(delimiter)
+            .ok_or_else(|| self.error(format!("missing '{}'", delimiter)))
+    }
+
+    // the fields between the delimiters, as many as the delimiters plus one
+    pub fn split(&self, delimiter: &'a str) -> impl Iterator<Item = Self> + Clone + 'a {
+        let mut rest = Some(*self);
+
+        from_fn(move || {
+            let current = rest?;
+            match current.find(delimiter) {
+                Some((field, after)) => {
+                    rest = Some(after);
+                    Some(field)
+                }
+                None => {
+                    rest = None;
+                    Some(current)
+                }
+            }
+        })
+    }
+
+    // the fields separated by whitespace, none for a blank cursor
+    pub fn words(&self) -> impl Iterator<Item = Self> + Clone + 'a {
+        let mut rest = self.trim_start();
+
+        from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let word_len = rest
+                .rest()
+                .find(char::is_whitespace)
+                .unwrap_or(rest.rest().len());
+            let word = rest.slice(0, word_len);
+            rest = rest.slice(word_len, rest.end - rest.start).trim_start();
+            Some(word)
+        })
+    }
+
+    // the whole cursor as a value, the error located at its start
+    pub fn value<T: FromStr>(&self) -> Result<T, ParseError>
+    where
+        T::Err: Display,
+    {
+        self.rest()
+            .parse()
+            .map_err(|e| self.error(format!("invalid value '{}': {}", self.rest(), e)))
+    }
+
+    // nothing must be left
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format!("unexpected '{}'", self.rest())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::{Cursor, ParseError};
+
+    fn texts<'a>(cursors: impl Iterator<Item = Cursor<'a>>) -> Vec<(usize, &'a str)> {
+        cursors
+            .map(|cursor| (cursor.column(), cursor.rest()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fields() -> Result<(), ParseError> {
+        let mut cursor = Cursor::new("Game 12: 3 blue, 4 red; 1 green");
+        cursor.tag("Game ")?;
+        let id = cursor.until(":")?;
+        assert_eq!(id.value::<u32>()?, 12);
+        assert_eq!(cursor.column(), 9);
+
+        let draws = cursor.split(";").collect::<Vec<_>>();
+        assert_eq!(
+            texts(draws.iter().copied()),
+            vec![(9, " 3 blue, 4 red"), (24, " 1 green")]
+        );
+        assert_eq!(
+            texts(draws[0].split(",").map(|cubes| cubes.trim())),
+            vec![(10, "3 blue"), (18, "4 red")]
+        );
+        assert_eq!(texts(draws[1].words()), vec![(25, "1"), (27, "green")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split() {
+        let cursor = Cursor::new("a,,b");
+        assert_eq!(texts(cursor.split(",")), vec![(1, "a"), (3, ""), (4, "b")]);
+        assert_eq!(texts(Cursor::new("").split(",")), vec![(1, "")]);
+        assert_eq!(texts(Cursor::new("  ").words()), vec![]);
+        assert_eq!(
+            texts(Cursor::new(" é  b ").words()),
+            vec![(2, "é"), (5, "b")]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut cursor = Cursor::new("é");
+        assert_eq!(cursor.char(), Ok('é'));
+        assert_eq!(cursor.char().map_err(|e| e.column), Err(2));
+
+        let mut cursor = Cursor::new("Card é1: x");
+        assert_eq!(
+            cursor.tag("Game"),
+            Err(ParseError {
+                column: 1,
+                message: "expected 'Game'".to_string()
+            })
+        );
+
+        cursor.tag("Card ").ok();
+        let id = cursor.until(":").map(|id| id.value::<u32>());
+        assert_eq!(
+            id.ok().and_then(|id| id.err()).map(|e| e.to_string()),
+            Some("column 6: invalid value 'é1': invalid digit found in string".to_string())
+        );
+        assert_eq!(cursor.until("|").err().map(|e| e.column), Some(9));
+        assert_eq!(
+            cursor.trim().end().map_err(|e| e.to_string()),
+            Err("column 10: unexpected 'x'".to_string())
+        );
+    }
+}
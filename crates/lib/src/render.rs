@@ -0,0 +1,129 @@
+// a minimal svg writer for the drawings of the days. The shapes are given in the coordinates of
+// the puzzle, the y axis going up, and scaled to a canvas of a given width so that the widths of
+// the lines and the radiuses of the points are in pixels whatever the size of the area drawn
+
+use std::{error::Error, iter::once};
+
+pub type Point = (f64, f64);
+
+pub struct Svg {
+    min: Point,
+    max: Point,
+    scale: f64,
+    elements: Vec<String>,
+}
+
+// the attributes are written between double quotes
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+}
+
+impl Svg {
+    pub fn new(min: Point, max: Point, width: f64) -> Result<Self, Box<dyn Error>> {
+        let finite = [min.0, min.1, max.0, max.1, width]
+            .iter()
+            .all(|value| value.is_finite());
+        if !finite || min.0 >= max.0 || min.1 >= max.1 || width <= 0. {
+            return Err("The area to draw must be a non empty rectangle".into());
+        }
+
+        Ok(Svg {
+            min,
+            max,
+            scale: width / (max.0 - min.0),
+            elements: Vec::new(),
+        })
+    }
+
+    fn to_canvas(&self, (x, y): Point) -> Point {
+        ((x - self.min.0) * self.scale, (self.max.1 - y) * self.scale)
+    }
+
+    pub fn line(&mut self, from: Point, to: Point, color: &str, width: f64) {
+        let (x1, y1) = self.to_canvas(from);
+        let (x2, y2) = self.to_canvas(to);
+        self.elements.push(format!(
+            "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+            x1,
+            y1,
+            x2,
+            y2,
+            escape(color),
+            width
+        ));
+    }
+
+    pub fn circle(&mut self, center: Point, radius: f64, color: &str) {
+        let (x, y) = self.to_canvas(center);
+        self.elements.push(format!(
+            "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{}\" fill=\"{}\"/>",
+            x,
+            y,
+            radius,
+            escape(color)
+        ));
+    }
+
+    // the outline of the rectangle between two corners
+    pub fn rect(&mut self, corner1: Point, corner2: Point, color: &str, width: f64) {
+        let (x1, y1) = self.to_canvas(corner1);
+        let (x2, y2) = self.to_canvas(corner2);
+        self.elements.push(format!(
+            "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+            x1.min(x2),
+            y1.min(y2),
+            (x2 - x1).abs(),
+            (y2 - y1).abs(),
+            escape(color),
+            width
+        ));
+    }
+
+    // the document with the shapes in the order they were added, the later ones on top
+    pub fn render(&self) -> String {
+        let (width, height) = self.to_canvas((self.max.0, self.min.1));
+
+        once(format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"0 0 {:.2} {:.2}\">\n",
+            width, height, width, height
+        ))
+        .chain(self.elements.iter().map(|element| format!("  {}\n", element)))
+        .chain(once("</svg>\n".to_string()))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render::Svg;
+    use std::error::Error;
+
+    #[test]
+    fn test_render() -> Result<(), Box<dyn Error>> {
+        let mut svg = Svg::new((10., 0.), (20., 5.), 100.)?;
+        svg.line((10., 0.), (20., 5.), "black", 1.);
+        svg.circle((15., 4.), 3., "red");
+        svg.rect((10., 5.), (20., 0.), "a\"b", 2.);
+
+        assert_eq!(
+            svg.render(),
+            "\
+            <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"50\" viewBox=\"0 0 100.00 50.00\">\n  \
+            <line x1=\"0.00\" y1=\"50.00\" x2=\"100.00\" y2=\"0.00\" stroke=\"black\" stroke-width=\"1\"/>\n  \
+            <circle cx=\"50.00\" cy=\"10.00\" r=\"3\" fill=\"red\"/>\n  \
+            <rect x=\"0.00\" y=\"0.00\" width=\"100.00\" height=\"50.00\" fill=\"none\" stroke=\"a&quot;b\" stroke-width=\"2\"/>\n\
+            </svg>\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_area() {
+        assert!(Svg::new((0., 0.), (0., 1.), 100.).is_err());
+        assert!(Svg::new((0., 1.), (1., 0.), 100.).is_err());
+        assert!(Svg::new((0., 0.), (f64::INFINITY, 1.), 100.).is_err());
+        assert!(Svg::new((0., 0.), (1., 1.), 0.).is_err());
+    }
+}
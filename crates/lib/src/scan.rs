@@ -0,0 +1,183 @@
+// scanning of lines for digits, either plain or spelled out. Spelled out digits may overlap, as
+// in "oneight" which holds both 1 and 8, so every position of the line is tried as a start.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+const WORDS: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+#[derive(Default)]
+struct Node {
+    children: HashMap<char, usize>,
+    value: Option<u32>,
+}
+
+// the nodes are stored in a vector, the root being the first one
+pub struct Trie {
+    nodes: Vec<Node>,
+}
+
+impl Trie {
+    pub fn new<'a>(words: impl IntoIterator<Item = (&'a str, u32)>) -> Self {
+        let mut trie = Trie {
+            nodes: vec![Node::default()],
+        };
+        for (word, value) in words {
+            trie.insert(word, value);
+        }
+        trie
+    }
+
+    pub fn insert(&mut self, word: &str, value: u32) {
+        let mut current = 0;
+        for c in word.chars() {
+            current = match self.nodes[current].children.get(&c) {
+                Some(next) => *next,
+                None => {
+                    self.nodes.push(Node::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[current].children.insert(c, next);
+                    next
+                }
+            };
+        }
+        self.nodes[current].value = Some(value);
+    }
+
+    // the value of the shortest word starting the string
+    pub fn prefix_of(&self, s: &str) -> Option<u32> {
+        let mut current = 0;
+        for c in s.chars() {
+            current = *self.nodes[current].children.get(&c)?;
+            if let Some(value) = self.nodes[current].value {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+fn words() -> &'static Trie {
+    static WORDS_TRIE: OnceLock<Trie> = OnceLock::new();
+    WORDS_TRIE.get_or_init(|| Trie::new(WORDS))
+}
+
+// all the digits of the line in order, including the spelled out ones when allowed
+pub fn digits(line: &str, allow_words: bool) -> impl Iterator<Item = u32> + '_ {
+    line.char_indices().filter_map(move |(i, c)| {
+        c.to_digit(10)
+            .or_else(|| allow_words.then(|| words().prefix_of(&line[i..])).flatten())
+    })
+}
+
+pub fn first_last_digits(line: &str, allow_words: bool) -> Option<(u32, u32)> {
+    let mut itr = digits(line, allow_words);
+    let first = itr.next()?;
+    Some((first, itr.last().unwrap_or(first)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scan::{digits, first_last_digits, Trie, WORDS};
+
+    // the straightforward way, trying every word at every position
+    fn naive(line: &str, allow_words: bool) -> Vec<u32> {
+        line.char_indices()
+            .filter_map(|(i, c)| {
+                c.to_digit(10).or_else(|| {
+                    WORDS
+                        .iter()
+                        .find(|(word, _)| allow_words && line[i..].starts_with(word))
+                        .map(|(_, value)| *value)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_trie() {
+        let trie = Trie::new([("ab", 1), ("abc", 2), ("b", 3)]);
+        assert_eq!(trie.prefix_of("abc"), Some(1));
+        assert_eq!(trie.prefix_of("bc"), Some(3));
+        assert_eq!(trie.prefix_of("a"), None);
+        assert_eq!(trie.prefix_of("c"), None);
+        assert_eq!(trie.prefix_of(""), None);
+    }
+
+    #[test]
+    fn test_plain_digits() {
+        assert_eq!(first_last_digits("1abc2", false), Some((1, 2)));
+        assert_eq!(first_last_digits("treb7uchet", false), Some((7, 7)));
+        assert_eq!(first_last_digits("one2three", false), Some((2, 2)));
+        assert_eq!(first_last_digits("0", false), Some((0, 0)));
+        assert_eq!(first_last_digits("abc", false), None);
+        assert_eq!(first_last_digits("", false), None);
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(first_last_digits("two1nine", true), Some((2, 9)));
+        assert_eq!(first_last_digits("7pqrstsixteen", true), Some((7, 6)));
+        assert_eq!(first_last_digits("zero", true), None);
+        assert_eq!(first_last_digits("fiv", true), None);
+        assert_eq!(first_last_digits("One", true), None);
+        assert_eq!(first_last_digits("éeight", true), Some((8, 8)));
+        assert_eq!(first_last_digits("", true), None);
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        assert_eq!(digits("oneight", true).collect::<Vec<_>>(), vec![1, 8]);
+        assert_eq!(digits("twone", true).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(digits("eightwo", true).collect::<Vec<_>>(), vec![8, 2]);
+        assert_eq!(digits("eighthree", true).collect::<Vec<_>>(), vec![8, 3]);
+        assert_eq!(digits("sevenine", true).collect::<Vec<_>>(), vec![7, 9]);
+        assert_eq!(digits("nineight", true).collect::<Vec<_>>(), vec![9, 8]);
+        assert_eq!(
+            digits("twoneighthreeightwo", true).collect::<Vec<_>>(),
+            vec![2, 1, 8, 3, 8, 2]
+        );
+        assert_eq!(first_last_digits("zoneight", true), Some((1, 8)));
+    }
+
+    #[test]
+    fn test_word_pairs() {
+        for (first, x) in WORDS {
+            for (second, y) in WORDS {
+                let line = format!("{}{}", first, second);
+                assert_eq!(first_last_digits(&line, true), Some((x, y)));
+                assert_eq!(first_last_digits(&line, false), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_against_naive() {
+        // every short line made of letters of the words and a digit
+        let alphabet = ['o', 'n', 'e', 't', 'w', 'i', 'g', 'h', '5'];
+        let mut lines = vec![String::new()];
+        for _ in 0..5 {
+            lines = lines
+                .iter()
+                .flat_map(|line| alphabet.iter().map(move |c| format!("{}{}", line, c)))
+                .collect();
+            for line in &lines {
+                for allow_words in [false, true] {
+                    assert_eq!(
+                        digits(line, allow_words).collect::<Vec<_>>(),
+                        naive(line, allow_words)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// plain text rendering of tables and histograms for the analysis modes of the days
+
+use std::iter::once;
+
+// render rows as aligned columns below a header, numbers are right aligned
+pub fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let widths = (0..header.len())
+        .map(|column| {
+            once(header[column].len())
+                .chain(
+                    rows.iter()
+                        .map(|row| row.get(column).map_or(0, |cell| cell.len())),
+                )
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let render = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                if cell.parse::<f64>().is_ok() {
+                    format!("{:>width$}", cell)
+                } else {
+                    format!("{:<width$}", cell)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let separator = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    once(render(header.to_vec()))
+        .chain(once(separator))
+        .chain(rows.iter().map(|row| {
+            render(
+                (0..header.len())
+                    .map(|column| row.get(column).map_or("", |cell| cell.as_str()))
+                    .collect(),
+            )
+        }))
+        .map(|line| line + "\n")
+        .collect()
+}
+
+// render one bar per row, the largest count takes the full width and any non zero count gets
+// at least one character
+pub fn histogram(rows: &[(String, u64)], width: usize) -> String {
+    let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let count_width = rows
+        .iter()
+        .map(|(_, count)| count.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let max = rows.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+    rows.iter()
+        .map(|(label, count)| {
+            let bar = if *count == 0 {
+                0
+            } else {
+                ((*count as u128 * width as u128).div_ceil(max as u128) as usize).max(1)
+            };
+            format!(
+                "{:<label_width$}  {:>count_width$}  {}",
+                label,
+                count,
+                "#".repeat(bar)
+            )
+            .trim_end()
+            .to_string()
+                + "\n"
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::{histogram, table};
+
+    #[test]
+    fn test_table() {
+        let rows = vec![
+            vec!["1".to_string(), "12".to_string(), "3.5".to_string()],
+            vec!["25".to_string(), "7".to_string(), "n/a".to_string()],
+        ];
+
+        assert_eq!(
+            table(&["day", "part", "time"], &rows),
+            "\
+            day  part  time\n\
+            ---  ----  ----\n\
+            \x20 1    12   3.5\n\
+            \x2025     7  n/a\n"
+        );
+    }
+
+    #[test]
+    fn test_table_missing_cells() {
+        let rows = vec![vec!["a".to_string()], vec![]];
+
+        assert_eq!(table(&["x", "y"], &rows), "x  y\n-  -\na\n\n");
+    }
+
+    #[test]
+    fn test_histogram() {
+        let rows = vec![
+            ("a".to_string(), 10),
+            ("bcd".to_string(), 5),
+            ("e".to_string(), 1),
+            ("f".to_string(), 0),
+        ];
+
+        assert_eq!(
+            histogram(&rows, 4),
+            "\
+            a    10  ####\n\
+            bcd   5  ##\n\
+            e     1  #\n\
+            f     0\n"
+        );
+    }
+
+    #[test]
+    fn test_histogram_empty() {
+        assert_eq!(histogram(&[], 10), "");
+    }
+}
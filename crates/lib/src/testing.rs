@@ -0,0 +1,145 @@
+// helpers for the tests of the days, which all check the answers of the solvers on the examples
+// of the puzzle and on the input. The lines are read once and handed to the parser, whose result
+// goes to the solver
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    vec::IntoIter,
+};
+
+pub type Lines = IntoIter<String>;
+
+// the lines of an example written inline in the tests
+pub fn example_lines(example: &str) -> Lines {
+    example
+        .lines()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+pub fn file_lines(path: impl AsRef<Path>) -> Result<Lines, Box<dyn Error>> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    Ok(BufReader::new(file)
+        .lines()
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter())
+}
+
+pub fn solve_lines<P, T>(
+    lines: Lines,
+    parse: impl FnOnce(Lines) -> Result<P, Box<dyn Error>>,
+    solve: impl FnOnce(P) -> Result<T, Box<dyn Error>>,
+) -> Result<T, Box<dyn Error>> {
+    solve(parse(lines)?)
+}
+
+pub fn solve_example<P, T>(
+    example: &str,
+    parse: impl FnOnce(Lines) -> Result<P, Box<dyn Error>>,
+    solve: impl FnOnce(P) -> Result<T, Box<dyn Error>>,
+) -> Result<T, Box<dyn Error>> {
+    solve_lines(example_lines(example), parse, solve)
+}
+
+pub fn solve_file<P, T>(
+    path: impl AsRef<Path>,
+    parse: impl FnOnce(Lines) -> Result<P, Box<dyn Error>>,
+    solve: impl FnOnce(P) -> Result<T, Box<dyn Error>>,
+) -> Result<T, Box<dyn Error>> {
+    solve_lines(file_lines(path)?, parse, solve)
+}
+
+// one test per line, checking the answer of a solver on an example or on a file:
+//
+//     aoc_tests! {
+//         test_solve1_example: example(EXAMPLE), parse, solve1 => 142;
+//         test_solve1_input: file("input"), parse, solve1 => 56397;
+//     }
+//
+// the solvers working on the lines directly take Ok as parser
+#[macro_export]
+macro_rules! aoc_tests {
+    () => {};
+    ($name:ident: example($example:expr), $parse:expr, $solve:expr => $expected:expr; $($rest:tt)*) => {
+        #[test]
+        fn $name() -> Result<(), Box<dyn std::error::Error>> {
+            assert_eq!($crate::testing::solve_example($example, $parse, $solve)?, $expected);
+            Ok(())
+        }
+
+        $crate::aoc_tests!($($rest)*);
+    };
+    ($name:ident: file($path:expr), $parse:expr, $solve:expr => $expected:expr; $($rest:tt)*) => {
+        #[test]
+        fn $name() -> Result<(), Box<dyn std::error::Error>> {
+            assert_eq!($crate::testing::solve_file($path, $parse, $solve)?, $expected);
+            Ok(())
+        }
+
+        $crate::aoc_tests!($($rest)*);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{example_lines, file_lines, solve_example, solve_file, Lines};
+    use std::{
+        env::temp_dir,
+        error::Error,
+        fs::{remove_file, write},
+        process,
+    };
+
+    const EXAMPLE: &str = "\
+        1 2\n\
+        3 4";
+
+    fn parse(lines: Lines) -> Result<Vec<Vec<i32>>, Box<dyn Error>> {
+        lines
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|n| Ok(n.parse()?))
+                    .collect::<Result<Vec<_>, Box<dyn Error>>>()
+            })
+            .collect()
+    }
+
+    fn sum(rows: Vec<Vec<i32>>) -> Result<i32, Box<dyn Error>> {
+        Ok(rows.iter().flatten().sum())
+    }
+
+    crate::aoc_tests! {
+        test_macro_example: example(EXAMPLE), parse, sum => 10;
+        test_macro_lines: example(EXAMPLE), Ok, |lines: Lines| Ok(lines.count()) => 2;
+    }
+
+    #[test]
+    fn test_solve_example() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            example_lines(EXAMPLE).collect::<Vec<_>>(),
+            vec!["1 2", "3 4"]
+        );
+        assert_eq!(solve_example(EXAMPLE, parse, sum)?, 10);
+        assert!(solve_example("1 x", parse, sum).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_file() -> Result<(), Box<dyn Error>> {
+        let path = temp_dir().join(format!("testing-{}", process::id()));
+        write(&path, "5 6\n7\n")?;
+
+        assert_eq!(file_lines(&path)?.count(), 2);
+        assert_eq!(solve_file(&path, parse, sum)?, 18);
+
+        remove_file(&path)?;
+        assert!(solve_file(&path, parse, sum).is_err());
+        Ok(())
+    }
+}
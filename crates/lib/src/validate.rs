@@ -0,0 +1,360 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io::BufRead,
+    ops::RangeInclusive,
+    process::exit,
+};
+
+// a structural problem found in the input, lines and columns start at 1
+#[derive(Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(line: usize, column: Option<usize>, message: impl Into<String>) -> Self {
+        Problem {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}: {}", self.line, column, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+pub fn check_not_empty(lines: &[String]) -> Vec<Problem> {
+    if lines.iter().all(|line| line.is_empty()) {
+        vec![Problem::new(1, None, "empty input")]
+    } else {
+        Vec::new()
+    }
+}
+
+// every line must have the same length as the first one
+pub fn check_line_lengths(lines: &[String]) -> Vec<Problem> {
+    let width = lines.first().map_or(0, |line| line.chars().count());
+
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let length = line.chars().count();
+            (length != width).then(|| {
+                Problem::new(
+                    i + 1,
+                    None,
+                    format!(
+                        "inconsistent line length: expected {}, got {}",
+                        width, length
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+pub fn check_chars(lines: &[String], allowed: impl Fn(char) -> bool) -> Vec<Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, c)| !allowed(*c))
+                .map(move |(j, c)| {
+                    Problem::new(i + 1, Some(j + 1), format!("unknown character '{}'", c))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// a non empty rectangular grid made of the allowed characters
+pub fn check_grid(lines: &[String], allowed: &str) -> Vec<Problem> {
+    check_not_empty(lines)
+        .into_iter()
+        .chain(check_line_lengths(lines))
+        .chain(check_chars(lines, |c| allowed.contains(c)))
+        .collect()
+}
+
+// a character which must appear exactly once in the input
+pub fn check_unique_char(lines: &[String], c: char) -> Vec<Problem> {
+    let positions = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, c_)| *c_ == c)
+                .map(move |(j, _)| (i + 1, j + 1))
+        })
+        .collect::<Vec<_>>();
+
+    match positions.as_slice() {
+        [] => vec![Problem::new(1, None, format!("missing '{}'", c))],
+        [_] => Vec::new(),
+        [_, others @ ..] => others
+            .iter()
+            .map(|(line, column)| Problem::new(*line, Some(*column), format!("duplicate '{}'", c)))
+            .collect(),
+    }
+}
+
+// every line must be accepted by the parser of the day, offset is the line number of the first
+// line
+pub fn check_lines<T, E: Display>(
+    lines: &[String],
+    offset: usize,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Vec<Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            if line.is_empty() {
+                Some(Problem::new(i + offset, None, "empty line"))
+            } else {
+                parse(line)
+                    .err()
+                    .map(|e| Problem::new(i + offset, None, e.to_string()))
+            }
+        })
+        .collect()
+}
+
+// every number of the input must be in the range
+pub fn check_numbers(lines: &[String], range: RangeInclusive<i128>) -> Vec<Problem> {
+    let range = &range;
+
+    lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            numbers(line)
+                .into_iter()
+                .filter(|(_, number)| !number.is_some_and(|number| range.contains(&number)))
+                .map(move |(j, number)| {
+                    let message = match number {
+                        Some(number) => format!(
+                            "value {} out of range {}..={}",
+                            number,
+                            range.start(),
+                            range.end()
+                        ),
+                        None => "value out of range".to_string(),
+                    };
+                    Problem::new(i + 1, Some(j + 1), message)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// the numbers of a line with their column, None for those that don't fit in an i128
+fn numbers(line: &str) -> Vec<(usize, Option<i128>)> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let negative = chars[i] == '-' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit());
+        if chars[i].is_ascii_digit() || negative {
+            let start = i;
+            i += 1;
+            while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+                i += 1;
+            }
+            let number = chars[start..i].iter().collect::<String>().parse().ok();
+            numbers.push((start, number));
+        } else {
+            i += 1;
+        }
+    }
+
+    numbers
+}
+
+// the sections of the input separated by empty lines, with the line number of their first line.
+// Consecutive empty lines produce empty sections.
+pub fn sections(lines: &[String]) -> Vec<(usize, &[String])> {
+    let mut start = 0;
+    let mut sections = Vec::new();
+
+    lines.iter().enumerate().for_each(|(i, line)| {
+        if line.is_empty() {
+            sections.push((start + 1, &lines[start..i]));
+            start = i + 1;
+        }
+    });
+    if start < lines.len() {
+        sections.push((start + 1, &lines[start..]));
+    }
+
+    sections
+}
+
+pub fn check_sections(lines: &[String], count: Option<usize>) -> Vec<Problem> {
+    let sections = sections(lines);
+
+    let empty_sections = sections
+        .iter()
+        .filter(|(_, section)| section.is_empty())
+        .map(|(line, _)| Problem::new(*line, None, "empty section"));
+
+    let wrong_count = count.filter(|count| *count != sections.len()).map(|count| {
+        Problem::new(
+            lines.len().max(1),
+            None,
+            format!("expected {} sections, got {}", count, sections.len()),
+        )
+    });
+
+    empty_sections.chain(wrong_count).collect()
+}
+
+// applies the check to every section, the lines of the problems being relative to the input
+pub fn check_each_section(
+    lines: &[String],
+    check: impl Fn(&[String]) -> Vec<Problem>,
+) -> Vec<Problem> {
+    sections(lines)
+        .into_iter()
+        .filter(|(_, section)| !section.is_empty())
+        .flat_map(|(line, section)| {
+            check(section).into_iter().map(move |problem| Problem {
+                line: problem.line + line - 1,
+                ..problem
+            })
+        })
+        .collect()
+}
+
+// reads the input, prints the problems found by the validator in order and exits with a non zero
+// code if there are any
+pub fn run(
+    input: impl BufRead,
+    validator: impl Fn(&[String]) -> Vec<Problem>,
+) -> Result<(), Box<dyn Error>> {
+    let lines = input.lines().collect::<Result<Vec<_>, _>>()?;
+    let mut problems = validator(&lines);
+    problems.sort_by_key(|problem| (problem.line, problem.column));
+
+    if problems.is_empty() {
+        println!("OK");
+        Ok(())
+    } else {
+        problems.iter().for_each(|problem| println!("{}", problem));
+        exit(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::validate::{
+        check_each_section, check_grid, check_lines, check_numbers, check_sections,
+        check_unique_char, sections, Problem,
+    };
+
+    fn lines(s: &str) -> Vec<String> {
+        s.lines().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_check_grid() {
+        assert_eq!(check_grid(&lines("..#\n#..\n"), ".#"), vec![]);
+        assert_eq!(
+            check_grid(&lines("..#\n#.\n.x."), ".#"),
+            vec![
+                Problem::new(2, None, "inconsistent line length: expected 3, got 2"),
+                Problem::new(3, Some(2), "unknown character 'x'"),
+            ]
+        );
+        assert_eq!(
+            check_grid(&lines(""), ".#"),
+            vec![Problem::new(1, None, "empty input")]
+        );
+    }
+
+    #[test]
+    fn test_check_unique_char() {
+        assert_eq!(check_unique_char(&lines(".S.\n..."), 'S'), vec![]);
+        assert_eq!(
+            check_unique_char(&lines("...\n..."), 'S'),
+            vec![Problem::new(1, None, "missing 'S'")]
+        );
+        assert_eq!(
+            check_unique_char(&lines(".S.\n..S"), 'S'),
+            vec![Problem::new(2, Some(3), "duplicate 'S'")]
+        );
+    }
+
+    #[test]
+    fn test_check_lines() {
+        assert_eq!(
+            check_lines(&lines("1\nx\n\n3"), 10, |s| s.parse::<i32>()),
+            vec![
+                Problem::new(11, None, "invalid digit found in string"),
+                Problem::new(12, None, "empty line"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_numbers() {
+        assert_eq!(
+            check_numbers(
+                &lines("a 12 -3\nb 1000 99999999999999999999999999999999999999999"),
+                0..=999
+            ),
+            vec![
+                Problem::new(1, Some(6), "value -3 out of range 0..=999"),
+                Problem::new(2, Some(3), "value 1000 out of range 0..=999"),
+                Problem::new(2, Some(8), "value out of range"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let lines = lines("a\nb\n\nc\n\n\nd");
+        let sections = sections(&lines);
+        assert_eq!(
+            sections,
+            vec![
+                (1, &lines[0..2]),
+                (4, &lines[3..4]),
+                (6, &lines[5..5]),
+                (7, &lines[6..7]),
+            ]
+        );
+        assert_eq!(
+            check_sections(&lines, Some(2)),
+            vec![
+                Problem::new(6, None, "empty section"),
+                Problem::new(7, None, "expected 2 sections, got 4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_each_section() {
+        assert_eq!(
+            check_each_section(&lines("..\n..\n\n..\n.x"), |section| check_grid(
+                section, "."
+            )),
+            vec![Problem::new(5, Some(2), "unknown character 'x'")]
+        );
+    }
+}
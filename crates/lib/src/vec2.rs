@@ -0,0 +1,253 @@
+// the positions and the moves on the grids of the days. The y axis goes down as the lines of the
+// inputs do, so Up is (0, -1) and turning clockwise goes from Up to Right
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Vec2 { x, y }
+    }
+
+    // the length in steps on the grid
+    pub fn manhattan(self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn rotate_cw(self) -> Self {
+        Vec2::new(-self.y, self.x)
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        Vec2::new(self.y, -self.x)
+    }
+}
+
+impl From<(i64, i64)> for Vec2 {
+    fn from((x, y): (i64, i64)) -> Self {
+        Vec2 { x, y }
+    }
+}
+
+impl From<Vec2> for (i64, i64) {
+    fn from(v: Vec2) -> Self {
+        (v.x, v.y)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, factor: i64) -> Vec2 {
+        Vec2::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, other: Vec2) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, other: Vec2) {
+        *self = *self - other;
+    }
+}
+
+// the four directions in clockwise order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    // the position in ALL, for the tables indexed by direction
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Dir4::Up => Vec2::new(0, -1),
+            Dir4::Right => Vec2::new(1, 0),
+            Dir4::Down => Vec2::new(0, 1),
+            Dir4::Left => Vec2::new(-1, 0),
+        }
+    }
+
+    pub fn rotate_cw(self) -> Self {
+        Dir4::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        Dir4::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Dir4::ALL[(self.index() + 2) % 4]
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Dir4::Up | Dir4::Down)
+    }
+}
+
+// the eight directions in clockwise order, the diagonals between the directions they mix
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Dir8::Up => Vec2::new(0, -1),
+            Dir8::UpRight => Vec2::new(1, -1),
+            Dir8::Right => Vec2::new(1, 0),
+            Dir8::DownRight => Vec2::new(1, 1),
+            Dir8::Down => Vec2::new(0, 1),
+            Dir8::DownLeft => Vec2::new(-1, 1),
+            Dir8::Left => Vec2::new(-1, 0),
+            Dir8::UpLeft => Vec2::new(-1, -1),
+        }
+    }
+
+    // by 45 degrees
+    pub fn rotate_cw(self) -> Self {
+        Dir8::ALL[(self.index() + 1) % 8]
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        Dir8::ALL[(self.index() + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Dir8::ALL[(self.index() + 4) % 8]
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(direction: Dir4) -> Self {
+        Dir8::ALL[direction.index() * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vec2::{Dir4, Dir8, Vec2};
+
+    #[test]
+    fn test_vec2() {
+        let mut v = Vec2::new(2, -3);
+        assert_eq!(v + Vec2::new(1, 1), Vec2::new(3, -2));
+        assert_eq!(v - Vec2::new(1, 1), Vec2::new(1, -4));
+        assert_eq!(-v * 2, Vec2::new(-4, 6));
+        assert_eq!(v.manhattan(), 5);
+        assert_eq!(Vec2::from((2, -3)), v);
+        assert_eq!(<(i64, i64)>::from(v), (2, -3));
+
+        v += Vec2::new(-2, 3);
+        assert_eq!(v, Vec2::ZERO);
+        v -= Vec2::new(1, 0);
+        assert_eq!(v, Vec2::new(-1, 0));
+    }
+
+    #[test]
+    fn test_dir4() {
+        for direction in Dir4::ALL {
+            assert_eq!(direction.rotate_cw().rotate_ccw(), direction);
+            assert_eq!(direction.rotate_cw().rotate_cw(), direction.opposite());
+            assert_eq!(
+                direction.offset().rotate_cw(),
+                direction.rotate_cw().offset()
+            );
+            assert_eq!(
+                direction.offset().rotate_ccw(),
+                direction.rotate_ccw().offset()
+            );
+            assert_eq!(direction.opposite().offset(), -direction.offset());
+            assert_eq!(Dir8::from(direction).offset(), direction.offset());
+        }
+        assert_eq!(Dir4::Up.rotate_cw(), Dir4::Right);
+        assert!(Dir4::Down.is_vertical() && !Dir4::Left.is_vertical());
+    }
+
+    #[test]
+    fn test_dir8() {
+        for direction in Dir8::ALL {
+            assert_eq!(direction.rotate_cw().rotate_ccw(), direction);
+            assert_eq!(direction.opposite().offset(), -direction.offset());
+            assert_eq!(
+                direction.rotate_cw().rotate_cw().offset(),
+                direction.offset().rotate_cw()
+            );
+        }
+        assert_eq!(Dir8::Up.rotate_cw(), Dir8::UpRight);
+        assert_eq!(
+            Dir8::ALL
+                .iter()
+                .map(|d| d.offset())
+                .fold(Vec2::ZERO, |a, b| a + b),
+            Vec2::ZERO
+        );
+    }
+}
@@ -0,0 +1,467 @@
+// the performance regression suite of every day and of the variants of some parts: each one runs
+// several times on its input, the medians are saved as a baseline and the next runs fail when a part got
+// slower than its baseline by more than a threshold
+
+mod stats;
+mod variants;
+
+use lib::{get_args, input, table::table};
+use runner::{input_path, registry, Solver};
+use serde::{Deserialize, Serialize};
+use stats::{change, stats, Stats};
+use std::{
+    error::Error,
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+    process::exit,
+    time::Instant,
+};
+use variants::{variants, Variant};
+
+fn usage(prog_name: String) {
+    println!(
+        "Usage: {} [--inputs DIR] [--runs N] [--baseline FILE] [--threshold PERCENT] [--update]|-h",
+        prog_name
+    );
+    exit(0)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let (prog_name, args) = get_args()?;
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        usage(prog_name);
+    }
+    let options = parse_perf_options(&args)?;
+
+    let timings = measure_all(
+        &every_day(registry())?,
+        &variants(),
+        &options.inputs,
+        options.runs,
+    )?;
+
+    // the first run makes the baseline
+    if options.update || !options.baseline.exists() {
+        let baseline = Baseline {
+            runs: options.runs,
+            timings,
+        };
+        write(
+            &options.baseline,
+            serde_json::to_string_pretty(&baseline)? + "\n",
+        )?;
+        println!("Baseline written to {}", options.baseline.display());
+        return Ok(());
+    }
+
+    let baseline = read_baseline(&options.baseline)?;
+    print!("{}", report(&baseline, &timings));
+
+    let regressed = regressions(&baseline, &timings, options.threshold);
+    if !regressed.is_empty() {
+        return Err(format!(
+            "{} parts regressed by more than {}%",
+            regressed.len(),
+            options.threshold
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct PerfOptions {
+    // the inputs are cached in the crate of each day
+    inputs: PathBuf,
+    runs: usize,
+    baseline: PathBuf,
+    // the slowdown allowed in percent
+    threshold: f64,
+    update: bool,
+}
+
+impl Default for PerfOptions {
+    fn default() -> Self {
+        PerfOptions {
+            inputs: PathBuf::from("crates"),
+            runs: 10,
+            baseline: PathBuf::from("perf.json"),
+            threshold: 20.,
+            update: false,
+        }
+    }
+}
+
+fn parse_perf_options(args: &[String]) -> Result<PerfOptions, Box<dyn Error>> {
+    let mut options = PerfOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--inputs" => options.inputs = args.next().ok_or("missing inputs")?.into(),
+            "--runs" => options.runs = args.next().ok_or("missing runs")?.parse()?,
+            "--baseline" => options.baseline = args.next().ok_or("missing baseline")?.into(),
+            "--threshold" => options.threshold = args.next().ok_or("missing threshold")?.parse()?,
+            "--update" => options.update = true,
+            _ => return Err(format!("unknown option: {}", arg).into()),
+        }
+    }
+
+    if options.runs == 0 {
+        return Err("At least one run is required".into());
+    }
+    Ok(options)
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Timing {
+    day: u32,
+    part: usize,
+    // the name of the variant, none for the solver of the registry
+    variant: Option<String>,
+    median_ms: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Baseline {
+    runs: usize,
+    timings: Vec<Timing>,
+}
+
+fn read_baseline(path: &Path) -> Result<Baseline, Box<dyn Error>> {
+    let content = read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+// the solvers of all the days, a day missing from the registry fails the suite instead of being
+// left out of the timings
+fn every_day(solvers: Vec<Solver>) -> Result<Vec<Solver>, Box<dyn Error>> {
+    let missing = (1..=25)
+        .filter(|day| !solvers.iter().any(|solver| solver.day == *day))
+        .map(|day| day.to_string())
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        return Err(format!("No solver for the days {}", missing.join(", ")).into());
+    }
+    Ok(solvers)
+}
+
+// the runs of a part one after the other, the solvers being deterministic they must give the same
+// answer every time, it comes with the statistics
+fn measure(
+    solve: runner::Solve,
+    lines: &[String],
+    runs: usize,
+) -> Result<(String, Stats), Box<dyn Error>> {
+    let mut answers = Vec::with_capacity(runs);
+    let mut times = Vec::with_capacity(runs);
+
+    for _ in 0..runs {
+        let start = Instant::now();
+        answers.push(solve(lines)?);
+        times.push(start.elapsed());
+    }
+
+    if answers.windows(2).any(|pair| pair[0] != pair[1]) {
+        return Err("Results differ".into());
+    }
+    let stats = stats(&times).ok_or("No run")?;
+    Ok((answers.swap_remove(0), stats))
+}
+
+// the medians of both parts of every solver and of the variants of its day, the input of a day is
+// read once. A variant must give the same answer as the part of the solver
+fn measure_all(
+    solvers: &[Solver],
+    variants: &[Variant],
+    inputs: &Path,
+    runs: usize,
+) -> Result<Vec<Timing>, Box<dyn Error>> {
+    let mut measured = Vec::new();
+
+    for solver in solvers {
+        let path = input_path(inputs, solver.day);
+        let lines = input::read(&path.to_string_lossy())?.collect::<Result<Vec<_>, _>>()?;
+
+        for (part, solve) in (1..).zip(solver.parts) {
+            let (answer, stats) = measure(solve, &lines, runs)
+                .map_err(|e| format!("day {} part {}: {}", solver.day, part, e))?;
+            measured.push(Timing {
+                day: solver.day,
+                part,
+                variant: None,
+                median_ms: stats.median.as_secs_f64() * 1000.,
+            });
+
+            for variant in variants
+                .iter()
+                .filter(|variant| variant.day == solver.day && variant.part == part)
+            {
+                let (variant_answer, stats) =
+                    measure(variant.solve, &lines, runs).map_err(|e| {
+                        format!("day {} part {} {}: {}", solver.day, part, variant.name, e)
+                    })?;
+                if variant_answer != answer {
+                    return Err(format!(
+                        "day {} part {} {}: {} instead of {}",
+                        solver.day, part, variant.name, variant_answer, answer
+                    )
+                    .into());
+                }
+                measured.push(Timing {
+                    day: solver.day,
+                    part,
+                    variant: Some(variant.name.to_string()),
+                    median_ms: stats.median.as_secs_f64() * 1000.,
+                });
+            }
+        }
+    }
+
+    Ok(measured)
+}
+
+// the timing of the same part and variant
+fn find<'a>(timings: &'a [Timing], timing: &Timing) -> Option<&'a Timing> {
+    timings.iter().find(|other| {
+        other.day == timing.day && other.part == timing.part && other.variant == timing.variant
+    })
+}
+
+// the parts slower than their baseline by more than the threshold, with their change. The parts
+// missing from the baseline aren't compared
+fn regressions<'a>(
+    baseline: &Baseline,
+    timings: &'a [Timing],
+    threshold: f64,
+) -> Vec<(&'a Timing, f64)> {
+    timings
+        .iter()
+        .filter_map(|timing| {
+            let reference = find(&baseline.timings, timing)?;
+            let change = change(reference.median_ms, timing.median_ms);
+            (change > threshold).then_some((timing, change))
+        })
+        .collect()
+}
+
+fn report(baseline: &Baseline, timings: &[Timing]) -> String {
+    let rows = timings
+        .iter()
+        .map(|timing| {
+            let reference = find(&baseline.timings, timing);
+            vec![
+                timing.day.to_string(),
+                timing.part.to_string(),
+                timing.variant.clone().unwrap_or("-".to_string()),
+                reference.map_or("-".to_string(), |reference| {
+                    format!("{:.3}", reference.median_ms)
+                }),
+                format!("{:.3}", timing.median_ms),
+                reference.map_or("-".to_string(), |reference| {
+                    format!("{:+.1}", change(reference.median_ms, timing.median_ms))
+                }),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    table(
+        &[
+            "day",
+            "part",
+            "variant",
+            "baseline (ms)",
+            "median (ms)",
+            "change (%)",
+        ],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod perf {
+    use std::{
+        env::temp_dir,
+        error::Error,
+        fs::{create_dir_all, remove_dir_all, write},
+        process,
+        time::Duration,
+    };
+
+    use runner::{input_path, registry, Solver};
+
+    use crate::{
+        every_day, measure, measure_all, parse_perf_options, regressions, report, Baseline,
+        PerfOptions, Timing, Variant,
+    };
+
+    fn timing(day: u32, part: usize, median_ms: f64) -> Timing {
+        Timing {
+            day,
+            part,
+            variant: None,
+            median_ms,
+        }
+    }
+
+    fn variant(day: u32, part: usize, name: &str, median_ms: f64) -> Timing {
+        Timing {
+            variant: Some(name.to_string()),
+            ..timing(day, part, median_ms)
+        }
+    }
+
+    fn baseline() -> Baseline {
+        Baseline {
+            runs: 10,
+            timings: vec![
+                timing(9, 1, 2.),
+                timing(9, 2, 4.),
+                variant(9, 2, "fast", 1.),
+                timing(18, 1, 1.),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_parse_perf_options() -> Result<(), Box<dyn Error>> {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(parse_perf_options(&[])?, PerfOptions::default());
+        assert_eq!(
+            parse_perf_options(&args(&["--runs", "3", "--threshold", "5.5", "--update"]))?,
+            PerfOptions {
+                runs: 3,
+                threshold: 5.5,
+                update: true,
+                ..PerfOptions::default()
+            }
+        );
+        assert!(parse_perf_options(&args(&["--runs", "0"])).is_err());
+        assert!(parse_perf_options(&args(&["--runs"])).is_err());
+        assert!(parse_perf_options(&args(&["--fast"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_regressions() {
+        let timings = [
+            timing(9, 1, 2.5),
+            timing(9, 2, 4.7),
+            variant(9, 2, "fast", 1.25),
+            variant(9, 2, "slow", 100.),
+            timing(18, 2, 100.),
+        ];
+
+        // the slow variant and day 18 part 2 aren't in the baseline
+        assert_eq!(
+            regressions(&baseline(), &timings, 20.),
+            vec![(&timings[0], 25.), (&timings[2], 25.)]
+        );
+        assert!(regressions(&baseline(), &timings, 30.).is_empty());
+    }
+
+    #[test]
+    fn test_report() {
+        assert_eq!(
+            report(
+                &baseline(),
+                &[
+                    timing(9, 1, 3.),
+                    variant(9, 2, "fast", 1.),
+                    timing(18, 2, 1.)
+                ]
+            ),
+            "\
+            day  part  variant  baseline (ms)  median (ms)  change (%)\n\
+            ---  ----  -------  -------------  -----------  ----------\n\
+            \x20 9     1  -                2.000        3.000       +50.0\n\
+            \x20 9     2  fast             1.000        1.000        +0.0\n\
+            \x2018     2  -        -                    1.000  -\n"
+        );
+    }
+
+    #[test]
+    fn test_baseline_json() -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string(&baseline())?;
+        assert_eq!(serde_json::from_str::<Baseline>(&json)?, baseline());
+        Ok(())
+    }
+
+    #[test]
+    fn test_every_day() -> Result<(), Box<dyn Error>> {
+        assert_eq!(every_day(registry())?.len(), 25);
+
+        let solvers = registry()
+            .into_iter()
+            .filter(|solver| solver.day != 3 && solver.day != 17)
+            .collect();
+        assert_eq!(
+            every_day(solvers).err().map(|e| e.to_string()),
+            Some("No solver for the days 3, 17".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_measure() -> Result<(), Box<dyn Error>> {
+        let lines = ["1".to_string(), "2".to_string()];
+        let (answer, stats) = measure(|lines| Ok(lines.len().to_string()), &lines, 5)?;
+        assert_eq!(answer, "2");
+        assert!(stats.min <= stats.median && stats.median <= Duration::from_secs(1));
+
+        assert!(measure(|_| Err("broken".into()), &lines, 5).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_measure_all() -> Result<(), Box<dyn Error>> {
+        let dir = temp_dir().join(format!("perf-test-{}", process::id()));
+        let input = input_path(&dir, 1);
+        create_dir_all(input.parent().ok_or("No parent")?)?;
+        write(&input, "1\n2\n3\n")?;
+
+        let solvers = [Solver {
+            day: 1,
+            parts: [
+                |lines| Ok(lines.len().to_string()),
+                |lines| Ok(lines.concat()),
+            ],
+        }];
+        let same = Variant {
+            day: 1,
+            part: 1,
+            name: "same",
+            solve: |lines| {
+                Ok(lines
+                    .iter()
+                    .filter(|line| !line.is_empty())
+                    .count()
+                    .to_string())
+            },
+        };
+        let wrong = Variant {
+            name: "wrong",
+            solve: |_| Ok("0".to_string()),
+            ..same
+        };
+
+        let measured = measure_all(&solvers, &[same], &dir, 3);
+        let failed = measure_all(&solvers, &[wrong], &dir, 3);
+        remove_dir_all(&dir)?;
+
+        assert_eq!(
+            measured?
+                .iter()
+                .map(|timing| (timing.part, timing.variant.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![(1, None), (1, Some("same")), (2, None)]
+        );
+        assert_eq!(
+            failed.err().map(|e| e.to_string()),
+            Some("day 1 part 1 wrong: 0 instead of 3".to_string())
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+// the statistics of the repeated runs of a solver. The median is what gets compared to the
+// baseline, the slow runs caused by the rest of the machine don't move it much
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+// None without any run
+pub fn stats(times: &[Duration]) -> Option<Stats> {
+    let mut sorted = times.to_vec();
+    sorted.sort();
+
+    let min = *sorted.first()?;
+    let middle = sorted.len() / 2;
+    let median = if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2
+    } else {
+        sorted[middle]
+    };
+    let mean = sorted.iter().sum::<Duration>() / u32::try_from(sorted.len()).ok()?;
+
+    Some(Stats { min, median, mean })
+}
+
+// the change from the baseline in percent, positive when slower, both times in the same unit
+pub fn change(baseline: f64, current: f64) -> f64 {
+    if baseline <= 0. {
+        0.
+    } else {
+        (current / baseline - 1.) * 100.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::{change, stats, Stats};
+    use std::time::Duration;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values
+            .iter()
+            .map(|value| Duration::from_millis(*value))
+            .collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(
+            stats(&millis(&[30, 10, 20])),
+            Some(Stats {
+                min: Duration::from_millis(10),
+                median: Duration::from_millis(20),
+                mean: Duration::from_millis(20),
+            })
+        );
+        // a single slow run doesn't move the median
+        assert_eq!(
+            stats(&millis(&[10, 12, 1000, 14])).map(|stats| stats.median),
+            Some(Duration::from_millis(13))
+        );
+        assert_eq!(stats(&[]), None);
+    }
+
+    #[test]
+    fn test_change() {
+        assert_eq!(change(100., 150.), 50.);
+        assert_eq!(change(100., 50.), -50.);
+        assert_eq!(change(0., 50.), 0.);
+    }
+}
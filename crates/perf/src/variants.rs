@@ -0,0 +1,60 @@
+// the other implementations of some parts, timed next to the ones of the registry to compare them.
+// The bitboards are only built with the simd feature
+
+use runner::Solve;
+
+pub struct Variant {
+    pub day: u32,
+    pub part: usize,
+    pub name: &'static str,
+    pub solve: Solve,
+}
+
+pub fn variants() -> Vec<Variant> {
+    vec![
+        Variant {
+            day: 12,
+            part: 2,
+            name: "sequential",
+            solve: |lines| {
+                let lines = lines
+                    .iter()
+                    .cloned()
+                    .map(day_12::parse_line)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(day_12::solve2_sequential(&lines).to_string())
+            },
+        },
+        Variant {
+            day: 14,
+            part: 2,
+            name: "sparse",
+            solve: |lines| {
+                let cells = day_14::parse(lines.iter().cloned())?;
+                Ok(day_14::solve2_sparse(cells)?.to_string())
+            },
+        },
+        #[cfg(feature = "simd")]
+        Variant {
+            day: 14,
+            part: 2,
+            name: "bitboard",
+            solve: |lines| {
+                let cells = day_14::parse(lines.iter().cloned())?;
+                Ok(day_14::bitboard::solve2(cells)?.to_string())
+            },
+        },
+        #[cfg(feature = "simd")]
+        Variant {
+            day: 16,
+            part: 2,
+            name: "bitboard",
+            solve: |lines| {
+                let grid = day_16::parse(lines.iter().cloned())?;
+                Ok(day_16::bitboard::solve2_limited(&grid, None)?
+                    .count
+                    .to_string())
+            },
+        },
+    ]
+}
@@ -0,0 +1,743 @@
+// the registry of the solvers of all the days, from the libraries of their crates, and a parallel
+// run of all of them on the inputs cached in their crates. The days can also run as the binaries of
+// their crates, each in its own process
+
+use day_09::{extrapolate_backward, extrapolate_forward, Extrapolate};
+use day_18::{draw, Color, DigPlanParser, Plain};
+use itertools::Itertools;
+use lib::{
+    export::{json_records, Field},
+    geom::lattice_point_count,
+    input,
+    par::map_indexed,
+    table::table,
+};
+use std::{
+    env::consts::EXE_SUFFIX,
+    error::Error,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+    vec::IntoIter,
+};
+
+// solves a part of a day from the lines of its input
+pub type Solve = fn(&[String]) -> Result<String, Box<dyn Error>>;
+
+// the answer of a solver of a day, before it is turned into a string
+type Answer<T> = Result<T, Box<dyn Error>>;
+
+pub struct Solver {
+    pub day: u32,
+    pub parts: [Solve; 2],
+}
+
+// both parts of every day, with the options the binaries of the days default to
+pub fn registry() -> Vec<Solver> {
+    vec![
+        Solver {
+            day: 1,
+            parts: [
+                |lines| Ok(day_01::solve1(lines.iter().cloned())?.to_string()),
+                |lines| Ok(day_01::solve2(lines.iter().cloned())?.to_string()),
+            ],
+        },
+        Solver {
+            day: 2,
+            parts: [
+                |lines| {
+                    let games = parse_lines::<day_02::Game>(lines)?;
+                    Ok(day_02::solve1(&Default::default(), games.into_iter()).to_string())
+                },
+                |lines| Ok(day_02::solve2(parse_lines(lines)?.into_iter()).to_string()),
+            ],
+        },
+        Solver {
+            day: 3,
+            parts: [
+                |lines| Ok(day_03::solve1(&day03(lines)?).to_string()),
+                |lines| Ok(day_03::solve2(&day03(lines)?, &Default::default())?.to_string()),
+            ],
+        },
+        Solver {
+            day: 4,
+            parts: [
+                |lines| Ok(day_04::solve1(parse_lines(lines)?.into_iter())?.to_string()),
+                |lines| Ok(day_04::solve2(parse_lines(lines)?.into_iter())?.to_string()),
+            ],
+        },
+        Solver {
+            day: 5,
+            parts: [
+                |lines| day05(lines, day_05::solve1),
+                |lines| day05(lines, day_05::solve2_intervals),
+            ],
+        },
+        Solver {
+            day: 6,
+            parts: [
+                |lines| {
+                    let races = day_06::parse_races(lines.iter().cloned())?;
+                    Ok(day_06::solve(races.into_iter(), day_06::solve_race)?.to_string())
+                },
+                |lines| {
+                    let race = day_06::parse_race(lines.iter().cloned())?;
+                    Ok(day_06::solve_race(race)?.to_string())
+                },
+            ],
+        },
+        Solver {
+            day: 7,
+            parts: [
+                |lines| day07(lines, day_07::solve1),
+                |lines| day07(lines, day_07::solve2),
+            ],
+        },
+        Solver {
+            day: 8,
+            parts: [
+                |lines| {
+                    let (path, nodes) = day_08::parse_input(lines.iter().cloned())?;
+                    Ok(day_08::solve1(path, "AAA".to_string(), nodes)?.to_string())
+                },
+                |lines| {
+                    let (path, nodes) = day_08::parse_input(lines.iter().cloned())?;
+                    Ok(day_08::solve2(path, nodes)?.to_string())
+                },
+            ],
+        },
+        Solver {
+            day: 9,
+            parts: [
+                |lines| day09(lines, extrapolate_forward),
+                |lines| day09(lines, extrapolate_backward),
+            ],
+        },
+        Solver {
+            day: 10,
+            parts: [
+                |lines| day10(lines, day_10::solve1),
+                |lines| day10(lines, day_10::solve2),
+            ],
+        },
+        Solver {
+            day: 11,
+            parts: [|lines| day11(lines, 2), |lines| day11(lines, 1_000_000)],
+        },
+        Solver {
+            day: 12,
+            parts: [
+                |lines| day12(lines, day_12::solve1),
+                |lines| day12(lines, day_12::solve2),
+            ],
+        },
+        Solver {
+            day: 13,
+            parts: [
+                |lines| day13(lines, day_13::solve_pattern1),
+                |lines| day13(lines, day_13::solve_pattern2),
+            ],
+        },
+        Solver {
+            day: 14,
+            parts: [
+                |lines| day14(lines, day_14::solve1),
+                |lines| day14(lines, day_14::solve2),
+            ],
+        },
+        Solver {
+            day: 15,
+            parts: [
+                // the steps are on one line, the line breaks are ignored
+                |lines| Ok(day_15::solve1(&lines.concat()).to_string()),
+                |lines| Ok(day_15::solve2(&lines.concat())?.to_string()),
+            ],
+        },
+        Solver {
+            day: 16,
+            parts: [
+                |lines| day16(lines, day_16::solve1_limited),
+                |lines| day16(lines, day_16::solve2_limited),
+            ],
+        },
+        Solver {
+            day: 17,
+            parts: [
+                |lines| day17(lines, day_17::solve1),
+                |lines| day17(lines, day_17::solve2),
+            ],
+        },
+        Solver {
+            day: 18,
+            parts: [|lines| day18(lines, &Plain), |lines| day18(lines, &Color)],
+        },
+        Solver {
+            day: 19,
+            parts: [
+                |lines| {
+                    let (workflows, parts) = day_19::parse(lines.iter().cloned())?;
+                    Ok(day_19::solve1(&workflows, &parts)?.to_string())
+                },
+                |lines| {
+                    let (workflows, _) = day_19::parse(lines.iter().cloned())?;
+                    Ok(day_19::solve2(&workflows)?.to_string())
+                },
+            ],
+        },
+        Solver {
+            day: 20,
+            parts: [
+                |lines| day20(lines, day_20::solve1),
+                |lines| day20(lines, day_20::solve2),
+            ],
+        },
+        Solver {
+            day: 21,
+            parts: [
+                |lines| {
+                    let (grid, start) = day_21::parse(lines.iter().cloned())?;
+                    Ok(day_21::solve1(&grid, &start)?.to_string())
+                },
+                |lines| {
+                    let (grid, start) = day_21::parse(lines.iter().cloned())?;
+                    Ok(day_21::solve2(&grid, &start)?.to_string())
+                },
+            ],
+        },
+        Solver {
+            day: 22,
+            parts: [
+                |lines| day22(lines, day_22::solve1),
+                |lines| day22(lines, day_22::solve2),
+            ],
+        },
+        Solver {
+            day: 23,
+            parts: [
+                |lines| Ok(day_23::solve1(&day_23::parse(lines.iter().cloned())?)?.to_string()),
+                // the slopes are regular paths in part 2
+                |lines| {
+                    let map = day_23::parse(lines.iter().map(|line| day_23::remove_slopes(line)))?;
+                    Ok(day_23::solve2(&map)?.to_string())
+                },
+            ],
+        },
+        Solver {
+            day: 24,
+            parts: [
+                |lines| {
+                    let hailstones = day_24::parse(lines.iter().cloned())?;
+                    Ok(day_24::solve1(&hailstones, &Default::default()).to_string())
+                },
+                |lines| Ok(day_24::solve2(&day_24::parse(lines.iter().cloned())?)?.to_string()),
+            ],
+        },
+        Solver {
+            day: 25,
+            // the last day has a single puzzle, its binary gives the same answer for both parts. The
+            // minimum cut is used rather than the default sampling of paths, which can fail
+            parts: [day25, day25],
+        },
+    ]
+}
+
+fn parse_lines<T: FromStr<Err = Box<dyn Error>>>(
+    lines: &[String],
+) -> Result<Vec<T>, Box<dyn Error>> {
+    lines.iter().map(|line| line.parse()).collect()
+}
+
+fn day03(lines: &[String]) -> Result<day_03::Schematic, Box<dyn Error>> {
+    day_03::Schematic::new(
+        lines
+            .iter()
+            .map(|line| day_03::char_to_located_element(line.chars()).collect()),
+    )
+}
+
+fn day05(
+    lines: &[String],
+    solve: fn(day_05::Input) -> Answer<u64>,
+) -> Result<String, Box<dyn Error>> {
+    Ok(solve(day_05::parse_input(lines.iter().cloned())?)?.to_string())
+}
+
+fn day07(
+    lines: &[String],
+    solve: fn(&[day_07::HandAndBid]) -> u32,
+) -> Result<String, Box<dyn Error>> {
+    let hand_and_bids = day_07::parse_input(lines.join("\n").as_bytes(), false)?;
+    Ok(solve(&hand_and_bids).to_string())
+}
+
+fn day09(lines: &[String], extrapolate: Extrapolate) -> Result<String, Box<dyn Error>> {
+    Ok(day_09::solve(lines.iter().cloned(), extrapolate)?.to_string())
+}
+
+fn day10(
+    lines: &[String],
+    solve: fn(day_10::Maze) -> Answer<u32>,
+) -> Result<String, Box<dyn Error>> {
+    Ok(solve(day_10::parse_maze(lines.iter().cloned())?)?.to_string())
+}
+
+// each empty line or column is replaced by `expansion` of them
+fn day11(lines: &[String], expansion: i64) -> Result<String, Box<dyn Error>> {
+    let universe = day_11::parse(lines.iter().cloned())?;
+    Ok(day_11::solve(&day_11::expand(&universe, expansion - 1)?)?.to_string())
+}
+
+fn day12(
+    lines: &[String],
+    solve: fn(IntoIter<day_12::InputLine>, &day_12::LineCache) -> i64,
+) -> Result<String, Box<dyn Error>> {
+    let input_lines = lines
+        .iter()
+        .cloned()
+        .map(day_12::parse_line)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(solve(input_lines.into_iter(), &Default::default()).to_string())
+}
+
+fn day13(
+    lines: &[String],
+    solve_pattern: fn(&day_13::Pattern) -> Answer<i32>,
+) -> Result<String, Box<dyn Error>> {
+    let patterns = day_13::parse(lines.iter().cloned())?;
+    Ok(day_13::solve(&patterns, solve_pattern)?.to_string())
+}
+
+fn day14(
+    lines: &[String],
+    solve: fn(Vec<Vec<day_14::Cell>>) -> Answer<i32>,
+) -> Result<String, Box<dyn Error>> {
+    Ok(solve(day_14::parse(lines.iter().cloned())?)?.to_string())
+}
+
+fn day16(lines: &[String], solve: day_16::LimitedSolver) -> Result<String, Box<dyn Error>> {
+    let grid = day_16::parse(lines.iter().cloned())?;
+    Ok(solve(&grid, None)?.count.to_string())
+}
+
+fn day17(
+    lines: &[String],
+    solve: fn(day_17::Graph) -> Answer<u32>,
+) -> Result<String, Box<dyn Error>> {
+    Ok(solve(day_17::parse(lines.iter().cloned())?)?.to_string())
+}
+
+fn day18(lines: &[String], parser: &dyn DigPlanParser) -> Result<String, Box<dyn Error>> {
+    Ok(lattice_point_count(&draw(&parser.parse(lines)?)).to_string())
+}
+
+fn day20(
+    lines: &[String],
+    solve: fn(Vec<day_20::Node>) -> Answer<i64>,
+) -> Result<String, Box<dyn Error>> {
+    Ok(solve(day_20::parse(lines.iter().cloned())?)?.to_string())
+}
+
+// the bricks settle before either part
+fn day22(
+    lines: &[String],
+    solve: fn(&Vec<day_22::Brick>) -> Answer<i32>,
+) -> Result<String, Box<dyn Error>> {
+    let bricks = day_22::parse(lines.iter().cloned())?;
+    Ok(solve(&day_22::fall(&bricks))?.to_string())
+}
+
+fn day25(lines: &[String]) -> Result<String, Box<dyn Error>> {
+    Ok(day_25::solve_min_cut(&day_25::parse(lines.iter().cloned())?)?.to_string())
+}
+
+// the answer of a part, or the error preventing it, and the time it took including the reading
+// of the input
+#[derive(Debug)]
+pub struct Report {
+    pub day: u32,
+    pub part: usize,
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+}
+
+pub fn input_path(dir: &Path, day: u32) -> PathBuf {
+    dir.join(format!("day-{:02}", day)).join("input")
+}
+
+// runs both parts of every solver on the rayon thread pool, the reports come in the order of the
+// solvers
+pub fn run_all(solvers: &[Solver], dir: &Path) -> Vec<Report> {
+    let jobs = solvers.iter().cartesian_product(1..=2).collect::<Vec<_>>();
+
+    map_indexed(&jobs, |(solver, part)| -> Result<String, String> {
+        let path = input_path(dir, solver.day);
+        let lines = input::read(&path.to_string_lossy())
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        solver.parts[part - 1](&lines).map_err(|e| e.to_string())
+    })
+    .into_iter()
+    .zip(&jobs)
+    .map(|(timed, (solver, part))| Report {
+        day: solver.day,
+        part: *part,
+        answer: timed.value,
+        elapsed: timed.elapsed,
+    })
+    .collect()
+}
+
+pub fn day_binary(bin_dir: &Path, day: u32) -> PathBuf {
+    bin_dir.join(format!("day-{:02}{}", day, EXE_SUFFIX))
+}
+
+// the days whose binary is built in the directory
+pub fn available_days(bin_dir: &Path) -> Vec<u32> {
+    (1..=25)
+        .filter(|day| day_binary(bin_dir, *day).is_file())
+        .collect()
+}
+
+// the answer is the output of the binary when it succeeds. Otherwise the error is the panic
+// message, on the line after "thread 'main' panicked at FILE:LINE:COLUMN:", or the last line
+// written on stderr, the error returned by main. The days print their usage for the modes they
+// don't have
+fn run_process(binary: &Path, input: &Path, part: usize) -> Result<String, String> {
+    let output = Command::new(binary)
+        .arg("--input")
+        .arg(input)
+        .arg(format!("-{}", part))
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("{}: {}", binary.display(), e))?;
+
+    if output.status.success() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.starts_with("Usage:") {
+            Err(format!("no mode -{}", part))
+        } else {
+            Ok(stdout.trim().to_string())
+        }
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(match error_message(&stderr) {
+            Some(message) => format!("{}, {}", output.status, message),
+            None => output.status.to_string(),
+        })
+    }
+}
+
+fn error_message(stderr: &str) -> Option<&str> {
+    let lines = stderr
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+
+    // before Rust 1.73 the message was on the same line, as in panicked at 'MESSAGE', FILE
+    match lines.iter().position(|line| line.contains(" panicked at ")) {
+        Some(i) if lines[i].ends_with(':') => lines.get(i + 1).copied(),
+        Some(i) => Some(lines[i]),
+        None => lines.last().copied(),
+    }
+}
+
+// runs both parts of the days as separate processes, at most `jobs` at a time, so that a crash
+// only fails the reports of its own part. The reports come in the order of the days
+pub fn run_isolated(days: &[u32], bin_dir: &Path, dir: &Path, jobs: usize) -> Vec<Report> {
+    let tasks = days.iter().cartesian_product(1..=2).collect::<Vec<_>>();
+    let next = AtomicUsize::new(0);
+
+    // each worker takes the next task until there are none left
+    let worker = || {
+        let mut reports = Vec::new();
+        while let Some((day, part)) = tasks.get(next.fetch_add(1, Ordering::Relaxed)) {
+            let start = Instant::now();
+            let answer = run_process(&day_binary(bin_dir, **day), &input_path(dir, **day), *part);
+            reports.push(Report {
+                day: **day,
+                part: *part,
+                answer,
+                elapsed: start.elapsed(),
+            });
+        }
+        reports
+    };
+
+    let mut reports = thread::scope(|scope| {
+        let workers = (0..jobs.clamp(1, tasks.len().max(1)))
+            .map(|_| scope.spawn(worker))
+            .collect::<Vec<_>>();
+
+        // the workers don't panic, the processes do
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect::<Vec<_>>()
+    });
+
+    reports.sort_by_key(|report| (report.day, report.part));
+    reports
+}
+
+fn milliseconds(duration: &Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.)
+}
+
+pub fn summary(reports: &[Report]) -> String {
+    let rows = reports
+        .iter()
+        .map(|report| {
+            vec![
+                report.day.to_string(),
+                report.part.to_string(),
+                report
+                    .answer
+                    .clone()
+                    .unwrap_or_else(|e| format!("error: {}", e)),
+                milliseconds(&report.elapsed),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    table(&["day", "part", "answer", "time (ms)"], &rows)
+}
+
+// one object per report, with either the answer or the error
+pub fn to_json(reports: &[Report]) -> Result<String, Box<dyn Error>> {
+    let records = reports
+        .iter()
+        .map(|report| {
+            let (answer, error) = match &report.answer {
+                Ok(answer) => (Field::Text(answer.clone()), Field::Null),
+                Err(e) => (Field::Null, Field::Text(e.clone())),
+            };
+
+            Ok(vec![
+                Field::Number(i64::from(report.day)),
+                Field::Number(i64::try_from(report.part)?),
+                answer,
+                error,
+                Field::Float(report.elapsed.as_micros() as f64 / 1000.),
+            ])
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    json_records(&["day", "part", "answer", "error", "time_ms"], &records)
+}
+
+#[cfg(test)]
+mod runner {
+    use std::{
+        error::Error,
+        fs::{create_dir_all, remove_dir_all, write},
+        time::Duration,
+    };
+
+    use crate::{
+        available_days, day_binary, error_message, input_path, registry, run_all, run_isolated,
+        summary, to_json, Report,
+    };
+
+    const DAY09: &str = "\
+        0 3 6 9 12 15\n\
+        1 3 6 10 15 21\n\
+        10 13 16 21 30 45";
+
+    const DAY18: &str = "\
+        R 6 (#70c710)\n\
+        D 5 (#0dc571)\n\
+        L 2 (#5713f0)\n\
+        D 2 (#d2c081)\n\
+        R 2 (#59c680)\n\
+        D 2 (#411b91)\n\
+        L 5 (#8ceee2)\n\
+        U 2 (#caa173)\n\
+        L 1 (#1b58a2)\n\
+        U 2 (#caa171)\n\
+        R 2 (#7807d2)\n\
+        U 3 (#a77fa3)\n\
+        L 2 (#015232)\n\
+        U 2 (#7a21e3)";
+
+    fn lines(s: &str) -> Vec<String> {
+        s.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_registry() -> Result<(), Box<dyn Error>> {
+        let registry = registry();
+        let solver = |day| registry.iter().find(|solver| solver.day == day);
+        assert_eq!(
+            registry.iter().map(|solver| solver.day).collect::<Vec<_>>(),
+            (1..=25).collect::<Vec<_>>()
+        );
+
+        let day09 = solver(9).ok_or("No day 9")?;
+        assert_eq!(day09.parts[0](&lines(DAY09))?, "114");
+        assert_eq!(day09.parts[1](&lines(DAY09))?, "2");
+
+        let day18 = solver(18).ok_or("No day 18")?;
+        assert_eq!(day18.parts[0](&lines(DAY18))?, "62");
+        assert_eq!(day18.parts[1](&lines(DAY18))?, "952408144115");
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_all() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("runner-test-{}", std::process::id()));
+        let input = input_path(&dir, 9);
+        create_dir_all(input.parent().ok_or("No parent")?)?;
+        write(&input, DAY09)?;
+
+        let reports = run_all(&registry(), &dir);
+        remove_dir_all(&dir)?;
+
+        assert_eq!(reports.len(), 50);
+        assert_eq!(
+            reports
+                .iter()
+                .filter(|report| report.day == 9)
+                .map(|report| (report.part, report.answer.clone().ok()))
+                .collect::<Vec<_>>(),
+            vec![(1, Some("114".to_string())), (2, Some("2".to_string()))]
+        );
+        // the missing inputs are reported
+        assert!(reports
+            .iter()
+            .filter(|report| report.day != 9)
+            .all(|report| report
+                .answer
+                .as_ref()
+                .is_err_and(|e| e.contains(&format!("day-{:02}", report.day)))));
+        Ok(())
+    }
+
+    // stand-ins for the binaries of the days: day 1 answers its part, day 2 panics on part 2 and
+    // day 4 only has a part 1
+    #[cfg(unix)]
+    #[test]
+    fn test_run_isolated() -> Result<(), Box<dyn Error>> {
+        use std::{fs::set_permissions, os::unix::fs::PermissionsExt};
+
+        let dir = std::env::temp_dir().join(format!("runner-isolated-{}", std::process::id()));
+        let bin_dir = dir.join("bin");
+        create_dir_all(&bin_dir)?;
+        let scripts = [
+            (1, "#!/bin/sh\necho \"answer $3\"\n"),
+            (
+                2,
+                "#!/bin/sh\n[ \"$3\" = -1 ] && echo 7 && exit 0\n\
+                echo \"thread 'main' panicked at src/main.rs:3:5:\" >&2\n\
+                echo 'attempt to overflow' >&2\n\
+                echo 'note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace' >&2\n\
+                exit 101\n",
+            ),
+            (
+                4,
+                "#!/bin/sh\n[ \"$3\" = -1 ] && echo 4 || echo \"Usage: day-04 [-1|-h]\"\n",
+            ),
+        ];
+        for (day, script) in scripts {
+            let binary = day_binary(&bin_dir, day);
+            let input = input_path(&dir, day);
+            create_dir_all(input.parent().ok_or("No parent")?)?;
+            write(&input, "")?;
+            write(&binary, script)?;
+            set_permissions(&binary, PermissionsExt::from_mode(0o755))?;
+        }
+
+        let days = available_days(&bin_dir);
+        let reports = run_isolated(&days, &bin_dir, &dir, 3);
+        // a day without its binary
+        let missing = run_isolated(&[3], &bin_dir, &dir, 1);
+        remove_dir_all(&dir)?;
+
+        assert_eq!(days, vec![1, 2, 4]);
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| (report.day, report.part, report.answer.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 1, Ok("answer -1".to_string())),
+                (1, 2, Ok("answer -2".to_string())),
+                (2, 1, Ok("7".to_string())),
+                (
+                    2,
+                    2,
+                    Err("exit status: 101, attempt to overflow".to_string())
+                ),
+                (4, 1, Ok("4".to_string())),
+                (4, 2, Err("no mode -2".to_string())),
+            ]
+        );
+        assert_eq!(missing.len(), 2);
+        assert!(missing[0]
+            .answer
+            .as_ref()
+            .is_err_and(|e| e.contains("day-03")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_message() {
+        let backtrace = "\
+            thread 'main' panicked at src/main.rs:3:5:\n\
+            index out of bounds\n\
+            stack backtrace:\n   \
+            0: rust_begin_unwind\n";
+        assert_eq!(error_message(backtrace), Some("index out of bounds"));
+        assert_eq!(
+            error_message("Error: \"No path found\"\n\n"),
+            Some("Error: \"No path found\"")
+        );
+        assert_eq!(error_message(""), None);
+    }
+
+    fn reports() -> Vec<Report> {
+        vec![
+            Report {
+                day: 9,
+                part: 1,
+                answer: Ok("114".to_string()),
+                elapsed: Duration::from_micros(1500),
+            },
+            Report {
+                day: 18,
+                part: 2,
+                answer: Err("no \"input\"".to_string()),
+                elapsed: Duration::from_micros(20),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(
+            summary(&reports()),
+            "\
+            day  part  answer             time (ms)\n\
+            ---  ----  -----------------  ---------\n\
+            \x20 9     1                114      1.500\n\
+            \x2018     2  error: no \"input\"      0.020\n"
+        );
+    }
+
+    #[test]
+    fn test_to_json() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            to_json(&reports())?,
+            "[\
+            {\"day\":9,\"part\":1,\"answer\":\"114\",\"error\":null,\"time_ms\":1.5},\
+            {\"day\":18,\"part\":2,\"answer\":null,\"error\":\"no \\\"input\\\"\",\"time_ms\":0.02}\
+            ]\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+#![no_main]
+
+use day_02::{parse_costs, Game};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|s: &str| {
+    for line in s.lines() {
+        let _ = line.parse::<Game>();
+    }
+    let _ = parse_costs(s);
+});
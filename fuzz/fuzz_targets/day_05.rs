@@ -0,0 +1,8 @@
+#![no_main]
+
+use day_05::parse_input;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|s: &str| {
+    let _ = parse_input(s.lines().map(|line| line.to_string()));
+});
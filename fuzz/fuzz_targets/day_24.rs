@@ -0,0 +1,8 @@
+#![no_main]
+
+use day_24::parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|s: &str| {
+    let _ = parse(s.lines().map(|line| line.to_string()));
+});